@@ -1,4 +1,6 @@
-use super::storage::{BrainStorage, ExportFormat, ExportOptions};
+use super::storage::{
+    export_manifest_path, BrainStorage, ExportFilter, ExportFormat, ExportOptions,
+};
 use super::{BrainRecord, ContentType, Signal};
 use anyhow::Result;
 use tempfile::TempDir;
@@ -56,6 +58,8 @@ async fn test_canonical_export_deduplication() -> Result<()> {
         output_path: Some(export_path.clone()),
         include_source_ids: true,
         limit: 50,
+        manifest: false,
+        ..Default::default()
     };
     println!("Exporting to {:?}", export_path);
     storage.export(&options).await.expect("Failed to export");
@@ -109,3 +113,266 @@ async fn test_content_type_migration() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_html_export_groups_sources_and_escapes_chunks() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let storage = BrainStorage::new_for_test(temp_dir.path().join("brain"))?;
+
+    let record = |source_id: &str, path: &str, text: &str| BrainRecord {
+        source_id: source_id.to_string(),
+        commit: "abc".to_string(),
+        license: "MIT".to_string(),
+        language: "Rust".to_string(),
+        path: path.to_string(),
+        content_type: ContentType::Code,
+        signals: vec![Signal::StateMachine],
+        summary: "Summary <em>here</em>".to_string(),
+        chunks: vec![crate::brain::ContentChunk {
+            chunk_id: format!("{}_{}", source_id, path),
+            text: text.to_string(),
+            start_line: 1,
+            end_line: 1,
+        }],
+        tags: vec![],
+    };
+    for source_id in ["src_a", "src_b"] {
+        storage
+            .save_source(&super::SourceMeta {
+                source_id: source_id.to_string(),
+                commit: "abc".to_string(),
+                license: "MIT".to_string(),
+                language: "Rust".to_string(),
+                fetched_at: chrono::Utc::now(),
+                files_count: 1,
+                chunks_count: 1,
+                stars: 0,
+            })
+            .await?;
+    }
+    storage
+        .save_records(&[
+            record("src_a", "src/lib.rs", "fn run() {}"),
+            record(
+                "src_b",
+                "web/app.js",
+                "const x = 1; <script>alert('x')</script>",
+            ),
+        ])
+        .await?;
+
+    let export_path = temp_dir.path().join("brain.html");
+    let mut options = ExportOptions {
+        format: ExportFormat::Html,
+        output_path: Some(export_path.clone()),
+        include_source_ids: true,
+        limit: 50,
+        manifest: false,
+        ..Default::default()
+    };
+    storage.export(&options).await?;
+
+    let html = std::fs::read_to_string(&export_path)?;
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>Source src_a</h2>"));
+    assert!(html.contains("<h2>Source src_b</h2>"));
+    assert_eq!(html.matches("<section>").count(), 2);
+    assert!(html.contains("<h3>web/app.js</h3>"));
+    assert!(html.contains("StateMachine"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("<script>"));
+    assert!(!html.contains("<em>"));
+
+    // Without source IDs, sections are numbered; the limit caps files
+    options.include_source_ids = false;
+    options.limit = 1;
+    storage.export(&options).await?;
+    let html = std::fs::read_to_string(&export_path)?;
+    assert!(html.contains("<h2>Source #1</h2>"));
+    assert!(!html.contains("src_a"));
+    assert_eq!(html.matches("<article>").count(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_jsonl_export_manifest_matches_export() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let storage = BrainStorage::new_for_test(temp_dir.path().join("brain"))?;
+
+    storage
+        .save_source(&super::SourceMeta {
+            source_id: "src_a".to_string(),
+            commit: "abc".to_string(),
+            license: "MIT".to_string(),
+            language: "Rust".to_string(),
+            fetched_at: chrono::Utc::now(),
+            files_count: 2,
+            chunks_count: 3,
+            stars: 0,
+        })
+        .await?;
+    let chunk = |id: &str, line: u32| crate::brain::ContentChunk {
+        chunk_id: id.to_string(),
+        text: format!("line {}", line),
+        start_line: line,
+        end_line: line,
+    };
+    let record = |path: &str, chunks| BrainRecord {
+        source_id: "src_a".to_string(),
+        commit: "abc".to_string(),
+        license: "MIT".to_string(),
+        language: "Rust".to_string(),
+        path: path.to_string(),
+        content_type: ContentType::Code,
+        signals: vec![],
+        summary: "Summary".to_string(),
+        chunks,
+        tags: vec![],
+    };
+    storage
+        .save_records(&[
+            record("src/a.rs", vec![chunk("a1", 1), chunk("a2", 20)]),
+            record("src/b.rs", vec![chunk("b1", 1)]),
+        ])
+        .await?;
+
+    let export_path = temp_dir.path().join("brain.jsonl");
+    let options = ExportOptions {
+        format: ExportFormat::Jsonl,
+        output_path: Some(export_path.clone()),
+        manifest: true,
+        ..Default::default()
+    };
+    storage.export(&options).await?;
+
+    let manifest_path = export_manifest_path(&export_path);
+    assert_eq!(
+        manifest_path,
+        temp_dir.path().join("brain.jsonl.manifest.json")
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)?;
+
+    let exported = std::fs::read_to_string(&export_path)?;
+    let records: Vec<BrainRecord> = exported
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(manifest["records"], records.len());
+    assert_eq!(
+        manifest["chunks"],
+        records.iter().map(|r| r.chunks.len()).sum::<usize>()
+    );
+    assert_eq!(manifest["records"], 2);
+    assert_eq!(manifest["chunks"], 3);
+    assert_eq!(manifest["format"], "jsonl");
+    assert_eq!(manifest["file"], "brain.jsonl");
+    assert_eq!(manifest["include_source_ids"], false);
+    assert_eq!(manifest["tool_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        manifest["sha256"],
+        crate::evidence::content_hash(exported.as_bytes())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_filter_by_content_type_and_language() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let storage = BrainStorage::new_for_test(temp_dir.path().join("brain"))?;
+    storage
+        .save_source(&super::SourceMeta {
+            source_id: "src_a".to_string(),
+            commit: "abc".to_string(),
+            license: "MIT".to_string(),
+            language: "Rust".to_string(),
+            fetched_at: chrono::Utc::now(),
+            files_count: 3,
+            chunks_count: 3,
+            stars: 0,
+        })
+        .await?;
+    let record = |path: &str, language: &str, content_type| BrainRecord {
+        source_id: "src_a".to_string(),
+        commit: "abc".to_string(),
+        license: "MIT".to_string(),
+        language: language.to_string(),
+        path: path.to_string(),
+        content_type,
+        signals: vec![],
+        summary: "Summary".to_string(),
+        chunks: vec![crate::brain::ContentChunk {
+            chunk_id: format!("chunk_{}", path),
+            text: format!("contents of {}", path),
+            start_line: 1,
+            end_line: 1,
+        }],
+        tags: vec![],
+    };
+    storage
+        .save_records(&[
+            record("src/lib.rs", "Rust", ContentType::Code),
+            record("app.py", "Python", ContentType::Code),
+            record("docs/guide.md", "Rust", ContentType::Doc),
+        ])
+        .await?;
+
+    let filter = ExportFilter {
+        languages: vec!["rust".to_string()],
+        content_types: vec![ContentType::Code],
+        ..Default::default()
+    };
+
+    let jsonl_path = temp_dir.path().join("brain.jsonl");
+    storage
+        .export(&ExportOptions {
+            format: ExportFormat::Jsonl,
+            output_path: Some(jsonl_path.clone()),
+            manifest: true,
+            filter: filter.clone(),
+            ..Default::default()
+        })
+        .await?;
+    let records: Vec<BrainRecord> = std::fs::read_to_string(&jsonl_path)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].path, "src/lib.rs");
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(export_manifest_path(&jsonl_path))?)?;
+    assert_eq!(manifest["filter"]["languages"][0], "rust");
+    assert_eq!(manifest["filter"]["content_types"][0], "code");
+
+    let md_path = temp_dir.path().join("brain.md");
+    storage
+        .export(&ExportOptions {
+            format: ExportFormat::Markdown,
+            output_path: Some(md_path.clone()),
+            filter,
+            ..Default::default()
+        })
+        .await?;
+    let markdown = std::fs::read_to_string(&md_path)?;
+    assert!(markdown.contains("### src/lib.rs"));
+    assert!(!markdown.contains("app.py"));
+    assert!(!markdown.contains("docs/guide.md"));
+
+    // Two chunks are required, so nothing qualifies
+    storage
+        .export(&ExportOptions {
+            format: ExportFormat::Jsonl,
+            output_path: Some(jsonl_path.clone()),
+            filter: ExportFilter {
+                min_chunks: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await?;
+    assert!(std::fs::read_to_string(&jsonl_path)?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! Harvest lockfile for reproducible, auditable harvests
+//!
+//! `harvest.lock.json` records the exact search query that was sent to GitHub
+//! (including the resolved date cutoff) and the sources it yielded, so the
+//! same query can be re-run later and compared against what was harvested.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::anonymize_source;
+use super::harvester::{RepoInfo, RepoSort, SortOrder};
+use crate::workspace;
+
+/// Path to the harvest lockfile
+pub fn lock_path() -> PathBuf {
+    workspace::workspace_path().join("harvest.lock.json")
+}
+
+/// A harvested source as recorded in the lock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LockedSource {
+    /// Anonymized source ID
+    pub source_id: String,
+    /// Commit (or ref) that was harvested
+    pub commit: String,
+    /// Last push reported by GitHub at harvest time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushed_at: Option<String>,
+}
+
+impl LockedSource {
+    /// Lock entry for a search result, using the same source ID as the harvester
+    /// and the default branch as the ref
+    pub fn from_repo(repo: &RepoInfo) -> Self {
+        Self {
+            source_id: anonymize_source(&repo.full_name),
+            commit: repo.default_branch.clone(),
+            pushed_at: repo.pushed_at.clone(),
+        }
+    }
+
+    /// Record the commit SHA that was actually harvested
+    pub fn with_commit(mut self, commit: &str) -> Self {
+        self.commit = commit.to_string();
+        self
+    }
+}
+
+/// Recorded harvest: query, sources and tool version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarvestLock {
+    /// Tool version that produced the harvest
+    pub tool_version: String,
+    /// Search query exactly as sent to GitHub
+    pub query: String,
+    /// Maximum repos requested
+    pub max_repos: usize,
+    /// Sort the search used (locks from before `--sort` used stars)
+    #[serde(default)]
+    pub sort: RepoSort,
+    /// Sort direction the search used
+    #[serde(default)]
+    pub order: SortOrder,
+    /// When the harvest ran
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Sources harvested, in search order
+    pub sources: Vec<LockedSource>,
+}
+
+impl HarvestLock {
+    pub fn new(query: &str, max_repos: usize, sources: Vec<LockedSource>) -> Self {
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            query: query.to_string(),
+            max_repos,
+            sort: RepoSort::default(),
+            order: SortOrder::default(),
+            created_at: chrono::Utc::now(),
+            sources,
+        }
+    }
+
+    /// Record the sort the search used
+    pub fn with_sort(mut self, sort: RepoSort, order: SortOrder) -> Self {
+        self.sort = sort;
+        self.order = order;
+        self
+    }
+
+    /// Compare the recorded sources with the results of re-running the query
+    pub fn verify(&self, current: &[LockedSource]) -> LockVerification {
+        let mut report = LockVerification::default();
+
+        for locked in &self.sources {
+            match current.iter().find(|c| c.source_id == locked.source_id) {
+                Some(now) if now == locked => report.unchanged.push(locked.source_id.clone()),
+                Some(now) => report.changed.push(SourceChange {
+                    source_id: locked.source_id.clone(),
+                    locked: locked.clone(),
+                    current: now.clone(),
+                }),
+                None => report.missing.push(locked.source_id.clone()),
+            }
+        }
+        report.added = current
+            .iter()
+            .filter(|c| !self.sources.iter().any(|l| l.source_id == c.source_id))
+            .map(|c| c.source_id.clone())
+            .collect();
+
+        report
+    }
+
+    /// Load a lockfile
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write the lockfile
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// A source whose commit or push time differs from the lock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceChange {
+    pub source_id: String,
+    pub locked: LockedSource,
+    pub current: LockedSource,
+}
+
+impl fmt::Display for SourceChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pushed = |s: &LockedSource| s.pushed_at.clone().unwrap_or_else(|| "?".to_string());
+        write!(
+            f,
+            "{}: {} (pushed {}) → {} (pushed {})",
+            self.source_id,
+            self.locked.commit,
+            pushed(&self.locked),
+            self.current.commit,
+            pushed(&self.current)
+        )
+    }
+}
+
+/// Result of checking a lock against fresh search results
+#[derive(Debug, Clone, Default)]
+pub struct LockVerification {
+    /// Sources that still match the lock
+    pub unchanged: Vec<String>,
+    /// Sources whose commit or push time changed
+    pub changed: Vec<SourceChange>,
+    /// Locked sources the query no longer returns
+    pub missing: Vec<String>,
+    /// Sources the query now returns that weren't harvested
+    pub added: Vec<String>,
+}
+
+impl LockVerification {
+    /// Whether the harvest would reproduce exactly
+    pub fn is_clean(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty() && self.added.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(full_name: &str, pushed_at: &str) -> RepoInfo {
+        RepoInfo {
+            id: 1,
+            name: full_name.rsplit('/').next().unwrap().to_string(),
+            full_name: full_name.to_string(),
+            description: None,
+            stargazers_count: 100,
+            language: Some("Rust".to_string()),
+            license: None,
+            default_branch: "main".to_string(),
+            pushed_at: Some(pushed_at.to_string()),
+            download_url: None,
+        }
+    }
+
+    fn mocked_search() -> Vec<RepoInfo> {
+        vec![
+            repo("owner/alpha", "2026-01-01T00:00:00Z"),
+            repo("owner/beta", "2026-02-01T00:00:00Z"),
+        ]
+    }
+
+    #[test]
+    fn test_lock_verifies_against_same_results() {
+        let results: Vec<LockedSource> = mocked_search()
+            .iter()
+            .map(LockedSource::from_repo)
+            .collect();
+        let lock = HarvestLock::new("cli stars:>=10", 20, results.clone());
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("harvest.lock.json");
+        lock.save(&path).unwrap();
+        let loaded = HarvestLock::load(&path).unwrap();
+        assert_eq!(loaded.query, "cli stars:>=10");
+        assert_eq!(loaded.sources, results);
+        assert!(!loaded.sources[0].source_id.contains("alpha"));
+
+        let report = loaded.verify(&results);
+        assert!(report.is_clean());
+        assert_eq!(report.unchanged.len(), 2);
+    }
+
+    #[test]
+    fn test_lock_reports_changed_missing_and_added() {
+        let lock = HarvestLock::new(
+            "cli",
+            20,
+            mocked_search()
+                .iter()
+                .map(LockedSource::from_repo)
+                .collect(),
+        );
+
+        let now = [
+            repo("owner/alpha", "2026-03-01T00:00:00Z"),
+            repo("owner/gamma", "2026-03-02T00:00:00Z"),
+        ];
+        let current: Vec<LockedSource> = now.iter().map(LockedSource::from_repo).collect();
+        let report = lock.verify(&current);
+
+        assert!(!report.is_clean());
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(report.changed[0].source_id, anonymize_source("owner/alpha"));
+        assert!(report.changed[0].to_string().contains("2026-03-01"));
+        assert_eq!(report.missing, vec![anonymize_source("owner/beta")]);
+        assert_eq!(report.added, vec![anonymize_source("owner/gamma")]);
+    }
+}
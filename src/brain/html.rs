@@ -0,0 +1,155 @@
+//! Self-contained HTML rendering for BrainPack exports
+//!
+//! Everything taken from the pack goes through `escape`; chunk previews get
+//! a small keyword/string/comment highlighter so no external assets are needed.
+
+/// Page header with inline styles
+pub const HEADER: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>BrainPack Export</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; }
+section { border-top: 2px solid #d0d7de; margin-top: 2rem; }
+article { margin: 1.5rem 0; }
+.meta { color: #57606a; font-size: 0.9rem; }
+.signal { display: inline-block; background: #ddf4ff; border-radius: 1rem; padding: 0 0.5rem; margin-right: 0.25rem; font-size: 0.8rem; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; border-radius: 6px; }
+.kw { color: #cf222e; font-weight: 600; }
+.str { color: #0a3069; }
+.cm { color: #6e7781; font-style: italic; }
+</style>
+</head>
+<body>
+<h1>BrainPack Export</h1>
+"#;
+
+/// Page footer
+pub const FOOTER: &str = "</body>\n</html>\n";
+
+/// Keywords highlighted across the common harvested languages
+const KEYWORDS: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "else",
+    "enum",
+    "export",
+    "fn",
+    "for",
+    "func",
+    "function",
+    "if",
+    "impl",
+    "import",
+    "interface",
+    "let",
+    "match",
+    "mod",
+    "pub",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "switch",
+    "trait",
+    "type",
+    "use",
+    "var",
+    "while",
+];
+
+/// Escape text for use in HTML content or attribute values
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Highlight code as escaped HTML with `kw`, `str` and `cm` spans
+pub fn highlight(code: &str) -> String {
+    let mut out = String::with_capacity(code.len() * 2);
+    let chars: Vec<char> = code.chars().collect();
+    let mut i = 0;
+
+    let span = |out: &mut String, class: &str, text: &str| {
+        out.push_str(&format!(
+            "<span class=\"{}\">{}</span>",
+            class,
+            escape(text)
+        ));
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest_starts = |s: &str| chars[i..].iter().take(s.len()).copied().eq(s.chars());
+
+        if rest_starts("//") || rest_starts("# ") {
+            let end = chars[i..]
+                .iter()
+                .position(|&c| c == '\n')
+                .map_or(chars.len(), |p| i + p);
+            span(&mut out, "cm", &chars[i..end].iter().collect::<String>());
+            i = end;
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut end = i + 1;
+            while end < chars.len() && chars[end] != c && chars[end] != '\n' {
+                if chars[end] == '\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            let end = (end + 1).min(chars.len());
+            span(&mut out, "str", &chars[i..end].iter().collect::<String>());
+            i = end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                .map_or(chars.len(), |p| i + p);
+            let word: String = chars[i..end].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                span(&mut out, "kw", &word);
+            } else {
+                out.push_str(&escape(&word));
+            }
+            i = end;
+        } else {
+            out.push_str(&escape(&c.to_string()));
+            i += 1;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_escapes_everything() {
+        let html = highlight("fn main() { let s = \"<b>\"; } // <script>alert(1)</script>");
+        assert!(html.contains("<span class=\"kw\">fn</span>"));
+        assert!(html.contains("<span class=\"str\">&quot;&lt;b&gt;&quot;</span>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<b>"));
+    }
+}
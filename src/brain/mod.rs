@@ -5,11 +5,17 @@
 //! - Anonymized source IDs using SHA-256 hashing
 //! - User-driven search queries (no hardcoded repos)
 
+pub mod harvest_lock;
 pub mod harvester;
+pub mod html;
 pub mod map;
 pub mod pack;
 pub mod presets;
+pub mod query;
+pub mod search_cache;
+pub mod sources;
 pub mod storage;
+pub mod tokens;
 
 #[cfg(test)]
 mod core_jsonl_tests;
@@ -27,6 +33,34 @@ pub fn anonymize_source(repo_full_name: &str) -> String {
     format!("src_{}", hex::encode(&result[..12]))
 }
 
+/// Identity of a chunk's content for insert-time dedup: SHA-256 of
+/// `(source_id, path, start_line, text)`, independent of `chunk_id`
+pub fn chunk_content_hash(source_id: &str, path: &str, start_line: u32, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [source_id, path, &start_line.to_string(), text] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Lowercase, trim, and dedup tags, dropping empty entries; first occurrence wins
+pub fn normalize_tags<S: AsRef<str>>(tags: &[S]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.as_ref().trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Parse a comma-joined tags column, tolerating stray or trailing commas
+pub fn split_tags(tags: &str) -> Vec<String> {
+    normalize_tags(&tags.split(',').collect::<Vec<_>>())
+}
+
 /// Signal types detected in code
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -89,9 +123,11 @@ impl ContentType {
     }
 
     /// Parse content type from database string (case-insensitive)
-    /// Maps known type names directly, falls back to Other for unknown values
+    /// Maps known type names directly, falls back to Other for unknown values.
+    /// Surrounding whitespace and quotes left by older versions are ignored.
     pub fn from_db_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
+        let cleaned = s.trim_matches(|c: char| c.is_whitespace() || c == '"' || c == '\'');
+        match cleaned.to_lowercase().as_str() {
             "readme" => ContentType::Readme,
             "doc" => ContentType::Doc,
             "config" => ContentType::Config,
@@ -104,6 +140,20 @@ impl ContentType {
     }
 }
 
+impl std::fmt::Display for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Signal::CommandSurface => write!(f, "command_surface"),
+            Signal::StateMachine => write!(f, "state_machine"),
+            Signal::ContractLock => write!(f, "contract_lock"),
+            Signal::IterateLoop => write!(f, "iterate_loop"),
+            Signal::EvidenceAudit => write!(f, "evidence_audit"),
+            Signal::ProviderAdapter => write!(f, "provider_adapter"),
+            Signal::SecurityPattern => write!(f, "security_pattern"),
+        }
+    }
+}
+
 impl std::fmt::Display for ContentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -201,6 +251,40 @@ pub struct BrainStats {
     pub by_language: std::collections::HashMap<String, usize>,
     /// By license
     pub by_license: std::collections::HashMap<String, usize>,
+    /// Chunk count per source ID
+    pub by_source: std::collections::HashMap<String, usize>,
+    /// Chunk count per detected signal (a chunk counts once for each signal it has)
+    pub by_signal: std::collections::HashMap<String, usize>,
+}
+
+/// What the brain pack holds: sources, their content types and the files
+/// under each, with chunk counts
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BrainTopology {
+    pub sources: Vec<TopologySource>,
+}
+
+/// A harvested source in [`BrainTopology`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySource {
+    pub source_id: String,
+    pub chunks: usize,
+    pub content_types: Vec<TopologyContentType>,
+}
+
+/// The files of one content type within a source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyContentType {
+    pub content_type: String,
+    pub chunks: usize,
+    pub files: Vec<TopologyFile>,
+}
+
+/// A file and how many chunks it was split into
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyFile {
+    pub path: String,
+    pub chunks: usize,
 }
 
 /// Search result from brain
@@ -221,3 +305,19 @@ pub struct SearchResult {
     /// Tags
     pub tags: Vec<String>,
 }
+
+impl SearchResult {
+    /// Relevance mapped onto 0–1 (higher is more relevant)
+    pub fn normalized_score(&self) -> f64 {
+        let score = self.score.max(0.0);
+        score / (1.0 + score)
+    }
+}
+
+/// Keep only results whose normalized score reaches `min_score`
+pub fn filter_min_score(results: Vec<SearchResult>, min_score: f64) -> Vec<SearchResult> {
+    results
+        .into_iter()
+        .filter(|r| r.normalized_score() >= min_score)
+        .collect()
+}
@@ -3,8 +3,12 @@
 
 use anyhow::Result;
 use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::Path;
 
+use super::tokens;
+
 /// Output format for the packed context
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PackFormat {
@@ -33,20 +37,56 @@ impl std::str::FromStr for PackFormat {
     }
 }
 
-/// Pack the codebase into a string
-pub fn pack_codebase(root: &Path, format: PackFormat) -> Result<String> {
-    let mut output = String::new();
-    let mut total_tokens = 0;
+/// Token budget for a pack
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    /// Largest pack, in estimated tokens
+    pub max_tokens: usize,
+    /// Relative paths, most important first (see `RepoMap::ranked_files`);
+    /// files missing from it go last, in walk order
+    pub ranking: Vec<String>,
+}
 
-    // Header
-    match format {
-        PackFormat::Xml => {
-            output.push_str("<codebase>\n");
-        }
-        PackFormat::Markdown => {
-            output.push_str("# Codebase Context\n\n");
-        }
-    }
+/// A file in the pack manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackedFile {
+    pub path: String,
+    /// Estimated tokens of the file's entry in the pack
+    pub tokens: usize,
+}
+
+/// What went into a pack and what the budget left out
+#[derive(Debug, Clone, Serialize)]
+pub struct PackManifest {
+    pub format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+    /// Estimated tokens of the whole pack
+    pub total_tokens: usize,
+    pub included: Vec<PackedFile>,
+    pub excluded: Vec<PackedFile>,
+}
+
+/// A packed codebase
+#[derive(Debug, Clone)]
+pub struct Pack {
+    pub content: String,
+    pub manifest: PackManifest,
+}
+
+/// Pack the codebase into a string
+///
+/// With a budget, files are taken in ranking order until the next one
+/// would go over `max_tokens`; it and every file after it are excluded.
+pub fn pack_codebase(
+    root: &Path,
+    format: PackFormat,
+    budget: Option<&TokenBudget>,
+) -> Result<Pack> {
+    let (header, footer) = match format {
+        PackFormat::Xml => ("<codebase>\n", "</codebase>\n"),
+        PackFormat::Markdown => ("# Codebase Context\n\n", ""),
+    };
 
     // Walk files respecting gitignore
     let walker = WalkBuilder::new(root)
@@ -54,8 +94,11 @@ pub fn pack_codebase(root: &Path, format: PackFormat) -> Result<String> {
         .git_ignore(true)
         .ignore(false) // Don't use .ignore files by default, just .gitignore
         .add_custom_ignore_filename(".vibeanvilignore")
+        .sort_by_file_name(|a, b| a.cmp(b))
         .build();
 
+    // (relative path, rendered entry)
+    let mut entries: Vec<(String, String)> = Vec::new();
     for result in walker {
         match result {
             Ok(entry) => {
@@ -73,24 +116,9 @@ pub fn pack_codebase(root: &Path, format: PackFormat) -> Result<String> {
 
                     if let Ok(content) = std::fs::read_to_string(path) {
                         let relative_path = path.strip_prefix(root).unwrap_or(path);
-                        let path_str = relative_path.to_string_lossy();
-
-                        // Estimate tokens (char / 4)
-                        total_tokens += content.len() / 4;
-
-                        match format {
-                            PackFormat::Xml => {
-                                output.push_str(&format!("<file path=\"{}\">\n", path_str));
-                                output.push_str(&content);
-                                output.push_str("\n</file>\n");
-                            }
-                            PackFormat::Markdown => {
-                                output.push_str(&format!("## File: {}\n\n", path_str));
-                                output.push_str(&format!("```{}\n", detect_lang(path)));
-                                output.push_str(&content);
-                                output.push_str("\n```\n\n");
-                            }
-                        }
+                        let path_str = relative_path.to_string_lossy().replace('\\', "/");
+                        let rendered = render_file(format, path, &path_str, &content);
+                        entries.push((path_str, rendered));
                     }
                 }
             }
@@ -100,19 +128,73 @@ pub fn pack_codebase(root: &Path, format: PackFormat) -> Result<String> {
         }
     }
 
-    // Footer
-    if format == PackFormat::Xml {
-        output.push_str("</codebase>\n");
+    if let Some(budget) = budget {
+        // First position wins, as with a linear search
+        let mut ranks: HashMap<&str, usize> = HashMap::new();
+        for (i, path) in budget.ranking.iter().enumerate() {
+            ranks.entry(path.as_str()).or_insert(i);
+        }
+        entries.sort_by_key(|(path, _)| ranks.get(path.as_str()).copied().unwrap_or(usize::MAX));
     }
 
+    let mut output = String::from(header);
+    let mut total_tokens = tokens::estimate(header) + tokens::estimate(footer);
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for (path, rendered) in entries {
+        let file = PackedFile {
+            tokens: tokens::estimate(&rendered),
+            path,
+        };
+        let fits = budget.is_none_or(|b| total_tokens + file.tokens <= b.max_tokens);
+        if fits && excluded.is_empty() {
+            total_tokens += file.tokens;
+            output.push_str(&rendered);
+            included.push(file);
+        } else {
+            excluded.push(file);
+        }
+    }
+    output.push_str(footer);
+
     // Summary
     crate::cli::style::info(&format!(
         "Packed {} chars (~{} tokens)",
         output.len(),
         total_tokens
     ));
+    if let Some(budget) = budget {
+        crate::cli::style::info(&format!(
+            "Included {} files, excluded {} over the {}-token budget",
+            included.len(),
+            excluded.len(),
+            budget.max_tokens
+        ));
+    }
+
+    Ok(Pack {
+        content: output,
+        manifest: PackManifest {
+            format: format.to_string(),
+            max_tokens: budget.map(|b| b.max_tokens),
+            total_tokens,
+            included,
+            excluded,
+        },
+    })
+}
 
-    Ok(output)
+/// One file's entry in the pack
+fn render_file(format: PackFormat, path: &Path, path_str: &str, content: &str) -> String {
+    match format {
+        PackFormat::Xml => format!("<file path=\"{}\">\n{}\n</file>\n", path_str, content),
+        PackFormat::Markdown => format!(
+            "## File: {}\n\n```{}\n{}\n```\n\n",
+            path_str,
+            detect_lang(path),
+            content
+        ),
+    }
 }
 
 fn is_skippable(path: &Path) -> bool {
@@ -142,3 +224,65 @@ fn is_skippable(path: &Path) -> bool {
 fn detect_lang(path: &Path) -> &str {
     path.extension().and_then(|s| s.to_str()).unwrap_or("text")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::repomap::RepoMap;
+
+    #[tokio::test]
+    async fn test_token_budget_keeps_top_ranked_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let write = |name: &str, content: &str| {
+            std::fs::write(temp.path().join(name), content).unwrap();
+        };
+        // core.rs is imported by both others; leaf.rs by nobody and has no symbols
+        write("core.rs", "pub fn core() {}\n");
+        write("api.rs", "use crate::core;\npub fn api() {}\n");
+        write("cli.rs", "use crate::core;\npub fn cli() {}\n");
+        write("leaf.rs", &"// notes\n".repeat(50));
+
+        let ranking: Vec<String> = RepoMap::new(temp.path())
+            .await
+            .unwrap()
+            .ranked_files()
+            .into_iter()
+            .map(|f| f.path.clone())
+            .collect();
+        assert_eq!(ranking, vec!["core.rs", "api.rs", "cli.rs", "leaf.rs"]);
+
+        let full = pack_codebase(temp.path(), PackFormat::Xml, None).unwrap();
+        assert_eq!(full.manifest.included.len(), 4);
+        let tokens_of = |path: &str| {
+            full.manifest
+                .included
+                .iter()
+                .find(|f| f.path == path)
+                .unwrap()
+                .tokens
+        };
+
+        // Room for the wrapper, core.rs and api.rs, but not cli.rs
+        let max_tokens = tokens::estimate("<codebase>\n")
+            + tokens::estimate("</codebase>\n")
+            + tokens_of("core.rs")
+            + tokens_of("api.rs")
+            + tokens_of("cli.rs")
+            - 1;
+        let budget = TokenBudget {
+            max_tokens,
+            ranking,
+        };
+        let pack = pack_codebase(temp.path(), PackFormat::Xml, Some(&budget)).unwrap();
+
+        let paths = |files: &[PackedFile]| -> Vec<String> {
+            files.iter().map(|f| f.path.clone()).collect()
+        };
+        assert_eq!(paths(&pack.manifest.included), vec!["core.rs", "api.rs"]);
+        assert_eq!(paths(&pack.manifest.excluded), vec!["cli.rs", "leaf.rs"]);
+        assert!(pack.manifest.total_tokens <= max_tokens);
+        assert_eq!(pack.manifest.max_tokens, Some(max_tokens));
+        assert!(pack.content.contains("<file path=\"core.rs\">"));
+        assert!(!pack.content.contains("leaf.rs"));
+    }
+}
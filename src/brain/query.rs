@@ -0,0 +1,370 @@
+//! GitHub repository search query builder
+//!
+//! Validates search qualifiers and escapes free text before a query is sent,
+//! so mistakes surface as clear errors instead of opaque API responses.
+
+use anyhow::Result;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Maximum query length accepted by the GitHub search API
+pub const MAX_QUERY_LENGTH: usize = 256;
+
+/// Supported search qualifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Qualifier {
+    Language,
+    Topic,
+    Stars,
+    Pushed,
+    Fork,
+    Archived,
+}
+
+impl Qualifier {
+    /// All supported qualifiers
+    pub fn all() -> &'static [Qualifier] {
+        &[
+            Qualifier::Language,
+            Qualifier::Topic,
+            Qualifier::Stars,
+            Qualifier::Pushed,
+            Qualifier::Fork,
+            Qualifier::Archived,
+        ]
+    }
+
+    /// Qualifier keyword as used in the query
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Qualifier::Language => "language",
+            Qualifier::Topic => "topic",
+            Qualifier::Stars => "stars",
+            Qualifier::Pushed => "pushed",
+            Qualifier::Fork => "fork",
+            Qualifier::Archived => "archived",
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|q| q.keyword().eq_ignore_ascii_case(keyword))
+    }
+
+    /// Validate (and normalize) a qualifier value
+    fn validate(&self, value: &str) -> Result<String> {
+        let value = value.trim();
+        if value.is_empty() {
+            anyhow::bail!("Empty value for '{}:' qualifier", self.keyword());
+        }
+
+        match self {
+            Qualifier::Language => {
+                if !LANGUAGE_RE.is_match(value) {
+                    anyhow::bail!("Invalid language '{}'", value);
+                }
+                Ok(value.to_string())
+            }
+            Qualifier::Topic => {
+                let topic = value.to_lowercase();
+                if !TOPIC_RE.is_match(&topic) {
+                    anyhow::bail!(
+                        "Invalid topic '{}': use lowercase letters, numbers and hyphens (max 50 chars)",
+                        value
+                    );
+                }
+                Ok(topic)
+            }
+            Qualifier::Stars => {
+                validate_range(value, &NUMBER_RE, |a, b| {
+                    a.parse::<u64>().unwrap_or(0) <= b.parse::<u64>().unwrap_or(0)
+                })
+                .map_err(|e| anyhow::anyhow!("Invalid stars range '{}': {}", value, e))?;
+                Ok(value.to_string())
+            }
+            Qualifier::Pushed => {
+                validate_range(value, &DATE_RE, |a, b| a <= b)
+                    .map_err(|e| anyhow::anyhow!("Invalid pushed date '{}': {}", value, e))?;
+                Ok(value.to_string())
+            }
+            Qualifier::Fork => match value.to_lowercase().as_str() {
+                v @ ("true" | "false" | "only") => Ok(v.to_string()),
+                _ => anyhow::bail!("Invalid fork value '{}': use true, false or only", value),
+            },
+            Qualifier::Archived => match value.to_lowercase().as_str() {
+                v @ ("true" | "false") => Ok(v.to_string()),
+                _ => anyhow::bail!("Invalid archived value '{}': use true or false", value),
+            },
+        }
+    }
+}
+
+static LANGUAGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9+#.\-_]{1,40}$").unwrap());
+static TOPIC_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z0-9][a-z0-9\-]{0,49}$").unwrap());
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d+$").unwrap());
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+static KEYWORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Za-z_\-]+$").unwrap());
+
+/// Validate `N`, `>N`, `>=N`, `<N`, `<=N`, `N..M`, `N..*` or `*..M`
+fn validate_range(value: &str, item: &Regex, ordered: impl Fn(&str, &str) -> bool) -> Result<()> {
+    if let Some((low, high)) = value.split_once("..") {
+        let low_ok = low == "*" || item.is_match(low);
+        let high_ok = high == "*" || item.is_match(high);
+        if !low_ok || !high_ok || (low == "*" && high == "*") {
+            anyhow::bail!("expected a range like 10..100, 10..* or *..100");
+        }
+        if low != "*" && high != "*" && !ordered(low, high) {
+            anyhow::bail!("lower bound is greater than upper bound");
+        }
+        return Ok(());
+    }
+
+    let bare = value
+        .strip_prefix(">=")
+        .or_else(|| value.strip_prefix("<="))
+        .or_else(|| value.strip_prefix('>'))
+        .or_else(|| value.strip_prefix('<'))
+        .unwrap_or(value);
+
+    if !item.is_match(bare) {
+        anyhow::bail!("expected a value like 100, >=100 or 10..100");
+    }
+    Ok(())
+}
+
+/// Escape a free-text term, quoting it if it contains anything but plain word characters
+fn escape_term(term: &str) -> Option<String> {
+    let cleaned: String = term.chars().filter(|c| *c != '"' && *c != '\\').collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        return None;
+    }
+
+    let plain = cleaned
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '+' | '#'));
+    if plain {
+        Some(cleaned.to_string())
+    } else {
+        Some(format!("\"{}\"", cleaned))
+    }
+}
+
+/// Split user input into tokens, keeping "quoted phrases" together
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                if in_quotes && !current.is_empty() {
+                    tokens.push(format!("\"{}\"", current));
+                    current.clear();
+                }
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Builder for validated GitHub repository search queries
+#[derive(Debug, Default, Clone)]
+pub struct QueryBuilder {
+    terms: Vec<String>,
+    qualifiers: Vec<(Qualifier, String)>,
+}
+
+impl QueryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add user-supplied query text; embedded `key:value` qualifiers are validated
+    pub fn text(mut self, input: &str) -> Result<Self> {
+        for token in tokenize(input) {
+            if let Some(phrase) = token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                if let Some(term) = escape_term(phrase) {
+                    self.terms.push(term);
+                }
+                continue;
+            }
+
+            match token.split_once(':') {
+                Some((keyword, value)) if KEYWORD_RE.is_match(keyword) => {
+                    let qualifier = Qualifier::from_keyword(keyword).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unsupported search qualifier '{}:'. Supported: {}",
+                            keyword,
+                            Qualifier::all()
+                                .iter()
+                                .map(|q| format!("{}:", q.keyword()))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })?;
+                    self = self.qualifier(qualifier, value)?;
+                }
+                _ => {
+                    if let Some(term) = escape_term(&token) {
+                        self.terms.push(term);
+                    }
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Add a validated qualifier
+    pub fn qualifier(mut self, qualifier: Qualifier, value: &str) -> Result<Self> {
+        let value = qualifier.validate(value)?;
+        self.qualifiers.push((qualifier, value));
+        Ok(self)
+    }
+
+    pub fn language(self, language: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Language, language)
+    }
+
+    pub fn topic(self, topic: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Topic, topic)
+    }
+
+    pub fn stars(self, range: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Stars, range)
+    }
+
+    pub fn pushed(self, range: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Pushed, range)
+    }
+
+    pub fn fork(self, value: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Fork, value)
+    }
+
+    pub fn archived(self, value: &str) -> Result<Self> {
+        self.qualifier(Qualifier::Archived, value)
+    }
+
+    /// Whether the query already sets `qualifier`
+    pub fn has_qualifier(&self, qualifier: Qualifier) -> bool {
+        self.qualifiers.iter().any(|(q, _)| *q == qualifier)
+    }
+
+    /// Build the final query string
+    pub fn build(&self) -> Result<String> {
+        let mut parts = self.terms.clone();
+        parts.extend(
+            self.qualifiers
+                .iter()
+                .map(|(q, v)| format!("{}:{}", q.keyword(), v)),
+        );
+
+        if parts.is_empty() {
+            anyhow::bail!("Search query is empty");
+        }
+
+        let query = parts.join(" ");
+        if query.len() > MAX_QUERY_LENGTH {
+            anyhow::bail!(
+                "Search query is {} characters; GitHub accepts at most {}. Use fewer queries or topics.",
+                query.len(),
+                MAX_QUERY_LENGTH
+            );
+        }
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_valid_query() {
+        let query = QueryBuilder::new()
+            .text("cli \"state machine\" stars:>=50")
+            .unwrap()
+            .topic("Rust")
+            .unwrap()
+            .language("rust")
+            .unwrap()
+            .pushed(">2024-01-01")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            query,
+            "cli \"state machine\" stars:>=50 topic:rust language:rust pushed:>2024-01-01"
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_stars_range() {
+        let err = QueryBuilder::new().stars("100..10").unwrap_err();
+        assert!(err.to_string().contains("Invalid stars range"));
+
+        assert!(QueryBuilder::new().stars(">=abc").is_err());
+        assert!(QueryBuilder::new().text("stars:10...20").is_err());
+        assert!(QueryBuilder::new().stars("10..*").is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_qualifier() {
+        let err = QueryBuilder::new().text("user:octocat cli").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported search qualifier 'user:'"));
+    }
+
+    #[test]
+    fn test_fork_and_archived_values() {
+        let query = QueryBuilder::new()
+            .text("cli fork:TRUE")
+            .unwrap()
+            .archived("false")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(query, "cli fork:true archived:false");
+        assert!(QueryBuilder::new().fork("maybe").is_err());
+        assert!(QueryBuilder::new().archived("only").is_err());
+    }
+
+    #[test]
+    fn test_escapes_free_text() {
+        let query = QueryBuilder::new()
+            .text("foo\\bar a&b")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(query, "foobar \"a&b\"");
+    }
+
+    #[test]
+    fn test_caps_query_length() {
+        let long = "word ".repeat(60);
+        let err = QueryBuilder::new()
+            .text(&long)
+            .unwrap()
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("at most 256"));
+    }
+}
@@ -0,0 +1,128 @@
+//! In-memory LRU cache for brain search results
+//!
+//! Interactive flows repeat identical searches; caching them avoids reopening
+//! SQLite for every lookup. Any write to the store must call `clear()`.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::storage::SearchFilters;
+use super::SearchResult;
+
+/// Default number of cached queries
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Cache key: the query plus every parameter that affects the results
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchKey {
+    pub query: String,
+    pub limit: usize,
+    pub filters: SearchFilters,
+}
+
+/// Small least-recently-used cache of search results
+#[derive(Debug)]
+pub struct SearchCache {
+    capacity: usize,
+    entries: HashMap<SearchKey, Vec<SearchResult>>,
+    /// Keys ordered from least to most recently used
+    order: VecDeque<SearchKey>,
+    hits: usize,
+    misses: usize,
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl SearchCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Look up cached results, marking the entry as recently used
+    pub fn get(&mut self, key: &SearchKey) -> Option<Vec<SearchResult>> {
+        match self.entries.get(key) {
+            Some(results) => {
+                let results = results.clone();
+                self.touch(key);
+                self.hits += 1;
+                Some(results)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store results, evicting the least recently used entry when full
+    pub fn put(&mut self, key: SearchKey, results: Vec<SearchResult>) {
+        if self.entries.insert(key.clone(), results).is_some() {
+            self.touch(&key);
+            return;
+        }
+
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    /// Drop all cached results
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Cache (hits, misses) since creation
+    pub fn counters(&self) -> (usize, usize) {
+        (self.hits, self.misses)
+    }
+
+    fn touch(&mut self, key: &SearchKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(query: &str) -> SearchKey {
+        SearchKey {
+            query: query.to_string(),
+            limit: 10,
+            filters: SearchFilters::default(),
+        }
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = SearchCache::new(2);
+        cache.put(key("a"), vec![]);
+        cache.put(key("b"), vec![]);
+
+        // Touch "a" so "b" becomes the eviction candidate
+        assert!(cache.get(&key("a")).is_some());
+        cache.put(key("c"), vec![]);
+
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!(cache.counters(), (3, 1));
+    }
+}
@@ -0,0 +1,1302 @@
+//! Code hosts the harvester searches and downloads from
+//!
+//! Every host maps its repositories onto [`RepoInfo`], so caching, parsing
+//! and anonymization stay the same whichever host a source came from.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::Instrument;
+
+use super::harvester::{
+    retry_with_backoff, HarvestConfig, RepoInfo, RepoSort, SearchResponse, SortOrder,
+};
+
+/// Where to harvest from; tokens default to `GITHUB_TOKEN`, `GITLAB_TOKEN`
+/// and `BITBUCKET_TOKEN`
+#[derive(Clone)]
+pub enum HarvestSource {
+    GitHub {
+        token: Option<String>,
+    },
+    /// `host` is a bare name (`gitlab.mycompany.com`, served over HTTPS) or a full base URL
+    GitLab {
+        host: String,
+        token: Option<String>,
+    },
+    /// Repositories of one Bitbucket Cloud workspace
+    Bitbucket {
+        workspace: String,
+        token: Option<String>,
+    },
+}
+
+/// Default GitLab host
+pub const GITLAB_COM: &str = "gitlab.com";
+
+impl Default for HarvestSource {
+    fn default() -> Self {
+        HarvestSource::GitHub {
+            token: std::env::var("GITHUB_TOKEN").ok(),
+        }
+    }
+}
+
+// Tokens stay out of debug output
+impl std::fmt::Debug for HarvestSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let token = |t: &Option<String>| t.as_ref().map(|_| "***");
+        match self {
+            HarvestSource::GitHub { token: t } => {
+                f.debug_struct("GitHub").field("token", &token(t)).finish()
+            }
+            HarvestSource::GitLab { host, token: t } => f
+                .debug_struct("GitLab")
+                .field("host", host)
+                .field("token", &token(t))
+                .finish(),
+            HarvestSource::Bitbucket {
+                workspace,
+                token: t,
+            } => f
+                .debug_struct("Bitbucket")
+                .field("workspace", workspace)
+                .field("token", &token(t))
+                .finish(),
+        }
+    }
+}
+
+impl HarvestSource {
+    /// Host name shown while searching, e.g. `GitLab (gitlab.mycompany.com)`
+    pub fn label(&self) -> String {
+        match self {
+            HarvestSource::GitHub { .. } => "GitHub".to_string(),
+            HarvestSource::GitLab { host, .. } => format!("GitLab ({})", host),
+            HarvestSource::Bitbucket { workspace, .. } => format!("Bitbucket ({})", workspace),
+        }
+    }
+
+    pub fn has_token(&self) -> bool {
+        match self {
+            HarvestSource::GitHub { token }
+            | HarvestSource::GitLab { token, .. }
+            | HarvestSource::Bitbucket { token, .. } => token.is_some(),
+        }
+    }
+
+    /// Client for this host, retrying each request up to `max_retries` times
+    pub fn client(&self, max_retries: u8) -> Result<Box<dyn SourceClient>> {
+        Ok(match self {
+            HarvestSource::GitHub { token } => {
+                Box::new(GitHubClient::new(token.as_deref(), max_retries)?)
+            }
+            HarvestSource::GitLab { host, token } => {
+                Box::new(GitLabClient::new(host, token.as_deref(), max_retries)?)
+            }
+            HarvestSource::Bitbucket { workspace, token } => Box::new(BitbucketClient::new(
+                workspace,
+                token.as_deref(),
+                max_retries,
+            )?),
+        })
+    }
+}
+
+/// Search and download access to one code host
+#[async_trait]
+pub trait SourceClient: Send + Sync {
+    /// Repositories matching the harvest config, best first, at most `max_repos`
+    ///
+    /// `query` is the GitHub search query built from the config; other hosts
+    /// translate the config into their own parameters instead.
+    async fn search_repos(&self, query: &str, config: &HarvestConfig) -> Result<Vec<RepoInfo>>;
+
+    /// The repository at `git_ref` as a `.tar.gz`; `None` when the host
+    /// refuses the download
+    ///
+    /// `git_ref` is the SHA `head_commit` resolved, or the default branch
+    /// when it couldn't. Hosts without `head_commit` only ever get the
+    /// default branch, which their `download_url` already names.
+    async fn download_tarball(&self, repo: &RepoInfo, git_ref: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Head commit SHA of the default branch; `None` when the host can't tell
+    async fn head_commit(&self, _repo: &RepoInfo) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Files changed between two commits; `None` when the host can't list
+    /// them, e.g. because the range spans too many commits
+    async fn compare(
+        &self,
+        _repo: &RepoInfo,
+        _base: &str,
+        _head: &str,
+    ) -> Result<Option<Vec<ChangedFile>>> {
+        Ok(None)
+    }
+
+    /// One file's raw content at `commit`; `None` when the host refuses it
+    async fn download_file(
+        &self,
+        _repo: &RepoInfo,
+        _path: &str,
+        _commit: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// A file touched between two commits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    /// Path within the repository
+    pub path: String,
+    /// Deleted, or renamed away, at the head commit
+    pub removed: bool,
+}
+
+/// HTTP client that retries rate-limited and failed requests
+struct HttpClient {
+    inner: Client,
+    max_retries: u8,
+}
+
+impl HttpClient {
+    fn new(headers: reqwest::header::HeaderMap, max_retries: u8) -> Result<Self> {
+        Ok(Self {
+            inner: Client::builder()
+                .user_agent("vibeanvil/0.1.0")
+                .default_headers(headers)
+                .build()?,
+            max_retries,
+        })
+    }
+
+    /// `GET url` through [`retry_with_backoff`]
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        retry_with_backoff(|| self.inner.get(url).send(), self.max_retries).await
+    }
+
+    /// Like `get`, replacing the client's `Accept` header
+    async fn get_accepting(&self, url: &str, accept: &str) -> Result<reqwest::Response> {
+        retry_with_backoff(
+            || {
+                self.inner
+                    .get(url)
+                    .header(reqwest::header::ACCEPT, accept)
+                    .send()
+            },
+            self.max_retries,
+        )
+        .await
+    }
+}
+
+/// Fetch `url`, returning `None` (with a warning) on a non-success status
+async fn fetch_archive(client: &HttpClient, url: &str) -> Result<Option<Vec<u8>>> {
+    let response = client
+        .get(url)
+        .instrument(tracing::info_span!("download"))
+        .await?;
+    read_archive(response).await
+}
+
+/// Body of an archive download; `None` (with a warning) on a non-success status
+async fn read_archive(response: reqwest::Response) -> Result<Option<Vec<u8>>> {
+    if !response.status().is_success() {
+        tracing::warn!("Failed to download tarball: {}", response.status());
+        return Ok(None);
+    }
+    Ok(Some(
+        response
+            .bytes()
+            .instrument(tracing::info_span!("download"))
+            .await?
+            .to_vec(),
+    ))
+}
+
+/// Read a JSON body, turning an error status into a network error
+async fn json_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    host: &str,
+) -> Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(crate::error::network(format!(
+            "{} API error {}: {}",
+            host, status, body
+        )));
+    }
+    response
+        .json()
+        .await
+        .with_context(|| format!("Failed to parse {} search response", host))
+}
+
+/// Cutoff date for `updated_within_days`
+fn updated_after(config: &HarvestConfig) -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() - chrono::Duration::days(config.updated_within_days as i64)
+}
+
+/// GitHub search returns at most this many results per query, across all pages
+pub const GITHUB_SEARCH_CAP: usize = 1000;
+
+/// Pause between search pages, to stay clear of GitHub's secondary rate limits
+const PAGE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// api.github.com
+pub struct GitHubClient {
+    client: HttpClient,
+    api_base: String,
+    page_delay: std::time::Duration,
+}
+
+/// A commit from `GET /repos/{owner}/{repo}/commits/{ref}`
+#[derive(Debug, Deserialize)]
+struct GitHubCommit {
+    sha: String,
+}
+
+/// `GET /repos/{owner}/{repo}`, for the current default branch
+#[derive(Debug, Deserialize)]
+struct GitHubRepoBranch {
+    default_branch: String,
+}
+
+/// GitHub lists at most this many files in a comparison
+const COMPARE_FILE_CAP: usize = 300;
+
+/// `GET /repos/{owner}/{repo}/compare/{base}...{head}`
+#[derive(Debug, Deserialize)]
+struct GitHubComparison {
+    #[serde(default)]
+    files: Vec<GitHubComparedFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubComparedFile {
+    filename: String,
+    /// `added`, `removed`, `modified`, `renamed`, ...
+    status: String,
+    previous_filename: Option<String>,
+}
+
+impl GitHubClient {
+    pub fn new(token: Option<&str>, max_retries: u8) -> Result<Self> {
+        Self::with_base(token, max_retries, "https://api.github.com")
+    }
+
+    fn with_base(token: Option<&str>, max_retries: u8, api_base: &str) -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("Accept", "application/vnd.github+json".parse()?);
+        headers.insert("X-GitHub-Api-Version", "2022-11-28".parse()?);
+        match token {
+            Some(token) => {
+                headers.insert("Authorization", format!("Bearer {}", token).parse()?);
+            }
+            None => tracing::warn!("GITHUB_TOKEN not set. API rate limits will be restricted."),
+        }
+        Ok(Self {
+            client: HttpClient::new(headers, max_retries)?,
+            api_base: api_base.to_string(),
+            page_delay: PAGE_DELAY,
+        })
+    }
+
+    fn tarball_url(&self, repo: &RepoInfo, git_ref: &str) -> String {
+        format!(
+            "{}/repos/{}/tarball/{}",
+            self.api_base,
+            repo.full_name,
+            urlencoding::encode(git_ref)
+        )
+    }
+
+    /// The repo's default branch as GitHub reports it now, which differs
+    /// from the search result's after a rename; `None` when unchanged
+    async fn renamed_default_branch(&self, repo: &RepoInfo) -> Result<Option<String>> {
+        let url = format!("{}/repos/{}", self.api_base, repo.full_name);
+        let response = self.client.get(&url).await?;
+        let current: GitHubRepoBranch = json_response(response, "GitHub").await?;
+        Ok(Some(current.default_branch).filter(|branch| *branch != repo.default_branch))
+    }
+}
+
+#[async_trait]
+impl SourceClient for GitHubClient {
+    /// Pages through the results until `max_repos` are found, the results run
+    /// out, or GitHub's 1000-result cap is reached
+    async fn search_repos(&self, query: &str, config: &HarvestConfig) -> Result<Vec<RepoInfo>> {
+        tracing::info!("Searching GitHub: {}", query);
+
+        let wanted = config.max_repos.min(GITHUB_SEARCH_CAP);
+        let mut repos = vec![];
+        let mut total_count = 0;
+        for page in 1..=GITHUB_SEARCH_CAP.div_ceil(config.per_page()) {
+            if page > 1 {
+                tokio::time::sleep(self.page_delay).await;
+            }
+            let url = config.search_page_url(&self.api_base, query, page);
+            let response = self
+                .client
+                .get(&url)
+                .instrument(tracing::info_span!("search", page))
+                .await?;
+            let search_result: SearchResponse = json_response(response, "GitHub").await?;
+
+            total_count = search_result.total_count as usize;
+            let last_page = search_result.items.len() < config.per_page();
+            repos.extend(search_result.items);
+            if last_page || repos.len() >= wanted || repos.len() >= total_count {
+                break;
+            }
+        }
+
+        if config.max_repos > GITHUB_SEARCH_CAP && total_count > GITHUB_SEARCH_CAP {
+            tracing::warn!(
+                "GitHub search returns at most {} results; {} of {} matching repos are reachable",
+                GITHUB_SEARCH_CAP,
+                repos.len(),
+                total_count
+            );
+        }
+
+        repos.truncate(config.max_repos);
+        Ok(repos)
+    }
+
+    /// Retries once with the current default branch when downloading by the
+    /// branch name from the search results 404s; a resolved SHA can't go
+    /// stale that way
+    async fn download_tarball(&self, repo: &RepoInfo, git_ref: &str) -> Result<Option<Vec<u8>>> {
+        let url = self.tarball_url(repo, git_ref);
+        let response = self
+            .client
+            .get(&url)
+            .instrument(tracing::info_span!("download"))
+            .await?;
+        if response.status() != reqwest::StatusCode::NOT_FOUND || git_ref != repo.default_branch {
+            return read_archive(response).await;
+        }
+
+        let branch = match self.renamed_default_branch(repo).await {
+            Ok(Some(branch)) => branch,
+            Ok(None) => return read_archive(response).await,
+            Err(e) => {
+                tracing::warn!(
+                    "Could not refresh the default branch of {}: {:#}",
+                    repo.full_name,
+                    e
+                );
+                return read_archive(response).await;
+            }
+        };
+        tracing::info!(
+            "Default branch of {} is now '{}' (was '{}'); retrying the download",
+            repo.full_name,
+            branch,
+            repo.default_branch
+        );
+        fetch_archive(&self.client, &self.tarball_url(repo, &branch)).await
+    }
+
+    async fn head_commit(&self, repo: &RepoInfo) -> Result<Option<String>> {
+        let url = format!(
+            "{}/repos/{}/commits/{}",
+            self.api_base,
+            repo.full_name,
+            urlencoding::encode(&repo.default_branch)
+        );
+        let response = self.client.get(&url).await?;
+        let commit: GitHubCommit = json_response(response, "GitHub").await?;
+        Ok(Some(commit.sha))
+    }
+
+    async fn compare(
+        &self,
+        repo: &RepoInfo,
+        base: &str,
+        head: &str,
+    ) -> Result<Option<Vec<ChangedFile>>> {
+        let url = format!(
+            "{}/repos/{}/compare/{}...{}",
+            self.api_base,
+            repo.full_name,
+            urlencoding::encode(base),
+            urlencoding::encode(head)
+        );
+        let response = self.client.get(&url).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            // Too large a range, or a base commit that was force-pushed away
+            if status == reqwest::StatusCode::NOT_FOUND
+                || body.to_lowercase().contains("too many commits")
+            {
+                tracing::info!("No usable comparison for {}: {}", repo.full_name, status);
+                return Ok(None);
+            }
+            return Err(crate::error::network(format!(
+                "GitHub API error {}: {}",
+                status, body
+            )));
+        }
+        let comparison: GitHubComparison = response
+            .json()
+            .await
+            .context("Failed to parse GitHub compare response")?;
+        // A truncated file list would miss changes
+        if comparison.files.len() >= COMPARE_FILE_CAP {
+            return Ok(None);
+        }
+
+        let mut changes = vec![];
+        for file in comparison.files {
+            if let Some(previous) = file.previous_filename {
+                changes.push(ChangedFile {
+                    path: previous,
+                    removed: true,
+                });
+            }
+            changes.push(ChangedFile {
+                removed: file.status == "removed",
+                path: file.filename,
+            });
+        }
+        Ok(Some(changes))
+    }
+
+    async fn download_file(
+        &self,
+        repo: &RepoInfo,
+        path: &str,
+        commit: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        let path: Vec<_> = path.split('/').map(urlencoding::encode).collect();
+        let url = format!(
+            "{}/repos/{}/contents/{}?ref={}",
+            self.api_base,
+            repo.full_name,
+            path.join("/"),
+            urlencoding::encode(commit)
+        );
+        let response = self
+            .client
+            .get_accepting(&url, "application/vnd.github.raw+json")
+            .instrument(tracing::info_span!("download"))
+            .await?;
+        if !response.status().is_success() {
+            tracing::warn!("Failed to download {}: {}", url, response.status());
+            return Ok(None);
+        }
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+}
+
+/// GitLab REST API v4 (gitlab.com or self-managed)
+pub struct GitLabClient {
+    client: HttpClient,
+    /// Host shown in source names, e.g. `gitlab.mycompany.com`
+    host: String,
+    /// `https://<host>/api/v4`
+    api_base: String,
+}
+
+/// A project from `GET /projects`
+#[derive(Debug, Deserialize)]
+struct GitLabProject {
+    id: u64,
+    name: String,
+    path_with_namespace: String,
+    description: Option<String>,
+    #[serde(default)]
+    star_count: u32,
+    default_branch: Option<String>,
+    last_activity_at: Option<String>,
+    #[serde(default)]
+    forked_from_project: Option<serde_json::Value>,
+}
+
+impl GitLabClient {
+    pub fn new(host: &str, token: Option<&str>, max_retries: u8) -> Result<Self> {
+        let base = if host.starts_with("http://") || host.starts_with("https://") {
+            host.trim_end_matches('/').to_string()
+        } else {
+            format!("https://{}", host.trim_end_matches('/'))
+        };
+        let host_name = base
+            .split_once("://")
+            .map_or(base.as_str(), |(_, rest)| rest)
+            .to_string();
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        match token {
+            Some(token) => {
+                headers.insert("PRIVATE-TOKEN", token.parse()?);
+            }
+            None => tracing::warn!("GITLAB_TOKEN not set. Only public projects are visible."),
+        }
+        Ok(Self {
+            client: HttpClient::new(headers, max_retries)?,
+            host: host_name,
+            api_base: format!("{}/api/v4", base),
+        })
+    }
+
+    /// `GET /projects` URL for the config; stars and forks are filtered afterwards
+    fn search_url(&self, config: &HarvestConfig) -> String {
+        let mut params = vec![
+            ("archived".to_string(), config.include_archived.to_string()),
+            (
+                "last_activity_after".to_string(),
+                updated_after(config).to_rfc3339(),
+            ),
+            // Extra results make up for the projects filtered out afterwards
+            (
+                "per_page".to_string(),
+                (config.max_repos * 2).clamp(1, 100).to_string(),
+            ),
+        ];
+        if !config.queries.is_empty() {
+            params.push(("search".to_string(), config.queries.join(" ")));
+        }
+        if !config.topics.is_empty() {
+            params.push(("topic".to_string(), config.topics.join(",")));
+        }
+        if let Some(language) = &config.language {
+            params.push(("with_programming_language".to_string(), language.clone()));
+        }
+        let order_by = match config.sort {
+            RepoSort::Stars => Some("star_count"),
+            RepoSort::Updated => Some("last_activity_at"),
+            RepoSort::Relevance => None,
+        };
+        if let Some(order_by) = order_by {
+            params.push(("order_by".to_string(), order_by.to_string()));
+            params.push(("sort".to_string(), config.order.param().to_string()));
+        }
+
+        let query: Vec<String> = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+            .collect();
+        format!("{}/projects?{}", self.api_base, query.join("&"))
+    }
+}
+
+#[async_trait]
+impl SourceClient for GitLabClient {
+    async fn search_repos(&self, _query: &str, config: &HarvestConfig) -> Result<Vec<RepoInfo>> {
+        let url = self.search_url(config);
+        tracing::info!("Searching GitLab: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .instrument(tracing::info_span!("search"))
+            .await?;
+        let projects: Vec<GitLabProject> = json_response(response, "GitLab").await?;
+
+        Ok(projects
+            .into_iter()
+            .filter(|p| p.star_count >= config.min_stars)
+            .filter(|p| config.include_forks || p.forked_from_project.is_none())
+            // Empty projects have no branch to download
+            .filter_map(|p| {
+                let default_branch = p.default_branch?;
+                Some(RepoInfo {
+                    id: p.id,
+                    name: p.name,
+                    // Host-qualified so source IDs never collide with other hosts
+                    full_name: format!("{}/{}", self.host, p.path_with_namespace),
+                    description: p.description,
+                    stargazers_count: p.star_count,
+                    language: config.language.clone(),
+                    license: None,
+                    download_url: Some(format!(
+                        "{}/projects/{}/repository/archive.tar.gz?sha={}",
+                        self.api_base,
+                        p.id,
+                        urlencoding::encode(&default_branch)
+                    )),
+                    default_branch,
+                    pushed_at: p.last_activity_at,
+                })
+            })
+            .take(config.max_repos)
+            .collect())
+    }
+
+    async fn download_tarball(&self, repo: &RepoInfo, _git_ref: &str) -> Result<Option<Vec<u8>>> {
+        let url = repo
+            .download_url
+            .as_deref()
+            .context("GitLab project has no archive URL")?;
+        fetch_archive(&self.client, url).await
+    }
+}
+
+/// Bitbucket Cloud API 2.0, one workspace at a time
+pub struct BitbucketClient {
+    client: HttpClient,
+    workspace: String,
+    api_base: String,
+    web_base: String,
+}
+
+/// `GET /repositories/{workspace}` page
+#[derive(Debug, Deserialize)]
+struct BitbucketPage {
+    values: Vec<BitbucketRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketRepo {
+    name: String,
+    full_name: String,
+    description: Option<String>,
+    language: Option<String>,
+    mainbranch: Option<BitbucketBranch>,
+    updated_on: Option<String>,
+    #[serde(default)]
+    parent: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketBranch {
+    name: String,
+}
+
+impl BitbucketClient {
+    pub fn new(workspace: &str, token: Option<&str>, max_retries: u8) -> Result<Self> {
+        Self::with_base(
+            workspace,
+            token,
+            max_retries,
+            "https://api.bitbucket.org/2.0",
+            "https://bitbucket.org",
+        )
+    }
+
+    fn with_base(
+        workspace: &str,
+        token: Option<&str>,
+        max_retries: u8,
+        api_base: &str,
+        web_base: &str,
+    ) -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        match token {
+            Some(token) => {
+                headers.insert("Authorization", format!("Bearer {}", token).parse()?);
+            }
+            None => {
+                tracing::warn!("BITBUCKET_TOKEN not set. Only public repositories are visible.")
+            }
+        }
+        Ok(Self {
+            client: HttpClient::new(headers, max_retries)?,
+            workspace: workspace.to_string(),
+            api_base: api_base.to_string(),
+            web_base: web_base.to_string(),
+        })
+    }
+
+    /// Repository list URL; Bitbucket has no stars, so `min_stars` is ignored
+    fn search_url(&self, config: &HarvestConfig) -> String {
+        let mut filters = vec![format!(
+            "updated_on > {}",
+            updated_after(config).format("%Y-%m-%dT%H:%M:%S")
+        )];
+        for query in &config.queries {
+            filters.push(format!("name ~ \"{}\"", query.replace('"', "")));
+        }
+        if let Some(language) = &config.language {
+            filters.push(format!("language = \"{}\"", language.to_lowercase()));
+        }
+        let sort = match (config.sort, config.order) {
+            (RepoSort::Relevance, _) => None,
+            // Without stars, most recently updated is the closest ranking
+            (_, SortOrder::Desc) => Some("-updated_on"),
+            (_, SortOrder::Asc) => Some("updated_on"),
+        };
+
+        let mut url = format!(
+            "{}/repositories/{}?q={}&pagelen={}",
+            self.api_base,
+            urlencoding::encode(&self.workspace),
+            urlencoding::encode(&filters.join(" AND ")),
+            config.max_repos.clamp(1, 100)
+        );
+        if let Some(sort) = sort {
+            url.push_str(&format!("&sort={}", sort));
+        }
+        url
+    }
+}
+
+#[async_trait]
+impl SourceClient for BitbucketClient {
+    async fn search_repos(&self, _query: &str, config: &HarvestConfig) -> Result<Vec<RepoInfo>> {
+        let url = self.search_url(config);
+        tracing::info!("Searching Bitbucket: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .instrument(tracing::info_span!("search"))
+            .await?;
+        let page: BitbucketPage = json_response(response, "Bitbucket").await?;
+
+        Ok(page
+            .values
+            .into_iter()
+            .filter(|r| config.include_forks || r.parent.is_none())
+            .filter_map(|r| {
+                let default_branch = r.mainbranch?.name;
+                Some(RepoInfo {
+                    id: 0,
+                    name: r.name,
+                    full_name: format!("bitbucket.org/{}", r.full_name),
+                    description: r.description,
+                    stargazers_count: 0,
+                    language: r.language.filter(|l| !l.is_empty()),
+                    license: None,
+                    download_url: Some(format!(
+                        "{}/{}/get/{}.tar.gz",
+                        self.web_base,
+                        r.full_name,
+                        urlencoding::encode(&default_branch)
+                    )),
+                    default_branch,
+                    pushed_at: r.updated_on,
+                })
+            })
+            .take(config.max_repos)
+            .collect())
+    }
+
+    async fn download_tarball(&self, repo: &RepoInfo, _git_ref: &str) -> Result<Option<Vec<u8>>> {
+        let url = repo
+            .download_url
+            .as_deref()
+            .context("Bitbucket repository has no archive URL")?;
+        fetch_archive(&self.client, url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `responses` to one request each, in order, returning the
+    /// request heads received
+    async fn mock_server(
+        responses: Vec<(&'static str, Vec<u8>)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let responses = responses
+            .into_iter()
+            .map(|(content_type, body)| (format!("200 OK\r\nContent-Type: {}", content_type), body))
+            .collect();
+        mock_server_with_status(responses).await
+    }
+
+    /// Like `mock_server`, each response given as its status line (without
+    /// `HTTP/1.1`) plus any headers
+    async fn mock_server_with_status(
+        responses: Vec<(String, Vec<u8>)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut heads = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buf[..n]);
+                }
+                heads.push(String::from_utf8_lossy(&head).to_string());
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+            heads
+        });
+        (base, handle)
+    }
+
+    fn config() -> HarvestConfig {
+        HarvestConfig {
+            queries: vec!["cli tool".to_string()],
+            language: Some("Rust".to_string()),
+            max_repos: 2,
+            min_stars: 10,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_retries_after_rate_limit() {
+        let reset = chrono::Utc::now().timestamp();
+        let limited = || {
+            (
+                format!(
+                    "429 Too Many Requests\r\nX-RateLimit-Reset: {}\r\nContent-Type: text/plain",
+                    reset
+                ),
+                b"slow down".to_vec(),
+            )
+        };
+        let projects = serde_json::json!([{
+            "id": 7, "name": "anvil", "path_with_namespace": "tools/anvil",
+            "star_count": 50, "default_branch": "main"
+        }]);
+        let (base, server) = mock_server_with_status(vec![
+            limited(),
+            limited(),
+            (
+                "200 OK\r\nContent-Type: application/json".to_string(),
+                serde_json::to_vec(&projects).unwrap(),
+            ),
+        ])
+        .await;
+
+        let client = GitLabClient::new(&base, None, 3).unwrap();
+        let repos = client.search_repos("", &config()).await.unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "anvil");
+        assert_eq!(server.await.unwrap().len(), 3);
+
+        // Out of retries: the 429 surfaces as a network error
+        let (base, _server) = mock_server_with_status(vec![limited(), limited()]).await;
+        let client = GitLabClient::new(&base, None, 1).unwrap();
+        let err = client.search_repos("", &config()).await.unwrap_err();
+        assert_eq!(crate::error::exit_code(&err), crate::error::EXIT_NETWORK);
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_search_maps_and_filters_projects() {
+        let projects = serde_json::json!([
+            {
+                "id": 7, "name": "anvil", "path_with_namespace": "tools/anvil",
+                "description": "A CLI", "star_count": 50, "default_branch": "main",
+                "last_activity_at": "2026-09-01T00:00:00Z"
+            },
+            {
+                "id": 8, "name": "tiny", "path_with_namespace": "tools/tiny",
+                "star_count": 2, "default_branch": "main"
+            },
+            {
+                "id": 9, "name": "fork", "path_with_namespace": "me/anvil",
+                "star_count": 40, "default_branch": "main",
+                "forked_from_project": { "id": 7 }
+            },
+            { "id": 10, "name": "empty", "path_with_namespace": "tools/empty", "star_count": 90 },
+            {
+                "id": 11, "name": "hammer", "path_with_namespace": "tools/hammer",
+                "star_count": 30, "default_branch": "trunk"
+            }
+        ]);
+        let (base, server) = mock_server(vec![(
+            "application/json",
+            serde_json::to_vec(&projects).unwrap(),
+        )])
+        .await;
+
+        let client = GitLabClient::new(&base, Some("glpat-secret"), 0).unwrap();
+        let repos = client.search_repos("", &config()).await.unwrap();
+
+        let names: Vec<_> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["anvil", "hammer"]);
+        let host = base.trim_start_matches("http://");
+        assert_eq!(repos[0].full_name, format!("{}/tools/anvil", host));
+        assert_eq!(repos[0].stargazers_count, 50);
+        assert_eq!(repos[0].default_branch, "main");
+        assert_eq!(
+            repos[1].download_url.as_deref(),
+            Some(
+                format!(
+                    "{}/api/v4/projects/11/repository/archive.tar.gz?sha=trunk",
+                    base
+                )
+                .as_str()
+            )
+        );
+
+        let heads = server.await.unwrap();
+        let request_line = heads[0].lines().next().unwrap();
+        assert!(
+            request_line.starts_with("GET /api/v4/projects?"),
+            "{}",
+            request_line
+        );
+        assert!(request_line.contains("search=cli%20tool"));
+        assert!(request_line.contains("with_programming_language=Rust"));
+        assert!(request_line.contains("order_by=star_count&sort=desc"));
+        assert!(request_line.contains("archived=false"));
+        assert!(heads[0]
+            .to_lowercase()
+            .contains("private-token: glpat-secret"));
+    }
+
+    /// A GitHub search page of `count` repos numbered from `first`
+    fn github_page(total_count: usize, first: usize, count: usize) -> (&'static str, Vec<u8>) {
+        let items: Vec<_> = (first..first + count)
+            .map(|i| {
+                serde_json::json!({
+                    "id": i, "name": format!("repo{}", i), "full_name": format!("owner/repo{}", i),
+                    "description": null, "stargazers_count": 100, "language": "Rust",
+                    "license": null, "default_branch": "main", "pushed_at": null
+                })
+            })
+            .collect();
+        let body = serde_json::json!({ "total_count": total_count, "items": items });
+        ("application/json", serde_json::to_vec(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_github_search_pages_past_100_results() {
+        let (base, server) = mock_server(vec![
+            github_page(5000, 0, 100),
+            github_page(5000, 100, 100),
+            github_page(5000, 200, 100),
+        ])
+        .await;
+        let mut client = GitHubClient::with_base(None, 0, &base).unwrap();
+        client.page_delay = std::time::Duration::ZERO;
+        let config = HarvestConfig {
+            max_repos: 250,
+            ..Default::default()
+        };
+
+        let repos = client.search_repos("cli", &config).await.unwrap();
+        assert_eq!(repos.len(), 250);
+        assert_eq!(repos[249].full_name, "owner/repo249");
+
+        let heads = server.await.unwrap();
+        assert_eq!(heads.len(), 3);
+        assert!(heads[0].contains("per_page=100 "), "{}", heads[0]);
+        assert!(heads[1].contains("per_page=100&page=2 "), "{}", heads[1]);
+        assert!(heads[2].contains("per_page=100&page=3 "), "{}", heads[2]);
+
+        // Stops once total_count is exhausted
+        let (base, server) =
+            mock_server(vec![github_page(120, 0, 100), github_page(120, 100, 20)]).await;
+        let mut client = GitHubClient::with_base(None, 0, &base).unwrap();
+        client.page_delay = std::time::Duration::ZERO;
+        let repos = client.search_repos("cli", &config).await.unwrap();
+        assert_eq!(repos.len(), 120);
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+
+    fn github_repo() -> RepoInfo {
+        RepoInfo {
+            id: 1,
+            name: "anvil".to_string(),
+            full_name: "tools/anvil".to_string(),
+            description: None,
+            stargazers_count: 50,
+            language: None,
+            license: None,
+            default_branch: "main".to_string(),
+            pushed_at: None,
+            download_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_github_compare_lists_changed_files() {
+        let comparison = serde_json::json!({ "files": [
+            { "filename": "src/lib.rs", "status": "modified" },
+            { "filename": "old.rs", "status": "removed" },
+            { "filename": "src/new.rs", "status": "renamed", "previous_filename": "src/was.rs" },
+        ]});
+        let (base, server) = mock_server_with_status(vec![
+            (
+                "200 OK\r\nContent-Type: application/json".to_string(),
+                serde_json::to_vec(&comparison).unwrap(),
+            ),
+            (
+                "422 Unprocessable Entity\r\nContent-Type: application/json".to_string(),
+                br#"{"message":"Server Error: Sorry, this diff is taking too long to generate. Too many commits."}"#.to_vec(),
+            ),
+            ("200 OK\r\nContent-Type: text/plain".to_string(), b"fn main() {}".to_vec()),
+        ])
+        .await;
+        let client = GitHubClient::with_base(None, 0, &base).unwrap();
+        let repo = github_repo();
+
+        let changes = client.compare(&repo, "aaa", "bbb").await.unwrap().unwrap();
+        let change = |path: &str, removed| ChangedFile {
+            path: path.to_string(),
+            removed,
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("src/lib.rs", false),
+                change("old.rs", true),
+                change("src/was.rs", true),
+                change("src/new.rs", false),
+            ]
+        );
+
+        // Too large a range: the caller harvests in full
+        assert_eq!(client.compare(&repo, "aaa", "ccc").await.unwrap(), None);
+
+        let bytes = client
+            .download_file(&repo, "src/my file.rs", "bbb")
+            .await
+            .unwrap();
+        assert_eq!(bytes.as_deref(), Some(&b"fn main() {}"[..]));
+
+        let heads = server.await.unwrap();
+        assert!(heads[0].starts_with("GET /repos/tools/anvil/compare/aaa...bbb "));
+        assert!(heads[2].starts_with("GET /repos/tools/anvil/contents/src/my%20file.rs?ref=bbb "));
+        assert!(heads[2]
+            .to_lowercase()
+            .contains("accept: application/vnd.github.raw+json"));
+    }
+
+    #[tokio::test]
+    async fn test_github_resolves_head_commit() {
+        let sha = "3f786850e387550fdab836ed7e6dc881de23001b";
+        let (base, server) = mock_server(vec![(
+            "application/json",
+            serde_json::to_vec(&serde_json::json!({ "sha": sha })).unwrap(),
+        )])
+        .await;
+
+        let client = GitHubClient::with_base(Some("ghp_secret"), 0, &base).unwrap();
+        let repo = RepoInfo {
+            id: 1,
+            name: "anvil".to_string(),
+            full_name: "tools/anvil".to_string(),
+            description: None,
+            stargazers_count: 50,
+            language: None,
+            license: None,
+            default_branch: "release/1.x".to_string(),
+            pushed_at: None,
+            download_url: None,
+        };
+        assert_eq!(
+            client.head_commit(&repo).await.unwrap().as_deref(),
+            Some(sha)
+        );
+
+        let heads = server.await.unwrap();
+        assert!(
+            heads[0].starts_with("GET /repos/tools/anvil/commits/release%2F1.x "),
+            "{}",
+            heads[0]
+        );
+
+        // Hosts without a commit lookup keep using the branch name
+        let gitlab = GitLabClient::new("gitlab.example.com", None, 0).unwrap();
+        assert_eq!(gitlab.head_commit(&repo).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_github_tarball_retries_renamed_default_branch() {
+        let not_found = || {
+            (
+                "404 Not Found\r\nContent-Type: application/json".to_string(),
+                br#"{"message":"Not Found"}"#.to_vec(),
+            )
+        };
+        let metadata = |branch: &str| {
+            (
+                "200 OK\r\nContent-Type: application/json".to_string(),
+                serde_json::to_vec(&serde_json::json!({ "default_branch": branch })).unwrap(),
+            )
+        };
+        let (base, server) = mock_server_with_status(vec![
+            not_found(),
+            metadata("main"),
+            (
+                "200 OK\r\nContent-Type: application/x-gzip".to_string(),
+                b"tarball".to_vec(),
+            ),
+            not_found(),
+            metadata("master"),
+        ])
+        .await;
+
+        let client = GitHubClient::with_base(None, 0, &base).unwrap();
+        let repo = RepoInfo {
+            id: 1,
+            name: "anvil".to_string(),
+            full_name: "tools/anvil".to_string(),
+            description: None,
+            stargazers_count: 50,
+            language: None,
+            license: None,
+            default_branch: "master".to_string(),
+            pushed_at: None,
+            download_url: None,
+        };
+        assert_eq!(
+            client
+                .download_tarball(&repo, "master")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(&b"tarball"[..])
+        );
+        // An unchanged default branch means the repo is really gone
+        assert_eq!(
+            client.download_tarball(&repo, "master").await.unwrap(),
+            None
+        );
+
+        let heads = server.await.unwrap();
+        assert!(heads[0].starts_with("GET /repos/tools/anvil/tarball/master "));
+        assert!(heads[1].starts_with("GET /repos/tools/anvil "));
+        assert!(
+            heads[2].starts_with("GET /repos/tools/anvil/tarball/main "),
+            "{}",
+            heads[2]
+        );
+        assert!(heads[4].starts_with("GET /repos/tools/anvil "));
+    }
+
+    #[tokio::test]
+    async fn test_github_tarball_downloads_the_resolved_commit() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        let (base, server) = mock_server_with_status(vec![
+            (
+                "200 OK\r\nContent-Type: application/x-gzip".to_string(),
+                b"tarball".to_vec(),
+            ),
+            (
+                "404 Not Found\r\nContent-Type: application/json".to_string(),
+                br#"{"message":"Not Found"}"#.to_vec(),
+            ),
+        ])
+        .await;
+
+        let client = GitHubClient::with_base(None, 0, &base).unwrap();
+        let repo = RepoInfo {
+            id: 1,
+            name: "anvil".to_string(),
+            full_name: "tools/anvil".to_string(),
+            description: None,
+            stargazers_count: 50,
+            language: None,
+            license: None,
+            default_branch: "main".to_string(),
+            pushed_at: None,
+            download_url: None,
+        };
+        assert_eq!(
+            client
+                .download_tarball(&repo, sha)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(&b"tarball"[..])
+        );
+        // A missing commit isn't a renamed branch: no metadata lookup
+        assert_eq!(client.download_tarball(&repo, sha).await.unwrap(), None);
+
+        let heads = server.await.unwrap();
+        assert_eq!(heads.len(), 2);
+        assert!(
+            heads[0].starts_with(&format!("GET /repos/tools/anvil/tarball/{} ", sha)),
+            "{}",
+            heads[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_downloads_project_archive() {
+        let (base, server) = mock_server(vec![
+            (
+                "application/json",
+                serde_json::to_vec(&serde_json::json!([{
+                    "id": 7, "name": "anvil", "path_with_namespace": "tools/anvil",
+                    "star_count": 50, "default_branch": "main"
+                }]))
+                .unwrap(),
+            ),
+            ("application/gzip", b"tarball-bytes".to_vec()),
+        ])
+        .await;
+
+        let client = GitLabClient::new(&base, None, 0).unwrap();
+        let repos = client.search_repos("", &config()).await.unwrap();
+        let bytes = client.download_tarball(&repos[0], "main").await.unwrap();
+        assert_eq!(bytes.as_deref(), Some(&b"tarball-bytes"[..]));
+
+        let heads = server.await.unwrap();
+        assert!(heads[1].starts_with("GET /api/v4/projects/7/repository/archive.tar.gz?sha=main"));
+    }
+
+    #[tokio::test]
+    async fn test_bitbucket_search_maps_workspace_repos() {
+        let page = serde_json::json!({
+            "values": [
+                {
+                    "name": "anvil", "full_name": "acme/anvil", "language": "rust",
+                    "mainbranch": { "name": "main" }, "updated_on": "2026-09-01T00:00:00Z"
+                },
+                {
+                    "name": "fork", "full_name": "acme/fork", "language": "rust",
+                    "mainbranch": { "name": "main" }, "parent": { "full_name": "acme/anvil" }
+                }
+            ]
+        });
+        let (base, server) = mock_server(vec![(
+            "application/json",
+            serde_json::to_vec(&page).unwrap(),
+        )])
+        .await;
+
+        let client =
+            BitbucketClient::with_base("acme", Some("bb-token"), 0, &base, "https://bb.test")
+                .unwrap();
+        let repos = client.search_repos("", &config()).await.unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].full_name, "bitbucket.org/acme/anvil");
+        assert_eq!(
+            repos[0].download_url.as_deref(),
+            Some("https://bb.test/acme/anvil/get/main.tar.gz")
+        );
+
+        let heads = server.await.unwrap();
+        assert!(heads[0].starts_with("GET /repositories/acme?q="));
+        assert!(heads[0].contains("sort=-updated_on"));
+        assert!(heads[0]
+            .to_lowercase()
+            .contains("authorization: bearer bb-token"));
+    }
+
+    #[test]
+    fn test_debug_hides_tokens() {
+        let source = HarvestSource::GitLab {
+            host: GITLAB_COM.to_string(),
+            token: Some("glpat-secret".to_string()),
+        };
+        let shown = format!("{:?}", source);
+        assert!(shown.contains("gitlab.com"));
+        assert!(!shown.contains("glpat-secret"));
+    }
+}
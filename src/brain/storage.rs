@@ -7,18 +7,44 @@
 
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tokio::fs;
 
-use super::{BrainRecord, BrainStats, SearchResult, SourceMeta};
+use super::search_cache::{SearchCache, SearchKey};
+use super::{
+    BrainRecord, BrainStats, BrainTopology, ContentType, SearchResult, Signal, SourceMeta,
+    TopologyContentType, TopologyFile, TopologySource,
+};
 use crate::workspace;
 
+/// Batches with at least this many chunks skip the per-row FTS triggers
+/// and rebuild the index once instead
+pub const BULK_INSERT_THRESHOLD: usize = 1000;
+
+/// Source of the embedded core brainpack; never expires
+const CORE_SOURCE_ID: &str = "core";
+
+/// Triggers keeping `chunks_fts` in sync with `brain_chunks`
+const FTS_TRIGGERS_SQL: &str = "
+    CREATE TRIGGER IF NOT EXISTS chunks_ai AFTER INSERT ON brain_chunks BEGIN
+        INSERT INTO chunks_fts(rowid, text, tags, path)
+        VALUES (new.rowid, new.text, new.tags, new.path);
+    END;
+    CREATE TRIGGER IF NOT EXISTS chunks_ad AFTER DELETE ON brain_chunks BEGIN
+        INSERT INTO chunks_fts(chunks_fts, rowid, text, tags, path)
+        VALUES('delete', old.rowid, old.text, old.tags, old.path);
+    END;";
+
 /// Export format
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     Jsonl,
     Markdown,
+    Html,
 }
 
 /// Export options
@@ -28,8 +54,139 @@ pub struct ExportOptions {
     pub output_path: Option<PathBuf>,
     /// Include anonymized source IDs (default: false for privacy)
     pub include_source_ids: bool,
-    /// Limit entries for markdown export (default: 50, 0 = no limit)
+    /// Limit entries for markdown/HTML export (default: 50, 0 = no limit)
     pub limit: usize,
+    /// Write a `<output>.manifest.json` sidecar with counts and a checksum
+    pub manifest: bool,
+    /// Restrict the export to matching records
+    pub filter: ExportFilter,
+}
+
+/// Record filters for an export; non-empty criteria are AND-ed together
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExportFilter {
+    /// Keep records in any of these languages (case-insensitive)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    /// Keep records of any of these content types
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub content_types: Vec<ContentType>,
+    /// Keep records carrying any of these signals
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signals: Vec<Signal>,
+    /// Keep records with at least this many chunks (0 = no minimum)
+    #[serde(skip_serializing_if = "is_zero")]
+    pub min_chunks: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl ExportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+            && self.content_types.is_empty()
+            && self.signals.is_empty()
+            && self.min_chunks == 0
+    }
+
+    /// `WHERE` clause (over `brain_chunks <alias>`, empty when unfiltered) and
+    /// its parameters, numbered from `?1`
+    fn where_clause(&self, alias: &str) -> (String, Vec<rusqlite::types::Value>) {
+        let mut conditions = vec![];
+        let mut params: Vec<rusqlite::types::Value> = Vec::new();
+        let placeholders = |values: Vec<String>, params: &mut Vec<rusqlite::types::Value>| {
+            values
+                .into_iter()
+                .map(|v| {
+                    params.push(rusqlite::types::Value::Text(v));
+                    format!("?{}", params.len())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if !self.languages.is_empty() {
+            let values = self.languages.iter().map(|l| l.to_lowercase()).collect();
+            conditions.push(format!(
+                "LOWER({}.language) IN ({})",
+                alias,
+                placeholders(values, &mut params).join(", ")
+            ));
+        }
+        if !self.content_types.is_empty() {
+            let values = self.content_types.iter().map(|t| t.to_string()).collect();
+            conditions.push(format!(
+                "{}.content_type IN ({})",
+                alias,
+                placeholders(values, &mut params).join(", ")
+            ));
+        }
+        if !self.signals.is_empty() {
+            // `signals` holds the JSON array, e.g. ["state_machine","contract_lock"]
+            let values = self
+                .signals
+                .iter()
+                .map(|s| format!("%\"{}\"%", s))
+                .collect();
+            let any: Vec<String> = placeholders(values, &mut params)
+                .into_iter()
+                .map(|p| format!("{}.signals LIKE {}", alias, p))
+                .collect();
+            conditions.push(format!("({})", any.join(" OR ")));
+        }
+        if self.min_chunks > 0 {
+            params.push(rusqlite::types::Value::Integer(self.min_chunks as i64));
+            conditions.push(format!(
+                "(SELECT COUNT(*) FROM brain_chunks m \
+                 WHERE m.source_id = {a}.source_id AND m.path = {a}.path) >= ?{}",
+                params.len(),
+                a = alias
+            ));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), params)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), params)
+        }
+    }
+}
+
+/// Sidecar written next to an export so recipients can check it
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportManifest {
+    /// Export file name (the sidecar sits next to it)
+    pub file: String,
+    pub format: ExportFormat,
+    pub records: usize,
+    pub chunks: usize,
+    /// Whether source IDs were left in the export
+    pub include_source_ids: bool,
+    /// Entry limit applied to Markdown/HTML previews (absent for JSONL and no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Filters applied to the export (absent when unfiltered)
+    #[serde(skip_serializing_if = "ExportFilter::is_empty")]
+    pub filter: ExportFilter,
+    pub tool_version: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// SHA-256 of the export file
+    pub sha256: String,
+}
+
+/// Records and chunks written by an export
+#[derive(Debug, Clone, Copy, Default)]
+struct ExportCounts {
+    records: usize,
+    chunks: usize,
+}
+
+/// Sidecar path for an export: `<output>.manifest.json`
+pub fn export_manifest_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(".manifest.json");
+    PathBuf::from(name)
 }
 
 impl Default for ExportOptions {
@@ -39,16 +196,67 @@ impl Default for ExportOptions {
             output_path: None,
             include_source_ids: false,
             limit: 50,
+            manifest: false,
+            filter: ExportFilter::default(),
         }
     }
 }
 
+/// Optional filters applied to a brain search
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SearchFilters {
+    pub record_type: Option<String>,
+    /// Restrict to any of these content types (empty = all)
+    pub content_types: Vec<String>,
+    pub language: Option<String>,
+    pub tags: Vec<String>,
+    pub source_id: Option<String>,
+    /// Restrict to chunks with any of these signals (snake_case names, empty = all)
+    pub signals: Vec<String>,
+}
+
+/// Result of `save_records`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SaveStats {
+    /// Chunks written
+    pub inserted: usize,
+    /// Chunks skipped because one with the same content hash was already stored
+    pub skipped_duplicates: usize,
+}
+
+/// Stored chunks that a harvest's records replace (see `save_harvest`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Replace {
+    /// Nothing: chunks are added, skipping content already stored
+    Nothing,
+    /// The chunks of the records' paths, for an incremental update
+    Paths,
+    /// Everything stored for the source, for a full re-harvest
+    Source,
+}
+
+/// The fields of a JSONL line that decide whether a harvest replaces it
+#[derive(Deserialize)]
+struct RecordKey {
+    source_id: String,
+    path: String,
+}
+
 /// Result of compact operation
 pub struct CompactResult {
     pub records_written: usize,
     pub chunks_count: usize,
 }
 
+/// Result of repair operation
+#[derive(Debug, Default)]
+pub struct RepairResult {
+    /// Distinct content_type values inspected
+    pub values_checked: usize,
+    /// Rows rewritten with a normalized content_type
+    pub rows_fixed: usize,
+}
+
 /// Statistics from core import
 #[derive(Debug, Default)]
 pub struct ImportStats {
@@ -62,11 +270,65 @@ pub struct ImportStats {
     pub error_lines: Vec<usize>,
 }
 
+/// Longest pattern accepted by `brain search --regex`
+const MAX_REGEX_LEN: usize = 1024;
+
+/// Upper bound on the compiled size of a `--regex` pattern
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Characters of context kept on each side of a regex match in the snippet
+const REGEX_SNIPPET_CONTEXT: usize = 60;
+
+/// Characters of chunk text shown for results without a query (`list_filtered`)
+const LIST_SNIPPET_CHARS: usize = 120;
+
+/// Validate and compile a user-supplied search regex
+fn compile_search_regex(pattern: &str) -> Result<regex::Regex> {
+    if pattern.is_empty() {
+        return Err(crate::error::validation("Regex pattern is empty"));
+    }
+    if pattern.len() > MAX_REGEX_LEN {
+        return Err(crate::error::validation(format!(
+            "Regex pattern is too long ({} bytes, max {})",
+            pattern.len(),
+            MAX_REGEX_LEN
+        )));
+    }
+    regex::RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| crate::error::validation(format!("Invalid regex: {}", e)))
+}
+
+/// Snippet around a match, marked like FTS snippets (`→match←`)
+fn regex_snippet(text: &str, start: usize, end: usize) -> String {
+    let from = text[..start]
+        .char_indices()
+        .rev()
+        .nth(REGEX_SNIPPET_CONTEXT.saturating_sub(1))
+        .map_or(0, |(i, _)| i);
+    let to = text[end..]
+        .char_indices()
+        .nth(REGEX_SNIPPET_CONTEXT)
+        .map_or(text.len(), |(i, _)| end + i);
+
+    format!(
+        "{}{}→{}←{}{}",
+        if from > 0 { "..." } else { "" },
+        &text[from..start],
+        &text[start..end],
+        &text[end..to],
+        if to < text.len() { "..." } else { "" }
+    )
+}
+
 /// Storage for brain records
 pub struct BrainStorage {
     brainpack_dir: PathBuf,
     jsonl_path: PathBuf,
     sqlite_path: PathBuf,
+    search_cache: Mutex<SearchCache>,
 }
 
 impl BrainStorage {
@@ -82,6 +344,7 @@ impl BrainStorage {
             brainpack_dir,
             jsonl_path,
             sqlite_path,
+            search_cache: Mutex::new(SearchCache::default()),
         };
 
         storage.init_db()?;
@@ -100,6 +363,7 @@ impl BrainStorage {
             brainpack_dir: path,
             jsonl_path,
             sqlite_path,
+            search_cache: Mutex::new(SearchCache::default()),
         };
 
         storage.init_db()?;
@@ -136,6 +400,8 @@ impl BrainStorage {
                 text TEXT NOT NULL,
                 signals TEXT,
                 tags TEXT,
+                content_hash TEXT,
+                harvested_at INTEGER,
                 FOREIGN KEY (source_id) REFERENCES sources(source_id)
             )",
             [],
@@ -171,6 +437,34 @@ impl BrainStorage {
             );
         }
 
+        // MIGRATION: Add content_hash for insert-time dedup, back-filling rows
+        // written by older versions (and any left NULL by an interrupted run)
+        if !existing_columns.contains("content_hash") {
+            let _ = conn.execute("ALTER TABLE brain_chunks ADD COLUMN content_hash TEXT", []);
+        }
+        Self::backfill_content_hashes(&conn)?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_brain_chunks_content_hash ON brain_chunks(content_hash)",
+            [],
+        )?;
+
+        // MIGRATION: Add harvested_at (unix seconds) for TTL expiry. Older rows
+        // take their source's fetch time, or now when that is unknown
+        if !existing_columns.contains("harvested_at") {
+            let _ = conn.execute(
+                "ALTER TABLE brain_chunks ADD COLUMN harvested_at INTEGER",
+                [],
+            );
+        }
+        conn.execute(
+            "UPDATE brain_chunks SET harvested_at = COALESCE(
+                (SELECT CAST(strftime('%s', s.fetched_at) AS INTEGER)
+                 FROM sources s WHERE s.source_id = brain_chunks.source_id),
+                CAST(strftime('%s', 'now') AS INTEGER))
+             WHERE harvested_at IS NULL",
+            [],
+        )?;
+
         // MIGRATION: Fix content_type quotes
         // Update any content_type that starts/ends with quotes (JSON string artifact)
         conn.execute(
@@ -193,21 +487,7 @@ impl BrainStorage {
         )?;
 
         // Triggers to keep FTS in sync
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS chunks_ai AFTER INSERT ON brain_chunks BEGIN
-                INSERT INTO chunks_fts(rowid, text, tags, path)
-                VALUES (new.rowid, new.text, new.tags, new.path);
-            END",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE TRIGGER IF NOT EXISTS chunks_ad AFTER DELETE ON brain_chunks BEGIN
-                INSERT INTO chunks_fts(chunks_fts, rowid, text, tags, path)
-                VALUES('delete', old.rowid, old.text, old.tags, old.path);
-            END",
-            [],
-        )?;
+        conn.execute_batch(FTS_TRIGGERS_SQL)?;
 
         // Metadata table for tracking versions, hashes, etc.
         conn.execute(
@@ -221,6 +501,44 @@ impl BrainStorage {
         Ok(())
     }
 
+    /// Fill in `content_hash` for rows that don't have one
+    fn backfill_content_hashes(conn: &Connection) -> Result<()> {
+        let missing: Vec<(i64, String, String, u32, String)> = conn
+            .prepare(
+                "SELECT rowid, source_id, path, COALESCE(start_line, 0), text
+                 FROM brain_chunks WHERE content_hash IS NULL",
+            )?
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut update =
+                tx.prepare("UPDATE brain_chunks SET content_hash = ? WHERE rowid = ?")?;
+            for (rowid, source_id, path, start_line, text) in &missing {
+                let hash = crate::brain::chunk_content_hash(source_id, path, *start_line, text);
+                update.execute(params![hash, rowid])?;
+            }
+        }
+        tx.commit()?;
+        tracing::debug!(
+            rows = missing.len(),
+            "Back-filled brain chunk content hashes"
+        );
+        Ok(())
+    }
+
     /// Save source metadata
     pub async fn save_source(&self, source: &SourceMeta) -> Result<()> {
         let conn = Connection::open(&self.sqlite_path)?;
@@ -244,12 +562,185 @@ impl BrainStorage {
     }
 
     /// Save records (append to JSONL, insert to SQLite)
-    pub async fn save_records(&self, records: &[BrainRecord]) -> Result<usize> {
+    ///
+    /// Chunks whose content hash is already stored (or repeated within
+    /// `records`) are skipped, and only the chunks actually inserted are
+    /// appended to the JSONL, so re-harvesting the same files doesn't grow
+    /// the pack.
+    ///
+    /// All-or-nothing: chunks are inserted in one transaction that is only
+    /// committed after the JSONL append succeeds. On any failure the
+    /// transaction rolls back and the appended JSONL lines are truncated.
+    pub async fn save_records(&self, records: &[BrainRecord]) -> Result<SaveStats> {
         if records.is_empty() {
-            return Ok(0);
+            return Ok(SaveStats::default());
+        }
+        self.invalidate_search_cache();
+
+        // Insert chunks to SQLite (uncommitted)
+        let mut conn = Connection::open(&self.sqlite_path)?;
+        let tx = conn.transaction()?;
+        let chunk_count: usize = records.iter().map(|r| r.chunks.len()).sum();
+        let started = std::time::Instant::now();
+        let bulk = chunk_count >= BULK_INSERT_THRESHOLD;
+        let (stats, inserted) = if bulk {
+            Self::insert_chunks_bulk(&tx, records)?
+        } else {
+            Self::insert_chunks(&tx, records)?
+        };
+
+        // Append to JSONL, then commit
+        let jsonl_len = std::fs::metadata(&self.jsonl_path)
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let committed = self.append_jsonl(&inserted).and_then(|_| {
+            tx.commit()
+                .context("Failed to commit brain chunks to SQLite")
+        });
+        if let Err(e) = committed {
+            self.truncate_jsonl(jsonl_len);
+            return Err(e);
+        }
+
+        tracing::debug!(
+            chunks = chunk_count,
+            inserted = stats.inserted,
+            skipped_duplicates = stats.skipped_duplicates,
+            bulk,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Inserted brain chunks"
+        );
+
+        Ok(stats)
+    }
+
+    /// Save a harvested source and its records, replacing what `replace` says
+    ///
+    /// With `Replace::Nothing` this is `save_source` plus `save_records`.
+    /// Otherwise the old chunks are deleted and the new ones inserted in one
+    /// transaction, the source's counts are recomputed from what is stored
+    /// (so they stay cumulative across incremental updates). Only once that
+    /// transaction commits are the replaced source's JSONL lines swapped for
+    /// the new ones, via a temp file renamed into place, so a failed save
+    /// leaves the JSONL untouched and other sources' lines keep their commit.
+    pub async fn save_harvest(
+        &self,
+        source: &SourceMeta,
+        records: &[BrainRecord],
+        replace: Replace,
+    ) -> Result<SaveStats> {
+        if replace == Replace::Nothing {
+            self.save_source(source).await?;
+            return self.save_records(records).await;
+        }
+        self.invalidate_search_cache();
+
+        let mut conn = Connection::open(&self.sqlite_path)?;
+        let tx = conn.transaction()?;
+        match replace {
+            Replace::Source => {
+                tx.execute(
+                    "DELETE FROM brain_chunks WHERE source_id = ?",
+                    params![source.source_id],
+                )?;
+            }
+            _ => {
+                let mut stmt =
+                    tx.prepare("DELETE FROM brain_chunks WHERE source_id = ? AND path = ?")?;
+                for record in records {
+                    stmt.execute(params![source.source_id, record.path])?;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO sources
+            (source_id, \"commit\", license, language, fetched_at, files_count, chunks_count)
+            VALUES (?1, ?2, ?3, ?4, ?5, 0, 0)
+            ON CONFLICT(source_id) DO UPDATE SET
+                \"commit\" = ?2, license = ?3, language = ?4, fetched_at = ?5",
+            params![
+                source.source_id,
+                source.commit,
+                source.license,
+                source.language,
+                source.fetched_at.to_rfc3339(),
+            ],
+        )?;
+
+        let chunk_count: usize = records.iter().map(|r| r.chunks.len()).sum();
+        let (stats, inserted) = if chunk_count >= BULK_INSERT_THRESHOLD {
+            Self::insert_chunks_bulk(&tx, records)?
+        } else {
+            Self::insert_chunks(&tx, records)?
+        };
+
+        tx.execute(
+            "UPDATE sources SET
+                files_count = (SELECT COUNT(DISTINCT path) FROM brain_chunks WHERE source_id = ?1),
+                chunks_count = (SELECT COUNT(*) FROM brain_chunks WHERE source_id = ?1)
+            WHERE source_id = ?1",
+            params![source.source_id],
+        )?;
+
+        tx.commit()
+            .context("Failed to commit brain chunks to SQLite")?;
+
+        let replaced_paths: std::collections::HashSet<&str> =
+            records.iter().map(|r| r.path.as_str()).collect();
+        self.replace_jsonl(|output| {
+            let existing = match std::fs::File::open(&self.jsonl_path) {
+                Ok(file) => Some(BufReader::new(file)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(e.into()),
+            };
+            for line in existing.into_iter().flat_map(|reader| reader.lines()) {
+                let line = line?;
+                let replaced = serde_json::from_str::<RecordKey>(&line).is_ok_and(|key| {
+                    key.source_id == source.source_id
+                        && (replace == Replace::Source
+                            || replaced_paths.contains(key.path.as_str()))
+                });
+                if !replaced {
+                    writeln!(output, "{}", line)?;
+                }
+            }
+            for record in &inserted {
+                writeln!(output, "{}", serde_json::to_string(record)?)?;
+            }
+            Ok(())
+        })
+        .context(
+            "Saved the harvest to SQLite but failed to update the JSONL; \
+             run 'vibeanvil brain compact' to rebuild it",
+        )?;
+        Ok(stats)
+    }
+
+    /// Replace the JSONL with what `write` produces, writing to a temp file
+    /// first so a failure leaves the current JSONL intact
+    fn replace_jsonl<T>(
+        &self,
+        write: impl FnOnce(&mut BufWriter<std::fs::File>) -> Result<T>,
+    ) -> Result<T> {
+        let tmp_path = self.jsonl_path.with_extension("jsonl.tmp");
+        let result = (|| {
+            let mut output = BufWriter::new(std::fs::File::create(&tmp_path)?);
+            let value = write(&mut output)?;
+            output
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()?;
+            std::fs::rename(&tmp_path, &self.jsonl_path)?;
+            Ok(value)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
         }
+        result
+    }
 
-        // Append to JSONL
+    fn append_jsonl(&self, records: &[BrainRecord]) -> Result<()> {
         let mut file = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
@@ -260,38 +751,106 @@ impl BrainStorage {
             let json = serde_json::to_string(record)?;
             writeln!(file, "{}", json)?;
         }
+        file.flush()?;
+        Ok(())
+    }
 
-        // Insert chunks to SQLite
-        let conn = Connection::open(&self.sqlite_path)?;
+    /// Drop anything appended to the JSONL after `len` bytes
+    fn truncate_jsonl(&self, len: u64) {
+        let result = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&self.jsonl_path)
+            .and_then(|file| file.set_len(len));
+        if let Err(e) = result {
+            tracing::warn!("Failed to roll back JSONL append: {}", e);
+        }
+    }
+
+    /// Insert chunks one row at a time, skipping content already stored; the
+    /// triggers index each row
+    ///
+    /// A skipped chunk still has its `harvested_at` refreshed, so content
+    /// that is harvested again doesn't expire.
+    ///
+    /// Returns the counts and `records` narrowed to the chunks inserted
+    /// (records left with no chunks are dropped).
+    fn insert_chunks(
+        conn: &Connection,
+        records: &[BrainRecord],
+    ) -> Result<(SaveStats, Vec<BrainRecord>)> {
+        let mut stmt = conn.prepare_cached(
+            "INSERT OR REPLACE INTO brain_chunks
+            (chunk_id, source_id, path, content_type, start_line, end_line, text, signals, tags, summary, language, license, content_hash, harvested_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+        let mut refresh =
+            conn.prepare_cached("UPDATE brain_chunks SET harvested_at = ? WHERE content_hash = ?")?;
+        let now = chrono::Utc::now().timestamp();
 
+        let mut stats = SaveStats::default();
+        let mut inserted = Vec::new();
         for record in records {
             let signals_json = serde_json::to_string(&record.signals)?;
-            let tags_str = record.tags.join(",");
+            let tags_str = crate::brain::normalize_tags(&record.tags).join(",");
+            let mut kept = Vec::new();
 
             for chunk in &record.chunks {
-                conn.execute(
-                    "INSERT OR REPLACE INTO brain_chunks 
-                    (chunk_id, source_id, path, content_type, start_line, end_line, text, signals, tags, summary, language, license)
-                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                    params![
-                        chunk.chunk_id,
-                        record.source_id,
-                        record.path,
-                        record.content_type.to_string(), // Use Display trait (no quotes)
-                        chunk.start_line,
-                        chunk.end_line,
-                        chunk.text,
-                        signals_json,
-                        tags_str,
-                        record.summary,
-                        record.language,
-                        record.license,
-                    ],
-                )?;
+                let hash = crate::brain::chunk_content_hash(
+                    &record.source_id,
+                    &record.path,
+                    chunk.start_line,
+                    &chunk.text,
+                );
+                if refresh.execute(params![now, hash])? > 0 {
+                    stats.skipped_duplicates += 1;
+                    continue;
+                }
+                stmt.execute(params![
+                    chunk.chunk_id,
+                    record.source_id,
+                    record.path,
+                    record.content_type.to_string(), // Use Display trait (no quotes)
+                    chunk.start_line,
+                    chunk.end_line,
+                    chunk.text,
+                    signals_json,
+                    tags_str,
+                    record.summary,
+                    record.language,
+                    record.license,
+                    hash,
+                    now,
+                ])?;
+                stats.inserted += 1;
+                kept.push(chunk.clone());
+            }
+
+            if !kept.is_empty() {
+                inserted.push(BrainRecord {
+                    chunks: kept,
+                    ..record.clone()
+                });
             }
         }
 
-        Ok(records.len())
+        Ok((stats, inserted))
+    }
+
+    /// Insert a large batch with the FTS triggers dropped, then rebuild the
+    /// index once. Must run inside a transaction so the triggers come back
+    /// even if an insert fails.
+    fn insert_chunks_bulk(
+        conn: &Connection,
+        records: &[BrainRecord],
+    ) -> Result<(SaveStats, Vec<BrainRecord>)> {
+        conn.execute_batch(
+            "DROP TRIGGER IF EXISTS chunks_ai;
+             DROP TRIGGER IF EXISTS chunks_ad;",
+        )?;
+        let inserted = Self::insert_chunks(conn, records)?;
+        conn.execute("INSERT INTO chunks_fts(chunks_fts) VALUES('rebuild')", [])?;
+        conn.execute_batch(FTS_TRIGGERS_SQL)?;
+        Ok(inserted)
     }
 
     /// Search the brain using FTS5
@@ -313,7 +872,7 @@ impl BrainStorage {
 
         let results = stmt.query_map(params![query, limit as i64], |row| {
             let tags_str: String = row.get(6)?;
-            let tags: Vec<String> = tags_str.split(',').map(|s| s.to_string()).collect();
+            let tags = crate::brain::split_tags(&tags_str);
 
             Ok(SearchResult {
                 chunk_id: row.get(0)?,
@@ -339,38 +898,35 @@ impl BrainStorage {
         &self,
         query: &str,
         limit: usize,
-        record_type: Option<&str>,
-        language: Option<&str>,
-        tags: &[String],
-        source_id: Option<&str>,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        let key = SearchKey {
+            query: query.to_string(),
+            limit,
+            filters: filters.clone(),
+        };
+        if let Some(results) = self.cache().get(&key) {
+            return Ok(results);
+        }
+
+        let results = self.search_filtered_uncached(query, limit, filters)?;
+        self.cache().put(key, results.clone());
+        Ok(results)
+    }
+
+    fn search_filtered_uncached(
+        &self,
+        query: &str,
+        limit: usize,
+        filters: &SearchFilters,
     ) -> Result<Vec<SearchResult>> {
         let conn = Connection::open(&self.sqlite_path)?;
 
         let mut conditions = vec!["chunks_fts MATCH ?1".to_string()];
-        let mut params: Vec<rusqlite::types::Value> = Vec::new();
-        params.push(rusqlite::types::Value::Text(query.to_string()));
-        let mut next_idx = 2;
-
-        if let Some(t) = record_type {
-            conditions.push(format!("c.content_type = ?{}", next_idx));
-            params.push(rusqlite::types::Value::Text(t.to_string()));
-            next_idx += 1;
-        }
-        if let Some(l) = language {
-            conditions.push(format!("c.language = ?{}", next_idx));
-            params.push(rusqlite::types::Value::Text(l.to_string()));
-            next_idx += 1;
-        }
-        if let Some(source) = source_id {
-            conditions.push(format!("c.source_id = ?{}", next_idx));
-            params.push(rusqlite::types::Value::Text(source.to_string()));
-            next_idx += 1;
-        }
-        for tag in tags {
-            conditions.push(format!("c.tags LIKE ?{}", next_idx));
-            params.push(rusqlite::types::Value::Text(format!("%{}%", tag)));
-            next_idx += 1;
-        }
+        let mut params = vec![rusqlite::types::Value::Text(query.to_string())];
+        let (filter_conditions, filter_params) = Self::filter_conditions(filters, 2);
+        conditions.extend(filter_conditions);
+        params.extend(filter_params);
 
         let where_clause = conditions.join(" AND ");
 
@@ -417,56 +973,312 @@ impl BrainStorage {
         Ok(search_results)
     }
 
-    /// Map a row to SearchResult (helper for search methods)
-    fn map_search_row(row: &rusqlite::Row) -> rusqlite::Result<SearchResult> {
-        let tags_str: String = row.get(6)?;
-        let tags: Vec<String> = tags_str.split(',').map(|s| s.to_string()).collect();
+    /// Search FTS5 restricted to chunks with any of `signals` and any of `content_types`
+    ///
+    /// Empty slices don't filter. Ranking is the same as `search_filtered`.
+    pub fn search_with_filter(
+        &self,
+        query: &str,
+        signals: &[Signal],
+        content_types: &[ContentType],
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filters = SearchFilters {
+            signals: signals.iter().map(Signal::to_string).collect(),
+            content_types: content_types.iter().map(ContentType::to_string).collect(),
+            ..Default::default()
+        };
+        self.search_filtered(query, limit, &filters)
+    }
 
-        Ok(SearchResult {
-            chunk_id: row.get(0)?,
-            source_id: row.get(1)?,
-            path: row.get(2)?,
-            content_type: row.get(3)?,
-            snippet: row.get(4)?,
-            score: row.get::<_, f64>(5)?.abs(),
-            tags,
-        })
+    /// Chunks carrying `signal`, in storage order
+    pub fn search_by_signal(&self, signal: &Signal, limit: usize) -> Result<Vec<SearchResult>> {
+        let filters = SearchFilters {
+            signals: vec![signal.to_string()],
+            ..Default::default()
+        };
+        self.list_filtered(limit, &filters)
     }
 
-    /// Get statistics
-    pub async fn stats(&self) -> Result<BrainStats> {
-        let mut stats = BrainStats::default();
+    /// Chunks matching `filters` without a text query, in storage order
+    ///
+    /// Every result scores 1.0; the snippet is the start of the chunk.
+    pub fn list_filtered(
+        &self,
+        limit: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        let conn = Connection::open(&self.sqlite_path)?;
 
-        // File sizes
-        if self.jsonl_path.exists() {
-            stats.jsonl_size_bytes = fs::metadata(&self.jsonl_path).await?.len();
-        }
-        if self.sqlite_path.exists() {
-            stats.sqlite_size_bytes = fs::metadata(&self.sqlite_path).await?.len();
-        }
+        let (conditions, mut params) = Self::filter_conditions(filters, 1);
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT c.chunk_id, c.source_id, c.path, c.content_type,
+                    substr(c.text, 1, {}) as snippet,
+                    1.0 as score,
+                    c.tags
+            FROM brain_chunks c
+            {}
+            ORDER BY c.rowid
+            LIMIT ?{}",
+            LIST_SNIPPET_CHARS,
+            where_clause,
+            params.len() + 1
+        );
+        params.push(rusqlite::types::Value::Integer(limit as i64));
 
-        // Query SQLite for counts
-        if self.sqlite_path.exists() {
-            let conn = Connection::open(&self.sqlite_path)?;
+        let mut stmt = conn.prepare(&sql)?;
+        let results = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| {
+                Self::map_search_row(row)
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(results)
+    }
 
-            // Total sources
-            let sources: i64 = conn
-                .query_row("SELECT COUNT(*) FROM sources", [], |row| row.get(0))
-                .unwrap_or(0);
-            stats.total_sources = sources as usize;
+    /// Scan chunk text with a regular expression, bypassing FTS
+    ///
+    /// For patterns FTS5 can't express. The pattern is validated up front and
+    /// compiled under a size limit. Results are ordered by match count, and the
+    /// score is that count.
+    pub fn search_regex(
+        &self,
+        pattern: &str,
+        limit: usize,
+        filters: &SearchFilters,
+    ) -> Result<Vec<SearchResult>> {
+        let re = compile_search_regex(pattern)?;
+        let conn = Connection::open(&self.sqlite_path)?;
 
-            // Total chunks
-            let chunks: i64 = conn
-                .query_row("SELECT COUNT(*) FROM brain_chunks", [], |row| row.get(0))
-                .unwrap_or(0);
-            stats.total_chunks = chunks as usize;
+        let (conditions, params) = Self::filter_conditions(filters, 1);
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT c.chunk_id, c.source_id, c.path, c.content_type, c.text, c.tags
+            FROM brain_chunks c
+            {}
+            ORDER BY c.rowid",
+            where_clause
+        );
 
-            // Count JSONL records
-            if self.jsonl_path.exists() {
-                let file = std::fs::File::open(&self.jsonl_path)?;
-                let reader = BufReader::new(file);
-                stats.total_records = reader.lines().count();
-            }
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            ))
+        })?;
+
+        let mut results = vec![];
+        for row in rows {
+            let (chunk_id, source_id, path, content_type, text, tags) = row?;
+            let Some(first) = re.find(&text) else {
+                continue;
+            };
+            results.push(SearchResult {
+                chunk_id,
+                source_id,
+                path,
+                content_type,
+                snippet: regex_snippet(&text, first.start(), first.end()),
+                score: re.find_iter(&text).count() as f64,
+                tags: crate::brain::split_tags(&tags),
+            });
+        }
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// SQL conditions (over `brain_chunks c`) and parameters for search filters,
+    /// numbering placeholders from `first_idx`
+    fn filter_conditions(
+        filters: &SearchFilters,
+        first_idx: usize,
+    ) -> (Vec<String>, Vec<rusqlite::types::Value>) {
+        let mut conditions = vec![];
+        let mut params: Vec<rusqlite::types::Value> = Vec::new();
+        let mut next_idx = first_idx;
+
+        if let Some(t) = &filters.record_type {
+            conditions.push(format!("c.content_type = ?{}", next_idx));
+            params.push(rusqlite::types::Value::Text(t.to_string()));
+            next_idx += 1;
+        }
+        if !filters.content_types.is_empty() {
+            let placeholders: Vec<String> = (0..filters.content_types.len())
+                .map(|i| format!("?{}", next_idx + i))
+                .collect();
+            conditions.push(format!("c.content_type IN ({})", placeholders.join(", ")));
+            for content_type in &filters.content_types {
+                params.push(rusqlite::types::Value::Text(content_type.to_string()));
+            }
+            next_idx += filters.content_types.len();
+        }
+        if let Some(l) = &filters.language {
+            conditions.push(format!("c.language = ?{}", next_idx));
+            params.push(rusqlite::types::Value::Text(l.to_string()));
+            next_idx += 1;
+        }
+        if let Some(source) = &filters.source_id {
+            conditions.push(format!("c.source_id = ?{}", next_idx));
+            params.push(rusqlite::types::Value::Text(source.to_string()));
+            next_idx += 1;
+        }
+        if !filters.signals.is_empty() {
+            // `signals` holds the JSON array, e.g. ["state_machine","contract_lock"]
+            let any: Vec<String> = (0..filters.signals.len())
+                .map(|i| format!("c.signals LIKE ?{}", next_idx + i))
+                .collect();
+            conditions.push(format!("({})", any.join(" OR ")));
+            for signal in &filters.signals {
+                params.push(rusqlite::types::Value::Text(format!("%\"{}\"%", signal)));
+            }
+            next_idx += filters.signals.len();
+        }
+        for tag in &filters.tags {
+            conditions.push(format!("c.tags LIKE ?{}", next_idx));
+            params.push(rusqlite::types::Value::Text(format!("%{}%", tag)));
+            next_idx += 1;
+        }
+
+        (conditions, params)
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, SearchCache> {
+        // A poisoned cache only holds stale results; recover and keep going
+        self.search_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Drop cached search results after the store changes
+    fn invalidate_search_cache(&self) {
+        self.cache().clear();
+    }
+
+    /// Map a row to SearchResult (helper for search methods)
+    fn map_search_row(row: &rusqlite::Row) -> rusqlite::Result<SearchResult> {
+        let tags_str: String = row.get(6)?;
+        let tags = crate::brain::split_tags(&tags_str);
+
+        Ok(SearchResult {
+            chunk_id: row.get(0)?,
+            source_id: row.get(1)?,
+            path: row.get(2)?,
+            content_type: row.get(3)?,
+            snippet: row.get(4)?,
+            score: row.get::<_, f64>(5)?.abs(),
+            tags,
+        })
+    }
+
+    /// Source → content type → file tree of the pack, sorted by name at each level
+    pub async fn topology(&self) -> Result<BrainTopology> {
+        use std::collections::BTreeMap;
+
+        if !self.sqlite_path.exists() {
+            return Ok(BrainTopology::default());
+        }
+        let conn = Connection::open(&self.sqlite_path)?;
+        let mut stmt = conn.prepare(
+            "SELECT source_id, content_type, path, COUNT(*) FROM brain_chunks
+             GROUP BY source_id, content_type, path",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        // Legacy rows may spell a content type differently, so group after normalizing
+        let mut tree: BTreeMap<String, BTreeMap<String, BTreeMap<String, usize>>> = BTreeMap::new();
+        for row in rows {
+            let (source_id, content_type, path, count) = row?;
+            *tree
+                .entry(source_id)
+                .or_default()
+                .entry(ContentType::from_db_str(&content_type).to_string())
+                .or_default()
+                .entry(path)
+                .or_default() += count as usize;
+        }
+
+        let sources = tree
+            .into_iter()
+            .map(|(source_id, types)| {
+                let content_types: Vec<TopologyContentType> = types
+                    .into_iter()
+                    .map(|(content_type, files)| {
+                        let files: Vec<TopologyFile> = files
+                            .into_iter()
+                            .map(|(path, chunks)| TopologyFile { path, chunks })
+                            .collect();
+                        TopologyContentType {
+                            content_type,
+                            chunks: files.iter().map(|f| f.chunks).sum(),
+                            files,
+                        }
+                    })
+                    .collect();
+                TopologySource {
+                    source_id,
+                    chunks: content_types.iter().map(|t| t.chunks).sum(),
+                    content_types,
+                }
+            })
+            .collect();
+
+        Ok(BrainTopology { sources })
+    }
+
+    /// Get statistics
+    pub async fn stats(&self) -> Result<BrainStats> {
+        let mut stats = BrainStats::default();
+
+        // File sizes
+        if self.jsonl_path.exists() {
+            stats.jsonl_size_bytes = fs::metadata(&self.jsonl_path).await?.len();
+        }
+        if self.sqlite_path.exists() {
+            stats.sqlite_size_bytes = fs::metadata(&self.sqlite_path).await?.len();
+        }
+
+        // Query SQLite for counts
+        if self.sqlite_path.exists() {
+            let conn = Connection::open(&self.sqlite_path)?;
+
+            // Total sources
+            let sources: i64 = conn
+                .query_row("SELECT COUNT(*) FROM sources", [], |row| row.get(0))
+                .unwrap_or(0);
+            stats.total_sources = sources as usize;
+
+            // Total chunks
+            let chunks: i64 = conn
+                .query_row("SELECT COUNT(*) FROM brain_chunks", [], |row| row.get(0))
+                .unwrap_or(0);
+            stats.total_chunks = chunks as usize;
+
+            // Count JSONL records
+            if self.jsonl_path.exists() {
+                stats.total_records = count_jsonl_records(&self.jsonl_path)?;
+            }
 
             // By content type
             let mut stmt = conn
@@ -498,6 +1310,29 @@ impl BrainStorage {
                 stats.by_license.insert(license, count as usize);
             }
 
+            // By source (chunk counts)
+            let mut stmt =
+                conn.prepare("SELECT source_id, COUNT(*) FROM brain_chunks GROUP BY source_id")?;
+            let results = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?;
+            for (source_id, count) in results.flatten() {
+                stats.by_source.insert(source_id, count as usize);
+            }
+
+            // By signal: `signals` holds a JSON array, so parse each distinct value
+            let mut stmt =
+                conn.prepare("SELECT signals, COUNT(*) FROM brain_chunks GROUP BY signals")?;
+            let results = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?;
+            for (signals_json, count) in results.flatten() {
+                let signals: Vec<Signal> = serde_json::from_str(&signals_json).unwrap_or_default();
+                for signal in signals {
+                    *stats.by_signal.entry(signal.to_string()).or_insert(0) += count as usize;
+                }
+            }
+
             // Last updated
             let last: Option<String> = conn
                 .query_row("SELECT MAX(fetched_at) FROM sources", [], |row| row.get(0))
@@ -560,6 +1395,17 @@ impl BrainStorage {
         Ok(())
     }
 
+    /// Commit SHA a source was last harvested at (see `set_harvested_commit`)
+    pub fn harvested_commit(&self, source_id: &str) -> Option<String> {
+        self.get_meta(&format!("harvest_commit:{}", source_id))
+    }
+
+    /// Record the commit SHA a source was harvested at, the base for the next
+    /// incremental harvest
+    pub fn set_harvested_commit(&self, source_id: &str, commit: &str) -> Result<()> {
+        self.set_meta(&format!("harvest_commit:{}", source_id), commit)
+    }
+
     /// Get the commit/fingerprint for a source
     pub fn get_source_commit(&self, source_id: &str) -> Option<String> {
         if !self.sqlite_path.exists() {
@@ -577,6 +1423,7 @@ impl BrainStorage {
 
     /// Delete all data for a source (chunks + source row)
     pub fn delete_source(&self, source_id: &str) -> Result<usize> {
+        self.invalidate_search_cache();
         let conn = Connection::open(&self.sqlite_path)?;
 
         // Delete chunks first (foreign key)
@@ -632,31 +1479,69 @@ impl BrainStorage {
             let ext = match options.format {
                 ExportFormat::Jsonl => "jsonl",
                 ExportFormat::Markdown => "md",
+                ExportFormat::Html => "html",
             };
             std::env::current_dir()
                 .unwrap()
                 .join(format!("brainpack_export.{}", ext))
         });
 
-        match options.format {
-            ExportFormat::Jsonl => self.export_jsonl(&output_path, options).await,
-            ExportFormat::Markdown => self.export_markdown(&output_path, options).await,
+        let counts = match options.format {
+            ExportFormat::Jsonl => self.export_jsonl(&output_path, options).await?,
+            ExportFormat::Markdown => self.export_markdown(&output_path, options).await?,
+            ExportFormat::Html => self.export_html(&output_path, options).await?,
+        };
+
+        if options.manifest {
+            let manifest = ExportManifest {
+                file: output_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                format: options.format.clone(),
+                records: counts.records,
+                chunks: counts.chunks,
+                include_source_ids: options.include_source_ids,
+                limit: match options.format {
+                    ExportFormat::Jsonl => None,
+                    _ => Some(options.limit).filter(|l| *l > 0),
+                },
+                filter: options.filter.clone(),
+                tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                created_at: chrono::Utc::now(),
+                sha256: crate::evidence::content_hash(&fs::read(&output_path).await?),
+            };
+            fs::write(
+                export_manifest_path(&output_path),
+                serde_json::to_string_pretty(&manifest)?,
+            )
+            .await
+            .context("Failed to write export manifest")?;
         }
+
+        Ok(output_path.to_string_lossy().to_string())
     }
 
-    async fn export_jsonl(&self, output_path: &PathBuf, options: &ExportOptions) -> Result<String> {
-        let mut output = std::fs::File::create(output_path)?;
+    async fn export_jsonl(
+        &self,
+        output_path: &PathBuf,
+        options: &ExportOptions,
+    ) -> Result<ExportCounts> {
+        let mut output = BufWriter::new(std::fs::File::create(output_path)?);
         let conn = Connection::open(&self.sqlite_path)?;
 
-        // Query all chunks ordered by source and path to group them
-        let mut stmt = conn.prepare(
-            "SELECT source_id, path, content_type, summary, language, license, 
-                    chunk_id, start_line, end_line, text, signals, tags
-             FROM brain_chunks 
-             ORDER BY source_id, path, start_line",
-        )?;
+        // Query matching chunks ordered by source and path to group them
+        let (where_clause, params) = options.filter.where_clause("c");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT c.source_id, c.path, c.content_type, c.summary, c.language, c.license,
+                    c.chunk_id, c.start_line, c.end_line, c.text, c.signals, c.tags
+             FROM brain_chunks c
+             {}
+             ORDER BY c.source_id, c.path, c.start_line",
+            where_clause
+        ))?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
             Ok((
                 row.get::<_, String>(0)?,  // source_id
                 row.get::<_, String>(1)?,  // path
@@ -674,6 +1559,7 @@ impl BrainStorage {
         })?;
 
         let mut current_record: Option<BrainRecord> = None;
+        let mut counts = ExportCounts::default();
 
         for row in rows {
             let (
@@ -704,7 +1590,10 @@ impl BrainStorage {
                     if !options.include_source_ids {
                         record.source_id = String::new();
                     }
-                    writeln!(output, "{}", serde_json::to_string(&record)?)?;
+                    serde_json::to_writer(&mut output, &record)?;
+                    output.write_all(b"\n")?;
+                    counts.records += 1;
+                    counts.chunks += record.chunks.len();
                 }
 
                 // Parse content type (map DB string to ContentType)
@@ -714,11 +1603,7 @@ impl BrainStorage {
                 // Parse signals and tags
                 let signals: Vec<crate::brain::Signal> =
                     serde_json::from_str(&signals_json).unwrap_or_default();
-                let tags: Vec<String> = tags_str
-                    .split(',')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect();
+                let tags = crate::brain::split_tags(&tags_str);
 
                 // Start new record
                 current_record = Some(BrainRecord {
@@ -751,45 +1636,32 @@ impl BrainStorage {
             if !options.include_source_ids {
                 record.source_id = String::new();
             }
-            writeln!(output, "{}", serde_json::to_string(&record)?)?;
+            serde_json::to_writer(&mut output, &record)?;
+            output.write_all(b"\n")?;
+            counts.records += 1;
+            counts.chunks += record.chunks.len();
         }
+        output.flush()?;
 
-        Ok(output_path.to_string_lossy().to_string())
+        Ok(counts)
     }
 
     async fn export_markdown(
         &self,
         output_path: &PathBuf,
         options: &ExportOptions,
-    ) -> Result<String> {
-        let mut content = String::from("# BrainPack Export\n\n");
+    ) -> Result<ExportCounts> {
+        let mut output = BufWriter::new(std::fs::File::create(output_path)?);
+        output.write_all(b"# BrainPack Export\n\n")?;
+        let mut counts = ExportCounts::default();
 
         let conn = Connection::open(&self.sqlite_path)?;
 
-        // Deterministic query: select one chunk per (source_id, path) using MIN(start_line)
-        // This ensures consistent output across runs
-        let limit_clause = if options.limit > 0 {
-            format!("LIMIT {}", options.limit)
-        } else {
-            String::new() // No limit
-        };
-
-        let query = format!(
-            "SELECT bc.source_id, bc.path, bc.content_type, bc.summary, bc.language, bc.license, bc.text, bc.signals
-             FROM brain_chunks bc
-             INNER JOIN (
-                 SELECT source_id, path, MIN(start_line) as min_start
-                 FROM brain_chunks
-                 GROUP BY source_id, path
-             ) sub ON bc.source_id = sub.source_id AND bc.path = sub.path AND bc.start_line = sub.min_start
-             ORDER BY bc.source_id, bc.path, bc.chunk_id
-             {}",
-            limit_clause
-        );
+        let (query, params) = preview_query(options.limit, &options.filter);
 
         let mut stmt = conn.prepare(&query)?;
 
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
             Ok((
                 row.get::<_, String>(0)?, // source_id
                 row.get::<_, String>(1)?, // path
@@ -810,45 +1682,138 @@ impl BrainStorage {
 
             if options.include_source_ids && source_id != current_source {
                 current_source = source_id.clone();
-                content.push_str(&format!("\n## Source: {}\n\n", current_source));
+                write!(output, "\n## Source: {}\n\n", current_source)?;
             }
 
-            content.push_str(&format!("### {}\n\n", path));
-            content.push_str(&format!(
+            write!(output, "### {}\n\n", path)?;
+            write!(
+                output,
                 "**Type**: {} | **Language**: {} | **License**: {}\n\n",
                 content_type.trim_matches('"'),
                 language,
                 license
-            ));
+            )?;
 
             let signals: Vec<crate::brain::Signal> =
                 serde_json::from_str(&signals_json).unwrap_or_default();
             if !signals.is_empty() {
-                content.push_str("**Signals**: ");
-                content.push_str(
-                    &signals
-                        .iter()
-                        .map(|s| format!("{:?}", s))
-                        .collect::<Vec<_>>()
-                        .join(", "),
-                );
-                content.push_str("\n\n");
+                output.write_all(b"**Signals**: ")?;
+                for (i, signal) in signals.iter().enumerate() {
+                    if i > 0 {
+                        output.write_all(b", ")?;
+                    }
+                    write!(output, "{:?}", signal)?;
+                }
+                output.write_all(b"\n\n")?;
             }
 
-            content.push_str(&format!("{}\n\n", summary));
+            write!(output, "{}\n\n", summary)?;
+            // One entry per record, showing its first chunk
+            counts.records += 1;
+            counts.chunks += 1;
 
             // Show first chunk (UTF-8 safe truncation)
-            content.push_str("```\n");
-            let truncated: String = text.chars().take(300).collect();
-            content.push_str(&truncated);
-            if text.chars().count() > 300 {
-                content.push_str("\n... (truncated)");
+            output.write_all(b"```\n")?;
+            let end = text.char_indices().nth(300).map_or(text.len(), |(i, _)| i);
+            output.write_all(&text.as_bytes()[..end])?;
+            if end < text.len() {
+                output.write_all(b"\n... (truncated)")?;
             }
-            content.push_str("\n```\n\n");
+            output.write_all(b"\n```\n\n")?;
         }
 
-        tokio::fs::write(output_path, &content).await?;
-        Ok(output_path.to_string_lossy().to_string())
+        output.flush()?;
+        Ok(counts)
+    }
+
+    async fn export_html(
+        &self,
+        output_path: &PathBuf,
+        options: &ExportOptions,
+    ) -> Result<ExportCounts> {
+        use crate::brain::html::{escape, highlight, FOOTER, HEADER};
+
+        let mut output = BufWriter::new(std::fs::File::create(output_path)?);
+        output.write_all(HEADER.as_bytes())?;
+
+        let conn = Connection::open(&self.sqlite_path)?;
+        let (query, params) = preview_query(options.limit, &options.filter);
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok((
+                row.get::<_, String>(0)?, // source_id
+                row.get::<_, String>(1)?, // path
+                row.get::<_, String>(2)?, // content_type
+                row.get::<_, String>(3)?, // summary
+                row.get::<_, String>(4)?, // language
+                row.get::<_, String>(5)?, // license
+                row.get::<_, String>(6)?, // text
+                row.get::<_, String>(7)?, // signals
+            ))
+        })?;
+
+        // Sources are numbered instead of named unless IDs were requested
+        let mut current_source: Option<String> = None;
+        let mut source_count = 0;
+        let mut counts = ExportCounts::default();
+
+        for row in rows {
+            let (source_id, path, content_type, summary, language, license, text, signals_json) =
+                row?;
+
+            if current_source.as_deref() != Some(source_id.as_str()) {
+                if current_source.is_some() {
+                    output.write_all(b"</section>\n")?;
+                }
+                source_count += 1;
+                let label = if options.include_source_ids {
+                    escape(&source_id)
+                } else {
+                    format!("#{}", source_count)
+                };
+                writeln!(output, "<section>\n<h2>Source {}</h2>", label)?;
+                current_source = Some(source_id);
+            }
+
+            writeln!(output, "<article>\n<h3>{}</h3>", escape(&path))?;
+            counts.records += 1;
+            counts.chunks += 1;
+            writeln!(
+                output,
+                "<p class=\"meta\">{} · {} · {}</p>",
+                escape(content_type.trim_matches('"')),
+                escape(&language),
+                escape(&license)
+            )?;
+
+            let signals: Vec<crate::brain::Signal> =
+                serde_json::from_str(&signals_json).unwrap_or_default();
+            if !signals.is_empty() {
+                output.write_all(b"<p>")?;
+                for signal in &signals {
+                    write!(output, "<span class=\"signal\">{:?}</span>", signal)?;
+                }
+                output.write_all(b"</p>\n")?;
+            }
+
+            writeln!(output, "<p>{}</p>", escape(&summary))?;
+
+            // First chunk only (UTF-8 safe truncation)
+            let end = text.char_indices().nth(300).map_or(text.len(), |(i, _)| i);
+            write!(output, "<pre><code>{}", highlight(&text[..end]))?;
+            if end < text.len() {
+                output.write_all(b"\n... (truncated)")?;
+            }
+            output.write_all(b"</code></pre>\n</article>\n")?;
+        }
+
+        if current_source.is_some() {
+            output.write_all(b"</section>\n")?;
+        }
+        output.write_all(FOOTER.as_bytes())?;
+        output.flush()?;
+
+        Ok(counts)
     }
 
     /// Import the core brainpack from embedded data
@@ -857,6 +1822,7 @@ impl BrainStorage {
         // Embedded core brainpack JSONL
         const CORE_JSONL: &str = include_str!("../../brainpacks/core/core.jsonl");
 
+        self.invalidate_search_cache();
         let mut stats = ImportStats::default();
         let desired_fingerprint = Self::core_fingerprint();
 
@@ -918,10 +1884,8 @@ impl BrainStorage {
 
             let signals_str = serde_json::to_string(&signals)?;
             let tags_str = if let Some(arr) = tags.as_array() {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .collect::<Vec<_>>()
-                    .join(",")
+                let tags: Vec<&str> = arr.iter().filter_map(|v| v.as_str()).collect();
+                crate::brain::normalize_tags(&tags).join(",")
             } else {
                 String::new()
             };
@@ -934,10 +1898,11 @@ impl BrainStorage {
                     let start_line = chunk["start_line"].as_u64().unwrap_or(1) as u32;
                     let end_line = chunk["end_line"].as_u64().unwrap_or(1) as u32;
 
+                    let hash = crate::brain::chunk_content_hash(source_id, title, start_line, text);
                     conn.execute(
                         "INSERT OR REPLACE INTO brain_chunks 
-                        (chunk_id, source_id, path, content_type, start_line, end_line, text, signals, tags, summary, language, license)
-                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        (chunk_id, source_id, path, content_type, start_line, end_line, text, signals, tags, summary, language, license, content_hash, harvested_at)
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
                         params![
                             chunk_id,
                             source_id,
@@ -951,6 +1916,7 @@ impl BrainStorage {
                             summary,
                             "mixed", // Core brainpack language
                             "MIT",   // Core brainpack license
+                            hash,
                         ],
                     )?;
                     stats.inserted += 1;
@@ -967,18 +1933,113 @@ impl BrainStorage {
         Ok(stats)
     }
 
+    /// Rebuild the FTS index from `brain_chunks`, for when it has drifted
+    /// out of sync; returns the number of chunks indexed
+    pub fn reindex(&self) -> Result<usize> {
+        self.invalidate_search_cache();
+        let conn = Connection::open(&self.sqlite_path)?;
+        conn.execute("INSERT INTO chunks_fts(chunks_fts) VALUES('rebuild')", [])?;
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM brain_chunks", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Normalize content_type values left malformed by older versions
+    /// (quotes, whitespace, casing) so type filters match them again
+    pub async fn repair(&self) -> Result<RepairResult> {
+        let mut conn = Connection::open(&self.sqlite_path)?;
+        let tx = conn.transaction()?;
+
+        let values: Vec<String> = tx
+            .prepare("SELECT DISTINCT content_type FROM brain_chunks")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut result = RepairResult {
+            values_checked: values.len(),
+            ..Default::default()
+        };
+        for raw in values {
+            let normalized = crate::brain::ContentType::from_db_str(&raw).to_string();
+            if raw != normalized {
+                result.rows_fixed += tx.execute(
+                    "UPDATE brain_chunks SET content_type = ?1 WHERE content_type = ?2",
+                    params![normalized, raw],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        if result.rows_fixed > 0 {
+            self.invalidate_search_cache();
+        }
+        Ok(result)
+    }
+
     /// Compact the brain pack: rewrite JSONL from SQLite (dedup), run VACUUM
     pub async fn compact(&self) -> Result<CompactResult> {
-        use std::io::Write;
+        self.invalidate_search_cache();
+
+        let conn = Connection::open(&self.sqlite_path)?;
+        let result = self.rewrite_jsonl(&conn)?;
 
+        // Run VACUUM on SQLite
+        conn.execute("VACUUM", [])?;
+
+        Ok(result)
+    }
+
+    /// Delete harvested chunks older than `days`, along with sources left
+    /// without chunks, and rewrite the JSONL to match
+    ///
+    /// The embedded core brainpack is never expired. Returns the number of
+    /// chunks deleted.
+    pub fn purge_older_than(&self, days: u32) -> Result<usize> {
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
+
+        let mut conn = Connection::open(&self.sqlite_path)?;
+        let tx = conn.transaction()?;
+        let deleted = tx.execute(
+            "DELETE FROM brain_chunks WHERE source_id != ?1 AND harvested_at < ?2",
+            params![CORE_SOURCE_ID, cutoff],
+        )?;
+        tx.execute(
+            "DELETE FROM sources WHERE source_id != ?1
+             AND source_id NOT IN (SELECT DISTINCT source_id FROM brain_chunks)",
+            params![CORE_SOURCE_ID],
+        )?;
+        tx.commit()?;
+
+        if deleted > 0 {
+            self.invalidate_search_cache();
+            self.rewrite_jsonl(&conn)?;
+        }
+        Ok(deleted)
+    }
+
+    /// Harvested chunks (excluding the core brainpack) older than `days`
+    pub fn count_older_than(&self, days: u32) -> Result<usize> {
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
         let conn = Connection::open(&self.sqlite_path)?;
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM brain_chunks WHERE source_id != ?1 AND harvested_at < ?2",
+            params![CORE_SOURCE_ID, cutoff],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
 
+    /// Rewrite the JSONL from SQLite, one record per (source_id, path),
+    /// tagged with each source's harvested commit
+    fn rewrite_jsonl(&self, conn: &Connection) -> Result<CompactResult> {
         // Query all distinct records from SQLite, group by source_id + path
         let mut stmt = conn.prepare(
-            "SELECT source_id, path, content_type, summary, language, license, 
-                    chunk_id, start_line, end_line, text, signals, tags
-             FROM brain_chunks 
-             ORDER BY source_id, path, start_line",
+            "SELECT bc.source_id, bc.path, bc.content_type, bc.summary, bc.language, bc.license,
+                    bc.chunk_id, bc.start_line, bc.end_line, bc.text, bc.signals, bc.tags,
+                    COALESCE(s.\"commit\", 'compact')
+             FROM brain_chunks bc
+             LEFT JOIN sources s ON s.source_id = bc.source_id
+             ORDER BY bc.source_id, bc.path, bc.start_line",
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -995,6 +2056,7 @@ impl BrainStorage {
                 row.get::<_, String>(9)?,  // text
                 row.get::<_, String>(10)?, // signals
                 row.get::<_, String>(11)?, // tags
+                row.get::<_, String>(12)?, // commit
             ))
         })?;
 
@@ -1005,92 +2067,131 @@ impl BrainStorage {
         }
 
         // Rewrite JSONL file from scratch
-        let mut output = std::fs::File::create(&self.jsonl_path)?;
-        let mut current_record: Option<BrainRecord> = None;
-        let mut records_written = 0;
-        let chunks_count = all_rows.len();
-
-        for (
-            source_id,
-            path,
-            content_type_str,
-            summary,
-            language,
-            license,
-            chunk_id,
-            start_line,
-            end_line,
-            text,
-            signals_json,
-            tags_str,
-        ) in all_rows
-        {
-            // Check if we need to start a new record
-            let is_new_record = match &current_record {
-                Some(r) => r.source_id != source_id || r.path != path,
-                None => true,
-            };
+        self.replace_jsonl(|output| {
+            let mut current_record: Option<BrainRecord> = None;
+            let mut records_written = 0;
+            let chunks_count = all_rows.len();
 
-            if is_new_record {
-                // Write previous record if exists
-                if let Some(record) = current_record.take() {
-                    writeln!(output, "{}", serde_json::to_string(&record)?)?;
-                    records_written += 1;
-                }
-
-                // Parse content type (map DB string to ContentType)
-                let clean_type = content_type_str.trim_matches('"');
-                let content_type = crate::brain::ContentType::from_db_str(clean_type);
+            for (
+                source_id,
+                path,
+                content_type_str,
+                summary,
+                language,
+                license,
+                chunk_id,
+                start_line,
+                end_line,
+                text,
+                signals_json,
+                tags_str,
+                commit,
+            ) in all_rows
+            {
+                // Check if we need to start a new record
+                let is_new_record = match &current_record {
+                    Some(r) => r.source_id != source_id || r.path != path,
+                    None => true,
+                };
+
+                if is_new_record {
+                    // Write previous record if exists
+                    if let Some(record) = current_record.take() {
+                        writeln!(output, "{}", serde_json::to_string(&record)?)?;
+                        records_written += 1;
+                    }
 
-                // Parse signals and tags
-                let signals: Vec<crate::brain::Signal> =
-                    serde_json::from_str(&signals_json).unwrap_or_default();
-                let tags: Vec<String> = tags_str
-                    .split(',')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect();
+                    // Parse content type (map DB string to ContentType)
+                    let clean_type = content_type_str.trim_matches('"');
+                    let content_type = crate::brain::ContentType::from_db_str(clean_type);
+
+                    // Parse signals and tags
+                    let signals: Vec<crate::brain::Signal> =
+                        serde_json::from_str(&signals_json).unwrap_or_default();
+                    let tags = crate::brain::split_tags(&tags_str);
+
+                    // Start new record
+                    current_record = Some(BrainRecord {
+                        source_id: source_id.clone(),
+                        commit,
+                        license,
+                        language,
+                        path,
+                        content_type,
+                        signals,
+                        summary,
+                        chunks: Vec::new(),
+                        tags,
+                    });
+                }
 
-                // Start new record
-                current_record = Some(BrainRecord {
-                    source_id: source_id.clone(),
-                    commit: "compact".to_string(),
-                    license,
-                    language,
-                    path,
-                    content_type,
-                    signals,
-                    summary,
-                    chunks: Vec::new(),
-                    tags,
-                });
+                // Add chunk to current record
+                if let Some(record) = &mut current_record {
+                    record.chunks.push(crate::brain::ContentChunk {
+                        chunk_id,
+                        text,
+                        start_line,
+                        end_line,
+                    });
+                }
             }
 
-            // Add chunk to current record
-            if let Some(record) = &mut current_record {
-                record.chunks.push(crate::brain::ContentChunk {
-                    chunk_id,
-                    text,
-                    start_line,
-                    end_line,
-                });
+            // Write last record
+            if let Some(record) = current_record {
+                writeln!(output, "{}", serde_json::to_string(&record)?)?;
+                records_written += 1;
             }
-        }
 
-        // Write last record
-        if let Some(record) = current_record {
-            writeln!(output, "{}", serde_json::to_string(&record)?)?;
-            records_written += 1;
-        }
+            Ok(CompactResult {
+                records_written,
+                chunks_count,
+            })
+        })
+    }
+}
 
-        // Run VACUUM on SQLite
-        conn.execute("VACUUM", [])?;
+/// One chunk per (source_id, path) using MIN(start_line), so previews are
+/// deterministic across runs, restricted by `filter`
+fn preview_query(limit: usize, filter: &ExportFilter) -> (String, Vec<rusqlite::types::Value>) {
+    let limit_clause = if limit > 0 {
+        format!("LIMIT {}", limit)
+    } else {
+        String::new() // No limit
+    };
+    let (where_clause, params) = filter.where_clause("bc");
+
+    let query = format!(
+        "SELECT bc.source_id, bc.path, bc.content_type, bc.summary, bc.language, bc.license, bc.text, bc.signals
+             FROM brain_chunks bc
+             INNER JOIN (
+                 SELECT source_id, path, MIN(start_line) as min_start
+                 FROM brain_chunks
+                 GROUP BY source_id, path
+             ) sub ON bc.source_id = sub.source_id AND bc.path = sub.path AND bc.start_line = sub.min_start
+             {}
+             ORDER BY bc.source_id, bc.path, bc.chunk_id
+             {}",
+        where_clause, limit_clause
+    );
+    (query, params)
+}
 
-        Ok(CompactResult {
-            records_written,
-            chunks_count,
-        })
+/// Count non-blank JSONL records with a reused line buffer, so huge packs
+/// are never held in memory
+fn count_jsonl_records(path: &Path) -> Result<usize> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let mut line = Vec::new();
+    let mut count = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if !line.iter().all(u8::is_ascii_whitespace) {
+            count += 1;
+        }
     }
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -1102,4 +2203,894 @@ mod tests {
         let opts = ExportOptions::default();
         assert!(!opts.include_source_ids);
     }
+
+    fn record(source_id: &str, chunk_ids: &[&str]) -> BrainRecord {
+        BrainRecord {
+            source_id: source_id.to_string(),
+            commit: "abc".to_string(),
+            license: "MIT".to_string(),
+            language: "Rust".to_string(),
+            path: "src/lib.rs".to_string(),
+            content_type: crate::brain::ContentType::Code,
+            signals: vec![],
+            summary: "Test".to_string(),
+            chunks: chunk_ids
+                .iter()
+                .map(|id| crate::brain::ContentChunk {
+                    chunk_id: id.to_string(),
+                    text: format!("fn {}() {{}}", id),
+                    start_line: 1,
+                    end_line: 1,
+                })
+                .collect(),
+            tags: vec![],
+        }
+    }
+
+    fn source(source_id: &str) -> SourceMeta {
+        SourceMeta {
+            source_id: source_id.to_string(),
+            commit: "abc".to_string(),
+            license: "MIT".to_string(),
+            language: "Rust".to_string(),
+            fetched_at: chrono::Utc::now(),
+            files_count: 1,
+            chunks_count: 0,
+            stars: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_insert_matches_per_row_index() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let bulk = BrainStorage::new_for_test(temp.path().join("bulk")).unwrap();
+        let per_row = BrainStorage::new_for_test(temp.path().join("per_row")).unwrap();
+
+        let ids: Vec<String> = (0..BULK_INSERT_THRESHOLD + 200)
+            .map(|i| format!("chunk{}", i))
+            .collect();
+        let mut records = vec![];
+        for group in ids.chunks(100) {
+            let group: Vec<&str> = group.iter().map(String::as_str).collect();
+            let mut rec = record("src_a", &group);
+            for (i, chunk) in rec.chunks.iter_mut().enumerate() {
+                let word = if i % 7 == 0 { "needle" } else { "hay" };
+                chunk.text = format!("{} {} parser", chunk.chunk_id, word);
+            }
+            records.push(rec);
+        }
+
+        for storage in [&bulk, &per_row] {
+            storage.save_source(&source("src_a")).await.unwrap();
+        }
+        bulk.save_records(&records).await.unwrap();
+        let conn = Connection::open(&per_row.sqlite_path).unwrap();
+        BrainStorage::insert_chunks(&conn, &records).unwrap();
+
+        let filters = SearchFilters::default();
+        for query in ["needle", "hay", "parser", "chunk1000"] {
+            let mut a = bulk.search_filtered(query, 5000, &filters).unwrap();
+            let mut b = per_row.search_filtered(query, 5000, &filters).unwrap();
+            a.sort_by(|x, y| x.chunk_id.cmp(&y.chunk_id));
+            b.sort_by(|x, y| x.chunk_id.cmp(&y.chunk_id));
+            assert!(!a.is_empty(), "no results for {}", query);
+            assert_eq!(a.len(), b.len(), "result count for {}", query);
+            for (x, y) in a.iter().zip(&b) {
+                assert_eq!(x.chunk_id, y.chunk_id);
+                assert!((x.score - y.score).abs() < 1e-9);
+            }
+        }
+        let needles = bulk.search_filtered("needle", 5000, &filters).unwrap();
+        assert_eq!(needles.len(), ids.len().div_ceil(100) * 15);
+
+        // Triggers are restored: a small follow-up save is indexed per row
+        bulk.save_records(&[record("src_a", &["latecomer"])])
+            .await
+            .unwrap();
+        assert_eq!(
+            bulk.search_filtered("latecomer", 10, &filters)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_harvest_replaces_paths_and_keeps_counts_cumulative() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        let mut other = record("src_a", &["kept"]);
+        other.path = "src/main.rs".to_string();
+        storage
+            .save_harvest(
+                &source("src_a"),
+                &[record("src_a", &["old1", "old2"]), other],
+                Replace::Source,
+            )
+            .await
+            .unwrap();
+        let counts = |storage: &BrainStorage| -> (i64, i64) {
+            Connection::open(&storage.sqlite_path)
+                .unwrap()
+                .query_row(
+                    "SELECT files_count, chunks_count FROM sources WHERE source_id = 'src_a'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap()
+        };
+        assert_eq!(counts(&storage), (2, 3));
+
+        // An incremental update that changed src/lib.rs only
+        let mut delta = source("src_a");
+        delta.files_count = 1;
+        delta.chunks_count = 1;
+        storage
+            .save_harvest(&delta, &[record("src_a", &["new1"])], Replace::Paths)
+            .await
+            .unwrap();
+        assert_eq!(storage.get_source_chunk_count("src_a"), 2);
+        assert!(storage.search("old1", 10).unwrap().is_empty());
+        assert_eq!(storage.search("kept", 10).unwrap().len(), 1);
+        assert_eq!(storage.search("new1", 10).unwrap().len(), 1);
+        assert_eq!(counts(&storage), (2, 2));
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        assert!(!jsonl.contains("old1"));
+        assert!(jsonl.contains("new1") && jsonl.contains("kept"));
+
+        // A full re-harvest drops files that are gone
+        storage
+            .save_harvest(
+                &source("src_a"),
+                &[record("src_a", &["full"])],
+                Replace::Source,
+            )
+            .await
+            .unwrap();
+        assert!(storage.search("kept", 10).unwrap().is_empty());
+        assert_eq!(counts(&storage), (1, 1));
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        assert!(!jsonl.contains("kept"));
+
+        assert_eq!(storage.harvested_commit("src_a"), None);
+        storage.set_harvested_commit("src_a", "3f78685").unwrap();
+        assert_eq!(
+            storage.harvested_commit("src_a").as_deref(),
+            Some("3f78685")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reindex_restores_a_stale_fts_index() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage
+            .save_records(&[record("src_a", &["alpha", "beta"])])
+            .await
+            .unwrap();
+
+        Connection::open(&storage.sqlite_path)
+            .unwrap()
+            .execute(
+                "INSERT INTO chunks_fts(chunks_fts) VALUES('delete-all')",
+                [],
+            )
+            .unwrap();
+        assert!(storage.search("alpha", 10).unwrap().is_empty());
+
+        assert_eq!(storage.reindex().unwrap(), 2);
+        assert_eq!(storage.search("alpha", 10).unwrap().len(), 1);
+        assert_eq!(storage.search("beta", 10).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_save_records_skips_duplicate_content() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage.save_source(&source("src_b")).await.unwrap();
+
+        let first = storage
+            .save_records(&[record("src_a", &["one", "two"])])
+            .await
+            .unwrap();
+        assert_eq!(
+            first,
+            SaveStats {
+                inserted: 2,
+                skipped_duplicates: 0
+            }
+        );
+
+        // Same content under new chunk ids, plus a repeat inside the batch
+        let mut again = record("src_a", &["one_v2", "two_v2", "three", "three_v2"]);
+        for chunk in &mut again.chunks {
+            chunk.text = format!("fn {}() {{}}", chunk.chunk_id.trim_end_matches("_v2"));
+        }
+        let second = storage.save_records(&[again]).await.unwrap();
+        assert_eq!(
+            second,
+            SaveStats {
+                inserted: 1,
+                skipped_duplicates: 3
+            }
+        );
+
+        // The hash covers the source, so another repo's copy is kept
+        let mut copy = record("src_b", &["b_one"]);
+        copy.chunks[0].text = "fn one() {}".into();
+        let other = storage.save_records(&[copy]).await.unwrap();
+        assert_eq!(other.inserted, 1);
+
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        let rows: usize = conn
+            .query_row("SELECT COUNT(*) FROM brain_chunks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 4);
+        // Only inserted chunks reach the JSONL; a fully duplicate record adds no line
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        let chunks: Vec<usize> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str::<BrainRecord>(l).unwrap().chunks.len())
+            .collect();
+        assert_eq!(chunks, vec![2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_init_backfills_content_hash() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("brain");
+        let storage = BrainStorage::new_for_test(dir.clone()).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage
+            .save_records(&[record("src_a", &["legacy"])])
+            .await
+            .unwrap();
+
+        // Rows written before the column existed have no hash
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        conn.execute("UPDATE brain_chunks SET content_hash = NULL", [])
+            .unwrap();
+
+        let reopened = BrainStorage::new_for_test(dir).unwrap();
+        let hash: Option<String> = conn
+            .query_row("SELECT content_hash FROM brain_chunks", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(
+            hash.as_deref(),
+            Some(
+                crate::brain::chunk_content_hash("src_a", "src/lib.rs", 1, "fn legacy() {}")
+                    .as_str()
+            )
+        );
+        let index: usize = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'idx_brain_chunks_content_hash'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(index, 1);
+
+        let stats = reopened
+            .save_records(&[record("src_a", &["legacy_again"])])
+            .await
+            .unwrap();
+        assert_eq!(stats.inserted, 1);
+        let mut same = record("src_a", &["legacy_copy"]);
+        same.chunks[0].text = "fn legacy() {}".into();
+        assert_eq!(
+            reopened
+                .save_records(&[same])
+                .await
+                .unwrap()
+                .skipped_duplicates,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_purge_older_than_expires_stale_records() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("brain");
+        let storage = BrainStorage::new_for_test(dir.clone()).unwrap();
+        for id in ["src_old", "src_new", "core"] {
+            storage.save_source(&source(id)).await.unwrap();
+        }
+        storage
+            .save_records(&[
+                record("src_old", &["old1", "old2"]),
+                record("src_new", &["new1"]),
+                record("core", &["core1"]),
+            ])
+            .await
+            .unwrap();
+
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        let long_ago = chrono::Utc::now().timestamp() - 100 * 86_400;
+        conn.execute(
+            "UPDATE brain_chunks SET harvested_at = ? WHERE source_id IN ('src_old', 'core')",
+            [long_ago],
+        )
+        .unwrap();
+
+        assert_eq!(storage.count_older_than(90).unwrap(), 2);
+        assert_eq!(storage.count_older_than(365).unwrap(), 0);
+        assert_eq!(storage.purge_older_than(365).unwrap(), 0);
+        assert_eq!(storage.purge_older_than(90).unwrap(), 2);
+
+        let sources: Vec<String> = conn
+            .prepare("SELECT source_id FROM sources ORDER BY source_id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(sources, vec!["core", "src_new"]);
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        let kept: Vec<String> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str::<BrainRecord>(l).unwrap().source_id)
+            .collect();
+        assert_eq!(kept, vec!["core", "src_new"]);
+        assert!(storage.search("old1", 10).unwrap().is_empty());
+
+        // Rows from before the column existed take their source's fetch time
+        conn.execute("UPDATE brain_chunks SET harvested_at = NULL", [])
+            .unwrap();
+        conn.execute(
+            "UPDATE sources SET fetched_at = '2020-01-01T00:00:00+00:00' WHERE source_id = 'src_new'",
+            [],
+        )
+        .unwrap();
+        BrainStorage::new_for_test(dir).unwrap();
+        let harvested_at: i64 = conn
+            .query_row(
+                "SELECT harvested_at FROM brain_chunks WHERE source_id = 'src_new'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(harvested_at, 1_577_836_800);
+    }
+
+    #[tokio::test]
+    async fn test_search_regex_matches_chunk_text() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage.save_source(&source("src_b")).await.unwrap();
+
+        let mut a = record("src_a", &["a1", "a2", "a3"]);
+        a.chunks[0].text = "fn transition(&mut self) -> Result<State> { todo!() }".into();
+        a.chunks[1].text = "let x = 1; // no match here".into();
+        a.chunks[2].text = "fn apply() -> Result<()> {}\nfn undo() -> Result<()> {}".into();
+        let mut b = record("src_b", &["b1"]);
+        b.chunks[0].text = "pub fn run() -> Result<()> { Ok(()) }".into();
+        storage.save_records(&[a, b]).await.unwrap();
+
+        let pattern = r"fn \w+\([^)]*\) -> Result<";
+        let results = storage
+            .search_regex(pattern, 10, &SearchFilters::default())
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk_id.as_str()).collect();
+        // Two matches in a3 rank it first; the rest keep storage order
+        assert_eq!(ids, vec!["a3", "a1", "b1"]);
+        assert_eq!(results[0].score, 2.0);
+        assert!(results[1]
+            .snippet
+            .contains("→fn transition(&mut self) -> Result<←"));
+
+        let filtered = storage
+            .search_regex(
+                pattern,
+                10,
+                &SearchFilters {
+                    source_id: Some("src_b".into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].chunk_id, "b1");
+
+        assert_eq!(
+            storage
+                .search_regex(pattern, 1, &SearchFilters::default())
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(storage
+            .search_regex("fn (", 10, &SearchFilters::default())
+            .is_err());
+        assert!(storage
+            .search_regex(
+                &"a".repeat(MAX_REGEX_LEN + 1),
+                10,
+                &SearchFilters::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_regex_snippet_trims_on_char_boundaries() {
+        let text = format!("{}needle{}", "é".repeat(100), "ü".repeat(100));
+        let start = text.find("needle").unwrap();
+        let snippet = regex_snippet(&text, start, start + "needle".len());
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.contains("→needle←"));
+        assert_eq!(snippet.matches('é').count(), REGEX_SNIPPET_CONTEXT);
+        assert_eq!(regex_snippet("abc", 1, 2), "a→b←c");
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_leaves_both_stores_untouched() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage
+            .save_records(&[record("src_a", &["existing"])])
+            .await
+            .unwrap();
+        let jsonl_before = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+
+        // Inject a failure on the second chunk of the batch
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        conn.execute_batch(
+            "CREATE TRIGGER fail_poison BEFORE INSERT ON brain_chunks
+             WHEN new.chunk_id = 'poison'
+             BEGIN SELECT RAISE(ABORT, 'injected failure'); END;",
+        )
+        .unwrap();
+
+        let err = storage
+            .save_records(&[record("src_a", &["fresh"]), record("src_a", &["poison"])])
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("injected failure"));
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM brain_chunks", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            std::fs::read_to_string(&storage.jsonl_path).unwrap(),
+            jsonl_before
+        );
+        assert!(storage
+            .search_filtered("fresh", 10, &SearchFilters::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_harvest_leaves_the_jsonl_byte_identical() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage
+            .save_harvest(
+                &source("src_a"),
+                &[record("src_a", &["existing"])],
+                Replace::Source,
+            )
+            .await
+            .unwrap();
+        let jsonl_before = std::fs::read(&storage.jsonl_path).unwrap();
+
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        conn.execute_batch(
+            "CREATE TRIGGER fail_poison BEFORE INSERT ON brain_chunks
+             WHEN new.chunk_id = 'poison'
+             BEGIN SELECT RAISE(ABORT, 'injected failure'); END;",
+        )
+        .unwrap();
+
+        for replace in [Replace::Source, Replace::Paths] {
+            let err = storage
+                .save_harvest(
+                    &source("src_a"),
+                    &[record("src_a", &["fresh", "poison"])],
+                    replace,
+                )
+                .await
+                .unwrap_err();
+            assert!(format!("{:#}", err).contains("injected failure"));
+            assert_eq!(std::fs::read(&storage.jsonl_path).unwrap(), jsonl_before);
+            assert_eq!(storage.search("existing", 10).unwrap().len(), 1);
+        }
+        assert!(!storage.jsonl_path.with_extension("jsonl.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn test_harvest_keeps_other_sources_jsonl_lines() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        let mut other = source("src_b");
+        other.commit = "b0b0b0b".to_string();
+        let mut other_record = record("src_b", &["theirs"]);
+        other_record.commit = other.commit.clone();
+        storage
+            .save_harvest(&other, &[other_record], Replace::Source)
+            .await
+            .unwrap();
+        let other_line = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+
+        for _ in 0..2 {
+            storage
+                .save_harvest(
+                    &source("src_a"),
+                    &[record("src_a", &["ours"])],
+                    Replace::Source,
+                )
+                .await
+                .unwrap();
+        }
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        assert!(jsonl.starts_with(&other_line));
+        assert_eq!(jsonl.matches("fn ours()").count(), 1);
+
+        // Compaction keeps each source's harvested commit too
+        storage.compact().await.unwrap();
+        let jsonl = std::fs::read_to_string(&storage.jsonl_path).unwrap();
+        assert!(jsonl.contains("\"commit\":\"b0b0b0b\""));
+        assert!(!jsonl.contains("\"commit\":\"compact\""));
+    }
+
+    #[tokio::test]
+    async fn test_repair_normalizes_content_type() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage
+            .save_records(&[record("src_a", &["legacy", "quoted", "clean"])])
+            .await
+            .unwrap();
+
+        let conn = Connection::open(&storage.sqlite_path).unwrap();
+        conn.execute(
+            "UPDATE brain_chunks SET content_type = '  Code ' WHERE chunk_id = 'legacy'",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE brain_chunks SET content_type = ' \"code\"' WHERE chunk_id = 'quoted'",
+            [],
+        )
+        .unwrap();
+
+        let filters = SearchFilters {
+            content_types: vec!["code".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            storage
+                .search_filtered("legacy", 10, &filters)
+                .unwrap()
+                .len(),
+            0
+        );
+
+        let result = storage.repair().await.unwrap();
+        assert_eq!(result.values_checked, 3);
+        assert_eq!(result.rows_fixed, 2);
+
+        let types: Vec<String> = conn
+            .prepare("SELECT DISTINCT content_type FROM brain_chunks")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(types, vec!["code".to_string()]);
+        assert_eq!(
+            storage
+                .search_filtered("legacy", 10, &filters)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // A second run has nothing to do
+        assert_eq!(storage.repair().await.unwrap().rows_fixed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_cache_hits_and_invalidates_on_save() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage
+            .save_records(&[record("src_a", &["alpha"])])
+            .await
+            .unwrap();
+
+        let first = storage
+            .search_filtered("alpha", 10, &SearchFilters::default())
+            .unwrap();
+        let second = storage
+            .search_filtered("alpha", 10, &SearchFilters::default())
+            .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_eq!(storage.cache().counters(), (1, 1));
+
+        // A different filter is a different cache entry
+        storage
+            .search_filtered(
+                "alpha",
+                10,
+                &SearchFilters {
+                    record_type: Some("code".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(storage.cache().counters(), (1, 2));
+
+        storage
+            .save_records(&[record("src_a", &["alpha_two"])])
+            .await
+            .unwrap();
+        storage
+            .search_filtered("alpha", 10, &SearchFilters::default())
+            .unwrap();
+        assert_eq!(storage.cache().counters(), (1, 3));
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_content_type() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+
+        let mut readme = record("src_a", &["widget_readme"]);
+        readme.path = "README.md".to_string();
+        readme.content_type = crate::brain::ContentType::Readme;
+        let mut config = record("src_a", &["widget_config"]);
+        config.path = "widget.toml".to_string();
+        config.content_type = crate::brain::ContentType::Config;
+        let code = record("src_a", &["widget_code"]);
+        storage.save_records(&[readme, config, code]).await.unwrap();
+
+        let all = storage
+            .search_filtered("widget*", 10, &SearchFilters::default())
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let code_only = storage
+            .search_filtered(
+                "widget*",
+                10,
+                &SearchFilters {
+                    content_types: vec!["code".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(code_only.len(), 1);
+        assert_eq!(code_only[0].content_type, "code");
+
+        let docs_and_config = storage
+            .search_filtered(
+                "widget*",
+                10,
+                &SearchFilters {
+                    content_types: vec!["readme".to_string(), "config".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(docs_and_config.len(), 2);
+        assert!(docs_and_config.iter().all(|r| r.content_type != "code"));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_signal_and_with_filter() {
+        use crate::brain::{ContentType, Signal};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+
+        let mut machine = record("src_a", &["widget_machine"]);
+        machine.signals = vec![Signal::StateMachine, Signal::ContractLock];
+        let mut docs = record("src_a", &["widget_docs"]);
+        docs.path = "docs/state.md".to_string();
+        docs.content_type = ContentType::Doc;
+        docs.signals = vec![Signal::StateMachine];
+        let mut adapter = record("src_a", &["widget_adapter"]);
+        adapter.signals = vec![Signal::ProviderAdapter];
+        let plain = record("src_a", &["widget_plain"]);
+        storage
+            .save_records(&[machine, docs, adapter, plain])
+            .await
+            .unwrap();
+
+        let ids = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.chunk_id).collect()
+        };
+
+        assert_eq!(
+            ids(storage.search_by_signal(&Signal::StateMachine, 10).unwrap()),
+            vec!["widget_machine", "widget_docs"]
+        );
+        assert_eq!(
+            ids(storage.search_by_signal(&Signal::StateMachine, 1).unwrap()),
+            vec!["widget_machine"]
+        );
+        assert!(storage
+            .search_by_signal(&Signal::IterateLoop, 10)
+            .unwrap()
+            .is_empty());
+
+        // Signals are any-of; content types narrow further
+        let mut both = ids(storage
+            .search_with_filter(
+                "widget*",
+                &[Signal::ContractLock, Signal::ProviderAdapter],
+                &[],
+                10,
+            )
+            .unwrap());
+        both.sort();
+        assert_eq!(both, vec!["widget_adapter", "widget_machine"]);
+        assert_eq!(
+            ids(storage
+                .search_with_filter("widget*", &[Signal::StateMachine], &[ContentType::Doc], 10)
+                .unwrap()),
+            vec!["widget_docs"]
+        );
+        assert_eq!(
+            storage
+                .search_with_filter("widget*", &[], &[], 10)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_filter_min_score_keeps_strong_matches() {
+        let result = |chunk_id: &str, score: f64| SearchResult {
+            chunk_id: chunk_id.to_string(),
+            source_id: "src_a".to_string(),
+            path: "README.md".to_string(),
+            content_type: "readme".to_string(),
+            snippet: String::new(),
+            score,
+            tags: vec![],
+        };
+        let results = vec![result("weak", 0.2), result("strong", 9.0)];
+
+        let kept = crate::brain::filter_min_score(results.clone(), 0.8);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].chunk_id, "strong");
+        assert!((kept[0].normalized_score() - 0.9).abs() < 1e-9);
+
+        assert!(crate::brain::filter_min_score(results.clone(), 0.95).is_empty());
+        assert_eq!(crate::brain::filter_min_score(results, 0.0).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stats_by_source() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage.save_source(&source("src_b")).await.unwrap();
+        storage
+            .save_records(&[
+                record("src_a", &["a1", "a2", "a3"]),
+                record("src_b", &["b1"]),
+            ])
+            .await
+            .unwrap();
+
+        let stats = storage.stats().await.unwrap();
+        assert_eq!(stats.by_source.len(), 2);
+        assert_eq!(stats.by_source["src_a"], 3);
+        assert_eq!(stats.by_source["src_b"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_topology_groups_sources_types_and_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        assert!(storage.topology().await.unwrap().sources.is_empty());
+
+        storage.save_source(&source("src_a")).await.unwrap();
+        storage.save_source(&source("src_b")).await.unwrap();
+        let mut readme = record("src_a", &["r1"]);
+        readme.path = "README.md".to_string();
+        readme.content_type = ContentType::Readme;
+        storage
+            .save_records(&[
+                record("src_a", &["a1", "a2"]),
+                readme,
+                record("src_b", &["b1"]),
+            ])
+            .await
+            .unwrap();
+
+        let topology = storage.topology().await.unwrap();
+        assert_eq!(topology.sources.len(), 2);
+        let a = &topology.sources[0];
+        assert_eq!((a.source_id.as_str(), a.chunks), ("src_a", 3));
+        let types: Vec<_> = a
+            .content_types
+            .iter()
+            .map(|t| (t.content_type.as_str(), t.chunks))
+            .collect();
+        assert_eq!(types, vec![("code", 2), ("readme", 1)]);
+        assert_eq!(a.content_types[0].files[0].path, "src/lib.rs");
+        assert_eq!(a.content_types[0].files[0].chunks, 2);
+        assert_eq!(topology.sources[1].chunks, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stats_by_signal() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage.save_source(&source("src_a")).await.unwrap();
+
+        let with_signals = |path: &str, chunk_ids: &[&str], signals: Vec<Signal>| {
+            let mut r = record("src_a", chunk_ids);
+            r.path = path.to_string();
+            r.signals = signals;
+            r
+        };
+        storage
+            .save_records(&[
+                with_signals(
+                    "src/state.rs",
+                    &["s1", "s2"],
+                    vec![Signal::StateMachine, Signal::SecurityPattern],
+                ),
+                with_signals("src/auth.rs", &["a1"], vec![Signal::SecurityPattern]),
+                with_signals("src/plain.rs", &["p1"], vec![]),
+            ])
+            .await
+            .unwrap();
+
+        let stats = storage.stats().await.unwrap();
+        assert_eq!(stats.by_signal.len(), 2);
+        assert_eq!(stats.by_signal["state_machine"], 2);
+        assert_eq!(stats.by_signal["security_pattern"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_stats_counts_large_jsonl_by_streaming() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+
+        // ~25 MB synthetic pack, with blank lines and no trailing newline
+        let base = serde_json::to_string(&record("src_big", &["c1"])).unwrap();
+        let line = format!(
+            "{},\"pad\":\"{}\"}}",
+            base.trim_end_matches('}'),
+            "x".repeat(256)
+        );
+        {
+            let mut out = BufWriter::new(std::fs::File::create(&storage.jsonl_path).unwrap());
+            for i in 0..100_000 {
+                if i % 10_000 == 0 {
+                    out.write_all(b"\n  \n").unwrap();
+                }
+                out.write_all(line.as_bytes()).unwrap();
+                if i < 99_999 {
+                    out.write_all(b"\n").unwrap();
+                }
+            }
+            out.flush().unwrap();
+        }
+        assert!(std::fs::metadata(&storage.jsonl_path).unwrap().len() > 20_000_000);
+
+        let stats = storage.stats().await.unwrap();
+        assert_eq!(stats.total_records, 100_000);
+    }
 }
@@ -0,0 +1,22 @@
+//! Token estimates shared by the context packer and the repo map
+//!
+//! A rough count, not a model tokenizer: one token per four bytes is close
+//! enough for budgeting across the models providers use.
+
+/// Estimated tokens in `text`, rounded up
+pub fn estimate(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_rounds_up() {
+        assert_eq!(estimate(""), 0);
+        assert_eq!(estimate("abc"), 1);
+        assert_eq!(estimate("abcd"), 1);
+        assert_eq!(estimate("abcde"), 2);
+    }
+}
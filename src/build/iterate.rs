@@ -2,14 +2,22 @@
 
 use anyhow::Result;
 use std::process::Command;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-use super::{BuildConfig, BuildResult};
+use super::{
+    execute_provider, provider_timeout_error, BuildConfig, BuildIterationSummary, BuildPhase,
+    BuildResult,
+};
 use crate::audit::AuditLogger;
+use crate::cli::progress::BuildProgress;
+use crate::cli::run::{detect_lint_command, detect_test_command};
+use crate::evidence::command::run_command;
 use crate::evidence::EvidenceCollector;
 use crate::guardrails::capsule::{generate_capsule_id, Capsule, CapsuleMeta};
 use crate::guardrails::classifier::RiskClassifier;
 use crate::guardrails::gate::ApprovalGate;
-use crate::provider::{get_provider, Context};
+use crate::provider::Context;
 use crate::workspace;
 
 /// Iteration result
@@ -27,93 +35,249 @@ pub struct IterateBuild {
     config: BuildConfig,
     session_id: String,
     evidence: EvidenceCollector,
+    /// Test command; `None` when none was given or detected
+    test_cmd: Option<CheckCommand>,
+    /// Lint command; `None` when none was given or detected
+    lint_cmd: Option<CheckCommand>,
+    /// Saved progress to continue from, used by the first `execute`
+    resume: std::sync::Mutex<Option<BuildProgress>>,
+    /// Stops the run; see [`IterateBuild::with_cancel`]
+    cancel: CancellationToken,
+    /// Provider the fallback chain picked, once one has run
+    provider_used: std::sync::Mutex<Option<String>>,
+}
+
+/// A test or lint command run on every iteration
+#[derive(Debug, Clone, PartialEq)]
+struct CheckCommand {
+    /// The command line as shown to the user and the provider
+    display: String,
+    program: String,
+    args: Vec<String>,
+}
+
+impl CheckCommand {
+    /// A `--test-cmd`/`--lint-cmd` override, run through the shell
+    fn shell(command: &str) -> Self {
+        let (shell, flag) = if cfg!(target_os = "windows") {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        Self {
+            display: command.to_string(),
+            program: shell.to_string(),
+            args: vec![flag.to_string(), command.to_string()],
+        }
+    }
+
+    /// A detected command such as `cargo test`, run directly
+    fn detected(command: &str) -> Self {
+        let mut parts = command.split_whitespace().map(String::from);
+        Self {
+            display: command.to_string(),
+            program: parts.next().unwrap_or_default(),
+            args: parts.collect(),
+        }
+    }
+
+    /// The override when given, otherwise the command detected from the
+    /// project's files (`Cargo.toml`, `package.json`, `pyproject.toml`, ...)
+    fn resolve(override_cmd: Option<&str>, detect: fn() -> Result<String>) -> Option<Self> {
+        match override_cmd {
+            Some(command) => Some(Self::shell(command)),
+            None => detect().ok().map(|command| Self::detected(&command)),
+        }
+    }
 }
 
 impl IterateBuild {
     /// Create new iterate build
     pub async fn new(config: BuildConfig, session_id: &str) -> Result<Self> {
         let evidence = EvidenceCollector::new(session_id).await?;
+        let test_cmd = CheckCommand::resolve(config.test_cmd.as_deref(), detect_test_command);
+        let lint_cmd = CheckCommand::resolve(config.lint_cmd.as_deref(), detect_lint_command);
         Ok(Self {
             config,
             session_id: session_id.to_string(),
             evidence,
+            test_cmd,
+            lint_cmd,
+            resume: std::sync::Mutex::new(None),
+            cancel: CancellationToken::new(),
+            provider_used: std::sync::Mutex::new(None),
         })
     }
 
+    /// Provider that ran, which differs from `config.provider` after a
+    /// fallback; `config.provider` until one has run
+    pub fn provider_used(&self) -> String {
+        self.provider_used
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| self.config.provider.clone())
+    }
+
+    /// Continue from saved progress: skip the iterations it completed and
+    /// send its pending prompt first
+    pub fn resume_from(self, progress: BuildProgress) -> Self {
+        *self.resume.lock().unwrap() = Some(progress);
+        self
+    }
+
+    /// Stop the run when `cancel` fires, e.g. on Ctrl-C in watch mode
+    ///
+    /// Providers that run a process (and the mock) abort their call in
+    /// flight; the others finish it, and the run stops before the next step.
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     /// Execute iterate loop
-    #[allow(unused_assignments)]
     pub async fn execute(&self, initial_prompt: &str) -> Result<BuildResult> {
-        let mut iterations = 0;
+        self.execute_task(None, initial_prompt).await
+    }
+
+    /// Execute the iterate loop for one plan task, recording `task` in the
+    /// saved progress so a resumed build knows where to pick up
+    #[allow(unused_assignments)]
+    pub async fn execute_task(
+        &self,
+        task: Option<&str>,
+        initial_prompt: &str,
+    ) -> Result<BuildResult> {
+        let resumed = self.resume.lock().unwrap().take();
+        let mut first_prompt = initial_prompt.to_string();
+        let mut progress = match resumed {
+            Some(progress) => {
+                if !progress.last_prompt.is_empty() {
+                    first_prompt = progress.last_prompt.clone();
+                }
+                progress
+            }
+            None => BuildProgress::new(
+                &self.session_id,
+                "iterate",
+                &self.config.provider,
+                self.config.max_iterations,
+            ),
+        };
+        progress.max_iterations = self.config.max_iterations;
+        progress.task = task.map(String::from);
+        let earlier_evidence = progress.evidence_files.clone();
+
+        let mut iterations = progress.current_iteration;
         let mut all_errors = vec![];
         let all_warnings = vec![];
         let mut last_output = String::new();
+        let mut summaries: Vec<BuildIterationSummary> = vec![];
 
-        let provider = get_provider(&self.config.provider)?;
+        let phases = &self.config.phases;
         let context = Context {
             working_dir: std::env::current_dir()?,
             session_id: self.session_id.clone(),
             contract_hash: None,
+            cancel: self.cancel.clone(),
         };
 
-        // Initial apply
-        println!("→ Iteration 1: Applying initial changes...");
-        let response = provider.execute(initial_prompt, &context).await?;
-        last_output = response.output.clone();
-        iterations += 1;
+        // Without the provider phase the checks run once and nothing is fixed
+        let provider = if phases.runs(BuildPhase::Provider) {
+            let (name, provider) = self.config.select_provider()?;
+            progress.provider = name.clone();
+            *self.provider_used.lock().unwrap() = Some(name);
+            Some(provider)
+        } else {
+            println!("→ Provider phase skipped; running checks only");
+            None
+        };
 
-        // Apply guardrails gate to initial changes
-        if !self.gatekeep_changes(iterations).await? {
-            return Ok(BuildResult {
-                success: false,
-                iterations,
-                errors: vec!["Change denied by guardrails".to_string()],
-                warnings: all_warnings,
-                evidence_files: vec![],
-                output: "Guardrails denied the changes".to_string(),
-            });
-        }
+        iterations += 1;
+        if let Some(provider) = &provider {
+            // Initial apply, or the interrupted call when resuming
+            if iterations > 1 {
+                println!(
+                    "→ Iteration {}: Resuming with the saved prompt...",
+                    iterations
+                );
+            } else {
+                println!("→ Iteration 1: Applying initial changes...");
+            }
+            progress.checkpoint(iterations - 1, &first_prompt, earlier_evidence.clone());
+            progress.save().await?;
+            let Some(response) = self
+                .execute_provider(provider.as_ref(), &first_prompt, &context)
+                .await?
+            else {
+                return self
+                    .timed_out(iterations, all_errors, summaries, last_output)
+                    .await;
+            };
+            if response.cancelled || self.cancel.is_cancelled() {
+                return Ok(cancelled(iterations, all_errors, summaries, last_output));
+            }
+            last_output = response.output.clone();
 
-        loop {
-            if iterations > self.config.max_iterations {
-                println!("✗ Max iterations ({}) reached", self.config.max_iterations);
-                break;
+            // Apply guardrails gate to initial changes
+            if !self.gatekeep_changes(iterations).await? {
+                return Ok(BuildResult {
+                    success: false,
+                    iterations,
+                    errors: vec!["Change denied by guardrails".to_string()],
+                    warnings: all_warnings,
+                    evidence_files: vec![],
+                    output: "Guardrails denied the changes".to_string(),
+                    iteration_summaries: summaries,
+                    timed_out: false,
+                });
             }
+        }
 
-            // Run tests (unless skipped)
-            let test_result = if !self.config.skip_tests {
-                self.run_tests().await?
-            } else {
-                TestResult {
-                    passed: true,
-                    output: String::new(),
-                    errors: vec![],
-                }
-            };
+        if phases.runs(BuildPhase::Test) {
+            println!("→ Test command: {}", describe(&self.test_cmd));
+        }
+        if phases.runs(BuildPhase::Lint) {
+            println!("→ Lint command: {}", describe(&self.lint_cmd));
+        }
 
-            // Run lint (unless skipped)
-            let lint_result = if !self.config.skip_lint {
-                self.run_lint().await?
-            } else {
-                LintResult {
-                    passed: true,
-                    output: String::new(),
-                    errors: vec![],
-                }
-            };
+        loop {
+            let (test_result, lint_result, summary) =
+                self.run_iteration(iterations, &last_output).await?;
+            let passed = summary.passed();
+            summaries.push(summary);
 
             // Check if all passed
-            if test_result.passed && lint_result.passed {
+            if passed {
                 println!("✓ All checks passed after {} iteration(s)", iterations);
                 return Ok(BuildResult {
                     success: true,
                     iterations,
                     errors: all_errors,
                     warnings: all_warnings,
-                    evidence_files: vec![],
+                    evidence_files: evidence_files(&summaries),
                     output: last_output,
+                    iteration_summaries: summaries,
+                    timed_out: false,
                 });
             }
 
+            let Some(provider) = &provider else {
+                all_errors.extend(test_result.errors);
+                all_errors.extend(lint_result.errors);
+                println!("✗ Checks failed (provider phase skipped, nothing to fix)");
+                return Ok(BuildResult {
+                    success: false,
+                    iterations,
+                    errors: all_errors,
+                    warnings: all_warnings,
+                    evidence_files: evidence_files(&summaries),
+                    output: format!("{}\n{}", test_result.output, lint_result.output),
+                    iteration_summaries: summaries,
+                    timed_out: false,
+                });
+            };
+
             // Strict mode - fail on first error
             if self.config.strict && (!test_result.passed || !lint_result.passed) {
                 all_errors.extend(test_result.errors.clone());
@@ -124,25 +288,58 @@ impl IterateBuild {
                     iterations,
                     errors: all_errors,
                     warnings: all_warnings,
-                    evidence_files: vec![],
+                    evidence_files: evidence_files(&summaries),
                     output: format!(
                         "Strict mode: Failing on first error\nTests: {:?}\nLint: {:?}",
                         test_result.errors, lint_result.errors
                     ),
+                    iteration_summaries: summaries,
+                    timed_out: false,
                 });
             }
 
+            if iterations >= self.config.max_iterations {
+                all_errors.extend(test_result.errors);
+                all_errors.extend(lint_result.errors);
+                println!("✗ Max iterations ({}) reached", self.config.max_iterations);
+                break;
+            }
+
+            if self.cancel.is_cancelled() {
+                return Ok(cancelled(iterations, all_errors, summaries, last_output));
+            }
+
             iterations += 1;
             println!(
                 "→ Iteration {}: Analyzing failures and applying fixes...",
                 iterations
             );
 
-            // Build fix prompt
-            let fix_prompt = self.build_fix_prompt(&test_result, &lint_result);
+            // Re-prompt with the failing output appended
+            let fix_prompt = self.build_fix_prompt(initial_prompt, &test_result, &lint_result);
+
+            // Save before the provider call so an interrupted build can resume
+            progress.errors.extend(test_result.errors.iter().cloned());
+            progress.errors.extend(lint_result.errors.iter().cloned());
+            progress.checkpoint(
+                iterations - 1,
+                &fix_prompt,
+                [earlier_evidence.clone(), evidence_files(&summaries)].concat(),
+            );
+            progress.save().await?;
 
             // Apply fix
-            let response = provider.execute(&fix_prompt, &context).await?;
+            let Some(response) = self
+                .execute_provider(provider.as_ref(), &fix_prompt, &context)
+                .await?
+            else {
+                return self
+                    .timed_out(iterations, all_errors, summaries, last_output)
+                    .await;
+            };
+            if response.cancelled || self.cancel.is_cancelled() {
+                return Ok(cancelled(iterations, all_errors, summaries, last_output));
+            }
             last_output = response.output.clone();
 
             // Apply guardrails gate to fix changes
@@ -152,13 +349,12 @@ impl IterateBuild {
                     iterations,
                     errors: vec!["Fix denied by guardrails".to_string()],
                     warnings: all_warnings.clone(),
-                    evidence_files: vec![],
+                    evidence_files: evidence_files(&summaries),
                     output: "Guardrails denied the fix".to_string(),
+                    iteration_summaries: summaries,
+                    timed_out: false,
                 });
             }
-
-            // Capture evidence
-            self.evidence.capture_build_log(&last_output).await?;
         }
 
         Ok(BuildResult {
@@ -166,105 +362,208 @@ impl IterateBuild {
             iterations,
             errors: all_errors,
             warnings: all_warnings,
-            evidence_files: vec![],
+            evidence_files: evidence_files(&summaries),
             output: last_output,
+            iteration_summaries: summaries,
+            timed_out: false,
         })
     }
 
-    /// Run tests and capture results
-    async fn run_tests(&self) -> Result<TestResult> {
-        // Try common test commands
-        let test_commands = vec![
-            ("cargo", vec!["test"]),
-            ("npm", vec!["test"]),
-            ("pytest", vec![]),
-            ("go", vec!["test", "./..."]),
-        ];
-
-        for (cmd, args) in test_commands {
-            if which::which(cmd).is_ok() {
-                let output = Command::new(cmd).args(&args).output();
-
-                if let Ok(output) = output {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    let full_output = format!("{}\n{}", stdout, stderr);
-
-                    self.evidence.capture_test_log(&full_output).await?;
-
-                    return Ok(TestResult {
-                        passed: output.status.success(),
-                        output: full_output.clone(),
-                        errors: if output.status.success() {
-                            vec![]
-                        } else {
-                            vec![full_output]
-                        },
-                    });
-                }
+    async fn execute_provider(
+        &self,
+        provider: &dyn crate::provider::Provider,
+        prompt: &str,
+        context: &Context,
+    ) -> Result<Option<crate::provider::ProviderResponse>> {
+        execute_provider(provider, prompt, context, self.config.timeout_secs).await
+    }
+
+    /// Stop after the provider ran past the timeout in `iteration`, keeping
+    /// the evidence and output of the iterations before it
+    async fn timed_out(
+        &self,
+        iteration: u32,
+        mut errors: Vec<String>,
+        summaries: Vec<BuildIterationSummary>,
+        last_output: String,
+    ) -> Result<BuildResult> {
+        let error = provider_timeout_error(self.config.timeout_secs);
+        println!("✗ {} in iteration {}", error, iteration);
+
+        let mut files = evidence_files(&summaries);
+        if self.config.phases.runs(BuildPhase::Capture) {
+            let evidence = self.evidence.clone().with_iteration(iteration);
+            files.push(evidence.capture_build_log(&error).await?.filename);
+        }
+        errors.push(error);
+
+        Ok(BuildResult {
+            success: false,
+            iterations: iteration,
+            errors,
+            warnings: vec![],
+            evidence_files: files,
+            output: last_output,
+            iteration_summaries: summaries,
+            timed_out: true,
+        })
+    }
+
+    /// Run the checks for one iteration, capturing its evidence under the
+    /// iteration's number (`test_log_iter_3_…`)
+    ///
+    /// `provider_output` is what the provider returned for this iteration,
+    /// empty when the provider phase is skipped.
+    async fn run_iteration(
+        &self,
+        iteration: u32,
+        provider_output: &str,
+    ) -> Result<(TestResult, LintResult, BuildIterationSummary)> {
+        let phases = &self.config.phases;
+        let evidence = phases
+            .runs(BuildPhase::Capture)
+            .then(|| self.evidence.clone().with_iteration(iteration));
+        let mut files = vec![];
+
+        if let Some(evidence) = &evidence {
+            if !provider_output.is_empty() {
+                files.push(evidence.capture_build_log(provider_output).await?.filename);
+            }
+            // Builds can run outside a repo; the diff is then the only thing missing
+            match evidence.capture_git_diff().await {
+                Ok(diff) => files.push(diff.filename),
+                Err(e) => println!("⚠️  No diff evidence for iteration {}: {}", iteration, e),
             }
         }
 
+        // Run tests (unless skipped)
+        let test_result = if phases.runs(BuildPhase::Test) {
+            self.run_tests(evidence.as_ref()).await?
+        } else {
+            TestResult {
+                passed: true,
+                output: String::new(),
+                errors: vec![],
+                evidence_file: None,
+            }
+        };
+
+        // Run lint (unless skipped)
+        let lint_result = if phases.runs(BuildPhase::Lint) {
+            self.run_lint(evidence.as_ref()).await?
+        } else {
+            LintResult {
+                passed: true,
+                output: String::new(),
+                errors: vec![],
+                evidence_file: None,
+            }
+        };
+
+        files.extend(test_result.evidence_file.clone());
+        files.extend(lint_result.evidence_file.clone());
+        let summary = BuildIterationSummary {
+            iteration,
+            tests_passed: test_result.passed,
+            lint_passed: lint_result.passed,
+            evidence_files: files,
+        };
+        Ok((test_result, lint_result, summary))
+    }
+
+    /// Run tests and capture results
+    async fn run_tests(&self, evidence: Option<&EvidenceCollector>) -> Result<TestResult> {
+        let Some(command) = &self.test_cmd else {
+            return Ok(TestResult {
+                passed: true,
+                output: "No test framework detected".to_string(),
+                errors: vec![],
+                evidence_file: None,
+            });
+        };
+
+        let (passed, output) = self.run_check(command).await;
+        let evidence_file = match evidence {
+            Some(evidence) => Some(evidence.capture_test_log(&output).await?.filename),
+            None => None,
+        };
+
         Ok(TestResult {
-            passed: true,
-            output: "No test framework detected".to_string(),
-            errors: vec![],
+            passed,
+            errors: if passed { vec![] } else { vec![output.clone()] },
+            output,
+            evidence_file,
         })
     }
 
     /// Run lint and capture results
-    async fn run_lint(&self) -> Result<LintResult> {
-        // Try common lint commands
-        let lint_commands = vec![
-            ("cargo", vec!["clippy", "--", "-D", "warnings"]),
-            ("npm", vec!["run", "lint"]),
-            ("eslint", vec!["."]),
-            ("pylint", vec!["."]),
-        ];
-
-        for (cmd, args) in lint_commands {
-            if which::which(cmd).is_ok() {
-                let output = Command::new(cmd).args(&args).output();
-
-                if let Ok(output) = output {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    let full_output = format!("{}\n{}", stdout, stderr);
-
-                    self.evidence.capture_lint_log(&full_output).await?;
-
-                    return Ok(LintResult {
-                        passed: output.status.success(),
-                        output: full_output.clone(),
-                        errors: if output.status.success() {
-                            vec![]
-                        } else {
-                            vec![full_output]
-                        },
-                    });
-                }
-            }
-        }
+    async fn run_lint(&self, evidence: Option<&EvidenceCollector>) -> Result<LintResult> {
+        let Some(command) = &self.lint_cmd else {
+            return Ok(LintResult {
+                passed: true,
+                output: "No lint tool detected".to_string(),
+                errors: vec![],
+                evidence_file: None,
+            });
+        };
+
+        let (passed, output) = self.run_check(command).await;
+        let evidence_file = match evidence {
+            Some(evidence) => Some(evidence.capture_lint_log(&output).await?.filename),
+            None => None,
+        };
 
         Ok(LintResult {
-            passed: true,
-            output: "No lint tool detected".to_string(),
-            errors: vec![],
+            passed,
+            errors: if passed { vec![] } else { vec![output.clone()] },
+            output,
+            evidence_file,
         })
     }
 
+    /// Run a check under the per-iteration timeout; a command that can't be
+    /// started counts as a failure so the provider sees why
+    async fn run_check(&self, command: &CheckCommand) -> (bool, String) {
+        let args: Vec<&str> = command.args.iter().map(String::as_str).collect();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        match run_command(&command.program, &args, timeout).await {
+            Ok(out) => {
+                let mut output = format!("{}\n{}", out.stdout, out.stderr);
+                if out.run.timed_out {
+                    output.push_str(&format!("\n`{}` {}", command.display, out.run.outcome()));
+                }
+                (out.run.success(), output)
+            }
+            Err(e) => (
+                false,
+                format!("`{}` could not be run: {:#}", command.display, e),
+            ),
+        }
+    }
+
     /// Build a prompt to fix errors
-    fn build_fix_prompt(&self, test_result: &TestResult, lint_result: &LintResult) -> String {
-        let mut prompt = String::from("Fix the following errors:\n\n");
+    fn build_fix_prompt(
+        &self,
+        initial_prompt: &str,
+        test_result: &TestResult,
+        lint_result: &LintResult,
+    ) -> String {
+        let mut prompt = format!(
+            "{}\n\n---\n\nThe changes so far fail these checks. Fix the following errors:\n\n",
+            initial_prompt
+        );
 
         if !test_result.passed {
-            prompt.push_str("## Test Failures:\n");
+            prompt.push_str(&format!(
+                "## Test Failures ({}):\n",
+                describe(&self.test_cmd)
+            ));
             prompt.push_str(&test_result.output);
             prompt.push_str("\n\n");
         }
 
         if !lint_result.passed {
-            prompt.push_str("## Lint Errors:\n");
+            prompt.push_str(&format!("## Lint Errors ({}):\n", describe(&self.lint_cmd)));
             prompt.push_str(&lint_result.output);
             prompt.push_str("\n\n");
         }
@@ -305,7 +604,7 @@ impl IterateBuild {
             classification.public_surface_changes,
         )
         .with_why(format!("Auto-generated by iteration {}", iteration))
-        .with_provider(&self.config.provider);
+        .with_provider(self.provider_used());
 
         let mut capsule = Capsule::new(meta, diff, self.session_id.clone());
 
@@ -327,11 +626,28 @@ impl IterateBuild {
     }
 }
 
+/// `cargo test`, or `none detected`
+fn describe(command: &Option<CheckCommand>) -> &str {
+    command
+        .as_ref()
+        .map_or("none detected", |c| c.display.as_str())
+}
+
+/// Evidence files captured over a run, in iteration order
+fn evidence_files(summaries: &[BuildIterationSummary]) -> Vec<String> {
+    summaries
+        .iter()
+        .flat_map(|s| s.evidence_files.iter().cloned())
+        .collect()
+}
+
 #[derive(Debug)]
 struct TestResult {
     passed: bool,
     output: String,
     errors: Vec<String>,
+    /// Test log captured for this run
+    evidence_file: Option<String>,
 }
 
 #[derive(Debug)]
@@ -339,4 +655,56 @@ struct LintResult {
     passed: bool,
     output: String,
     errors: Vec<String>,
+    /// Lint log captured for this run
+    evidence_file: Option<String>,
+}
+
+/// Result of a run stopped through `with_cancel`
+fn cancelled(
+    iteration: u32,
+    mut errors: Vec<String>,
+    summaries: Vec<BuildIterationSummary>,
+    last_output: String,
+) -> BuildResult {
+    println!("✗ Build cancelled in iteration {}", iteration);
+    errors.push("Build cancelled".to_string());
+    BuildResult {
+        success: false,
+        iterations: iteration,
+        errors,
+        warnings: vec![],
+        evidence_files: evidence_files(&summaries),
+        output: last_output,
+        iteration_summaries: summaries,
+        timed_out: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_command_resolution() {
+        let detected = CheckCommand::resolve(None, || Ok("cargo clippy -- -D warnings".into()));
+        assert_eq!(
+            detected,
+            Some(CheckCommand {
+                display: "cargo clippy -- -D warnings".into(),
+                program: "cargo".into(),
+                args: vec!["clippy".into(), "--".into(), "-D".into(), "warnings".into()],
+            })
+        );
+
+        let overridden =
+            CheckCommand::resolve(Some("make check && echo ok"), || Ok("cargo test".into()))
+                .unwrap();
+        assert_eq!(overridden.display, "make check && echo ok");
+        assert_eq!(overridden.args.last().unwrap(), "make check && echo ok");
+
+        assert_eq!(
+            CheckCommand::resolve(None, || anyhow::bail!("nothing detected")),
+            None
+        );
+    }
 }
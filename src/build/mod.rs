@@ -1,13 +1,16 @@
 //! Build execution modes: manual, auto, iterate
 
 pub mod iterate;
+pub mod order;
 pub mod provenance;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 use crate::evidence::EvidenceCollector;
+use crate::provider::{Provider, ProviderResponse};
 
 /// Build mode configuration
 #[derive(Debug, Clone)]
@@ -16,18 +19,24 @@ pub struct BuildConfig {
     pub mode: BuildMode,
     /// Provider name
     pub provider: String,
+    /// Providers tried in order when `provider` is not available
+    pub fallbacks: Vec<String>,
     /// Maximum iterations (for iterate mode)
     pub max_iterations: u32,
     /// Strict mode - fail on first error
     pub strict: bool,
     /// Timeout per iteration in seconds
     pub timeout_secs: u64,
-    /// Skip tests
-    pub skip_tests: bool,
-    /// Skip lint
-    pub skip_lint: bool,
+    /// Pipeline phases to run
+    pub phases: BuildPhases,
     /// Capture evidence
     pub capture_evidence: bool,
+    /// Prompt template used for build prompts
+    pub template: String,
+    /// Test command (iterate mode); detected from the project when `None`
+    pub test_cmd: Option<String>,
+    /// Lint command (iterate mode); detected from the project when `None`
+    pub lint_cmd: Option<String>,
 }
 
 /// Build mode enum
@@ -39,6 +48,100 @@ pub enum BuildMode {
     Iterate,
 }
 
+/// A stage of the build pipeline, in pipeline order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildPhase {
+    /// Evidence capture (build/test/lint logs)
+    Capture,
+    /// Provider invocation (initial changes and fixes)
+    Provider,
+    /// Test run
+    Test,
+    /// Lint run
+    Lint,
+    /// Auto-commit (manual builds)
+    Commit,
+}
+
+impl BuildPhase {
+    /// All phases in pipeline order
+    pub fn all() -> &'static [BuildPhase] {
+        &[
+            BuildPhase::Capture,
+            BuildPhase::Provider,
+            BuildPhase::Test,
+            BuildPhase::Lint,
+            BuildPhase::Commit,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildPhase::Capture => "capture",
+            BuildPhase::Provider => "provider",
+            BuildPhase::Test => "test",
+            BuildPhase::Lint => "lint",
+            BuildPhase::Commit => "commit",
+        }
+    }
+}
+
+/// The set of phases a build runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildPhases {
+    enabled: Vec<BuildPhase>,
+}
+
+impl Default for BuildPhases {
+    fn default() -> Self {
+        Self {
+            enabled: BuildPhase::all().to_vec(),
+        }
+    }
+}
+
+impl BuildPhases {
+    /// Phases from `--only` (all when empty) minus `--skip`
+    pub fn select(only: &[BuildPhase], skip: &[BuildPhase]) -> Result<Self> {
+        let enabled: Vec<BuildPhase> = BuildPhase::all()
+            .iter()
+            .copied()
+            .filter(|p| only.is_empty() || only.contains(p))
+            .filter(|p| !skip.contains(p))
+            .collect();
+        if enabled.is_empty() {
+            return Err(crate::error::validation("No build phases left to run"));
+        }
+        Ok(Self { enabled })
+    }
+
+    /// Remove a phase (used by `--no-test` / `--no-lint`)
+    pub fn without(mut self, phase: BuildPhase) -> Result<Self> {
+        self.enabled.retain(|p| *p != phase);
+        if self.enabled.is_empty() {
+            return Err(crate::error::validation("No build phases left to run"));
+        }
+        Ok(self)
+    }
+
+    /// Whether `phase` runs
+    pub fn runs(&self, phase: BuildPhase) -> bool {
+        self.enabled.contains(&phase)
+    }
+
+    /// Whether every phase runs
+    pub fn is_full(&self) -> bool {
+        self.enabled.len() == BuildPhase::all().len()
+    }
+}
+
+impl std::fmt::Display for BuildPhases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.enabled.iter().map(|p| p.name()).collect();
+        write!(f, "{}", names.join(" → "))
+    }
+}
+
 /// Build result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildResult {
@@ -54,6 +157,69 @@ pub struct BuildResult {
     pub evidence_files: Vec<String>,
     /// Build output
     pub output: String,
+    /// Per-iteration check results (iterate mode)
+    #[serde(default)]
+    pub iteration_summaries: Vec<BuildIterationSummary>,
+    /// The provider ran past `timeout_secs`; the build stopped there
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+/// Run `prompt` on the provider, giving up after `timeout_secs`
+///
+/// Returns `Ok(None)` when the provider timed out, and a cancelled response
+/// as soon as `context.cancel` fires.
+pub async fn execute_provider(
+    provider: &dyn Provider,
+    prompt: &str,
+    context: &crate::provider::Context,
+    timeout_secs: u64,
+) -> Result<Option<ProviderResponse>> {
+    let call = tokio::time::timeout(
+        Duration::from_secs(timeout_secs),
+        provider.execute(prompt, context),
+    );
+    tokio::select! {
+        result = call => match result {
+            Ok(response) => response.map(Some),
+            Err(_) => Ok(None),
+        },
+        _ = context.cancel.cancelled() => Ok(Some(ProviderResponse::cancelled())),
+    }
+}
+
+/// Error recorded for a provider call that ran past `--timeout`
+pub fn provider_timeout_error(timeout_secs: u64) -> String {
+    format!("Provider timed out after {}s", timeout_secs)
+}
+
+/// Outcome of one iterate-mode iteration, with the evidence it produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildIterationSummary {
+    pub iteration: u32,
+    pub tests_passed: bool,
+    pub lint_passed: bool,
+    pub evidence_files: Vec<String>,
+}
+
+impl BuildIterationSummary {
+    pub fn passed(&self) -> bool {
+        self.tests_passed && self.lint_passed
+    }
+}
+
+impl BuildConfig {
+    /// `provider` followed by its fallbacks
+    pub fn provider_chain(&self) -> Vec<String> {
+        std::iter::once(self.provider.clone())
+            .chain(self.fallbacks.iter().cloned())
+            .collect()
+    }
+
+    /// The first available provider of the chain, with its name
+    pub fn select_provider(&self) -> Result<(String, Box<dyn Provider>)> {
+        crate::provider::first_available(&self.provider_chain())
+    }
 }
 
 impl Default for BuildConfig {
@@ -61,49 +227,111 @@ impl Default for BuildConfig {
         Self {
             mode: BuildMode::Manual,
             provider: "claude-code".to_string(),
+            fallbacks: vec![],
             max_iterations: 5,
             strict: false,
             timeout_secs: 300,
-            skip_tests: false,
-            skip_lint: false,
+            phases: BuildPhases::default(),
             capture_evidence: true,
+            template: "developer".to_string(),
+            test_cmd: None,
+            lint_cmd: None,
         }
     }
 }
 
+/// How `ManualBuild::complete` commits the build's changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitMode {
+    /// Leave the changes uncommitted
+    Skip,
+    /// Commit with the generated message without asking
+    Auto,
+    /// Confirm or edit the generated message; without a TTY it is saved to
+    /// a file and nothing is committed
+    Prompt,
+}
+
+/// File in the session directory holding the proposed commit message when
+/// `complete` cannot ask
+pub const COMMIT_MESSAGE_FILE: &str = "commit_message.txt";
+
 /// Manual build handler
 pub struct ManualBuild {
-    #[allow(dead_code)]
     session_id: String,
     evidence: EvidenceCollector,
 }
 
 impl ManualBuild {
-    /// Create new manual build
+    /// Create a manual build, resuming the session of a build already in progress
+    ///
+    /// `session_id` is only used when no build is in progress, so `evidence`
+    /// and `complete` keep writing to the session that `start` opened, even
+    /// from a new process after a restart.
     pub async fn new(session_id: &str) -> Result<Self> {
-        let evidence = EvidenceCollector::new(session_id).await?;
-        Ok(Self {
-            session_id: session_id.to_string(),
-            evidence,
-        })
+        let state = crate::workspace::load_state().await.ok();
+        let build = Self::from_state(state.as_ref(), session_id);
+        tokio::fs::create_dir_all(&build.evidence.evidence_dir).await?;
+        Ok(build)
     }
 
-    /// Check if a build is in progress by reading state from disk
-    async fn is_build_in_progress() -> bool {
-        if let Ok(state) = crate::workspace::load_state().await {
-            state.current_state == crate::state::State::BuildInProgress
-        } else {
-            false
+    /// Manual build for the given workspace state, without touching disk
+    pub fn from_state(state: Option<&crate::state::StateData>, session_id: &str) -> Self {
+        let session_id = state
+            .filter(|s| s.current_state == crate::state::State::BuildInProgress)
+            .and_then(|s| s.current_session_id.as_deref())
+            .unwrap_or(session_id);
+        Self {
+            session_id: session_id.to_string(),
+            evidence: EvidenceCollector::with_dir(
+                session_id,
+                crate::workspace::evidence_path(session_id),
+            )
+            .with_custom_patterns(crate::evidence::workspace_patterns().to_vec()),
         }
     }
 
-    /// Start the manual build
+    /// Session the build's evidence is recorded under
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Directory evidence is written to
+    pub fn evidence_dir(&self) -> &std::path::Path {
+        &self.evidence.evidence_dir
+    }
+
+    /// Fail unless a build is in progress, explaining what the workspace
+    /// state on disk allows instead
+    async fn ensure_build_in_progress() -> Result<()> {
+        use crate::state::State;
+
+        let state = crate::workspace::load_state().await?.current_state;
+        let message = match state {
+            State::BuildInProgress => return Ok(()),
+            State::PlanCreated => {
+                "Build not started. Run 'vibeanvil build manual start' first.".to_string()
+            }
+            State::BuildDone | State::ReviewFailed => format!(
+                "No build in progress: the last one is finished (state: {}). \
+                 Run 'vibeanvil build manual start' to start another.",
+                state
+            ),
+            State::ReviewPassed | State::Shipped => format!(
+                "No build in progress: the build already passed review (state: {}).",
+                state
+            ),
+            _ => format!(
+                "No plan yet (state: {}). Run 'vibeanvil plan' before building.",
+                state
+            ),
+        };
+        Err(crate::error::invalid_state(message))
+    }
+
+    /// Start the manual build; the caller moves the workspace into `BuildInProgress`
     pub async fn start(&mut self) -> Result<()> {
-        if Self::is_build_in_progress().await {
-            anyhow::bail!(
-                "Build already in progress. Run 'vibeanvil build manual complete' to finish."
-            );
-        }
+        Self::ensure_build_in_progress().await?;
 
         // Capture initial git diff
         let _ = self.evidence.capture_git_diff().await;
@@ -114,9 +342,7 @@ impl ManualBuild {
 
     /// Capture evidence
     pub async fn capture_evidence(&self) -> Result<()> {
-        if !Self::is_build_in_progress().await {
-            anyhow::bail!("Build not started. Run 'vibeanvil build manual start' first.");
-        }
+        Self::ensure_build_in_progress().await?;
 
         let evidence = self.evidence.capture_git_diff().await?;
         println!("✓ Captured evidence: {}", evidence.filename);
@@ -124,11 +350,9 @@ impl ManualBuild {
         Ok(())
     }
 
-    /// Complete the build
-    pub async fn complete(&self) -> Result<BuildResult> {
-        if !Self::is_build_in_progress().await {
-            anyhow::bail!("Build not started. Run 'vibeanvil build manual start' first.");
-        }
+    /// Complete the build, committing the changes as `commit` says
+    pub async fn complete(&self, commit: CommitMode) -> Result<BuildResult> {
+        Self::ensure_build_in_progress().await?;
 
         // Capture final diff
         let evidence_file = self.evidence.capture_git_diff().await?;
@@ -137,10 +361,12 @@ impl ManualBuild {
         crate::cli::style::step("Vibe Commit");
 
         // Read the diff content
-        let full_path = self.evidence.evidence_dir.join(&evidence_file.filename);
-        let diff_content = std::fs::read_to_string(full_path).unwrap_or_default();
+        let diff_content = std::fs::read_to_string(self.evidence.path_of(&evidence_file))
+            .context("Failed to read the captured diff")?;
 
-        if diff_content.trim().is_empty() {
+        if commit == CommitMode::Skip {
+            crate::cli::style::info("Commit phase skipped.");
+        } else if diff_content.trim().is_empty() {
             crate::cli::style::warn("No changes detected, skipping auto-commit.");
         } else {
             // Get provider for commit message generation
@@ -151,6 +377,7 @@ impl ManualBuild {
                 working_dir: std::env::current_dir()?,
                 session_id: self.session_id.clone(),
                 contract_hash: None,
+                cancel: Default::default(),
             };
 
             let pb = crate::cli::style::spinner("Dreaming up a commit message...");
@@ -161,16 +388,25 @@ impl ManualBuild {
                 Ok(msg) => {
                     pb.finish_and_clear();
 
-                    // Interactive prompt - only if TTY and not CI
-                    let is_ci = std::env::var("CI").is_ok();
-                    let is_tty = console::user_attended();
+                    // Interactive prompt - only if stdin is a TTY and not CI
+                    use std::io::IsTerminal;
+                    let interactive =
+                        std::io::stdin().is_terminal() && std::env::var("CI").is_err();
 
-                    if is_ci || !is_tty {
-                        // In CI/non-interactive, just confirm automatically or skip
-                        crate::cli::style::info(
-                            "Non-interactive mode detected. Auto-confirming commit.",
-                        );
+                    if commit == CommitMode::Auto {
                         Self::execute_commit(&msg)?;
+                    } else if !interactive {
+                        // Nobody can answer the prompt: leave the commit to the user
+                        let path = crate::workspace::session_path(&self.session_id)
+                            .join(COMMIT_MESSAGE_FILE);
+                        tokio::fs::create_dir_all(crate::workspace::session_path(&self.session_id))
+                            .await?;
+                        tokio::fs::write(&path, format!("{}\n", msg.trim_end())).await?;
+                        crate::cli::style::warn(&format!(
+                            "No TTY, skipping the commit. Proposed message saved to {} \
+                             (use --auto-commit to commit without asking).",
+                            path.display()
+                        ));
                     } else {
                         let options = vec!["Confirm", "Edit", "Cancel"];
                         let ans = inquire::Select::new(
@@ -209,6 +445,8 @@ impl ManualBuild {
             warnings: vec![],
             evidence_files: vec![evidence_file.filename],
             output: "Manual build completed.".to_string(),
+            iteration_summaries: vec![],
+            timed_out: false,
         })
     }
 
@@ -235,6 +473,8 @@ impl ManualBuild {
 pub struct AutoBuild {
     config: BuildConfig,
     session_id: String,
+    /// Provider the fallback chain picked, once one has run
+    provider_used: std::sync::Mutex<Option<String>>,
 }
 
 impl AutoBuild {
@@ -242,25 +482,66 @@ impl AutoBuild {
         Self {
             config,
             session_id: session_id.to_string(),
+            provider_used: std::sync::Mutex::new(None),
         }
     }
 
+    /// Provider that ran, which differs from `config.provider` after a
+    /// fallback; `config.provider` until one has run
+    pub fn provider_used(&self) -> String {
+        self.provider_used
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| self.config.provider.clone())
+    }
+
     /// Execute auto build
     pub async fn execute(&self, prompt: &str) -> Result<BuildResult> {
-        use crate::provider::{get_provider, Context};
+        use crate::provider::Context;
+
+        if !self.config.phases.runs(BuildPhase::Provider) {
+            return Err(crate::error::validation(
+                "'build auto' needs the provider phase",
+            ));
+        }
 
-        let provider = get_provider(&self.config.provider)?;
+        let (provider_name, provider) = self.config.select_provider()?;
+        *self.provider_used.lock().unwrap() = Some(provider_name);
 
         let context = Context {
             working_dir: std::env::current_dir()?,
             session_id: self.session_id.clone(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
-        let response = provider.execute(prompt, &context).await?;
+        let timeout_secs = self.config.timeout_secs;
+        let Some(response) =
+            execute_provider(provider.as_ref(), prompt, &context, timeout_secs).await?
+        else {
+            let error = provider_timeout_error(timeout_secs);
+            let mut evidence_files = vec![];
+            if self.config.phases.runs(BuildPhase::Capture) {
+                let evidence = EvidenceCollector::new(&self.session_id).await?;
+                evidence_files.push(evidence.capture_build_log(&error).await?.filename);
+            }
+            return Ok(BuildResult {
+                success: false,
+                iterations: 1,
+                errors: vec![error.clone()],
+                warnings: vec![],
+                evidence_files,
+                output: error,
+                iteration_summaries: vec![],
+                timed_out: true,
+            });
+        };
 
-        let evidence = EvidenceCollector::new(&self.session_id).await?;
-        evidence.capture_build_log(&response.output).await?;
+        if self.config.phases.runs(BuildPhase::Capture) {
+            let evidence = EvidenceCollector::new(&self.session_id).await?;
+            evidence.capture_build_log(&response.output).await?;
+        }
 
         Ok(BuildResult {
             success: response.success,
@@ -269,6 +550,67 @@ impl AutoBuild {
             warnings: response.warnings,
             evidence_files: vec![],
             output: response.output,
+            iteration_summaries: vec![],
+            timed_out: false,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_phases() {
+        let only = BuildPhases::select(&[BuildPhase::Lint, BuildPhase::Test], &[]).unwrap();
+        assert!(only.runs(BuildPhase::Test) && only.runs(BuildPhase::Lint));
+        assert!(!only.runs(BuildPhase::Provider) && !only.runs(BuildPhase::Commit));
+        assert_eq!(only.to_string(), "test → lint");
+
+        let skip = BuildPhases::select(&[], &[BuildPhase::Commit]).unwrap();
+        assert!(skip.runs(BuildPhase::Provider) && !skip.runs(BuildPhase::Commit));
+        assert!(!skip.is_full());
+        assert!(BuildPhases::default().is_full());
+
+        // --only test combined with --no-test leaves nothing
+        let test = BuildPhases::select(&[BuildPhase::Test], &[]).unwrap();
+        assert!(test.without(BuildPhase::Test).is_err());
+        assert!(BuildPhases::select(&[BuildPhase::Test], &[BuildPhase::Test]).is_err());
+    }
+
+    #[test]
+    fn test_manual_build_resumes_session_from_state() {
+        use crate::state::{State, StateData};
+
+        let mut state = StateData {
+            current_state: State::PlanCreated,
+            ..Default::default()
+        };
+        let started = ManualBuild::from_state(Some(&state), "sess-start");
+        state
+            .transition_to(State::BuildInProgress, "build start", "sess-start")
+            .unwrap();
+
+        // A later process loads state from disk and brings its own fresh session id
+        let loaded: StateData =
+            serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+        let resumed = ManualBuild::from_state(Some(&loaded), "sess-after-reboot");
+        assert_eq!(resumed.session_id(), "sess-start");
+        assert_eq!(resumed.evidence_dir(), started.evidence_dir());
+        assert_eq!(
+            resumed.evidence_dir(),
+            crate::workspace::evidence_path("sess-start")
+        );
+
+        // Outside a build the fresh session is used
+        state
+            .transition_to(State::BuildDone, "build complete", "sess-start")
+            .unwrap();
+        let next = ManualBuild::from_state(Some(&state), "sess-next");
+        assert_eq!(next.session_id(), "sess-next");
+        assert_eq!(
+            ManualBuild::from_state(None, "sess-x").session_id(),
+            "sess-x"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Dependency-aware ordering for build tasks
+//!
+//! Items are sorted topologically so that every item comes after the items it
+//! depends on. Ties keep their original (plan/file) order.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use crate::cli::tasks::{Task, TaskList};
+
+/// Topologically sort items given as `(id, dependencies)` pairs.
+///
+/// Returns indices into `items`. Dependencies on unknown ids are ignored.
+/// Errors if the dependency graph contains a cycle, naming its path.
+pub fn topo_sort(items: &[(&str, &[String])]) -> Result<Vec<usize>> {
+    let index: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (*id, i))
+        .collect();
+
+    let mut in_degree = vec![0usize; items.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![vec![]; items.len()];
+
+    for (i, (id, deps)) in items.iter().enumerate() {
+        for dep in deps.iter() {
+            match index.get(dep.as_str()) {
+                Some(&d) => {
+                    in_degree[i] += 1;
+                    dependents[d].push(i);
+                }
+                None => tracing::warn!("'{}' depends on unknown item '{}'", id, dep),
+            }
+        }
+    }
+
+    let mut order = Vec::with_capacity(items.len());
+    let mut done = vec![false; items.len()];
+
+    // Repeatedly take the first ready item to keep the original order stable
+    while order.len() < items.len() {
+        let Some(next) = (0..items.len()).find(|&i| !done[i] && in_degree[i] == 0) else {
+            let cycle = find_cycle(items).unwrap_or_default();
+            anyhow::bail!("Dependency cycle detected: {}", cycle.join(" → "));
+        };
+
+        done[next] = true;
+        order.push(next);
+        for &dependent in &dependents[next] {
+            in_degree[dependent] -= 1;
+        }
+    }
+
+    Ok(order)
+}
+
+/// First dependency cycle among `(id, dependencies)` pairs, as the path
+/// `a → b → … → a` (ids in item and dependency order)
+///
+/// Dependencies on unknown ids are ignored.
+pub fn find_cycle<'a>(items: &[(&'a str, &[String])]) -> Option<Vec<&'a str>> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        Visiting,
+        Done,
+    }
+
+    /// DFS from `i`; returns the cycle path if one is reachable
+    fn visit<'a>(
+        i: usize,
+        items: &[(&'a str, &[String])],
+        index: &HashMap<&str, usize>,
+        marks: &mut [Option<Mark>],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<&'a str>> {
+        marks[i] = Some(Mark::Visiting);
+        path.push(i);
+        for dep in items[i].1 {
+            let Some(&d) = index.get(dep.as_str()) else {
+                continue;
+            };
+            match marks[d] {
+                Some(Mark::Visiting) => {
+                    let start = path.iter().position(|&p| p == d).unwrap_or(0);
+                    let mut cycle: Vec<&str> = path[start..].iter().map(|&p| items[p].0).collect();
+                    cycle.push(items[d].0);
+                    return Some(cycle);
+                }
+                Some(Mark::Done) => {}
+                None => {
+                    if let Some(cycle) = visit(d, items, index, marks, path) {
+                        return Some(cycle);
+                    }
+                }
+            }
+        }
+        path.pop();
+        marks[i] = Some(Mark::Done);
+        None
+    }
+
+    let index: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (*id, i))
+        .collect();
+    let mut marks = vec![None; items.len()];
+    (0..items.len()).find_map(|i| {
+        if marks[i].is_some() {
+            return None;
+        }
+        visit(i, items, &index, &mut marks, &mut vec![])
+    })
+}
+
+/// Order tasks so each task follows the tasks it depends on
+pub fn build_order(tasks: &TaskList) -> Result<Vec<&Task>> {
+    let items: Vec<(&str, &[String])> = tasks
+        .tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.dependencies.as_slice()))
+        .collect();
+
+    let order = topo_sort(&items)?;
+    Ok(order.into_iter().map(|i| &tasks.tasks[i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, deps: &[&str]) -> Task {
+        Task {
+            id: id.to_string(),
+            title: format!("Task {}", id),
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn task_list(tasks: Vec<Task>) -> TaskList {
+        TaskList {
+            total_count: tasks.len(),
+            tasks,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_order_respects_dependencies() {
+        let list = task_list(vec![
+            task("3", &["2"]),
+            task("1", &[]),
+            task("2", &["1"]),
+            task("4", &[]),
+        ]);
+
+        let ids: Vec<&str> = build_order(&list)
+            .unwrap()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_build_order_ignores_unknown_dependencies() {
+        let list = task_list(vec![task("1", &["0"]), task("2", &["1"])]);
+        let ids: Vec<&str> = build_order(&list)
+            .unwrap()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_build_order_reports_cycle() {
+        let list = task_list(vec![task("1", &[]), task("2", &["3"]), task("3", &["2"])]);
+
+        let err = build_order(&list).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "unexpected error: {}", err);
+        assert!(err.ends_with("2 → 3 → 2"), "unexpected error: {}", err);
+        assert!(!err.contains("1"));
+    }
+
+    #[test]
+    fn test_find_cycle_returns_the_path() {
+        let deps = |ids: &[&str]| -> Vec<String> { ids.iter().map(|d| d.to_string()).collect() };
+        let (a, b, c) = (deps(&["b"]), deps(&["c", "x"]), deps(&["a"]));
+        let items: Vec<(&str, &[String])> = vec![("a", &a), ("b", &b), ("c", &c), ("d", &[])];
+        assert_eq!(find_cycle(&items), Some(vec!["a", "b", "c", "a"]));
+        assert_eq!(find_cycle(&items[3..]), None);
+    }
+}
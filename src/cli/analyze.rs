@@ -5,6 +5,8 @@
 use anyhow::Result;
 use colored::*;
 
+use crate::error;
+use crate::provider::cache::ProviderCache;
 use crate::provider::{get_provider, Context};
 use crate::workspace;
 
@@ -28,7 +30,7 @@ pub struct AnalysisResult {
 }
 
 /// Run the analyze command
-pub async fn run_analyze(provider: &str) -> Result<()> {
+pub async fn run_analyze(provider: &str, no_cache: bool) -> Result<()> {
     use crate::cli::style;
 
     style::header("Analyze Artifacts");
@@ -41,7 +43,9 @@ pub async fn run_analyze(provider: &str) -> Result<()> {
         .current_state
         .is_at_least(crate::state::State::IntakeCaptured)
     {
-        anyhow::bail!("No intake captured yet. Run 'vibeanvil intake' first.");
+        return Err(error::invalid_state(
+            "No intake captured yet. Run 'vibeanvil intake' first.",
+        ));
     }
 
     // Load all artifacts
@@ -64,9 +68,21 @@ pub async fn run_analyze(provider: &str) -> Result<()> {
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
-    let response = provider_instance.execute(&prompt, &context).await?;
+    let (response, cached) = ProviderCache::new()
+        .execute(
+            "analyze",
+            provider_instance.as_ref(),
+            &prompt,
+            &context,
+            !no_cache,
+        )
+        .await?;
+    if cached {
+        style::info("Using cached provider response (--no-cache to refresh)");
+    }
 
     if response.success {
         println!("\n{}", "═".repeat(60).cyan());
@@ -4,6 +4,7 @@ use anyhow::Result;
 use tokio::fs;
 
 use crate::audit::{generate_session_id, AuditLogger};
+use crate::error;
 use crate::state::State;
 use crate::workspace;
 
@@ -11,7 +12,9 @@ pub async fn run(auto: bool) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::IntakeCaptured) {
-        anyhow::bail!("Intake not captured. Run 'vibeanvil intake' first.");
+        return Err(error::invalid_state(
+            "Intake not captured. Run 'vibeanvil intake' first.",
+        ));
     }
 
     if state_data
@@ -2,10 +2,14 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::brain::storage::{BrainStorage, ExportFormat, ExportOptions};
-use crate::cli::{BrainArgs, BrainCommands};
+use crate::brain::storage::{
+    export_manifest_path, BrainStorage, ExportFilter, ExportFormat, ExportOptions, SearchFilters,
+};
+use crate::brain::{ContentType, Signal};
+use crate::cli::{BrainArgs, BrainCommands, OutputFormat};
 
 pub async fn run(args: BrainArgs) -> Result<()> {
     match args.command {
@@ -13,33 +17,84 @@ pub async fn run(args: BrainArgs) -> Result<()> {
             refresh_core,
             verbose,
         } => ensure_core(refresh_core, verbose).await,
-        BrainCommands::Stats { json } => show_stats(json).await,
+        BrainCommands::Stats {
+            json,
+            format,
+            by_source,
+            include_source_ids,
+        } => {
+            show_stats(
+                OutputFormat::select(json, format),
+                by_source,
+                include_source_ids,
+            )
+            .await
+        }
+        BrainCommands::Map {
+            json,
+            include_source_ids,
+        } => show_map(json, include_source_ids).await,
         BrainCommands::Search {
             query,
+            regex,
             limit,
             record_type,
+            content_types,
             language,
             tags,
             source,
+            signals,
+            min_score,
         } => {
-            search(
-                &query,
-                limit,
-                record_type.as_deref(),
-                language.as_deref(),
-                &tags,
-                source.as_deref(),
-            )
-            .await
+            let filters = SearchFilters {
+                record_type,
+                content_types,
+                language,
+                tags,
+                source_id: source,
+                signals,
+            };
+            let query = match (regex, query) {
+                (Some(pattern), _) => SearchQuery::Regex(pattern),
+                (None, Some(q)) => SearchQuery::Keywords(q),
+                (None, None) => SearchQuery::Filters,
+            };
+            search(&query, limit, &filters, min_score).await
         }
         BrainCommands::Export {
             format,
             output,
             include_source_ids,
             limit,
-        } => export(format, output, include_source_ids, limit).await,
-        BrainCommands::Compact => compact().await,
-        BrainCommands::Pack { output, format } => pack_codebase(&output, &format).await,
+            manifest,
+            languages,
+            content_types,
+            signals,
+            min_chunks,
+        } => {
+            let filter = ExportFilter {
+                languages,
+                content_types: content_types
+                    .iter()
+                    .map(|t| ContentType::from_db_str(t))
+                    .collect(),
+                // clap restricts values to the snake_case signal names
+                signals: signals
+                    .into_iter()
+                    .map(|s| serde_json::from_value::<Signal>(serde_json::Value::String(s)))
+                    .collect::<Result<_, _>>()?,
+                min_chunks,
+            };
+            export(format, output, include_source_ids, limit, manifest, filter).await
+        }
+        BrainCommands::Compact { ttl_days } => compact(ttl_days).await,
+        BrainCommands::Repair => repair().await,
+        BrainCommands::Reindex => reindex().await,
+        BrainCommands::Pack {
+            output,
+            format,
+            max_tokens,
+        } => pack_codebase(&output, &format, max_tokens.map(|n| n as usize)).await,
     }
 }
 
@@ -53,12 +108,97 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
-async fn show_stats(json: bool) -> Result<()> {
+/// Per-source chunk counts, largest first, with source IDs masked unless requested
+fn source_breakdown(
+    by_source: &HashMap<String, usize>,
+    include_source_ids: bool,
+) -> Vec<(String, usize)> {
+    let mut sources: Vec<(&String, &usize)> = by_source.iter().collect();
+    sources.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    sources
+        .into_iter()
+        .enumerate()
+        .map(|(i, (source_id, count))| {
+            let label = if include_source_ids {
+                source_id.clone()
+            } else {
+                format!("source #{}", i + 1)
+            };
+            (label, *count)
+        })
+        .collect()
+}
+
+async fn show_map(json: bool, include_source_ids: bool) -> Result<()> {
     let storage = BrainStorage::new().await?;
-    let stats = storage.stats().await?;
+    let mut topology = storage.topology().await?;
+    if !include_source_ids {
+        for (i, source) in topology.sources.iter_mut().enumerate() {
+            source.source_id = format!("source #{}", i + 1);
+        }
+    }
 
     if json {
-        let output = serde_json::json!({
+        println!("{}", serde_json::to_string_pretty(&topology)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "🗺️  BrainPack Map".cyan().bold());
+    println!("{}", "─".repeat(40).dimmed());
+    if topology.sources.is_empty() {
+        println!(
+            "{}",
+            "The BrainPack is empty. Run 'vibeanvil harvest' to add sources.".yellow()
+        );
+        return Ok(());
+    }
+
+    let branch = |last: bool| if last { "└── " } else { "├── " };
+    let indent = |last: bool| if last { "    " } else { "│   " };
+    for source in &topology.sources {
+        println!(
+            "{} {}",
+            source.source_id.white().bold(),
+            format!("({} chunks)", source.chunks).dimmed()
+        );
+        for (i, content_type) in source.content_types.iter().enumerate() {
+            let last_type = i + 1 == source.content_types.len();
+            println!(
+                "{}{} {}",
+                branch(last_type).dimmed(),
+                content_type.content_type.cyan(),
+                format!("({})", content_type.chunks).dimmed()
+            );
+            for (j, file) in content_type.files.iter().enumerate() {
+                let last_file = j + 1 == content_type.files.len();
+                println!(
+                    "{}{}{} {}",
+                    indent(last_type).dimmed(),
+                    branch(last_file).dimmed(),
+                    file.path,
+                    format!("({})", file.chunks).dimmed()
+                );
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+async fn show_stats(
+    format: Option<OutputFormat>,
+    by_source: bool,
+    include_source_ids: bool,
+) -> Result<()> {
+    let storage = BrainStorage::new().await?;
+    let stats = storage.stats().await?;
+    let sources = source_breakdown(&stats.by_source, include_source_ids);
+
+    if let Some(format) = format {
+        let mut output = serde_json::json!({
             "total_sources": stats.total_sources,
             "total_records": stats.total_records,
             "total_chunks": stats.total_chunks,
@@ -66,9 +206,16 @@ async fn show_stats(json: bool) -> Result<()> {
             "sqlite_size_bytes": stats.sqlite_size_bytes,
             "by_type": stats.by_type,
             "by_language": stats.by_language,
+            "by_signal": stats.by_signal,
             "last_updated": stats.last_updated.map(|t| t.to_string()),
         });
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        if by_source {
+            output["by_source"] = sources
+                .iter()
+                .map(|(source, chunks)| serde_json::json!({ "source": source, "chunks": chunks }))
+                .collect();
+        }
+        println!("{}", format.render(&output)?);
         return Ok(());
     }
 
@@ -171,6 +318,40 @@ async fn show_stats(json: bool) -> Result<()> {
         }
     }
 
+    if !stats.by_signal.is_empty() {
+        println!();
+        println!("{}", "  🔎 By Signal:".white().bold());
+        let mut signals: Vec<_> = stats.by_signal.iter().collect();
+        signals.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (signal, count) in signals {
+            let bar_len = (*count as u64 * 20) / (stats.total_chunks as u64).max(1);
+            let bar = "█".repeat(bar_len as usize);
+            println!(
+                "    {} {:18} {:<20} ({})",
+                "•".cyan(),
+                signal,
+                bar.magenta(),
+                count.to_string().dimmed()
+            );
+        }
+    }
+
+    if by_source && !sources.is_empty() {
+        println!();
+        println!("{}", "  📦 By Source:".white().bold());
+        for (source, count) in &sources {
+            let bar_len = (*count as u64 * 20) / (stats.total_chunks as u64).max(1);
+            let bar = "█".repeat(bar_len as usize);
+            println!(
+                "    {} {:20} {:<20} ({})",
+                "•".cyan(),
+                source,
+                bar.magenta(),
+                count.to_string().dimmed()
+            );
+        }
+    }
+
     // Tips
     println!();
     println!("{}", "─".repeat(50).dimmed());
@@ -183,40 +364,82 @@ async fn show_stats(json: bool) -> Result<()> {
     Ok(())
 }
 
+/// What `brain search` matches against
+enum SearchQuery {
+    /// FTS keyword query
+    Keywords(String),
+    /// Regular expression scanned over chunk text
+    Regex(String),
+    /// No text query; every chunk matching the filters (e.g. `--signal`)
+    Filters,
+}
+
 async fn search(
-    query: &str,
+    query: &SearchQuery,
     limit: usize,
-    record_type: Option<&str>,
-    language: Option<&str>,
-    tags: &[String],
-    source_id: Option<&str>,
+    filters: &SearchFilters,
+    min_score: Option<f64>,
 ) -> Result<()> {
     let storage = BrainStorage::new().await?;
 
     println!();
     let mut filter_str = String::new();
-    if let Some(t) = record_type {
+    if let Some(t) = &filters.record_type {
         filter_str.push_str(&format!(" [type:{}]", t));
     }
-    if let Some(l) = language {
+    if !filters.content_types.is_empty() {
+        filter_str.push_str(&format!(" [types:{}]", filters.content_types.join(",")));
+    }
+    if let Some(l) = &filters.language {
         filter_str.push_str(&format!(" [lang:{}]", l));
     }
-    if !tags.is_empty() {
-        filter_str.push_str(&format!(" [tags:{}]", tags.join(",")));
+    if !filters.tags.is_empty() {
+        filter_str.push_str(&format!(" [tags:{}]", filters.tags.join(",")));
     }
-    if let Some(source) = source_id {
+    if let Some(source) = &filters.source_id {
         filter_str.push_str(&format!(" [source:{}]", source));
     }
+    if !filters.signals.is_empty() {
+        filter_str.push_str(&format!(" [signals:{}]", filters.signals.join(",")));
+    }
+    if let Some(min) = min_score {
+        filter_str.push_str(&format!(" [min-score:{:.2}]", min));
+    }
+    let shown = match query {
+        SearchQuery::Keywords(q) => q.clone(),
+        SearchQuery::Regex(pattern) => format!("/{}/", pattern),
+        SearchQuery::Filters => "*".to_string(),
+    };
     println!(
         "{} {} {}{}",
         "🔍".cyan(),
         "Searching for:".white().bold(),
-        query.cyan().bold(),
+        shown.cyan().bold(),
         filter_str.dimmed()
     );
     println!();
 
-    let results = storage.search_filtered(query, limit, record_type, language, tags, source_id)?;
+    let mut results = match query {
+        SearchQuery::Keywords(q) => storage.search_filtered(q, limit, filters)?,
+        SearchQuery::Regex(pattern) => storage.search_regex(pattern, limit, filters)?,
+        SearchQuery::Filters => storage.list_filtered(limit, filters)?,
+    };
+
+    if let Some(min) = min_score {
+        let total = results.len();
+        results = crate::brain::filter_min_score(results, min);
+        if results.is_empty() && total > 0 {
+            println!(
+                "{} {} match(es) found, but none scored at or above {:.2}",
+                "⚠️".yellow(),
+                total,
+                min
+            );
+            println!("  {} Lower --min-score to see weaker matches", "•".dimmed());
+            println!();
+            return Ok(());
+        }
+    }
 
     if results.is_empty() {
         println!("{}", "┌─────────────────────────────────────────┐".yellow());
@@ -257,7 +480,7 @@ async fn search(
         println!(
             "│  {} {:.2}  │  {} {}",
             "Score:".dimmed(),
-            result.score,
+            result.normalized_score(),
             "Type:".dimmed(),
             result.content_type.green()
         );
@@ -319,6 +542,8 @@ async fn export(
     output: Option<String>,
     include_source_ids: bool,
     limit: usize,
+    manifest: bool,
+    filter: ExportFilter,
 ) -> Result<()> {
     println!();
     println!(
@@ -333,10 +558,13 @@ async fn export(
         format: match format {
             crate::cli::ExportFormat::Jsonl => ExportFormat::Jsonl,
             crate::cli::ExportFormat::Md => ExportFormat::Markdown,
+            crate::cli::ExportFormat::Html => ExportFormat::Html,
         },
         output_path: output.map(PathBuf::from),
         include_source_ids,
         limit,
+        manifest,
+        filter,
     };
 
     let output_path = storage.export(&options).await?;
@@ -356,6 +584,16 @@ async fn export(
     );
     println!();
     println!("  {} {}", "📁 Output:".white().bold(), output_path.cyan());
+    if manifest {
+        println!(
+            "  {} {}",
+            "🧾 Manifest:".white().bold(),
+            export_manifest_path(Path::new(&output_path))
+                .display()
+                .to_string()
+                .cyan()
+        );
+    }
 
     if !include_source_ids {
         println!();
@@ -576,8 +814,52 @@ async fn ensure_core(refresh_core: bool, verbose: bool) -> Result<()> {
     Ok(())
 }
 
-/// Compact the brain pack (dedup JSONL, optimize SQLite)
-async fn compact() -> Result<()> {
+/// Normalize legacy content types
+async fn repair() -> Result<()> {
+    let storage = BrainStorage::new().await?;
+    let result = storage.repair().await?;
+
+    println!();
+    if result.rows_fixed == 0 {
+        println!(
+            "{} Nothing to repair ({} content type value(s) checked)",
+            "✓".green(),
+            result.values_checked
+        );
+    } else {
+        println!(
+            "{} Normalized content_type on {} row(s)",
+            "✓".green(),
+            result.rows_fixed.to_string().green().bold()
+        );
+        println!(
+            "  {} Run 'vibeanvil brain compact' to rewrite the JSONL as well",
+            "💡".dimmed()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+async fn reindex() -> Result<()> {
+    let storage = BrainStorage::new().await?;
+    let started = std::time::Instant::now();
+    let indexed = storage.reindex()?;
+
+    println!();
+    println!(
+        "{} Rebuilt the search index: {} chunk(s) in {:.1}s",
+        "✓".green(),
+        indexed.to_string().green().bold(),
+        started.elapsed().as_secs_f64()
+    );
+    println!();
+    Ok(())
+}
+
+/// Compact the brain pack (dedup JSONL, optimize SQLite), first expiring
+/// records older than `ttl_days`
+async fn compact(ttl_days: Option<u32>) -> Result<()> {
     println!();
     println!(
         "{}",
@@ -614,6 +896,17 @@ async fn compact() -> Result<()> {
     );
     println!();
 
+    if let Some(days) = ttl_days {
+        let expired = storage.purge_older_than(days)?;
+        println!(
+            "{} {} chunk(s) harvested more than {} day(s) ago",
+            "🗑️  Expired:".white().bold(),
+            expired.to_string().yellow(),
+            days
+        );
+        println!();
+    }
+
     println!("{}", "⏳ Compacting...".yellow());
     let result = storage.compact().await?;
 
@@ -659,17 +952,38 @@ async fn compact() -> Result<()> {
     Ok(())
 }
 
-async fn pack_codebase(output: &str, format: &str) -> Result<()> {
-    use crate::brain::pack::{pack_codebase as pack, PackFormat};
+async fn pack_codebase(output: &str, format: &str, max_tokens: Option<usize>) -> Result<()> {
+    use crate::brain::pack::{pack_codebase as pack, PackFormat, TokenBudget};
+    use crate::cli::repomap::RepoMap;
 
     let root = std::env::current_dir()?;
     let fmt: PackFormat = format.parse()?;
 
     crate::cli::style::step("Context Pack");
-    let content = pack(&root, fmt)?;
+    let budget = match max_tokens {
+        Some(max_tokens) => Some(TokenBudget {
+            max_tokens,
+            ranking: RepoMap::new(&root)
+                .await?
+                .ranked_files()
+                .into_iter()
+                .map(|f| f.path.clone())
+                .collect(),
+        }),
+        None => None,
+    };
+    let packed = pack(&root, fmt, budget.as_ref())?;
 
-    std::fs::write(output, &content)?;
+    std::fs::write(output, &packed.content)?;
     crate::cli::style::success(&format!("Packed to {}", output));
+    if budget.is_some() {
+        let manifest_path = format!("{}.manifest.json", output);
+        std::fs::write(
+            &manifest_path,
+            serde_json::to_string_pretty(&packed.manifest)?,
+        )?;
+        crate::cli::style::success(&format!("Manifest written to {}", manifest_path));
+    }
 
     Ok(())
 }
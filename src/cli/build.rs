@@ -4,29 +4,52 @@ use anyhow::Result;
 use colored::Colorize;
 use std::collections::HashMap;
 use std::path::Path;
+use tokio_util::sync::CancellationToken;
 
 use crate::audit::{generate_session_id, AuditLogger};
+use crate::brain::tokens;
 use crate::build::iterate::IterateBuild;
-use crate::build::{AutoBuild, BuildConfig, BuildMode, ManualBuild};
+use crate::build::order::build_order;
+use crate::build::{
+    AutoBuild, BuildConfig, BuildMode, BuildPhase, BuildPhases, BuildResult, CommitMode,
+    ManualBuild,
+};
 use crate::cli::progress::BuildProgress;
+use crate::cli::providers;
+use crate::cli::tasks::{self, Task};
 use crate::cli::{BuildArgs, ManualBuildAction};
+use crate::error;
 use crate::prompt;
+use crate::provider::capability::format_cost;
+use crate::provider::{CapabilityMatrix, TaskType};
 use crate::state::State;
 use crate::workspace;
 
 pub async fn run(args: BuildArgs) -> Result<()> {
-    // Handle resume flag
-    if args.resume {
-        return handle_resume().await;
+    if args.resume && !matches!(args.mode, crate::cli::BuildMode::Iterate) {
+        anyhow::bail!("--resume is only supported with 'build iterate'");
+    }
+    if args.auto_commit && !matches!(args.mode, crate::cli::BuildMode::Manual) {
+        anyhow::bail!("--auto-commit is only supported with 'build manual complete'");
     }
 
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::PlanCreated) {
-        anyhow::bail!("Plan not created. Run 'vibeanvil plan' first.");
+        return Err(error::invalid_state(
+            "Plan not created. Run 'vibeanvil plan' first.",
+        ));
     }
 
-    let session_id = generate_session_id();
+    let resume = if args.resume {
+        handle_resume().await?
+    } else {
+        None
+    };
+    let (provider, fallbacks) = provider_chain(&args.provider)?;
+    let session_id = resume
+        .as_ref()
+        .map_or_else(generate_session_id, |p| p.session_id.clone());
     let logger = AuditLogger::new(&session_id);
 
     // Build config from args
@@ -36,21 +59,69 @@ pub async fn run(args: BuildArgs) -> Result<()> {
             crate::cli::BuildMode::Auto => BuildMode::Auto,
             crate::cli::BuildMode::Iterate => BuildMode::Iterate,
         },
-        provider: args.provider.clone(),
+        provider,
+        fallbacks,
         max_iterations: args.max,
         strict: args.strict,
         timeout_secs: args.timeout,
-        skip_tests: args.no_test,
-        skip_lint: args.no_lint,
+        phases: build_phases(&args)?,
         capture_evidence: args.evidence,
+        template: match &args.prompt_template {
+            Some(name) => name.clone(),
+            None => args
+                .persona
+                .map_or("developer", |p| p.template())
+                .to_string(),
+        },
+        test_cmd: args.test_cmd.clone(),
+        lint_cmd: args.lint_cmd.clone(),
     };
 
+    if (args.test_cmd.is_some() || args.lint_cmd.is_some())
+        && !matches!(config.mode, BuildMode::Iterate)
+    {
+        anyhow::bail!("--test-cmd and --lint-cmd are only supported with 'build iterate'");
+    }
+    if let Some(name) = &args.prompt_template {
+        if matches!(config.mode, BuildMode::Manual) {
+            anyhow::bail!(
+                "--prompt-template is only supported with 'build auto' and 'build iterate'"
+            );
+        }
+        prompt::require_template(name)?;
+    }
+
+    // Auto mode has nothing to run but the provider; finishing the build
+    // without it would mark untouched code as BUILD_DONE
+    if matches!(config.mode, BuildMode::Auto) && !config.phases.runs(BuildPhase::Provider) {
+        return Err(error::validation(
+            "'build auto' needs the provider phase; use 'build iterate' to run only the checks",
+        ));
+    }
+
+    if !config.phases.is_full() {
+        println!("{} {}", "Phases:".dimmed(), config.phases);
+    }
+
     // Handle watch mode (only for iterate)
     if args.watch {
         if !matches!(config.mode, BuildMode::Iterate) {
             anyhow::bail!("Watch mode is only available for 'iterate' build mode. Use: vibeanvil build iterate --watch");
         }
-        return run_watch_mode(config, &session_id, &logger).await;
+        let debounce = std::time::Duration::from_millis(args.debounce_ms);
+        return run_watch_mode(config, debounce, resume, &session_id, &logger).await;
+    }
+
+    let agentic = matches!(config.mode, BuildMode::Iterate);
+    match (config.mode, args.persona) {
+        (BuildMode::Manual, _) => {}
+        (_, Some(persona)) => providers::announce_persona(persona, &config.provider, agentic),
+        (BuildMode::Auto, None) => {
+            providers::warn_if_weak(&config.provider, TaskType::CodeGeneration, false)
+        }
+        (BuildMode::Iterate, None) => {
+            providers::warn_if_weak(&config.provider, TaskType::MultiFileEdit, true)
+        }
     }
 
     match config.mode {
@@ -61,98 +132,138 @@ pub async fn run(args: BuildArgs) -> Result<()> {
             run_auto_build(config, &session_id, &logger).await?;
         }
         BuildMode::Iterate => {
-            run_iterate_build(config, &session_id, &logger).await?;
+            run_iterate_build(config, resume, &session_id, &logger, None).await?;
         }
     }
 
     Ok(())
 }
 
-/// Handle --resume flag
-async fn handle_resume() -> Result<()> {
-    println!("{}", "🔄 Checking for resumable build...".cyan());
+/// Split `--provider a,b,c` into the first choice and its fallbacks
+fn provider_chain(arg: &str) -> Result<(String, Vec<String>)> {
+    let mut names = arg
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from);
+    let first = names
+        .next()
+        .ok_or_else(|| error::validation("--provider needs at least one provider name"))?;
+    Ok((first, names.collect()))
+}
 
-    if let Some(progress) = BuildProgress::load().await? {
-        println!();
-        println!("{}", "Found previous build session:".white().bold());
-        println!("  {} {}", "Session:".dimmed(), progress.session_id);
-        println!("  {} {}", "Mode:".dimmed(), progress.mode);
-        println!("  {} {}", "Step:".dimmed(), progress.current_step);
-        println!(
-            "  {} {}/{}",
-            "Iteration:".dimmed(),
-            progress.current_iteration,
-            progress.max_iterations
-        );
-        println!(
-            "  {} {}",
-            "Started:".dimmed(),
-            progress.started_at.format("%Y-%m-%d %H:%M:%S")
-        );
+/// Phases selected by `--only`/`--skip`, then `--no-test`/`--no-lint`/`--no-commit`
+fn build_phases(args: &BuildArgs) -> Result<BuildPhases> {
+    let convert = |phases: &[crate::cli::BuildPhase]| -> Vec<BuildPhase> {
+        phases
+            .iter()
+            .map(|p| match p {
+                crate::cli::BuildPhase::Capture => BuildPhase::Capture,
+                crate::cli::BuildPhase::Provider => BuildPhase::Provider,
+                crate::cli::BuildPhase::Test => BuildPhase::Test,
+                crate::cli::BuildPhase::Lint => BuildPhase::Lint,
+                crate::cli::BuildPhase::Commit => BuildPhase::Commit,
+            })
+            .collect()
+    };
 
-        if let Some(ref error) = progress.last_error {
-            println!("  {} {}", "Last Error:".red(), error);
-        }
+    let mut phases = BuildPhases::select(&convert(&args.only), &convert(&args.skip))?;
+    if args.no_test {
+        phases = phases.without(BuildPhase::Test)?;
+    }
+    if args.no_lint {
+        phases = phases.without(BuildPhase::Lint)?;
+    }
+    if args.no_commit {
+        phases = phases.without(BuildPhase::Commit)?;
+    }
+    Ok(phases)
+}
 
-        println!();
-        println!(
-            "{}",
-            "To continue from this point, run the build command again without --resume.".dimmed()
-        );
-        println!(
-            "{}",
-            "The session ID will be reused automatically.".dimmed()
-        );
-    } else {
-        println!();
-        println!("{}", "No resumable build found.".yellow());
+/// Load the progress saved by an interrupted iterate build for `--resume`
+async fn handle_resume() -> Result<Option<BuildProgress>> {
+    println!("{}", "🔄 Checking for resumable build...".cyan());
+
+    let Some(progress) = BuildProgress::load().await? else {
         println!(
             "{}",
-            "Start a new build with: vibeanvil build iterate".dimmed()
+            "No resumable build found; starting a new one.".yellow()
         );
+        return Ok(None);
+    };
+
+    println!();
+    println!("{}", "Found previous build session:".white().bold());
+    println!("  {} {}", "Session:".dimmed(), progress.session_id);
+    println!("  {} {}", "Step:".dimmed(), progress.current_step);
+    println!(
+        "  {} {}/{}",
+        "Iteration:".dimmed(),
+        progress.current_iteration,
+        progress.max_iterations
+    );
+    println!(
+        "  {} {}",
+        "Started:".dimmed(),
+        progress.started_at.format("%Y-%m-%d %H:%M:%S")
+    );
+    if let Some(ref error) = progress.last_error {
+        println!("  {} {}", "Last Error:".red(), error);
     }
+    println!();
 
-    Ok(())
+    Ok(Some(progress))
 }
 
 /// Run in watch mode - auto-rebuild on file changes
-async fn run_watch_mode(config: BuildConfig, session_id: &str, logger: &AuditLogger) -> Result<()> {
+async fn run_watch_mode(
+    config: BuildConfig,
+    debounce: std::time::Duration,
+    resume: Option<BuildProgress>,
+    session_id: &str,
+    logger: &AuditLogger,
+) -> Result<()> {
     use crate::cli::watch::FileWatcher;
 
     println!("{}", "🔄 Starting watch mode...".cyan().bold());
     println!();
 
     // Initial build
-    run_iterate_build(config.clone(), session_id, logger).await?;
-
-    // Start watching
-    let watcher = FileWatcher::new();
-
-    // Clone what we need for the closure
-    let config_clone = config.clone();
-    let session_id_clone = session_id.to_string();
-
-    // Note: This is a blocking call that runs the event loop
-    // In a real implementation, you'd want to handle this with proper async
-    watcher.watch(move || {
-        // Create a new runtime for each rebuild
-        // This is a workaround for the sync callback requirement
-        let rt = tokio::runtime::Runtime::new()?;
-        let logger = AuditLogger::new(&session_id_clone);
+    let last = std::sync::Mutex::new(
+        run_iterate_build(config.clone(), resume, session_id, logger, None).await?,
+    );
 
-        rt.block_on(async {
-            // Reset state for rebuild
+    let watcher = FileWatcher::new(std::env::current_dir()?, debounce);
+    let (config, last_ref) = (&config, &last);
+    watcher
+        .watch(|_paths, cancel| async move {
+            // Re-enter the build from BUILD_DONE through the normal transition
             let mut state = workspace::load_state().await?;
             if state.current_state == State::BuildDone {
-                // Allow re-running build after completion
-                state.current_state = State::PlanCreated;
+                state.transition_to(State::BuildInProgress, "build iterate watch", session_id)?;
                 workspace::save_state(&state).await?;
+                logger
+                    .log_state_transition(
+                        "build iterate watch",
+                        State::BuildDone,
+                        State::BuildInProgress,
+                    )
+                    .await?;
             }
 
-            run_iterate_build(config_clone.clone(), &session_id_clone, &logger).await
+            let result =
+                run_iterate_build(config.clone(), None, session_id, logger, Some(cancel)).await?;
+            *last_ref.lock().unwrap() = result;
+            Ok(())
         })
-    })?;
+        .await?;
 
+    // Record the outcome of the last completed run
+    let result = last.into_inner().unwrap();
+    let path = workspace::session_path(session_id).join("build_result.json");
+    tokio::fs::create_dir_all(workspace::session_path(session_id)).await?;
+    tokio::fs::write(&path, serde_json::to_string_pretty(&result)?).await?;
+    println!("{} {}", "Final build result:".dimmed(), path.display());
     Ok(())
 }
 
@@ -163,16 +274,17 @@ async fn run_manual_build(args: &BuildArgs, session_id: &str, logger: &AuditLogg
         ManualBuildAction::Start => {
             // Update state to build in progress
             let mut state = workspace::load_state().await?;
-            if state.current_state == State::PlanCreated || state.current_state == State::BuildDone
-            {
+            if state.current_state == State::BuildInProgress {
+                anyhow::bail!(
+                    "Build already in progress. Run 'vibeanvil build manual complete' to finish."
+                );
+            }
+            let from = state.current_state;
+            if from.can_transition_to(State::BuildInProgress) {
                 state.transition_to(State::BuildInProgress, "build start", session_id)?;
                 workspace::save_state(&state).await?;
                 logger
-                    .log_state_transition(
-                        "build start",
-                        state.current_state,
-                        State::BuildInProgress,
-                    )
+                    .log_state_transition("build start", from, State::BuildInProgress)
                     .await?;
             }
 
@@ -185,11 +297,18 @@ async fn run_manual_build(args: &BuildArgs, session_id: &str, logger: &AuditLogg
         }
         ManualBuildAction::Complete => {
             let build = ManualBuild::new(session_id).await?;
-            let result = build.complete().await?;
+            let commit = if !build_phases(args)?.runs(BuildPhase::Commit) {
+                CommitMode::Skip
+            } else if args.auto_commit {
+                CommitMode::Auto
+            } else {
+                CommitMode::Prompt
+            };
+            let result = build.complete(commit).await?;
 
             // Update state to build done
             let mut state = workspace::load_state().await?;
-            state.transition_to(State::BuildDone, "build complete", session_id)?;
+            state.transition_to(State::BuildDone, "build complete", build.session_id())?;
             workspace::save_state(&state).await?;
             logger
                 .log_state_transition("build complete", State::BuildInProgress, State::BuildDone)
@@ -206,6 +325,8 @@ async fn run_manual_build(args: &BuildArgs, session_id: &str, logger: &AuditLogg
 }
 
 async fn run_auto_build(config: BuildConfig, session_id: &str, logger: &AuditLogger) -> Result<()> {
+    let pending_tasks = ordered_pending_tasks().await?;
+
     // Update state to build in progress
     let mut state = workspace::load_state().await?;
     if state.current_state == State::PlanCreated {
@@ -220,8 +341,17 @@ async fn run_auto_build(config: BuildConfig, session_id: &str, logger: &AuditLog
             .await?;
     }
 
-    println!("🔧 Running auto build with {} provider...", config.provider);
+    if config.fallbacks.is_empty() {
+        println!("🔧 Running auto build with {} provider...", config.provider);
+    } else {
+        println!(
+            "🔧 Running auto build with {} provider (fallbacks: {})...",
+            config.provider,
+            config.fallbacks.join(", ")
+        );
+    }
 
+    let template = config.template.clone();
     let build = AutoBuild::new(config, session_id);
 
     // Read plan and contract for context
@@ -232,8 +362,21 @@ async fn run_auto_build(config: BuildConfig, session_id: &str, logger: &AuditLog
     let contract = load_contract().await;
     let repo_context = build_repo_context();
 
-    let prompt = build_developer_prompt(&plan, &contract, &repo_context);
-    let result = build.execute(&prompt).await?;
+    let mut result = empty_result();
+    let mut prompt_tokens = 0;
+    for (label, prompt) in build_prompts(&plan, &contract, &repo_context, &pending_tasks, &template)
+    {
+        if let Some(label) = label {
+            println!("{} {}", "▶".cyan(), label);
+        }
+        let step = build.execute(&prompt).await?;
+        prompt_tokens += tokens::estimate(&prompt) * step.iterations.max(1) as usize;
+        merge_result(&mut result, step);
+        if result.timed_out {
+            break;
+        }
+    }
+    stop_if_timed_out(&result)?;
 
     // Update state to build done
     let mut state = workspace::load_state().await?;
@@ -255,6 +398,7 @@ async fn run_auto_build(config: BuildConfig, session_id: &str, logger: &AuditLog
             println!("  - {}", error);
         }
     }
+    print_cost_estimate(&build.provider_used(), prompt_tokens, &result.output);
 
     println!();
     println!("Next: vibeanvil review start");
@@ -264,9 +408,13 @@ async fn run_auto_build(config: BuildConfig, session_id: &str, logger: &AuditLog
 
 async fn run_iterate_build(
     config: BuildConfig,
+    resume: Option<BuildProgress>,
     session_id: &str,
     logger: &AuditLogger,
-) -> Result<()> {
+    cancel: Option<CancellationToken>,
+) -> Result<BuildResult> {
+    let pending_tasks = ordered_pending_tasks().await?;
+
     // Update state to build in progress
     let mut state = workspace::load_state().await?;
     if state.current_state == State::PlanCreated {
@@ -286,7 +434,7 @@ async fn run_iterate_build(
         config.max_iterations
     );
 
-    let build = IterateBuild::new(config.clone(), session_id).await?;
+    let template = config.template.clone();
 
     // Read plan and contract for context
     let plan_path = workspace::workspace_path().join("plan.md");
@@ -295,14 +443,72 @@ async fn run_iterate_build(
         .unwrap_or_default();
     let contract = load_contract().await;
     let repo_context = build_repo_context();
+    let prompts = build_prompts(&plan, &contract, &repo_context, &pending_tasks, &template);
+
+    // The saved prompt belongs to the task that was interrupted; the ones
+    // before it had finished
+    let cancel = cancel.unwrap_or_default();
+    let mut build = IterateBuild::new(config.clone(), session_id)
+        .await?
+        .with_cancel(cancel.clone());
+    let mut skip = 0;
+    if let Some(progress) = resume {
+        let position = match progress.task.as_deref() {
+            Some(task) => prompts
+                .iter()
+                .position(|(label, _)| label.as_deref() == Some(task)),
+            None => Some(0),
+        };
+        match position {
+            Some(position) => {
+                match progress.task.as_deref() {
+                    Some(task) => println!(
+                        "↩ Resuming {} after iteration {}",
+                        task, progress.current_iteration
+                    ),
+                    None => println!("↩ Resuming after iteration {}", progress.current_iteration),
+                }
+                if position > 0 {
+                    println!(
+                        "↩ Skipping {} task(s) finished before the interruption",
+                        position
+                    );
+                }
+                skip = position;
+                build = build.resume_from(progress);
+            }
+            None => println!(
+                "{}",
+                "⚠️  The interrupted task is no longer pending; starting the build over".yellow()
+            ),
+        }
+    }
 
-    let prompt = build_developer_prompt(&plan, &contract, &repo_context);
-    let result = build.execute(&prompt).await?;
+    let mut result = empty_result();
+    let mut prompt_tokens = 0;
+    for (label, prompt) in prompts.into_iter().skip(skip) {
+        if let Some(label) = &label {
+            println!("{} {}", "▶".cyan(), label);
+        }
+        let step = build.execute_task(label.as_deref(), &prompt).await?;
+        prompt_tokens += tokens::estimate(&prompt) * step.iterations.max(1) as usize;
+        merge_result(&mut result, step);
+        if result.timed_out || cancel.is_cancelled() {
+            break;
+        }
+    }
+    stop_if_timed_out(&result)?;
+    if cancel.is_cancelled() {
+        anyhow::bail!(
+            "Build cancelled; the build stays in progress. Continue it with 'vibeanvil build iterate --resume'"
+        );
+    }
 
     // Update state to build done
     let mut state = workspace::load_state().await?;
     state.transition_to(State::BuildDone, "build iterate complete", session_id)?;
     workspace::save_state(&state).await?;
+    BuildProgress::clear().await?;
     logger
         .log_state_transition(
             "build iterate complete",
@@ -311,6 +517,17 @@ async fn run_iterate_build(
         )
         .await?;
 
+    for summary in &result.iteration_summaries {
+        let mark = |passed: bool| if passed { "✓" } else { "✗" };
+        println!(
+            "  Iteration {}: tests {}, lint {} ({} evidence file(s))",
+            summary.iteration,
+            mark(summary.tests_passed),
+            mark(summary.lint_passed),
+            summary.evidence_files.len()
+        );
+    }
+
     if result.success {
         println!(
             "✓ Iterate build completed in {} iteration(s)",
@@ -325,15 +542,16 @@ async fn run_iterate_build(
             println!("  - {}", error);
         }
     }
+    print_cost_estimate(&build.provider_used(), prompt_tokens, &result.output);
 
     println!();
     println!("Next: vibeanvil review start");
 
-    Ok(())
+    Ok(result)
 }
 
 async fn load_contract() -> String {
-    let contract_path = workspace::contracts_path().join("contract.json");
+    let contract_path = crate::contract::contract_path();
     tokio::fs::read_to_string(&contract_path)
         .await
         .unwrap_or_default()
@@ -350,14 +568,138 @@ fn build_repo_context() -> String {
     }
 }
 
-fn build_developer_prompt(plan: &str, contract: &str, context: &str) -> String {
+/// Pending tasks from the task list, in dependency order (empty if no task list)
+async fn ordered_pending_tasks() -> Result<Vec<Task>> {
+    let Ok(task_list) = tasks::load_tasks().await else {
+        return Ok(vec![]);
+    };
+
+    Ok(build_order(&task_list)?
+        .into_iter()
+        .filter(|t| !t.completed)
+        .cloned()
+        .collect())
+}
+
+/// One developer prompt per pending task, or a single prompt for the whole plan
+fn build_prompts(
+    plan: &str,
+    contract: &str,
+    context: &str,
+    pending_tasks: &[Task],
+    template: &str,
+) -> Vec<(Option<String>, String)> {
+    if pending_tasks.is_empty() {
+        return vec![(
+            None,
+            build_developer_prompt(plan, contract, context, template),
+        )];
+    }
+
+    pending_tasks
+        .iter()
+        .map(|task| {
+            let label = format!("Task {}: {}", task.id, task.title);
+            let mut focus = format!("## Current Task\n\n{}\n", label);
+            if !task.description.is_empty() {
+                focus.push_str(&format!("\n{}\n", task.description));
+            }
+            if !task.files.is_empty() {
+                focus.push_str(&format!("\nFiles: {}\n", task.files.join(", ")));
+            }
+            focus.push_str(&format!("\n## Implementation Plan\n\n{}", plan));
+
+            (
+                Some(label),
+                build_developer_prompt(&focus, contract, context, template),
+            )
+        })
+        .collect()
+}
+
+/// Rough cost of the build on `provider`, from the prompt and output sizes
+fn print_cost_estimate(provider: &str, prompt_tokens: usize, output: &str) {
+    let matrix = CapabilityMatrix::build_default();
+    let Some(profile) = matrix.get(&provider.replace('-', "_")) else {
+        return;
+    };
+    if profile.cost_per_1k == 0.0 {
+        return;
+    }
+    let output_tokens = tokens::estimate(output);
+    let cost = profile.estimated_cost(
+        u32::try_from(prompt_tokens).unwrap_or(u32::MAX),
+        u32::try_from(output_tokens).unwrap_or(u32::MAX),
+    );
+    println!(
+        "  Estimated cost: {} (~{} prompt + ~{} output tokens on {})",
+        format_cost(cost),
+        prompt_tokens,
+        output_tokens,
+        provider
+    );
+}
+
+/// A provider timeout ends the build without reaching BUILD_DONE, so the
+/// build can be rerun (with a larger `--timeout`) from where it stopped
+fn stop_if_timed_out(result: &BuildResult) -> Result<()> {
+    if !result.timed_out {
+        return Ok(());
+    }
+    for file in &result.evidence_files {
+        println!("  Evidence: {}", file);
+    }
+    anyhow::bail!(
+        "{}; the build stays in progress. Rerun it, or raise --timeout",
+        result
+            .errors
+            .last()
+            .map_or("Provider timed out", String::as_str)
+    )
+}
+
+fn empty_result() -> BuildResult {
+    BuildResult {
+        success: true,
+        iterations: 0,
+        errors: vec![],
+        warnings: vec![],
+        evidence_files: vec![],
+        output: String::new(),
+        iteration_summaries: vec![],
+        timed_out: false,
+    }
+}
+
+/// Fold a per-task result into the overall build result
+fn merge_result(total: &mut BuildResult, result: BuildResult) {
+    total.success &= result.success;
+    total.timed_out |= result.timed_out;
+    total.iterations += result.iterations;
+    total.errors.extend(result.errors);
+    total.warnings.extend(result.warnings);
+    total.evidence_files.extend(result.evidence_files);
+    total.iteration_summaries.extend(result.iteration_summaries);
+    if !total.output.is_empty() {
+        total.output.push('\n');
+    }
+    total.output.push_str(&result.output);
+}
+
+fn build_developer_prompt(plan: &str, contract: &str, context: &str, template: &str) -> String {
+    // Review and QA personas work on the current changes
+    let diff = if prompt::uses_diff(template) {
+        crate::evidence::git_diff_head().unwrap_or_default()
+    } else {
+        String::new()
+    };
+
     let mut vars = HashMap::new();
     vars.insert("task", plan);
     vars.insert("contract", contract);
     vars.insert("context", context);
-
-    match prompt::load_template("developer") {
-        Ok(template) => prompt::render(&template, &vars),
-        Err(_) => format!("Implement the following plan:\n\n{}", plan),
-    }
+    vars.insert("diff", diff.as_str());
+    vars.insert("code", diff.as_str());
+    prompt::render_over(template, "developer", &vars)
+        .unwrap_or_else(|_| format!("Implement the following plan:\n\n{}", plan))
 }
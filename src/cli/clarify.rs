@@ -6,6 +6,7 @@
 use anyhow::Result;
 use colored::*;
 
+use crate::error;
 use crate::provider::{get_provider, Context};
 use crate::workspace;
 
@@ -23,7 +24,9 @@ pub async fn run_clarify(provider: &str) -> Result<()> {
         .current_state
         .is_at_least(crate::state::State::IntakeCaptured)
     {
-        anyhow::bail!("No intake captured yet. Run 'vibeanvil intake' first.");
+        return Err(error::invalid_state(
+            "No intake captured yet. Run 'vibeanvil intake' first.",
+        ));
     }
 
     // Load intake content
@@ -59,6 +62,7 @@ pub async fn run_clarify(provider: &str) -> Result<()> {
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
     let response = provider_instance.execute(&prompt, &context).await?;
@@ -95,6 +95,7 @@ pub async fn run_constitution(
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
     let response = provider_instance.execute(&prompt, &context).await?;
@@ -1,35 +1,218 @@
 //! Contract command handler
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
 use tokio::fs;
 
-use crate::audit::{generate_session_id, AuditLogger};
-use crate::cli::ContractAction;
-use crate::contract::{self, Contract, Priority};
+use crate::audit::{generate_session_id, AuditEntry, AuditLogger};
+use crate::build::order::topo_sort;
+use crate::cli::{ContractAction, ContractArgs, OutputFormat, RequirementAction};
+use crate::contract::{self, diff, signing, Contract, ContractFormat, ContractStatus, Priority};
+use crate::error;
 use crate::state::State;
 use crate::workspace;
 
-pub async fn run(action: ContractAction) -> Result<()> {
-    match action {
-        ContractAction::Create => create_contract().await,
-        ContractAction::Validate => validate_contract().await,
-        ContractAction::Lock => lock_contract().await,
-        ContractAction::Status => show_status().await,
+pub async fn run(args: ContractArgs) -> Result<()> {
+    if args.sign && !matches!(args.action, ContractAction::Lock) {
+        anyhow::bail!("--sign is only supported with 'contract lock'");
+    }
+    let format = OutputFormat::select(args.json, args.format);
+    if format.is_some()
+        && !matches!(
+            args.action,
+            ContractAction::Status | ContractAction::Validate | ContractAction::Diff
+        )
+    {
+        anyhow::bail!(
+            "--json and --format are only supported with 'contract status', 'contract validate' and 'contract diff'"
+        );
+    }
+    if args.freeze_until.is_some() && !matches!(args.action, ContractAction::Lock) {
+        anyhow::bail!("--freeze-until is only supported with 'contract lock'");
+    }
+    if let Some(until) = args.freeze_until {
+        if until <= chrono::Utc::now() {
+            return Err(error::validation(format!(
+                "--freeze-until {} is in the past",
+                until
+            )));
+        }
+    }
+    if (args.force_unfreeze || args.yes) && !matches!(args.action, ContractAction::Amend) {
+        anyhow::bail!("--force-unfreeze and --yes are only supported with 'contract amend'");
+    }
+    if args.diff && !matches!(args.action, ContractAction::Validate) {
+        anyhow::bail!("--diff is only supported with 'contract validate'");
+    }
+    if args.base.is_some() && !matches!(args.action, ContractAction::Merge) {
+        anyhow::bail!("--base and --theirs are only supported with 'contract merge'");
+    }
+    if args.to.is_some() && !matches!(args.action, ContractAction::Convert) {
+        anyhow::bail!("--to is only supported with 'contract convert'");
+    }
+    let is_req = matches!(args.action, ContractAction::Req);
+    if !is_req
+        && (args.req_action.is_some()
+            || args.requirement.is_some()
+            || args.id.is_some()
+            || args.desc.is_some()
+            || args.priority.is_some())
+    {
+        anyhow::bail!(
+            "Requirement IDs, --id, --desc and --priority are only supported with 'contract req'"
+        );
+    }
+
+    if let Some(name) = &args.name {
+        contract::validate_name(name)?;
+    }
+    let name = args.name.as_deref();
+
+    match args.action {
+        ContractAction::Create => create_contract(name).await,
+        ContractAction::Validate => match format {
+            Some(format) => validate_contract_report(name, args.diff, format).await,
+            None => validate_contract(name, args.diff).await,
+        },
+        ContractAction::Lock => {
+            let key = if args.sign { args.key.as_deref() } else { None };
+            match name {
+                Some(name) => lock_named_contract(name, key, args.freeze_until).await,
+                None => lock_contract(key, args.freeze_until).await,
+            }
+        }
+        ContractAction::Status => match format {
+            Some(format) => show_status_report(name, format).await,
+            None => show_status(name).await,
+        },
+        ContractAction::Verify => verify_contract(name).await,
+        ContractAction::Diff => match format {
+            Some(format) => diff_contract_report(name, format).await,
+            None => diff_contract(name).await,
+        },
+        ContractAction::Amend => amend_contract(name, args.force_unfreeze, args.yes).await,
+        ContractAction::Merge => match (&args.base, &args.theirs) {
+            (Some(base), Some(theirs)) => merge_contract(name, base, theirs).await,
+            _ => anyhow::bail!("'contract merge' requires --base <FILE> and --theirs <FILE>"),
+        },
+        ContractAction::Convert => match args.to {
+            Some(to) => convert_contract(name, to.into()).await,
+            None => anyhow::bail!("'contract convert' requires --to <json|toml>"),
+        },
+        ContractAction::Req => edit_requirements(name, &args).await,
     }
 }
 
-async fn create_contract() -> Result<()> {
+/// `contract req add|edit|rm`: change requirements on a draft and re-save it
+async fn edit_requirements(name: Option<&str>, args: &ContractArgs) -> Result<()> {
+    let Some(action) = args.req_action else {
+        anyhow::bail!("'contract req' requires an operation: add, edit or rm");
+    };
+    if action == RequirementAction::Add && args.requirement.is_some() {
+        anyhow::bail!("'contract req add' takes the ID as --id <ID>");
+    }
+    if action != RequirementAction::Add && args.id.is_some() {
+        anyhow::bail!("--id is only supported with 'contract req add'");
+    }
+    if action == RequirementAction::Rm && (args.desc.is_some() || args.priority.is_some()) {
+        anyhow::bail!("--desc and --priority are only supported with 'contract req add' and 'contract req edit'");
+    }
+
+    let mut contract = contract::load_named_contract(name).await?;
+    if contract.is_locked() {
+        return Err(error::invalid_state(
+            "Contract is locked. Run 'vibeanvil contract amend' to reopen it as a draft first.",
+        ));
+    }
+
+    let priority = args.priority.map(Priority::from);
+    let message = match action {
+        RequirementAction::Add => {
+            let (Some(id), Some(desc)) = (&args.id, &args.desc) else {
+                anyhow::bail!("'contract req add' requires --id <ID> and --desc <TEXT>");
+            };
+            contract.try_add_requirement(id, desc, priority.unwrap_or(Priority::Must))?;
+            format!("Added requirement {}", id.trim())
+        }
+        RequirementAction::Edit => {
+            let Some(id) = &args.requirement else {
+                anyhow::bail!("'contract req edit' requires a requirement ID");
+            };
+            if args.desc.is_none() && priority.is_none() {
+                anyhow::bail!("'contract req edit' requires --desc and/or --priority");
+            }
+            contract.update_requirement(id, args.desc.as_deref(), priority)?;
+            format!("Updated requirement {}", id)
+        }
+        RequirementAction::Rm => {
+            let Some(id) = &args.requirement else {
+                anyhow::bail!("'contract req rm' requires a requirement ID");
+            };
+            contract.remove_requirement(id)?;
+            format!("Removed requirement {}", id)
+        }
+    };
+    contract::save_named_contract(&contract, name).await?;
+
+    println!("✓ {}", message);
+    println!(
+        "  {} requirement(s) in the draft",
+        contract.requirements.len()
+    );
+    println!();
+    println!("Next: vibeanvil contract validate");
+
+    Ok(())
+}
+
+/// Starter contract filled in from the template
+fn starter_contract(project_name: &str) -> Contract {
+    let mut contract = Contract::new(project_name);
+    contract.description = "Project description from intake and blueprint".to_string();
+    contract.add_goal("Primary project goal");
+    contract.add_requirement("REQ-001", "First requirement", Priority::Must);
+    contract.add_acceptance_criterion("Acceptance criterion 1");
+    contract
+}
+
+async fn create_contract(name: Option<&str>) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data
         .current_state
         .is_at_least(State::BlueprintDrafted)
     {
-        anyhow::bail!("Blueprint not drafted. Run 'vibeanvil blueprint' first.");
+        return Err(error::invalid_state(
+            "Blueprint not drafted. Run 'vibeanvil blueprint' first.",
+        ));
+    }
+
+    // Named specs are tracked alongside the workflow, not by it
+    if let Some(name) = name {
+        if let Some((path, _)) = contract::find_contract(Some(name)) {
+            println!("Contract '{}' already exists at {}", name, path.display());
+            return Ok(());
+        }
+        contract::save_named_contract(&starter_contract(name), Some(name)).await?;
+        println!("✓ Contract '{}' created", name);
+        println!(
+            "  → Edit at .vibeanvil/contracts/{}/contract.{}",
+            name, state_data.contract_format
+        );
+        println!();
+        println!("Next: vibeanvil contract validate --name {}", name);
+        println!("Then: vibeanvil contract lock --name {}", name);
+        return Ok(());
     }
 
     if state_data.current_state.is_at_least(State::ContractDrafted) {
-        println!("Contract already exists. View with 'cat .vibeanvil/contracts/contract.json'");
+        println!(
+            "Contract already exists. View with 'cat {}'",
+            contract::contract_path().display()
+        );
         return Ok(());
     }
 
@@ -42,12 +225,7 @@ async fn create_contract() -> Result<()> {
     };
 
     // Create contract
-    let mut contract = Contract::new("Project Name");
-    contract.description = "Project description from intake and blueprint".to_string();
-    contract.add_goal("Primary project goal");
-    contract.add_requirement("REQ-001", "First requirement", Priority::Must);
-    contract.add_acceptance_criterion("Acceptance criterion 1");
-
+    let contract = starter_contract("Project Name");
     contract::save_contract(&contract).await?;
 
     // Update state
@@ -67,7 +245,10 @@ async fn create_contract() -> Result<()> {
         .await?;
 
     println!("✓ Contract created");
-    println!("  → Edit at .vibeanvil/contracts/contract.json");
+    println!(
+        "  → Edit at .vibeanvil/contracts/contract.{}",
+        state_data.contract_format
+    );
     println!();
     println!("Next: vibeanvil contract validate");
     println!("Then: vibeanvil contract lock");
@@ -75,8 +256,8 @@ async fn create_contract() -> Result<()> {
     Ok(())
 }
 
-async fn validate_contract() -> Result<()> {
-    let contract = contract::load_contract().await?;
+async fn validate_contract(name: Option<&str>, show_diff: bool) -> Result<()> {
+    let contract = contract::load_named_contract(name).await?;
     let validation = contract.validate();
 
     if validation.valid {
@@ -96,14 +277,129 @@ async fn validate_contract() -> Result<()> {
         }
     }
 
+    if show_diff {
+        println!();
+        match locked_diff(name, &contract).await? {
+            None => println!("Contract is not locked; nothing to diff against."),
+            Some((hash, None)) => println!(
+                "Lock {} has no contract snapshot; run 'vibeanvil contract diff' to compare hashes.",
+                contract::short_hash(&hash)
+            ),
+            Some((hash, Some(diff))) if diff.is_empty() => {
+                println!("No changes since lock {}", contract::short_hash(&hash))
+            }
+            Some((hash, Some(diff))) => {
+                println!("Changes since lock {}:", contract::short_hash(&hash));
+                print_diff(&diff);
+            }
+        }
+    }
+
+    if !validation.valid {
+        return Err(error::validation(format!(
+            "Contract has {} validation error(s)",
+            validation.errors.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Diff of the contract against its lock snapshot, with the lock hash
+///
+/// `None` when the contract isn't locked; the diff is `None` when the lock
+/// predates snapshots or was taken under another schema.
+async fn locked_diff(
+    name: Option<&str>,
+    contract: &Contract,
+) -> Result<Option<(String, Option<diff::ContractDiff>)>> {
+    if !contract::named_lock_path(name).exists() {
+        return Ok(None);
+    }
+    let lock = contract::load_named_lock(name).await?;
+    let diff = lock
+        .contract
+        .as_ref()
+        .filter(|_| lock.schema_version == contract.schema_version)
+        .map(|locked| diff::compute_diff(locked, contract));
+    Ok(Some((lock.hash, diff)))
+}
+
+/// Print diff lines with coloured `+`/`-` markers
+fn print_diff(diff: &diff::ContractDiff) {
+    for (sign, line) in diff.lines() {
+        let line = format!("{} {}", sign, line);
+        match sign {
+            '+' => println!("  {}", line.green()),
+            '-' => println!("  {}", line.red()),
+            _ => println!("  {}", line.yellow()),
+        }
+    }
+}
+
+/// Machine-readable validation result
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+    valid: bool,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    /// Contract hash, computed with the lock's tool version when locked so it
+    /// can be compared with the lock hash
+    hash: String,
+    schema_version: String,
+    /// Changes since the lock (`--diff` on a locked contract with a snapshot)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<diff::ContractDiff>,
+}
+
+async fn validate_contract_report(
+    name: Option<&str>,
+    show_diff: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let contract = contract::load_named_contract(name).await?;
+    let validation = contract.validate();
+    let tool_version = match contract::load_named_lock(name).await {
+        Ok(lock) => lock.tool_version,
+        Err(_) => env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let diff = if show_diff {
+        locked_diff(name, &contract)
+            .await?
+            .and_then(|(_, diff)| diff)
+    } else {
+        None
+    };
+
+    let report = ValidationReport {
+        valid: validation.valid,
+        hash: contract.generate_hash(&tool_version)?,
+        schema_version: contract.schema_version.clone(),
+        errors: validation.errors,
+        warnings: validation.warnings,
+        diff,
+    };
+    println!("{}", format.render(&report)?);
+
+    if !report.valid {
+        return Err(error::validation(format!(
+            "Contract has {} validation error(s)",
+            report.errors.len()
+        )));
+    }
     Ok(())
 }
 
-async fn lock_contract() -> Result<()> {
+async fn lock_contract(
+    signing_key: Option<&Path>,
+    frozen_until: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::ContractDrafted) {
-        anyhow::bail!("No contract to lock. Run 'vibeanvil contract create' first.");
+        return Err(error::invalid_state(
+            "No contract to lock. Run 'vibeanvil contract create' first.",
+        ));
     }
 
     if state_data.current_state.is_at_least(State::ContractLocked) {
@@ -117,7 +413,14 @@ async fn lock_contract() -> Result<()> {
     let mut contract = contract::load_contract().await?;
     let tool_version = env!("CARGO_PKG_VERSION");
 
-    let lock = contract.lock(tool_version)?;
+    // Load the key before locking so a bad key leaves the contract untouched
+    let key = signing_key.map(signing::load_signing_key).transpose()?;
+
+    let mut lock = contract.lock(tool_version)?;
+    lock.frozen_until = frozen_until;
+    if let Some(key) = &key {
+        signing::sign_lock(&mut lock, key);
+    }
     contract::save_contract(&contract).await?;
     contract::save_lock(&lock).await?;
 
@@ -129,19 +432,33 @@ async fn lock_contract() -> Result<()> {
     workspace::save_state(&state_data).await?;
 
     // Audit
-    let logger = AuditLogger::new(&session_id);
-    logger
-        .log_state_transition(
-            "contract lock",
-            State::ContractDrafted,
-            State::ContractLocked,
-        )
-        .await?;
+    let mut entry = AuditEntry::new("contract lock", vec![], &session_id)
+        .with_state_transition(State::ContractDrafted, State::ContractLocked);
+    if lock.supersedes.is_some() || lock.frozen_until.is_some() {
+        entry = entry.with_metadata(serde_json::json!({
+            "contract_version": contract.contract_version,
+            "supersedes": lock.supersedes,
+            "frozen_until": lock.frozen_until,
+        }));
+    }
+    AuditLogger::new(&session_id).log(&entry).await?;
 
     println!("🔒 Contract LOCKED");
     println!();
     println!("  Spec Hash: {}", lock.hash);
     println!("  Locked at: {}", lock.locked_at);
+    if let Some(previous) = &lock.supersedes {
+        println!(
+            "  Version {} (supersedes {})",
+            contract.contract_version, previous
+        );
+    }
+    if let Some(until) = lock.frozen_until {
+        println!("  Frozen until: {}", until);
+    }
+    if let Some(public_key) = &lock.public_key {
+        println!("  Signed by: {}", public_key);
+    }
     println!();
     println!("\"Contract LOCKED = License to Build\"");
     println!();
@@ -150,19 +467,78 @@ async fn lock_contract() -> Result<()> {
     Ok(())
 }
 
-async fn show_status() -> Result<()> {
-    match contract::load_contract().await {
+/// Lock a named contract independently of the workflow state
+async fn lock_named_contract(
+    name: &str,
+    signing_key: Option<&Path>,
+    frozen_until: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<()> {
+    workspace::load_state().await?;
+
+    let mut contract = contract::load_named_contract(Some(name)).await?;
+    if contract.is_locked() {
+        let lock = contract::load_named_lock(Some(name)).await?;
+        println!("Contract '{}' already locked.", name);
+        println!("  Hash: {}", lock.hash);
+        println!("  Locked at: {}", lock.locked_at);
+        return Ok(());
+    }
+
+    let key = signing_key.map(signing::load_signing_key).transpose()?;
+
+    let mut lock = contract.lock(env!("CARGO_PKG_VERSION"))?;
+    lock.frozen_until = frozen_until;
+    if let Some(key) = &key {
+        signing::sign_lock(&mut lock, key);
+    }
+    contract::save_named_contract(&contract, Some(name)).await?;
+    contract::save_named_lock(&lock, Some(name)).await?;
+
+    println!("🔒 Contract '{}' LOCKED", name);
+    println!();
+    println!("  Spec Hash: {}", lock.hash);
+    println!("  Locked at: {}", lock.locked_at);
+    if let Some(previous) = &lock.supersedes {
+        println!(
+            "  Version {} (supersedes {})",
+            contract.contract_version, previous
+        );
+    }
+    if let Some(until) = lock.frozen_until {
+        println!("  Frozen until: {}", until);
+    }
+    if let Some(public_key) = &lock.public_key {
+        println!("  Signed by: {}", public_key);
+    }
+
+    Ok(())
+}
+
+async fn show_status(name: Option<&str>) -> Result<()> {
+    match contract::load_named_contract(name).await {
         Ok(contract) => {
             println!("Contract Status: {:?}", contract.status);
+            println!("Version: {}", contract.contract_version);
+            if let Some(previous) = &contract.supersedes {
+                println!("Supersedes: {}", previous);
+            }
             println!("Project: {}", contract.project_name);
             println!("Goals: {}", contract.goals.len());
-            println!("Requirements: {}", contract.requirements.len());
+            println!(
+                "Requirements: {} ({})",
+                contract.requirements.len(),
+                crate::cli::status::priority_breakdown(&contract)
+            );
+            print_dependencies(&contract);
 
             if contract.is_locked() {
-                if let Ok(lock) = contract::load_lock().await {
+                if let Ok(lock) = contract::load_named_lock(name).await {
                     println!();
                     println!("Lock Hash: {}", lock.hash);
                     println!("Locked at: {}", lock.locked_at);
+                    if let Some(until) = lock.frozen_at(chrono::Utc::now()) {
+                        println!("Frozen until: {}", until);
+                    }
                 }
             }
         }
@@ -173,3 +549,376 @@ async fn show_status() -> Result<()> {
 
     Ok(())
 }
+
+/// Requirement dependency graph, prerequisites first
+fn print_dependencies(contract: &Contract) {
+    if contract
+        .requirements
+        .iter()
+        .all(|r| r.depends_on.is_empty())
+    {
+        return;
+    }
+
+    println!();
+    println!("Dependencies:");
+    if let Err(e) = contract.validate_requirement_deps() {
+        println!("  ⚠️  {}", e);
+        return;
+    }
+    let items: Vec<(&str, &[String])> = contract
+        .requirements
+        .iter()
+        .map(|r| (r.id.as_str(), r.depends_on.as_slice()))
+        .collect();
+    let order = topo_sort(&items).unwrap_or_else(|_| (0..items.len()).collect());
+    for req in order.into_iter().map(|i| &contract.requirements[i]) {
+        if req.depends_on.is_empty() {
+            println!("  {}", req.id);
+        } else {
+            println!("  {} → {}", req.id, req.depends_on.join(", "));
+        }
+    }
+}
+
+/// Machine-readable contract status (`contract status --json`, `serve`'s `GET /contract`)
+#[derive(Debug, Serialize)]
+pub struct ContractStatusReport {
+    status: ContractStatus,
+    schema_version: String,
+    contract_version: u32,
+    supersedes: Option<String>,
+    project_name: String,
+    goals_count: usize,
+    requirements_count: usize,
+    locked: bool,
+    lock_hash: Option<String>,
+    tool_version: Option<String>,
+    valid: bool,
+    /// Requirement ID → IDs it depends on (only requirements with dependencies)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    dependencies: BTreeMap<String, Vec<String>>,
+}
+
+async fn show_status_report(name: Option<&str>, format: OutputFormat) -> Result<()> {
+    let report = status_report(name).await?;
+    println!("{}", format.render(&report)?);
+    Ok(())
+}
+
+/// Status of the default contract, or the named one
+pub async fn status_report(name: Option<&str>) -> Result<ContractStatusReport> {
+    let contract = contract::load_named_contract(name)
+        .await
+        .map_err(|_| error::invalid_state("No contract found. Run 'vibeanvil contract create'"))?;
+    let lock = if contract.is_locked() {
+        contract::load_named_lock(name).await.ok()
+    } else {
+        None
+    };
+
+    let report = ContractStatusReport {
+        status: contract.status.clone(),
+        schema_version: contract.schema_version.clone(),
+        contract_version: contract.contract_version,
+        supersedes: contract.supersedes.clone(),
+        project_name: contract.project_name.clone(),
+        goals_count: contract.goals.len(),
+        requirements_count: contract.requirements.len(),
+        locked: contract.is_locked(),
+        lock_hash: lock.as_ref().map(|l| l.hash.clone()),
+        tool_version: lock.map(|l| l.tool_version),
+        valid: contract.validate().valid,
+        dependencies: contract
+            .requirements
+            .iter()
+            .filter(|r| !r.depends_on.is_empty())
+            .map(|r| (r.id.clone(), r.depends_on.clone()))
+            .collect(),
+    };
+    Ok(report)
+}
+
+async fn verify_contract(name: Option<&str>) -> Result<()> {
+    let contract = contract::load_named_contract(name).await?;
+    let lock = contract::load_named_lock(name).await?;
+
+    let hash = contract.generate_hash(&lock.tool_version)?;
+    if hash != lock.hash {
+        anyhow::bail!(
+            "Contract hash mismatch: lock has {} but contract hashes to {}",
+            lock.hash,
+            hash
+        );
+    }
+    println!("✓ Contract hash matches lock");
+
+    if lock.signature.is_some() {
+        signing::verify_lock(&lock, &contract)?;
+        println!(
+            "✓ Signature valid (public key {})",
+            lock.public_key.as_deref().unwrap_or_default()
+        );
+    } else {
+        println!("  Lock is not signed");
+    }
+
+    Ok(())
+}
+
+/// Compare the current contract with the one recorded at lock time
+async fn diff_contract(name: Option<&str>) -> Result<()> {
+    let contract = contract::load_named_contract(name).await?;
+    if !contract::named_lock_path(name).exists() {
+        return Err(error::invalid_state(
+            "Contract is not locked. Run 'vibeanvil contract lock' first.",
+        ));
+    }
+    let lock = contract::load_named_lock(name).await?;
+
+    if lock.schema_version != contract.schema_version {
+        println!(
+            "Schema changed: locked under schema {}, contract now uses schema {}.",
+            lock.schema_version, contract.schema_version
+        );
+        println!(
+            "  Fields can't be compared across schemas; review the contract and lock it again."
+        );
+        return Err(error::validation(
+            "Contract drift detected (schema changed)",
+        ));
+    }
+
+    if let Some(previous) = &lock.supersedes {
+        println!(
+            "Lock {} supersedes {}",
+            contract::short_hash(&lock.hash),
+            contract::short_hash(previous)
+        );
+    }
+    if contract.supersedes.as_deref() == Some(lock.hash.as_str()) {
+        println!(
+            "Draft version {} amends lock {}",
+            contract.contract_version,
+            contract::short_hash(&lock.hash)
+        );
+    }
+
+    let hash = contract.generate_hash(&lock.tool_version)?;
+    if hash == lock.hash {
+        println!(
+            "✓ No drift: contract matches lock {}",
+            contract::short_hash(&lock.hash)
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Contract drifted from lock {}:",
+        contract::short_hash(&lock.hash)
+    );
+    match &lock.contract {
+        Some(locked) => {
+            let diff = diff::compute_diff(locked, &contract);
+            if diff.is_empty() {
+                println!("  (no field changes; the lock snapshot itself was edited)");
+            }
+            print_diff(&diff);
+        }
+        None => {
+            println!("  Lock has no contract snapshot (locked by an older version); only the hash can be compared.");
+            println!("  Lock hash:     {}", lock.hash);
+            println!("  Contract hash: {}", hash);
+        }
+    }
+
+    Err(error::validation("Contract drift detected"))
+}
+
+/// Machine-readable `contract diff` result
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    lock_hash: String,
+    contract_hash: String,
+    drift: bool,
+    /// Schema the contract was locked under, when it differs from the current one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locked_schema_version: Option<String>,
+    /// Field changes; `null` when the lock has no comparable snapshot
+    changes: Option<Vec<diff::FieldChange>>,
+}
+
+async fn diff_contract_report(name: Option<&str>, format: OutputFormat) -> Result<()> {
+    let contract = contract::load_named_contract(name).await?;
+    if !contract::named_lock_path(name).exists() {
+        return Err(error::invalid_state(
+            "Contract is not locked. Run 'vibeanvil contract lock' first.",
+        ));
+    }
+    let lock = contract::load_named_lock(name).await?;
+    let contract_hash = contract.generate_hash(&lock.tool_version)?;
+    let schema_changed = lock.schema_version != contract.schema_version;
+
+    let report = DiffReport {
+        drift: schema_changed || contract_hash != lock.hash,
+        locked_schema_version: schema_changed.then(|| lock.schema_version.clone()),
+        changes: locked_diff(name, &contract)
+            .await?
+            .and_then(|(_, diff)| diff)
+            .map(|diff| diff.changes),
+        lock_hash: lock.hash,
+        contract_hash,
+    };
+    println!("{}", format.render(&report)?);
+
+    if report.drift {
+        return Err(error::validation("Contract drift detected"));
+    }
+    Ok(())
+}
+
+/// Reopen the locked contract as the next draft version
+///
+/// The previous lock stays in place until the amendment is locked, so
+/// `contract diff` shows what the amendment changes.
+async fn amend_contract(name: Option<&str>, force_unfreeze: bool, yes: bool) -> Result<()> {
+    let mut state_data = workspace::load_state().await?;
+    let contract = contract::load_named_contract(name).await?;
+    if !contract.is_locked() || !contract::named_lock_path(name).exists() {
+        return Err(error::invalid_state(
+            "Contract is not locked; edit the draft directly instead of amending.",
+        ));
+    }
+    let lock = contract::load_named_lock(name).await?;
+    let unfrozen = crate::cli::reset::check_lock_freeze(&lock, force_unfreeze, yes)?;
+    let amended = contract.amend(&lock)?;
+
+    let session_id = generate_session_id();
+    let mut entry =
+        AuditEntry::new("contract amend", vec![], &session_id).with_metadata(serde_json::json!({
+            "contract": name.unwrap_or("default"),
+            "contract_version": amended.contract_version,
+            "supersedes": lock.hash,
+            "force_unfreeze": unfrozen,
+        }));
+
+    // Named specs are tracked alongside the workflow, not by it
+    if name.is_none() {
+        let from = state_data.current_state;
+        state_data.amend_contract(&session_id)?;
+        entry = entry.with_state_transition(from, State::ContractDrafted);
+    }
+    contract::save_named_contract(&amended, name).await?;
+    if name.is_none() {
+        workspace::save_state(&state_data).await?;
+    }
+    AuditLogger::new(&session_id).log(&entry).await?;
+
+    let flag = name.map(|n| format!(" --name {}", n)).unwrap_or_default();
+    println!(
+        "✓ Contract reopened as draft version {}",
+        amended.contract_version
+    );
+    println!("  Supersedes: {}", lock.hash);
+    println!();
+    println!(
+        "Next: edit the contract, then review with 'vibeanvil contract diff{}'",
+        flag
+    );
+    println!("Then: vibeanvil contract lock{}", flag);
+
+    Ok(())
+}
+
+fn read_contract_file(path: &Path) -> Result<Contract> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    ContractFormat::from_path(path)
+        .unwrap_or_default()
+        .parse(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Merge `theirs` into the current draft, using `base` as the common ancestor
+async fn merge_contract(name: Option<&str>, base: &Path, theirs: &Path) -> Result<()> {
+    let ours = contract::load_named_contract(name).await?;
+    if ours.is_locked() {
+        return Err(error::invalid_state(
+            "Contract is locked. Run 'vibeanvil contract amend' before merging changes into it.",
+        ));
+    }
+    let base = read_contract_file(base)?;
+    let theirs = read_contract_file(theirs)?;
+
+    let result = Contract::merge(&base, &ours, &theirs);
+    if !result.is_clean() {
+        println!(
+            "✗ {} conflict(s); contract left unchanged:",
+            result.conflicts.len()
+        );
+        for conflict in &result.conflicts {
+            println!("  {}", conflict);
+        }
+        return Err(error::validation(format!(
+            "Contract merge has {} conflict(s)",
+            result.conflicts.len()
+        )));
+    }
+
+    let changes = diff::compute_diff(&ours, &result.merged);
+    contract::save_named_contract(&result.merged, name).await?;
+
+    println!("✓ Contract merged");
+    for change in changes.changes.iter().filter(|c| c.field() != "updated_at") {
+        println!("  {}", change);
+    }
+    println!();
+    println!("Next: vibeanvil contract validate");
+
+    Ok(())
+}
+
+/// Rewrite the contract in another format; the hash, and so the lock, is unchanged
+async fn convert_contract(name: Option<&str>, to: ContractFormat) -> Result<()> {
+    let Some((from_path, from)) = contract::find_contract(name) else {
+        return Err(error::invalid_state(
+            "No contract found. Create one with 'vibeanvil contract create'",
+        ));
+    };
+    if from == to {
+        println!("Contract is already {} ({})", to, from_path.display());
+        return Ok(());
+    }
+
+    let contract = contract::load_contract_as(name, from).await?;
+    contract::save_contract_as(&contract, name, to).await?;
+
+    // New contracts in this workspace follow the default contract's format
+    let session_id = generate_session_id();
+    if name.is_none() {
+        let mut state_data = workspace::load_state().await?;
+        state_data.contract_format = to;
+        workspace::save_state(&state_data).await?;
+    }
+
+    let entry = AuditEntry::new(
+        "contract convert",
+        vec![format!("--to {}", to)],
+        &session_id,
+    )
+    .with_metadata(serde_json::json!({
+        "contract": name.unwrap_or("default"),
+        "from": from,
+        "to": to,
+    }));
+    AuditLogger::new(&session_id).log(&entry).await?;
+
+    println!(
+        "✓ Converted {} → {}",
+        from_path.display(),
+        contract::contract_path_as(name, to).display()
+    );
+    println!("  Hash unchanged: the contract is hashed as canonical JSON in either format");
+
+    Ok(())
+}
@@ -5,9 +5,13 @@ use colored::Colorize;
 use std::process::Command;
 
 use crate::brain::storage::BrainStorage;
+use crate::security::secrets::RedactionConfig;
 use crate::state::State;
 use crate::workspace;
 
+/// Harvested brain records older than this are flagged for expiry
+const STALE_BRAIN_DAYS: u32 = 365;
+
 /// Health check result
 struct HealthCheck {
     name: String,
@@ -164,6 +168,34 @@ pub async fn run() -> Result<()> {
                 has_errors = true;
             }
         }
+
+        // Custom redaction patterns must all compile
+        if workspace::redaction_config_path().exists() {
+            match RedactionConfig::load() {
+                Ok(config) => match config.compile() {
+                    (patterns, invalid) if invalid.is_empty() => {
+                        checks.push(HealthCheck::ok(
+                            "Redaction",
+                            &format!("{} custom pattern(s) in redaction.toml", patterns.len()),
+                        ));
+                    }
+                    (_, invalid) => {
+                        checks.push(HealthCheck::error(
+                            "Redaction",
+                            &format!(
+                                "Invalid pattern(s) in redaction.toml: {}",
+                                invalid.join("; ")
+                            ),
+                        ));
+                        has_errors = true;
+                    }
+                },
+                Err(e) => {
+                    checks.push(HealthCheck::error("Redaction", &format!("{:#}", e)));
+                    has_errors = true;
+                }
+            }
+        }
     } else {
         checks.push(HealthCheck::warning(
             "Workspace",
@@ -185,6 +217,17 @@ pub async fn run() -> Result<()> {
                             stats.total_records, stats.total_chunks
                         ),
                     ));
+                    let stale = storage.count_older_than(STALE_BRAIN_DAYS).unwrap_or(0);
+                    if stale > 0 {
+                        checks.push(HealthCheck::warning(
+                            "BrainPack age",
+                            &format!(
+                                "{} chunk(s) harvested over {} days ago (run: vibeanvil brain compact --ttl-days {})",
+                                stale, STALE_BRAIN_DAYS, STALE_BRAIN_DAYS
+                            ),
+                        ));
+                        has_warnings = true;
+                    }
                 } else {
                     checks.push(HealthCheck::warning(
                         "BrainPack",
@@ -0,0 +1,304 @@
+//! Evidence command handler
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::{CoverageFormat, EvidenceArgs, EvidenceCommands, EvidenceKind};
+use crate::error;
+use crate::evidence::{self, Evidence, EvidenceCollector, EvidenceType, HashStatus};
+use crate::workspace;
+
+/// Evidence item with its verification result
+#[derive(Serialize)]
+struct EvidenceReport<'a> {
+    #[serde(flatten)]
+    evidence: &'a Evidence,
+    hash_status: HashStatus,
+}
+
+pub async fn run(args: EvidenceArgs) -> Result<()> {
+    match args.command {
+        EvidenceCommands::Show { session, json } => show(&session, json).await,
+        EvidenceCommands::Capture {
+            kind,
+            file,
+            name,
+            description,
+            format,
+        } => capture(kind, &file, name.as_deref(), description.as_deref(), format).await,
+    }
+}
+
+async fn capture(
+    kind: EvidenceKind,
+    file: &str,
+    name: Option<&str>,
+    description: Option<&str>,
+    format: Option<CoverageFormat>,
+) -> Result<()> {
+    let is_coverage = matches!(kind, EvidenceKind::Coverage);
+    if format.is_some() && !is_coverage {
+        return Err(error::validation(
+            "--format is only supported with --type coverage",
+        ));
+    }
+    if is_coverage && (name.is_some() || description.is_some()) {
+        return Err(error::validation(
+            "Coverage evidence is named and described from the report; drop --name and --description",
+        ));
+    }
+
+    let state = workspace::load_state().await?;
+    let session_id = state.current_session_id.ok_or_else(|| {
+        error::invalid_state(
+            "No active session. Run a workflow command (e.g. 'vibeanvil intake') first.",
+        )
+    })?;
+
+    let cwd = std::env::current_dir()?;
+    let path = crate::security::validate_path(&cwd, file)?;
+    if let Some(name) = name {
+        crate::security::validate_filename(name)?;
+    }
+
+    let (evidence_type, default_prefix) = match kind {
+        EvidenceKind::Build => (EvidenceType::BuildLog, "build_log"),
+        EvidenceKind::Test => (EvidenceType::TestLog, "test_log"),
+        EvidenceKind::Lint => (EvidenceType::LintLog, "lint_log"),
+        EvidenceKind::Coverage => (EvidenceType::Coverage, "coverage"),
+        EvidenceKind::Custom => {
+            let name =
+                name.ok_or_else(|| error::validation("--name is required for custom evidence"))?;
+            (EvidenceType::Custom(name.to_string()), name)
+        }
+    };
+    let prefix = name.unwrap_or(default_prefix);
+
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("Failed to read {}", file))?;
+    let default_description = format!("Imported from {}", file);
+    let description = description.unwrap_or(&default_description);
+
+    let collector = EvidenceCollector::new(&session_id).await?;
+    let evidence = if is_coverage {
+        let format = format
+            .map(evidence::CoverageFormat::from)
+            .or_else(|| evidence::CoverageFormat::detect(&path))
+            .ok_or_else(|| {
+                error::validation(format!(
+                    "Can't tell the coverage format of {}; pass --format",
+                    file
+                ))
+            })?;
+        collector.capture_coverage_report(format, &content).await?
+    } else {
+        collector
+            .capture_external(evidence_type, prefix, &content, description)
+            .await?
+    };
+
+    println!(
+        "{} Captured {} from {} → {}",
+        "✓".green(),
+        evidence.evidence_type.label(),
+        file,
+        evidence.filename.cyan()
+    );
+    if is_coverage {
+        if let Some(summary) = &evidence.description {
+            println!("  {}", summary);
+        }
+    }
+    if evidence.redacted && is_coverage {
+        println!(
+            "  {} Home directory paths or secrets were redacted",
+            "⚠".yellow()
+        );
+    } else if evidence.redacted {
+        match redaction_details(&evidence) {
+            Some(details) => println!("  {} Secrets were redacted: {}", "⚠".yellow(), details),
+            None => println!("  {} Secrets were redacted", "⚠".yellow()),
+        }
+    }
+    println!("  Session: {}", session_id);
+    Ok(())
+}
+
+async fn show(session_id: &str, json: bool) -> Result<()> {
+    crate::security::validate_filename(session_id)?;
+
+    let evidence_dir = workspace::evidence_path(session_id);
+    if !evidence_dir.exists() {
+        anyhow::bail!("No evidence found for session '{}'", session_id);
+    }
+
+    let collector = EvidenceCollector::with_dir(session_id, evidence_dir);
+    let items = collector.load_manifest().await?;
+
+    let statuses: Vec<HashStatus> = items
+        .iter()
+        .map(|item| item.integrity.unwrap_or(HashStatus::Unhashed))
+        .collect();
+
+    if json {
+        let reports: Vec<EvidenceReport> = items
+            .iter()
+            .zip(&statuses)
+            .map(|(evidence, status)| EvidenceReport {
+                evidence,
+                hash_status: *status,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        print!("{}", render_manifest(session_id, &items, &statuses));
+    }
+
+    let failed = statuses
+        .iter()
+        .filter(|s| matches!(s, HashStatus::Mismatch | HashStatus::Missing))
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{} evidence item(s) failed hash verification", failed);
+    }
+
+    Ok(())
+}
+
+fn status_label(status: HashStatus) -> String {
+    match status {
+        HashStatus::Verified => "✓ verified".green().to_string(),
+        HashStatus::Mismatch => "✗ HASH MISMATCH".red().bold().to_string(),
+        HashStatus::Missing => "✗ FILE MISSING".red().bold().to_string(),
+        HashStatus::Unhashed => "– no hash".dimmed().to_string(),
+    }
+}
+
+/// Render the manifest as a human-readable listing
+fn render_manifest(session_id: &str, items: &[Evidence], statuses: &[HashStatus]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "\n{} {}\n\n",
+        "🧾 Evidence for session".white().bold(),
+        session_id.cyan()
+    ));
+
+    if items.is_empty() {
+        out.push_str(&format!("  {}\n\n", "No evidence recorded.".dimmed()));
+        return out;
+    }
+
+    for (item, status) in items.iter().zip(statuses) {
+        out.push_str(&format!(
+            "  {} {}\n",
+            "•".cyan(),
+            item.filename.white().bold()
+        ));
+        out.push_str(&format!(
+            "    {} {}\n",
+            "Type:".dimmed(),
+            item.evidence_type.label()
+        ));
+        out.push_str(&format!(
+            "    {} {} bytes\n",
+            "Size:".dimmed(),
+            item.size_bytes
+        ));
+        out.push_str(&format!(
+            "    {} {}\n",
+            "Redacted:".dimmed(),
+            redaction_label(item)
+        ));
+        if let Some(description) = &item.description {
+            out.push_str(&format!(
+                "    {} {}\n",
+                "Description:".dimmed(),
+                description
+            ));
+        }
+        if let Some(run) = &item.command {
+            out.push_str(&format!(
+                "    {} {} ({})\n",
+                "Command:".dimmed(),
+                run.command,
+                run.outcome()
+            ));
+        }
+        out.push_str(&format!(
+            "    {} {}\n",
+            "Captured:".dimmed(),
+            item.captured_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        out.push_str(&format!(
+            "    {} {}\n",
+            "Hash:".dimmed(),
+            status_label(*status)
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+/// `aws_access_key ×2, jwt ×1`, when the manifest recorded what was redacted
+fn redaction_details(item: &Evidence) -> Option<String> {
+    let mut categories: Vec<_> = item.redaction_summary.as_ref()?.iter().collect();
+    categories.sort();
+    let details: Vec<String> = categories
+        .into_iter()
+        .map(|(category, count)| format!("{} ×{}", category, count))
+        .collect();
+    Some(details.join(", "))
+}
+
+fn redaction_label(item: &Evidence) -> String {
+    match (item.redacted, redaction_details(item)) {
+        (false, _) => "no".to_string(),
+        (true, Some(details)) => format!("yes ({})", details),
+        (true, None) => "yes".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_render_manifest_lists_items() {
+        let temp = TempDir::new().unwrap();
+        let collector = EvidenceCollector::with_dir("sess-1", temp.path().to_path_buf());
+
+        collector
+            .capture_build_log("compiled ok\napi_key = abcdefghijklmnopqrstuvwxyz")
+            .await
+            .unwrap();
+        collector
+            .capture_custom("coverage", "lines: 91%", "Coverage summary")
+            .await
+            .unwrap();
+
+        let items = collector.load_manifest().await.unwrap();
+        assert_eq!(items.len(), 2);
+
+        let mut statuses = vec![];
+        for item in &items {
+            statuses.push(collector.verify_item(item).await);
+        }
+
+        let rendered = render_manifest("sess-1", &items, &statuses);
+        assert!(rendered.contains("sess-1"));
+        assert!(rendered.contains(&items[0].filename));
+        assert!(rendered.contains(&items[1].filename));
+        assert!(rendered.contains("build_log"));
+        assert!(rendered.contains("custom:coverage"));
+        assert!(rendered.contains(&format!("{} bytes", items[1].size_bytes)));
+        assert!(rendered.contains("Coverage summary"));
+        assert!(
+            rendered.contains("yes (api_key ×1)"),
+            "build log should be flagged redacted"
+        );
+        assert_eq!(rendered.matches("verified").count(), 2);
+    }
+}
@@ -4,12 +4,18 @@ use anyhow::Result;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::Instrument;
 
 use crate::audit::{generate_session_id, AuditLogger};
-use crate::brain::harvester::{DownloadMethod, HarvestConfig, Harvester};
+use crate::brain::anonymize_source;
+use crate::brain::harvest_lock::{self, HarvestLock, LockedSource};
+use crate::brain::harvester::{self, DownloadMethod, HarvestConfig, Harvester};
 use crate::brain::presets::PresetsFile;
-use crate::brain::storage::BrainStorage;
-use crate::cli::{HarvestArgs, HarvestCommands};
+use crate::brain::sources::{self, HarvestSource};
+use crate::brain::storage::{BrainStorage, Replace};
+use crate::cli::{HarvestArgs, HarvestCommands, HarvestSourceKind};
+use crate::error;
 use crate::workspace;
 
 pub async fn run(args: HarvestArgs) -> Result<()> {
@@ -22,6 +28,22 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
         }
     }
 
+    if let Some(path) = &args.local {
+        return harvest_local(&args, path).await;
+    }
+
+    let source = harvest_source(&args)?;
+    if args.incremental && args.source != HarvestSourceKind::Github {
+        anyhow::bail!("--incremental is only supported with '--source github'");
+    }
+
+    if args.verify_lock {
+        if args.source != HarvestSourceKind::Github {
+            anyhow::bail!("--verify-lock is only supported with '--source github'");
+        }
+        return verify_lock(&args).await;
+    }
+
     // Load preset if specified
     let (
         queries,
@@ -114,6 +136,7 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
 
     // Show search parameters
     println!("{}", "📋 Search Parameters:".white().bold());
+    println!("  {} {}", "Source: ".dimmed(), source.label().cyan());
     if !queries.is_empty() {
         println!("  {} {}", "Queries:".dimmed(), queries.join(", ").cyan());
     }
@@ -134,22 +157,41 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
         "Updated:".dimmed(),
         updated_within_days.to_string().yellow()
     );
+    let sort = match args.sort {
+        crate::cli::RepoSort::Relevance => "best match".to_string(),
+        sort => format!("{:?} ({:?})", sort, args.order).to_lowercase(),
+    };
+    println!("  {} {}", "Sort:   ".dimmed(), sort.yellow());
+    let threads = args.threads.unwrap_or_else(harvester::default_threads);
+    println!(
+        "  {} {} tarball(s) at once",
+        "Threads:".dimmed(),
+        threads.to_string().yellow()
+    );
     println!();
 
-    // Check for GITHUB_TOKEN
-    if std::env::var("GITHUB_TOKEN").is_err() {
+    // Check for the host's token
+    if !source.has_token() {
+        let (warning, hint) = match args.source {
+            HarvestSourceKind::Github => (
+                "GITHUB_TOKEN not set - API rate limits restricted",
+                "Set GITHUB_TOKEN environment variable for more",
+            ),
+            HarvestSourceKind::Gitlab => (
+                "GITLAB_TOKEN not set - only public projects",
+                "Set GITLAB_TOKEN for private projects",
+            ),
+            HarvestSourceKind::Bitbucket => (
+                "BITBUCKET_TOKEN not set - only public repos",
+                "Set BITBUCKET_TOKEN for private repositories",
+            ),
+        };
         println!(
             "{}",
             "┌─────────────────────────────────────────────────────────┐".yellow()
         );
-        println!(
-            "{}",
-            "│  ⚠️  GITHUB_TOKEN not set - API rate limits restricted   │".yellow()
-        );
-        println!(
-            "{}",
-            "│  Set GITHUB_TOKEN environment variable for more         │".white()
-        );
+        println!("{}", format!("│  ⚠️  {:<52}│", warning).yellow());
+        println!("{}", format!("│  {:<55}│", hint).white());
         println!(
             "{}",
             "└─────────────────────────────────────────────────────────┘".yellow()
@@ -177,10 +219,20 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
         cache_dir,
         ignore_globs: ignore_globs.clone(),
         allow_globs: allow_globs.clone(),
+        include_forks: args.include_forks,
+        include_archived: args.include_archived,
+        sort: args.sort.into(),
+        order: args.order.into(),
+        threads,
+        source: source.clone(),
+        max_retries: args.max_retries,
+        incremental: args.incremental,
         ..Default::default()
     };
 
-    let mut harvester = Harvester::new(config).await?;
+    let harvester = Arc::new(Harvester::new(config).await?);
+    // Validate the query before touching the API
+    let query = harvester.build_query()?;
     let storage = BrainStorage::new().await?;
 
     // Search spinner
@@ -190,10 +242,10 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
             .template("{spinner:.cyan} {msg}")
             .unwrap(),
     );
-    spinner.set_message("Searching GitHub...");
+    spinner.set_message(format!("Searching {}...", source.label()));
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let repos = harvester.search_repos().await?;
+    let repos = harvester.search_repos_with_query(&query).await?;
     spinner.finish_and_clear();
 
     if repos.is_empty() {
@@ -237,10 +289,41 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
     // Harvest each repo
     let mut total_records = 0;
     let mut total_chunks = 0;
+    let mut duplicate_chunks = 0;
     let mut sources_processed = 0;
     let mut errors = 0;
+    let mut locked = vec![];
+
+    // One download runs ahead of the busy parsers so network and CPU overlap
+    let max_in_flight = threads + 1;
+    let mut pending = repos.iter().cloned().enumerate();
+    let mut in_flight = tokio::task::JoinSet::new();
+    loop {
+        while in_flight.len() < max_in_flight {
+            let Some((index, repo)) = pending.next() else {
+                break;
+            };
+            let harvester = harvester.clone();
+            let since = args
+                .incremental
+                .then(|| storage.harvested_commit(&anonymize_source(&repo.full_name)))
+                .flatten();
+            in_flight.spawn(async move {
+                let result = match &since {
+                    Some(since) => harvester.harvest_incremental(&repo, since).await,
+                    None => harvester
+                        .harvest_repo(&repo)
+                        .await
+                        .map(|(meta, records)| (meta, records, Replace::Nothing)),
+                };
+                (index, repo, result)
+            });
+        }
+        let Some(joined) = in_flight.join_next().await else {
+            break;
+        };
+        let (index, repo, result) = joined?;
 
-    for repo in &repos {
         let short_name = if repo.full_name.len() > 30 {
             format!("{}...", &repo.full_name[..27])
         } else {
@@ -248,16 +331,27 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
         };
         progress.set_message(short_name);
 
-        match harvester.harvest_repo(repo).await {
-            Ok((source_meta, records)) => {
+        match result {
+            Ok((source_meta, records, replace)) => {
+                locked.push((
+                    index,
+                    LockedSource::from_repo(&repo).with_commit(&source_meta.commit),
+                ));
                 if !records.is_empty() {
-                    let chunk_count: usize = records.iter().map(|r| r.chunks.len()).sum();
-                    storage.save_source(&source_meta).await?;
-                    storage.save_records(&records).await?;
+                    // Changed files replace their old chunks
+                    let saved = storage
+                        .save_harvest(&source_meta, &records, replace)
+                        .instrument(tracing::info_span!("db_write"))
+                        .await?;
                     total_records += records.len();
-                    total_chunks += chunk_count;
+                    total_chunks += saved.inserted;
+                    duplicate_chunks += saved.skipped_duplicates;
                     sources_processed += 1;
                 }
+                // A resolved SHA is the base for the next incremental harvest
+                if source_meta.commit != repo.default_branch && source_meta.license != "error" {
+                    storage.set_harvested_commit(&source_meta.source_id, &source_meta.commit)?;
+                }
             }
             Err(_) => {
                 errors += 1;
@@ -267,6 +361,17 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
     }
     progress.finish_and_clear();
 
+    // Record sources in search order, not completion order
+    locked.sort_by_key(|(index, _)| *index);
+    let locked = locked.into_iter().map(|(_, source)| source).collect();
+
+    // The lock records a GitHub search query, which other hosts can't replay
+    if args.source == HarvestSourceKind::Github {
+        HarvestLock::new(&query, max_repos, locked)
+            .with_sort(args.sort.into(), args.order.into())
+            .save(&harvest_lock::lock_path())?;
+    }
+
     // Summary
     println!();
     println!(
@@ -296,6 +401,13 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
         "🧩 Chunks: ".white(),
         total_chunks.to_string().cyan()
     );
+    if duplicate_chunks > 0 {
+        println!(
+            "║  {} {:>8}                       ║",
+            "🔁 Dupes:  ".white(),
+            duplicate_chunks.to_string().dimmed()
+        );
+    }
     if errors > 0 {
         println!(
             "║  {} {:>8}                       ║",
@@ -334,6 +446,166 @@ pub async fn run(args: HarvestArgs) -> Result<()> {
     Ok(())
 }
 
+/// `harvest --local <PATH> --label <NAME>`: harvest a directory on disk
+async fn harvest_local(args: &HarvestArgs, path: &std::path::Path) -> Result<()> {
+    let label = args.label.as_deref().unwrap_or_default();
+    let config = HarvestConfig {
+        cache_dir: args
+            .cache_dir
+            .clone()
+            .map(PathBuf::from)
+            .unwrap_or_else(workspace::cache_dir),
+        ignore_globs: args.ignore_glob.clone(),
+        allow_globs: args.allow_glob.clone(),
+        ..Default::default()
+    };
+
+    println!();
+    println!("{}", "📂 Local Harvest".white().bold());
+    println!(
+        "  {} {}",
+        "Path:   ".dimmed(),
+        path.display().to_string().cyan()
+    );
+    println!("  {} {}", "Label:  ".dimmed(), label.cyan());
+    println!();
+
+    let harvester = Harvester::new(config).await?;
+    let (source_meta, records) = harvester.harvest_local(path, label).await?;
+
+    // A re-harvest under the same label replaces what the last one stored,
+    // so edited and deleted files don't linger
+    let storage = BrainStorage::new().await?;
+    let saved = storage
+        .save_harvest(&source_meta, &records, Replace::Source)
+        .await?;
+
+    println!(
+        "  {} {} records, {} chunks ({} duplicates skipped) from {}",
+        "✓".green(),
+        records.len().to_string().green().bold(),
+        saved.inserted,
+        saved.skipped_duplicates,
+        source_meta.source_id.cyan()
+    );
+    println!();
+
+    let session_id = generate_session_id();
+    AuditLogger::new(&session_id)
+        .log_command(
+            "harvest",
+            vec![
+                "local".to_string(),
+                format!("source={}", source_meta.source_id),
+                format!("records={}", records.len()),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Host selected by `--source`, with its token from the environment
+fn harvest_source(args: &HarvestArgs) -> Result<HarvestSource> {
+    if args.gitlab_host.is_some() && args.source != HarvestSourceKind::Gitlab {
+        anyhow::bail!("--gitlab-host is only supported with '--source gitlab'");
+    }
+    if args.bitbucket_workspace.is_some() && args.source != HarvestSourceKind::Bitbucket {
+        anyhow::bail!("--bitbucket-workspace is only supported with '--source bitbucket'");
+    }
+
+    let token = |name: &str| std::env::var(name).ok().filter(|t| !t.is_empty());
+    Ok(match args.source {
+        HarvestSourceKind::Github => HarvestSource::GitHub {
+            token: token("GITHUB_TOKEN"),
+        },
+        HarvestSourceKind::Gitlab => HarvestSource::GitLab {
+            host: args
+                .gitlab_host
+                .clone()
+                .unwrap_or_else(|| sources::GITLAB_COM.to_string()),
+            token: token("GITLAB_TOKEN"),
+        },
+        HarvestSourceKind::Bitbucket => HarvestSource::Bitbucket {
+            workspace: args.bitbucket_workspace.clone().ok_or_else(|| {
+                error::validation("--source bitbucket requires --bitbucket-workspace <WORKSPACE>")
+            })?,
+            token: token("BITBUCKET_TOKEN"),
+        },
+    })
+}
+
+/// Re-run the locked query and report sources that changed or disappeared
+async fn verify_lock(args: &HarvestArgs) -> Result<()> {
+    let path = harvest_lock::lock_path();
+    if !path.exists() {
+        return Err(error::invalid_state(
+            "No harvest.lock.json found. Run 'vibeanvil harvest' first.",
+        ));
+    }
+    let lock = HarvestLock::load(&path)?;
+
+    let config = HarvestConfig {
+        max_repos: lock.max_repos,
+        sort: lock.sort,
+        order: lock.order,
+        cache_dir: args
+            .cache_dir
+            .clone()
+            .map(PathBuf::from)
+            .unwrap_or_else(workspace::cache_dir),
+        max_retries: args.max_retries,
+        ..Default::default()
+    };
+    let harvester = Harvester::new(config).await?;
+    let repos = harvester.search_repos_with_query(&lock.query).await?;
+    let mut current = vec![];
+    for repo in &repos {
+        let commit = harvester.resolve_commit(repo).await;
+        current.push(LockedSource::from_repo(repo).with_commit(&commit));
+    }
+    let report = lock.verify(&current);
+
+    println!("{} {}", "Query:".dimmed(), lock.query.cyan());
+    println!(
+        "{} {} (vibeanvil {})",
+        "Locked:".dimmed(),
+        lock.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        lock.tool_version
+    );
+    println!();
+
+    if report.is_clean() {
+        println!(
+            "{} All {} source(s) match the lock",
+            "✓".green(),
+            report.unchanged.len()
+        );
+        return Ok(());
+    }
+
+    for change in &report.changed {
+        println!("  {} {}", "~".yellow(), change);
+    }
+    for id in &report.missing {
+        println!("  {} {} no longer returned", "-".red(), id);
+    }
+    for id in &report.added {
+        println!("  {} {} not in lock", "+".green(), id);
+    }
+    println!();
+    println!(
+        "{} unchanged, {} changed, {} missing, {} new",
+        report.unchanged.len(),
+        report.changed.len(),
+        report.missing.len(),
+        report.added.len()
+    );
+
+    Err(error::validation(
+        "Harvest no longer matches harvest.lock.json",
+    ))
+}
+
 /// List all available harvest presets
 async fn list_presets() -> Result<()> {
     let presets = PresetsFile::load()?;
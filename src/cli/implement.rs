@@ -110,6 +110,7 @@ pub async fn run_implement(
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
     // Execute each task
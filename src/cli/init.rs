@@ -5,9 +5,10 @@ use colored::Colorize;
 
 use crate::audit::{generate_session_id, AuditLogger};
 use crate::cli::ui;
+use crate::contract::ContractFormat;
 use crate::workspace;
 
-pub async fn run(force: bool) -> Result<()> {
+pub async fn run(force: bool, contract_format: ContractFormat) -> Result<()> {
     // Print beautiful banner
     ui::print_banner();
 
@@ -33,12 +34,18 @@ pub async fn run(force: bool) -> Result<()> {
         return Ok(());
     }
 
-    workspace::init_workspace(force).await?;
+    workspace::init_workspace(force, contract_format).await?;
 
     let session_id = generate_session_id();
     let logger = AuditLogger::new(&session_id);
     logger
-        .log_command("init", vec![format!("force={}", force)])
+        .log_command(
+            "init",
+            vec![
+                format!("force={}", force),
+                format!("contract_format={}", contract_format),
+            ],
+        )
         .await?;
 
     println!();
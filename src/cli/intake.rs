@@ -5,6 +5,7 @@ use std::io::{self, Read};
 use tokio::fs;
 
 use crate::audit::{generate_session_id, AuditLogger};
+use crate::error;
 use crate::state::State;
 use crate::workspace;
 
@@ -29,8 +30,28 @@ pub async fn run(message: Option<String>) -> Result<()> {
         }
     };
 
-    if intake_content.is_empty() {
-        anyhow::bail!("Intake message cannot be empty");
+    save_intake(&intake_content).await?;
+
+    println!("✓ Intake captured");
+    println!("  → Saved to .vibeanvil/intake.md");
+    println!();
+    println!("Next: vibeanvil blueprint --auto");
+
+    Ok(())
+}
+
+/// Write `.vibeanvil/intake.md` and move the workspace to `IntakeCaptured`
+pub async fn save_intake(intake_content: &str) -> Result<()> {
+    if intake_content.trim().is_empty() {
+        return Err(error::validation("Intake message cannot be empty"));
+    }
+
+    let state_data = workspace::load_state().await?;
+    if state_data.current_state != State::Init {
+        return Err(error::invalid_state(format!(
+            "Intake already captured. Current state: {}",
+            state_data.current_state
+        )));
     }
 
     // Save intake
@@ -44,7 +65,7 @@ pub async fn run(message: Option<String>) -> Result<()> {
 
     // Update state
     let session_id = generate_session_id();
-    let mut state_data = workspace::load_state().await?;
+    let mut state_data = state_data;
     state_data.transition_to(State::IntakeCaptured, "intake", &session_id)?;
     workspace::save_state(&state_data).await?;
 
@@ -54,10 +75,5 @@ pub async fn run(message: Option<String>) -> Result<()> {
         .log_state_transition("intake", State::Init, State::IntakeCaptured)
         .await?;
 
-    println!("✓ Intake captured");
-    println!("  → Saved to .vibeanvil/intake.md");
-    println!();
-    println!("Next: vibeanvil blueprint --auto");
-
     Ok(())
 }
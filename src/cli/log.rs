@@ -3,8 +3,9 @@
 use anyhow::Result;
 
 use crate::audit::read_audit_log;
+use crate::cli::OutputFormat;
 
-pub async fn run(lines: usize, json: bool) -> Result<()> {
+pub async fn run(lines: usize, format: Option<OutputFormat>) -> Result<()> {
     let entries = read_audit_log(Some(lines)).await?;
 
     if entries.is_empty() {
@@ -12,10 +13,13 @@ pub async fn run(lines: usize, json: bool) -> Result<()> {
         return Ok(());
     }
 
-    if json {
+    if format == Some(OutputFormat::Json) {
+        // One entry per line, like the audit log itself
         for entry in &entries {
             println!("{}", serde_json::to_string(entry)?);
         }
+    } else if let Some(format) = format {
+        println!("{}", format.render(&entries)?);
     } else {
         println!("📜 Audit Log (last {} entries)", entries.len());
         println!();
@@ -1,6 +1,10 @@
 //! CLI command definitions and subcommand handlers
 
 use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::provider::selector::SelectionCriteria;
+use crate::provider::{ProviderSelector, TaskType};
 
 pub mod analyze;
 pub mod blueprint;
@@ -10,6 +14,7 @@ pub mod clarify;
 pub mod constitution;
 pub mod contract;
 pub mod doctor;
+pub mod evidence;
 pub mod harvest;
 pub mod implement;
 pub mod init;
@@ -18,14 +23,19 @@ pub mod log;
 pub mod mcp;
 pub mod mode;
 pub mod plan;
+pub mod profile;
 pub mod progress;
 pub mod prompt;
 pub mod providers;
 pub mod repomap;
+pub mod reset;
 pub mod review;
 pub mod run;
+pub mod selftest;
+pub mod serve;
 pub mod ship;
 pub mod snapshot;
+pub mod state;
 pub mod status;
 pub mod style;
 pub mod tasks;
@@ -42,7 +52,12 @@ pub use mcp::McpAction;
 #[command(name = "vibeanvil")]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[command(after_help = crate::error::EXIT_CODES_HELP)]
 pub struct Cli {
+    /// Print a timing summary of major phases when the command finishes
+    #[arg(long, global = true)]
+    pub profile: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -54,6 +69,10 @@ pub enum Commands {
         /// Force re-initialization even if workspace exists
         #[arg(short, long)]
         force: bool,
+
+        /// File format for contracts created in this workspace
+        #[arg(long, value_enum, default_value_t = ContractFormat::Json)]
+        contract_format: ContractFormat,
     },
 
     /// Capture requirements/intake for the project
@@ -71,17 +90,29 @@ pub enum Commands {
     },
 
     /// Manage the contract (create, validate, lock)
-    Contract {
-        /// Contract action to perform
-        #[arg(value_enum)]
-        action: ContractAction,
-    },
+    Contract(ContractArgs),
 
     /// Create an implementation plan
     Plan {
         /// Provider to use for plan generation
         #[arg(short, long, default_value = "claude-code")]
         provider: String,
+
+        /// Always invoke the provider instead of reusing a cached response
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Seed the plan with matching patterns from the BrainPack
+        #[arg(long)]
+        from_brain: bool,
+
+        /// Persona selecting the prompt template and provider recommendation
+        #[arg(long, value_enum)]
+        persona: Option<Persona>,
+
+        /// Built-in or custom (.vibeanvil/prompts/<name>.md) template for the provider prompt
+        #[arg(long, value_name = "NAME")]
+        prompt_template: Option<String>,
     },
 
     /// Execute a build
@@ -92,6 +123,25 @@ pub enum Commands {
         /// Review action
         #[arg(value_enum)]
         action: ReviewAction,
+
+        /// Evidence archive to restore (unpack) or bundle to check (verify-bundle)
+        archive: Option<PathBuf>,
+
+        /// Where to write the evidence archive (archive and bundle only)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Checklist item ID to mark as done (check only, repeatable)
+        #[arg(long = "item")]
+        items: Vec<String>,
+
+        /// Pass without checking the mandatory checklist items (pass only)
+        #[arg(long)]
+        skip_checklist: bool,
+
+        /// Re-hash all session evidence against its manifest (status only; exit 3 on mismatch)
+        #[arg(long)]
+        verify_evidence: bool,
     },
 
     /// Create a snapshot of current state
@@ -109,6 +159,9 @@ pub enum Commands {
         /// Ship message
         #[arg(short, long)]
         message: Option<String>,
+        /// Run the pre-flight checks without shipping
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Harvest repos for the brain pack (dynamic, user-driven)
@@ -117,6 +170,9 @@ pub enum Commands {
     /// Manage the brain pack
     Brain(BrainArgs),
 
+    /// Inspect captured session evidence
+    Evidence(EvidenceArgs),
+
     /// Show current workflow status
     Status {
         /// Show verbose status with history
@@ -124,18 +180,44 @@ pub enum Commands {
         verbose: bool,
 
         /// Output as JSON (machine-readable)
-        #[arg(long)]
+        #[arg(long, conflicts_with = "format")]
         json: bool,
+
+        /// Machine-readable output format (`--json` is short for `--format json`)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Print a one-line badge (combine with --json for a shields.io endpoint)
+        #[arg(long)]
+        badge: bool,
+
+        /// List state transitions, newest first (paginate with --limit/--offset)
+        #[arg(long, conflicts_with_all = ["verbose", "badge"])]
+        history: bool,
+
+        /// Transitions per page (with --history)
+        #[arg(long, default_value = "20", requires = "history")]
+        limit: usize,
+
+        /// Skip this many of the most recent transitions (with --history)
+        #[arg(long, default_value = "0", requires = "history")]
+        offset: usize,
     },
 
+    /// Inspect the workflow state history
+    State(StateArgs),
+
     /// View audit log
     Log {
         /// Number of lines to show
         #[arg(short = 'n', long, default_value = "20")]
         lines: usize,
         /// Output as JSON
-        #[arg(long)]
+        #[arg(long, conflicts_with = "format")]
         json: bool,
+        /// Machine-readable output format (`--json` is short for `--format json`)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
     },
 
     /// Check for updates
@@ -147,6 +229,13 @@ pub enum Commands {
     /// Check system and workspace health
     Doctor,
 
+    /// Run the full workflow end to end with the mock provider in a temp directory
+    Selftest {
+        /// Keep the temporary workspace for inspection
+        #[arg(long)]
+        keep: bool,
+    },
+
     /// Interactive wizard menu
     Wizard,
 
@@ -171,12 +260,59 @@ pub enum Commands {
 
     /// List available AI providers and capability matrix
     Providers {
-        /// Subcommand: list, matrix, recommend, compare
+        /// Subcommand: list, matrix, recommend, compare, test
         #[arg(value_name = "SUBCOMMAND")]
         subcommand: Option<String>,
-        /// Additional arguments (task description or provider names)
-        #[arg(trailing_var_arg = true)]
+        /// Additional arguments (task description, provider names, or the provider to test)
         args: Vec<String>,
+        /// Capability to probe with 'providers test'
+        #[arg(long, value_enum, ignore_case = true)]
+        capability: Option<crate::provider::Capability>,
+        /// Estimate each provider's cost for N tokens (half prompt, half output)
+        #[arg(long, value_name = "N")]
+        estimate_tokens: Option<u32>,
+    },
+
+    /// Roll the workflow back to an earlier state or start over (never touches git)
+    Reset {
+        /// Earlier checkpoint state to roll back to (e.g. IntakeCaptured)
+        #[arg(long, conflicts_with = "hard", required_unless_present = "hard")]
+        to: Option<crate::state::State>,
+
+        /// Reinitialize to INIT, archiving state and contracts under .vibeanvil/archive/
+        #[arg(long)]
+        hard: bool,
+
+        /// Why the rollback is needed (recorded in state history)
+        #[arg(long, requires = "to")]
+        reason: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Reset even though the contract is inside its freeze window (asks to confirm)
+        #[arg(long)]
+        force_unfreeze: bool,
+    },
+
+    /// Abandon the current workflow and return to INIT, keeping contracts and evidence
+    Abort {
+        /// Why the workflow is being aborted (recorded in state history)
+        #[arg(long)]
+        reason: String,
+
+        /// Allow aborting a shipped workflow
+        #[arg(long)]
+        force: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Abort even though the contract is inside its freeze window (asks to confirm)
+        #[arg(long)]
+        force_unfreeze: bool,
     },
 
     /// Undo the last AI-made change (reverts last commit)
@@ -225,6 +361,10 @@ pub enum Commands {
         /// Provider to use
         #[arg(short, long, default_value = "claude-code")]
         provider: String,
+
+        /// Always invoke the provider instead of reusing a cached response
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Execute tasks to implement the plan
@@ -280,6 +420,10 @@ pub enum Commands {
         /// Maximum tokens for context output
         #[arg(long)]
         max_tokens: Option<usize>,
+
+        /// Maximum directory depth to scan (default: unlimited)
+        #[arg(long)]
+        max_depth: Option<usize>,
     },
 
     /// Chat with AI in different modes (ask/code/architect/help)
@@ -299,6 +443,16 @@ pub enum Commands {
         #[command(subcommand)]
         action: McpAction,
     },
+
+    /// Serve workspace status over a local HTTP JSON API
+    Serve {
+        /// Port to listen on (0 picks a free port)
+        #[arg(long, default_value = "7878")]
+        port: u16,
+        /// Address to bind; anything other than loopback exposes the API to the network
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
 }
 
 /// Chat mode argument
@@ -314,6 +468,81 @@ pub enum ChatModeArg {
     Help,
 }
 
+/// Contract command arguments
+#[derive(clap::Args)]
+pub struct ContractArgs {
+    /// Contract action to perform
+    #[arg(value_enum)]
+    pub action: ContractAction,
+
+    /// Requirement operation (req only)
+    #[arg(value_enum)]
+    pub req_action: Option<RequirementAction>,
+
+    /// Requirement ID to edit or remove (req edit/rm only)
+    pub requirement: Option<String>,
+
+    /// ID of the requirement to add (req add only)
+    #[arg(long)]
+    pub id: Option<String>,
+
+    /// Requirement description (req add/edit)
+    #[arg(long)]
+    pub desc: Option<String>,
+
+    /// Requirement priority (req add/edit; add defaults to must)
+    #[arg(long, value_enum)]
+    pub priority: Option<RequirementPriority>,
+
+    /// Sign the lock with an ed25519 key (lock only)
+    #[arg(long, requires = "key")]
+    pub sign: bool,
+
+    /// Path to the ed25519 signing key (32-byte seed, raw or hex)
+    #[arg(long)]
+    pub key: Option<PathBuf>,
+
+    /// Freeze the locked contract until this date (YYYY-MM-DD or RFC 3339, lock only)
+    #[arg(long, value_parser = parse_freeze_until)]
+    pub freeze_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Work with a named contract under .vibeanvil/contracts/<name>/ instead of the default
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Output as JSON (status, validate and diff)
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Amend even though the lock is inside its freeze window (amend only)
+    #[arg(long)]
+    pub force_unfreeze: bool,
+
+    /// Skip the freeze override confirmation (amend only)
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Machine-readable output format (status, validate and diff; `--json` is short for `--format json`)
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Also show what changed since the contract was locked (validate only)
+    #[arg(long)]
+    pub diff: bool,
+
+    /// Common ancestor contract file (merge only)
+    #[arg(long, requires = "theirs")]
+    pub base: Option<PathBuf>,
+
+    /// Other side's contract file to merge into the current contract (merge only)
+    #[arg(long, requires = "base")]
+    pub theirs: Option<PathBuf>,
+
+    /// Target file format (convert only)
+    #[arg(long, value_enum)]
+    pub to: Option<ContractFormat>,
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum ContractAction {
     /// Create a new contract
@@ -324,6 +553,87 @@ pub enum ContractAction {
     Lock,
     /// Show contract status
     Status,
+    /// Verify the lock hash and signature against the contract
+    Verify,
+    /// Show what changed since the contract was locked (exit 3 on drift)
+    Diff,
+    /// Reopen a locked contract as a new draft version that supersedes the lock
+    Amend,
+    /// Three-way merge another draft into the current contract (exit 3 on conflicts)
+    Merge,
+    /// Rewrite the contract in another file format (requires --to)
+    Convert,
+    /// Add, edit or remove a requirement on the draft (add|edit|rm)
+    Req,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RequirementAction {
+    /// Add a requirement (requires --id and --desc)
+    Add,
+    /// Change a requirement's --desc and/or --priority
+    Edit,
+    /// Remove a requirement
+    #[value(alias = "remove")]
+    Rm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RequirementPriority {
+    Must,
+    Should,
+    Could,
+    Wont,
+}
+
+impl From<RequirementPriority> for crate::contract::Priority {
+    fn from(priority: RequirementPriority) -> Self {
+        match priority {
+            RequirementPriority::Must => crate::contract::Priority::Must,
+            RequirementPriority::Should => crate::contract::Priority::Should,
+            RequirementPriority::Could => crate::contract::Priority::Could,
+            RequirementPriority::Wont => crate::contract::Priority::Wont,
+        }
+    }
+}
+
+/// Machine-readable output for `status`, `log`, `contract` and `brain stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+}
+
+impl OutputFormat {
+    /// The format chosen with `--format` or `--json`; `None` for human-readable output
+    pub fn select(json: bool, format: Option<OutputFormat>) -> Option<OutputFormat> {
+        format.or(json.then_some(OutputFormat::Json))
+    }
+
+    /// Serialize `value` as pretty-printed JSON or YAML, without a trailing newline
+    pub fn render<T: serde::Serialize>(self, value: &T) -> anyhow::Result<String> {
+        Ok(match self {
+            OutputFormat::Json => serde_json::to_string_pretty(value)?,
+            OutputFormat::Yaml => serde_yaml::to_string(value)?.trim_end().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ContractFormat {
+    /// contract.json
+    Json,
+    /// contract.toml
+    Toml,
+}
+
+impl From<ContractFormat> for crate::contract::ContractFormat {
+    fn from(format: ContractFormat) -> Self {
+        match format {
+            ContractFormat::Json => crate::contract::ContractFormat::Json,
+            ContractFormat::Toml => crate::contract::ContractFormat::Toml,
+        }
+    }
 }
 
 #[derive(Clone, ValueEnum)]
@@ -336,6 +646,16 @@ pub enum ReviewAction {
     Fail,
     /// Show review status
     Status,
+    /// Mark review checklist items as done (requires --item)
+    Check,
+    /// Pack the current session's evidence into a .tar.gz (requires --output)
+    Archive,
+    /// Restore an evidence archive into a new session
+    Unpack,
+    /// Archive the current session's evidence with a .sha256 sidecar for audits (requires --output)
+    Bundle,
+    /// Check an evidence bundle against its .sha256 sidecar
+    VerifyBundle,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -387,7 +707,8 @@ pub struct BuildArgs {
     #[arg(value_enum, default_value = "manual")]
     pub mode: BuildMode,
 
-    /// Provider to use (for auto/iterate modes)
+    /// Provider to use (for auto/iterate modes); a comma-separated list
+    /// falls back to the first available, e.g. claude-code,mock
     #[arg(short, long, default_value = "claude-code")]
     pub provider: String,
 
@@ -419,15 +740,111 @@ pub struct BuildArgs {
     #[arg(long)]
     pub watch: bool,
 
+    /// Quiet period in milliseconds before a burst of changes triggers a rebuild (with --watch)
+    #[arg(long, default_value_t = crate::cli::watch::DEFAULT_DEBOUNCE_MS, requires = "watch")]
+    pub debounce_ms: u64,
+
     /// Resume from last saved progress
     #[arg(long)]
     pub resume: bool,
 
+    /// Run only these phases (comma-separated: capture,provider,test,lint,commit)
+    #[arg(long, value_enum, value_delimiter = ',', conflicts_with = "skip")]
+    pub only: Vec<BuildPhase>,
+
+    /// Skip these phases (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub skip: Vec<BuildPhase>,
+
+    /// Finish without committing (same as --skip commit)
+    #[arg(long, conflicts_with = "auto_commit")]
+    pub no_commit: bool,
+
+    /// Commit with the generated message without asking (manual complete)
+    #[arg(long)]
+    pub auto_commit: bool,
+
+    /// Persona selecting the prompt template and provider recommendation (auto/iterate)
+    #[arg(long, value_enum)]
+    pub persona: Option<Persona>,
+
+    /// Built-in or custom (.vibeanvil/prompts/<name>.md) template for the provider prompt (auto/iterate)
+    #[arg(long, value_name = "NAME")]
+    pub prompt_template: Option<String>,
+
+    /// Test command run each iteration, through the shell (iterate mode; default: detected)
+    #[arg(long)]
+    pub test_cmd: Option<String>,
+
+    /// Lint command run each iteration, through the shell (iterate mode; default: detected)
+    #[arg(long)]
+    pub lint_cmd: Option<String>,
+
     /// Build action (for manual mode)
     #[arg(value_enum)]
     pub action: Option<ManualBuildAction>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Persona {
+    /// System design and trade-offs
+    Architect,
+    /// Feature implementation
+    Developer,
+    /// Code review
+    Reviewer,
+    /// Testing and release readiness
+    Qa,
+}
+
+impl Persona {
+    /// Prompt template for this persona
+    pub fn template(&self) -> &'static str {
+        match self {
+            Persona::Architect => "architect",
+            Persona::Developer => "developer",
+            Persona::Reviewer => "review",
+            Persona::Qa => "qa",
+        }
+    }
+
+    /// Task type used to match providers
+    pub fn task_type(&self) -> TaskType {
+        match self {
+            Persona::Architect => TaskType::ArchitectureDesign,
+            Persona::Developer => TaskType::CodeGeneration,
+            Persona::Reviewer => TaskType::CodeReview,
+            Persona::Qa => TaskType::TestWriting,
+        }
+    }
+
+    /// Provider selection criteria for this persona
+    pub fn criteria(&self) -> SelectionCriteria {
+        SelectionCriteria::for_task(self.task_type())
+    }
+
+    /// Best-matching provider from the capability matrix
+    pub fn recommended_provider(&self) -> Option<String> {
+        ProviderSelector::new()
+            .select(&self.criteria())
+            .map(|p| p.name.replace('_', "-"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BuildPhase {
+    /// Evidence capture (build/test/lint logs)
+    Capture,
+    /// Provider invocation
+    Provider,
+    /// Test run
+    Test,
+    /// Lint run
+    Lint,
+    /// Auto-commit after a manual build
+    Commit,
+}
+
 #[derive(Clone, ValueEnum, Default)]
 pub enum BuildMode {
     /// Manual build with explicit steps
@@ -498,6 +915,73 @@ pub struct HarvestArgs {
     /// Allow glob patterns (repeatable)
     #[arg(long, action = clap::ArgAction::Append)]
     pub allow_glob: Vec<String>,
+
+    /// Include forked repositories (excluded by default)
+    #[arg(long)]
+    pub include_forks: bool,
+
+    /// Include archived repositories (excluded by default)
+    #[arg(long)]
+    pub include_archived: bool,
+
+    /// Rank search results by stars, last update, or GitHub's best match
+    #[arg(long, value_enum, default_value = "stars")]
+    pub sort: RepoSort,
+
+    /// Sort direction (ignored with --sort relevance)
+    #[arg(long, value_enum, default_value = "desc")]
+    pub order: SortOrder,
+
+    /// Re-run the query recorded in .vibeanvil/harvest.lock.json and report changed sources (exit 3 on drift)
+    #[arg(long)]
+    pub verify_lock: bool,
+
+    /// Tarballs to decompress and parse at once (default: number of CPU cores)
+    #[arg(long, value_parser = parse_threads)]
+    pub threads: Option<usize>,
+
+    /// Code host to harvest from
+    #[arg(long, value_enum, default_value = "github")]
+    pub source: HarvestSourceKind,
+
+    /// GitLab host, e.g. gitlab.mycompany.com (with --source gitlab; default: gitlab.com)
+    #[arg(long)]
+    pub gitlab_host: Option<String>,
+
+    /// Bitbucket workspace whose repositories are harvested (required with --source bitbucket)
+    #[arg(long)]
+    pub bitbucket_workspace: Option<String>,
+
+    /// Retries for a rate-limited (429) or failed API request, with exponential back-off
+    #[arg(long, default_value_t = crate::brain::harvester::DEFAULT_MAX_RETRIES)]
+    pub max_retries: u8,
+
+    /// Harvest this local directory instead of searching a code host
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "label",
+        conflicts_with = "verify_lock"
+    )]
+    pub local: Option<PathBuf>,
+
+    /// Name the local source is identified by (with --local; only its hash is stored)
+    #[arg(long, value_name = "NAME", requires = "local")]
+    pub label: Option<String>,
+
+    /// Re-harvest only the files changed since each repo's last harvest (GitHub only)
+    #[arg(long, conflicts_with_all = ["local", "verify_lock"])]
+    pub incremental: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HarvestSourceKind {
+    /// api.github.com (token: GITHUB_TOKEN)
+    Github,
+    /// gitlab.com or a self-managed GitLab (token: GITLAB_TOKEN)
+    Gitlab,
+    /// Bitbucket Cloud (token: BITBUCKET_TOKEN)
+    Bitbucket,
 }
 
 #[derive(Subcommand)]
@@ -513,6 +997,43 @@ pub enum DownloadMethod {
     Git,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RepoSort {
+    /// Most-starred first
+    Stars,
+    /// Most recently updated first
+    Updated,
+    /// GitHub's best match
+    Relevance,
+}
+
+impl From<RepoSort> for crate::brain::harvester::RepoSort {
+    fn from(sort: RepoSort) -> Self {
+        match sort {
+            RepoSort::Stars => crate::brain::harvester::RepoSort::Stars,
+            RepoSort::Updated => crate::brain::harvester::RepoSort::Updated,
+            RepoSort::Relevance => crate::brain::harvester::RepoSort::Relevance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SortOrder {
+    /// Ascending
+    Asc,
+    /// Descending
+    Desc,
+}
+
+impl From<SortOrder> for crate::brain::harvester::SortOrder {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Asc => crate::brain::harvester::SortOrder::Asc,
+            SortOrder::Desc => crate::brain::harvester::SortOrder::Desc,
+        }
+    }
+}
+
 /// Brain command arguments
 #[derive(clap::Args)]
 pub struct BrainArgs {
@@ -536,15 +1057,43 @@ pub enum BrainCommands {
 
     /// Show brain pack statistics
     Stats {
+        /// Output as JSON (machine-readable)
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+
+        /// Machine-readable output format (`--json` is short for `--format json`)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Show chunk counts per harvested source
+        #[arg(long)]
+        by_source: bool,
+
+        /// Show anonymized source IDs in the per-source breakdown (default: false for privacy)
+        #[arg(long, requires = "by_source")]
+        include_source_ids: bool,
+    },
+
+    /// Show the pack as a tree: source → content type → file (with chunk counts)
+    Map {
         /// Output as JSON (machine-readable)
         #[arg(long)]
         json: bool,
+
+        /// Show anonymized source IDs instead of `source #N` (default: false for privacy)
+        #[arg(long)]
+        include_source_ids: bool,
     },
 
     /// Search the brain pack
     Search {
-        /// Search query
-        query: String,
+        /// Search query (optional with --signal: list every chunk carrying the signal)
+        #[arg(required_unless_present_any = ["regex", "signals"])]
+        query: Option<String>,
+
+        /// Scan chunk text with a regular expression instead of keyword search
+        #[arg(long, value_name = "PATTERN", conflicts_with = "query")]
+        regex: Option<String>,
 
         /// Maximum results
         #[arg(short = 'n', long, default_value = "10")]
@@ -554,6 +1103,15 @@ pub enum BrainCommands {
         #[arg(short = 't', long)]
         record_type: Option<String>,
 
+        /// Restrict to content types (repeatable)
+        #[arg(
+            long = "content-type",
+            visible_alias = "type",
+            action = clap::ArgAction::Append,
+            value_parser = ["readme", "doc", "config", "code", "workflow", "template", "prompt", "other"]
+        )]
+        content_types: Vec<String>,
+
         /// Filter by language (e.g., rust, python, typescript)
         #[arg(short = 'l', long)]
         language: Option<String>,
@@ -563,6 +1121,26 @@ pub enum BrainCommands {
 
         #[arg(long)]
         source: Option<String>,
+
+        /// Restrict to chunks with any of these detected signals (repeatable)
+        #[arg(
+            long = "signal",
+            action = clap::ArgAction::Append,
+            value_parser = [
+                "command_surface",
+                "state_machine",
+                "contract_lock",
+                "iterate_loop",
+                "evidence_audit",
+                "provider_adapter",
+                "security_pattern",
+            ]
+        )]
+        signals: Vec<String>,
+
+        /// Hide results whose normalized score (0–1) is below this value
+        #[arg(long, value_parser = parse_min_score)]
+        min_score: Option<f64>,
     },
 
     /// Export the brain pack
@@ -579,13 +1157,60 @@ pub enum BrainCommands {
         #[arg(long)]
         include_source_ids: bool,
 
-        /// Limit entries for markdown export (default: 50, use 0 for no limit)
+        /// Limit entries for markdown/HTML export (default: 50, use 0 for no limit)
         #[arg(long, default_value = "50")]
         limit: usize,
+
+        /// Also write <output>.manifest.json with counts and the file's SHA-256
+        #[arg(long)]
+        manifest: bool,
+
+        /// Only export records in these languages (repeatable)
+        #[arg(short = 'l', long = "language", action = clap::ArgAction::Append)]
+        languages: Vec<String>,
+
+        /// Only export these content types (repeatable)
+        #[arg(
+            long = "content-type",
+            visible_alias = "type",
+            action = clap::ArgAction::Append,
+            value_parser = ["readme", "doc", "config", "code", "workflow", "template", "prompt", "other"]
+        )]
+        content_types: Vec<String>,
+
+        /// Only export records with any of these detected signals (repeatable)
+        #[arg(
+            long = "signal",
+            action = clap::ArgAction::Append,
+            value_parser = [
+                "command_surface",
+                "state_machine",
+                "contract_lock",
+                "iterate_loop",
+                "evidence_audit",
+                "provider_adapter",
+                "security_pattern",
+            ]
+        )]
+        signals: Vec<String>,
+
+        /// Only export records with at least this many chunks
+        #[arg(long, default_value = "0")]
+        min_chunks: usize,
     },
 
     /// Compact the brain pack (dedup JSONL, optimize SQLite)
-    Compact,
+    Compact {
+        /// First delete harvested records older than this many days
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        ttl_days: Option<u32>,
+    },
+
+    /// Repair legacy rows (normalize malformed content types)
+    Repair,
+
+    /// Rebuild the search index from the stored chunks
+    Reindex,
 
     /// Pack the current codebase into a single AI-friendly file
     Pack {
@@ -596,13 +1221,176 @@ pub enum BrainCommands {
         /// Output format (xml or markdown)
         #[arg(short, long, default_value = "xml")]
         format: String,
+
+        /// Pack the highest-ranked files (repo map ranking) up to this many
+        /// estimated tokens, writing <OUTPUT>.manifest.json
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        max_tokens: Option<u64>,
+    },
+}
+
+/// State command arguments
+#[derive(clap::Args)]
+pub struct StateArgs {
+    /// State command
+    #[command(subcommand)]
+    pub command: StateCommands,
+}
+
+#[derive(Subcommand)]
+pub enum StateCommands {
+    /// Show the transitions between two states (ordinals or names) or two session IDs
+    Diff {
+        /// Start of the window: state ordinal (e.g. 4), state name or session ID
+        from: String,
+
+        /// End of the window: state ordinal, state name or session ID
+        to: String,
+
+        /// Output as JSON (machine-readable)
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Evidence command arguments
+#[derive(clap::Args)]
+pub struct EvidenceArgs {
+    /// Evidence command
+    #[command(subcommand)]
+    pub command: EvidenceCommands,
+}
+
+#[derive(Subcommand)]
+pub enum EvidenceCommands {
+    /// Show a session's evidence manifest and verify content hashes
+    Show {
+        /// Session ID
+        session: String,
+
+        /// Output as JSON (machine-readable)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Attach an externally produced log (e.g. from CI) to the current session
+    Capture {
+        /// Evidence type
+        #[arg(long = "type", value_enum)]
+        kind: EvidenceKind,
+
+        /// File to attach (relative to the project root)
+        #[arg(long)]
+        file: String,
+
+        /// Evidence name, used as the filename prefix (required for custom)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Description recorded in the manifest
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Coverage report format (coverage only; detected from the file name by default)
+        #[arg(long, value_enum)]
+        format: Option<CoverageFormat>,
     },
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum EvidenceKind {
+    /// Build output
+    Build,
+    /// Test output
+    Test,
+    /// Lint output
+    Lint,
+    /// Test coverage report (lcov, Cobertura, tarpaulin or SimpleCov)
+    Coverage,
+    /// Anything else (requires --name)
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CoverageFormat {
+    /// lcov tracefile (lcov.info)
+    Lcov,
+    /// Cobertura XML
+    Cobertura,
+    /// cargo-tarpaulin JSON report
+    Tarpaulin,
+    /// SimpleCov .last_run.json
+    #[value(name = "simplecov")]
+    SimpleCov,
+}
+
+impl From<CoverageFormat> for crate::evidence::CoverageFormat {
+    fn from(format: CoverageFormat) -> Self {
+        match format {
+            CoverageFormat::Lcov => crate::evidence::CoverageFormat::Lcov,
+            CoverageFormat::Cobertura => crate::evidence::CoverageFormat::Cobertura,
+            CoverageFormat::Tarpaulin => crate::evidence::CoverageFormat::Tarpaulin,
+            CoverageFormat::SimpleCov => crate::evidence::CoverageFormat::SimpleCov,
+        }
+    }
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum ExportFormat {
     /// JSON Lines format
     Jsonl,
     /// Markdown format
     Md,
+    /// Self-contained HTML page
+    Html,
+}
+
+/// Parse a `--freeze-until` date; a bare date means midnight UTC
+fn parse_freeze_until(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|_| format!("'{}' is not a YYYY-MM-DD or RFC 3339 date", value))
+}
+
+/// Parse a `--threads` value (at least 1)
+fn parse_threads(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("'{}' is not a whole number", value)),
+    }
+}
+
+/// Parse a `--min-score` value in the 0–1 range
+fn parse_min_score(value: &str) -> Result<f64, String> {
+    let score: f64 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if !(0.0..=1.0).contains(&score) {
+        return Err(format!("{} is outside the 0–1 range", score));
+    }
+    Ok(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::capability::{Capability, CapabilityMatrix};
+
+    #[test]
+    fn test_reviewer_persona_uses_review_template_and_reviewer_provider() {
+        let persona = Persona::Reviewer;
+        assert_eq!(persona.template(), "review");
+        let template = crate::prompt::load_template(persona.template()).unwrap();
+        assert!(template.contains("code reviewer"));
+
+        assert_eq!(persona.criteria().task_type, TaskType::CodeReview);
+        let provider = persona.recommended_provider().unwrap();
+        let matrix = CapabilityMatrix::build_default();
+        let profile = matrix.get(&provider.replace('-', "_")).unwrap();
+        assert!(profile.capability_score(Capability::CodeReview) >= 9);
+    }
 }
@@ -169,6 +169,7 @@ User's question: {}"#,
             working_dir: self.config.working_dir.clone(),
             session_id: self.config.session_id.clone(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let prompt = self.get_mode_prompt(message);
@@ -5,17 +5,34 @@ use std::collections::HashMap;
 use tokio::fs;
 
 use crate::audit::{generate_session_id, AuditLogger};
+use crate::brain::storage::{BrainStorage, SearchFilters};
+use crate::brain::SearchResult;
+use crate::cli::Persona;
+use crate::contract::Contract;
+use crate::error;
 use crate::prompt;
-use crate::provider::{get_provider, Context};
+use crate::provider::cache::ProviderCache;
+use crate::provider::{get_provider, Context, TaskType};
 use crate::state::State;
 use crate::workspace;
 use colored::*;
 
-pub async fn run(provider_name: String) -> Result<()> {
+/// Maximum number of BrainPack patterns included in the plan prompt
+const MAX_BRAIN_PATTERNS: usize = 5;
+
+pub async fn run(
+    provider_name: String,
+    no_cache: bool,
+    from_brain: bool,
+    persona: Option<Persona>,
+    prompt_template: Option<String>,
+) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::ContractLocked) {
-        anyhow::bail!("Contract not locked. Run 'vibeanvil contract lock' first.");
+        return Err(error::invalid_state(
+            "Contract not locked. Run 'vibeanvil contract lock' first.",
+        ));
     }
 
     if state_data.current_state.is_at_least(State::PlanCreated) {
@@ -24,7 +41,7 @@ pub async fn run(provider_name: String) -> Result<()> {
     }
 
     // Read contract
-    let contract_path = workspace::contracts_path().join("contract.json");
+    let contract_path = crate::contract::contract_path();
     let contract = fs::read_to_string(&contract_path).await?;
 
     println!(
@@ -33,6 +50,17 @@ pub async fn run(provider_name: String) -> Result<()> {
     );
 
     let provider = get_provider(&provider_name)?;
+    match persona {
+        Some(persona) => crate::cli::providers::announce_persona(persona, &provider_name, false),
+        None => crate::cli::providers::warn_if_weak(&provider_name, TaskType::Planning, false),
+    }
+    let template = match &prompt_template {
+        Some(name) => {
+            prompt::require_template(name)?;
+            name.as_str()
+        }
+        None => persona.map_or("plan", |p| p.template()),
+    };
 
     // Scan repository map
     crate::cli::style::step("Context Awareness");
@@ -49,12 +77,28 @@ pub async fn run(provider_name: String) -> Result<()> {
         ));
     }
 
+    let references = if from_brain {
+        crate::cli::style::step("BrainPack Patterns");
+        let parsed = crate::contract::load_contract().await?;
+        let storage = BrainStorage::new().await?;
+        let patterns = find_brain_patterns(&storage, &parsed, MAX_BRAIN_PATTERNS);
+        if patterns.is_empty() {
+            crate::cli::style::warn("No matching patterns found in the BrainPack");
+        } else {
+            crate::cli::style::success(&format!("Found {} reference pattern(s)", patterns.len()));
+        }
+        format_brain_patterns(&patterns)
+    } else {
+        String::new()
+    };
+
     if !provider.is_available() {
         println!(
             "⚠️  Provider '{}' not available, generating template plan.",
             provider_name
         );
-        let plan = generate_template_plan(&contract);
+        let mut plan = generate_template_plan(&contract);
+        plan.push_str(&references);
         save_plan(&plan).await?;
     } else {
         let session_id = generate_session_id();
@@ -62,13 +106,22 @@ pub async fn run(provider_name: String) -> Result<()> {
             working_dir: std::env::current_dir()?,
             session_id: session_id.clone(),
             contract_hash: state_data.spec_hash.clone(),
+            cancel: Default::default(),
         };
 
-        let map_markdown = repo_map.to_markdown();
-        let prompt = build_plan_prompt(&contract, &map_markdown);
+        let map_markdown = repo_map.to_markdown() + &references;
+        let prompt = build_plan_prompt(&contract, &map_markdown, template);
 
         println!("{}", "🤖 Generating plan with AI...".cyan());
-        let response = provider.execute(&prompt, &context).await?;
+        let (response, cached) = ProviderCache::new()
+            .execute("plan", provider.as_ref(), &prompt, &context, !no_cache)
+            .await?;
+        if cached {
+            println!(
+                "{}",
+                "↩ Using cached provider response (--no-cache to refresh)".dimmed()
+            );
+        }
         save_plan(&response.output).await?;
     }
 
@@ -152,16 +205,183 @@ fn generate_template_plan(contract: &str) -> String {
     )
 }
 
-fn build_plan_prompt(contract: &str, context: &str) -> String {
+/// Search terms for a goal or requirement, OR-joined for FTS5
+fn brain_query(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 4)
+        .map(|w| w.to_lowercase())
+        .collect();
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" OR "))
+    }
+}
+
+/// Search the BrainPack using the contract's goals and requirements
+fn find_brain_patterns(
+    storage: &BrainStorage,
+    contract: &Contract,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let texts = contract
+        .goals
+        .iter()
+        .map(String::as_str)
+        .chain(contract.requirements.iter().map(|r| r.description.as_str()));
+
+    let mut seen = std::collections::HashSet::new();
+    let mut patterns = vec![];
+    for query in texts.filter_map(brain_query) {
+        let results = match storage.search_filtered(&query, limit, &SearchFilters::default()) {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::debug!("Brain search for '{}' failed: {}", query, e);
+                continue;
+            }
+        };
+        for result in results {
+            if patterns.len() >= limit {
+                return patterns;
+            }
+            if seen.insert(result.chunk_id.clone()) {
+                patterns.push(result);
+            }
+        }
+    }
+    patterns
+}
+
+/// Render patterns as reference material; attribution is path and type only,
+/// never source IDs
+fn format_brain_patterns(patterns: &[SearchResult]) -> String {
+    if patterns.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from(
+        "\n\n## Reference Patterns (BrainPack)\n\nHarvested examples relevant to the contract. Use them as inspiration, not as requirements.\n",
+    );
+    for (i, pattern) in patterns.iter().enumerate() {
+        out.push_str(&format!(
+            "\n### {}. {} ({})\n\n```\n{}\n```\n",
+            i + 1,
+            pattern.path,
+            pattern.content_type,
+            pattern.snippet.trim()
+        ));
+    }
+    out
+}
+
+fn build_plan_prompt(contract: &str, context: &str, template: &str) -> String {
     let mut vars = HashMap::new();
     vars.insert("contract", contract);
     vars.insert("context", context);
+    vars.insert(
+        "task",
+        "Create a detailed implementation plan for the contract.",
+    );
+    // Review and QA personas work on the current changes
+    let diff = if prompt::uses_diff(template) {
+        crate::evidence::git_diff_head().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    vars.insert("diff", diff.as_str());
+    vars.insert("code", diff.as_str());
 
-    match prompt::load_template("plan") {
-        Ok(template) => prompt::render(&template, &vars),
+    match prompt::render_over(template, "plan", &vars) {
+        Ok(prompt) => prompt,
         Err(_) => format!(
             "Based on this contract and the current codebase structure, create a detailed implementation plan.\n\nCONTRACT:\n{}\n\nCODEBASE STRUCTURE:\n{}",
             contract, context
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::{BrainRecord, ContentChunk, ContentType, SourceMeta};
+
+    fn record(path: &str, chunk_id: &str, text: &str) -> BrainRecord {
+        BrainRecord {
+            source_id: "src_secret".to_string(),
+            commit: "abc".to_string(),
+            license: "MIT".to_string(),
+            language: "Rust".to_string(),
+            path: path.to_string(),
+            content_type: ContentType::Code,
+            signals: vec![],
+            summary: String::new(),
+            chunks: vec![ContentChunk {
+                chunk_id: chunk_id.to_string(),
+                text: text.to_string(),
+                start_line: 1,
+                end_line: 1,
+            }],
+            tags: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_brain_patterns_appear_in_plan_prompt() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = BrainStorage::new_for_test(temp.path().join("brain")).unwrap();
+        storage
+            .save_source(&SourceMeta {
+                source_id: "src_secret".to_string(),
+                commit: "abc".to_string(),
+                license: "MIT".to_string(),
+                language: "Rust".to_string(),
+                fetched_at: chrono::Utc::now(),
+                files_count: 2,
+                chunks_count: 2,
+                stars: 0,
+            })
+            .await
+            .unwrap();
+        storage
+            .save_records(&[
+                record(
+                    "src/middleware/ratelimit.rs",
+                    "c1",
+                    "token bucket ratelimit middleware for axum",
+                ),
+                record("src/db/pool.rs", "c2", "connection pool setup"),
+            ])
+            .await
+            .unwrap();
+
+        let mut contract = Contract::new("Gateway");
+        contract.add_goal("Add ratelimit middleware to the gateway");
+
+        let patterns = find_brain_patterns(&storage, &contract, MAX_BRAIN_PATTERNS);
+        assert_eq!(patterns.len(), 1);
+
+        let references = format_brain_patterns(&patterns);
+        let prompt = build_plan_prompt("{}", &format!("src/\n{}", references), "plan");
+        assert!(prompt.contains("Reference Patterns (BrainPack)"));
+        assert!(prompt.contains("src/middleware/ratelimit.rs"));
+        assert!(prompt.contains("token bucket"));
+        assert!(!prompt.contains("src/db/pool.rs"));
+        assert!(!prompt.contains("src_secret"));
+    }
+
+    #[test]
+    fn test_persona_plan_prompt_keeps_the_contract() {
+        let contract = "{\"goals\": [\"Add ratelimit middleware\"]}";
+        for persona in [Persona::Architect, Persona::Reviewer, Persona::Qa] {
+            let prompt = build_plan_prompt(contract, "src/", persona.template());
+            assert!(prompt.contains("Add ratelimit middleware"), "{:?}", persona);
+        }
+    }
+
+    #[test]
+    fn test_brain_query_skips_short_words() {
+        assert_eq!(brain_query("Add a CLI flag").as_deref(), Some("flag"));
+        assert_eq!(brain_query("a b c"), None);
+    }
+}
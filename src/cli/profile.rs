@@ -0,0 +1,193 @@
+//! Lightweight span-timing collector for `--profile`
+//!
+//! Records the wall-clock duration of every closed `tracing` span, grouped by
+//! span name, and renders a summary table once the command finishes.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use tracing::span;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Accumulated timing for a single named phase
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub count: u32,
+    pub total: Duration,
+}
+
+/// Shared store of phase timings, in first-seen order
+#[derive(Debug, Clone, Default)]
+pub struct SpanTimings {
+    phases: Arc<Mutex<Vec<PhaseTiming>>>,
+}
+
+impl SpanTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a duration to the named phase
+    pub fn record(&self, name: &str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap_or_else(|e| e.into_inner());
+        match phases.iter_mut().find(|p| p.name == name) {
+            Some(phase) => {
+                phase.count += 1;
+                phase.total += elapsed;
+            }
+            None => phases.push(PhaseTiming {
+                name: name.to_string(),
+                count: 1,
+                total: elapsed,
+            }),
+        }
+    }
+
+    /// Snapshot of recorded phases
+    pub fn phases(&self) -> Vec<PhaseTiming> {
+        self.phases
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Render a plain-text summary table
+    pub fn render_table(&self) -> String {
+        let phases = self.phases();
+        let total: Duration = phases.iter().map(|p| p.total).sum();
+        let width = phases
+            .iter()
+            .map(|p| p.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Phase".len());
+
+        let mut out = String::new();
+        out.push_str(&format!(
+            "{:<width$}  {:>6}  {:>12}  {:>6}\n",
+            "Phase",
+            "Calls",
+            "Total",
+            "%",
+            width = width
+        ));
+        out.push_str(&format!("{}\n", "-".repeat(width + 32)));
+        for phase in &phases {
+            let pct = if total.is_zero() {
+                0.0
+            } else {
+                phase.total.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            out.push_str(&format!(
+                "{:<width$}  {:>6}  {:>12}  {:>5.1}%\n",
+                phase.name,
+                phase.count,
+                format_duration(phase.total),
+                pct,
+                width = width
+            ));
+        }
+        out
+    }
+
+    /// Print the summary table to stderr
+    pub fn print_report(&self) {
+        eprintln!();
+        eprintln!("{}", "⏱  Profile".cyan().bold());
+        if self.phases().is_empty() {
+            eprintln!("{}", "  (no phases recorded)".dimmed());
+            return;
+        }
+        eprint!("{}", self.render_table());
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    if d.as_secs() >= 1 {
+        format!("{:.2}s", d.as_secs_f64())
+    } else {
+        format!("{:.1}ms", d.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Start instant stored in span extensions
+struct SpanStart(Instant);
+
+/// `tracing` layer feeding closed span durations into [`SpanTimings`]
+pub struct ProfileLayer {
+    timings: SpanTimings,
+}
+
+impl ProfileLayer {
+    pub fn new(timings: SpanTimings) -> Self {
+        Self { timings }
+    }
+}
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(start) = span.extensions().get::<SpanStart>() {
+                self.timings.record(span.name(), start.0.elapsed());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_collector_accumulates_phase_durations() {
+        let timings = SpanTimings::new();
+        let subscriber = tracing_subscriber::registry().with(ProfileLayer::new(timings.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _span = tracing::info_span!("download").entered();
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            let _span = tracing::info_span!("parse").entered();
+        });
+
+        let phases = timings.phases();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].name, "download");
+        assert_eq!(phases[0].count, 2);
+        assert!(phases[0].total >= Duration::from_millis(4));
+        assert_eq!(phases[1].name, "parse");
+        assert_eq!(phases[1].count, 1);
+    }
+
+    #[test]
+    fn test_render_table_lists_each_phase() {
+        let timings = SpanTimings::new();
+        timings.record("search", Duration::from_millis(120));
+        timings.record("download", Duration::from_secs(2));
+        timings.record("parse", Duration::from_millis(30));
+        timings.record("db_write", Duration::from_millis(15));
+
+        let table = timings.render_table();
+        assert!(table.starts_with("Phase"));
+        for name in ["search", "download", "parse", "db_write"] {
+            assert!(table.contains(name), "missing phase {}", name);
+        }
+        assert!(table.contains("2.00s"));
+        assert!(table.contains("120.0ms"));
+    }
+}
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::workspace;
 
@@ -36,6 +36,19 @@ pub struct BuildProgress {
     pub last_error: Option<String>,
     /// Accumulated changes/files modified
     pub files_modified: Vec<String>,
+    /// Prompt for the next provider call; a resumed build sends it first
+    #[serde(default)]
+    pub last_prompt: String,
+    /// Check failures from every completed iteration
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// Evidence files captured so far
+    #[serde(default)]
+    pub evidence_files: Vec<String>,
+    /// Task whose loop was running (`Task 2: ...`); the tasks before it
+    /// had finished
+    #[serde(default)]
+    pub task: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -67,6 +80,10 @@ impl BuildProgress {
             lint_passed: false,
             last_error: None,
             files_modified: vec![],
+            last_prompt: String::new(),
+            errors: vec![],
+            evidence_files: vec![],
+            task: None,
         }
     }
 
@@ -77,9 +94,13 @@ impl BuildProgress {
 
     /// Save progress to file
     pub async fn save(&self) -> Result<()> {
-        let path = Self::progress_path();
+        self.save_to(&Self::progress_path()).await
+    }
+
+    /// Save progress to `path`
+    pub async fn save_to(&self, path: &Path) -> Result<()> {
         let json = serde_json::to_string_pretty(self)?;
-        tokio::fs::write(&path, json)
+        tokio::fs::write(path, json)
             .await
             .context("Failed to save build progress")?;
         Ok(())
@@ -87,13 +108,16 @@ impl BuildProgress {
 
     /// Load progress from file
     pub async fn load() -> Result<Option<Self>> {
-        let path = Self::progress_path();
+        Self::load_from(&Self::progress_path()).await
+    }
 
+    /// Load progress from `path`, `None` when there is none
+    pub async fn load_from(path: &Path) -> Result<Option<Self>> {
         if !path.exists() {
             return Ok(None);
         }
 
-        let content = tokio::fs::read_to_string(&path)
+        let content = tokio::fs::read_to_string(path)
             .await
             .context("Failed to read build progress")?;
 
@@ -162,6 +186,16 @@ impl BuildProgress {
         self.updated_at = Utc::now();
     }
 
+    /// Record that `completed` iterations have finished and `next_prompt`
+    /// is about to be sent to the provider
+    pub fn checkpoint(&mut self, completed: u32, next_prompt: &str, evidence_files: Vec<String>) {
+        self.current_iteration = completed;
+        self.last_prompt = next_prompt.to_string();
+        self.evidence_files = evidence_files;
+        self.current_step = BuildStep::Fixing;
+        self.updated_at = Utc::now();
+    }
+
     /// Mark as completed
     pub fn complete(&mut self) {
         self.current_step = BuildStep::Completed;
@@ -182,3 +216,33 @@ impl std::fmt::Display for BuildStep {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_progress_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join(PROGRESS_FILE);
+        assert!(BuildProgress::load_from(&path).await.unwrap().is_none());
+
+        let mut progress = BuildProgress::new("session_1", "iterate", "mock", 5);
+        progress.errors.push("test failed: it_works".to_string());
+        progress.checkpoint(
+            2,
+            "Fix the failing tests",
+            vec!["test_log_iter_1.txt".to_string()],
+        );
+        progress.save_to(&path).await.unwrap();
+
+        let loaded = BuildProgress::load_from(&path).await.unwrap().unwrap();
+        assert_eq!(loaded.session_id, "session_1");
+        assert_eq!(loaded.current_iteration, 2);
+        assert_eq!(loaded.max_iterations, 5);
+        assert_eq!(loaded.current_step, BuildStep::Fixing);
+        assert_eq!(loaded.last_prompt, "Fix the failing tests");
+        assert_eq!(loaded.errors, vec!["test failed: it_works"]);
+        assert_eq!(loaded.evidence_files, vec!["test_log_iter_1.txt"]);
+    }
+}
@@ -106,6 +106,5 @@ fn inject_workspace_defaults(vars: &mut HashMap<String, String>) {
         }
     }
 
-    vars.entry("tech_stack".to_string())
-        .or_insert_with(String::new);
+    vars.entry("tech_stack".to_string()).or_default();
 }
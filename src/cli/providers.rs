@@ -3,7 +3,11 @@
 use anyhow::Result;
 use colored::Colorize;
 
-use crate::provider::{get_provider, list_providers, CapabilityMatrix, ProviderSelector, TaskType};
+use crate::cli::Persona;
+use crate::provider::capability::format_cost;
+use crate::provider::{
+    get_provider, list_providers, Capability, CapabilityMatrix, ProviderSelector, TaskType,
+};
 
 /// Provider subcommand
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -17,6 +21,34 @@ pub enum ProviderSubcommand {
     Recommend(String),
     /// Compare providers
     Compare(Vec<String>),
+    /// Probe a provider for one capability
+    Test {
+        provider: String,
+        capability: Capability,
+    },
+    /// Estimate each provider's cost for a token count
+    Estimate(u32),
+}
+
+/// Warn (without blocking) when the chosen provider is weak for the task
+pub fn warn_if_weak(provider: &str, task_type: TaskType, require_agentic: bool) {
+    if let Some(warning) =
+        ProviderSelector::new().capability_warning(provider, task_type, require_agentic)
+    {
+        crate::cli::style::warn(&warning.to_string());
+    }
+}
+
+/// Show the persona's template and recommended provider, then check the chosen one
+pub fn announce_persona(persona: Persona, provider: &str, require_agentic: bool) {
+    let recommended = persona.recommended_provider();
+    crate::cli::style::info(&format!(
+        "Persona {:?}: template '{}', recommended provider: {}",
+        persona,
+        persona.template(),
+        recommended.as_deref().unwrap_or("none")
+    ));
+    warn_if_weak(provider, persona.task_type(), require_agentic);
 }
 
 pub async fn run() -> Result<()> {
@@ -29,6 +61,11 @@ pub async fn run_subcommand(cmd: ProviderSubcommand) -> Result<()> {
         ProviderSubcommand::Matrix => run_matrix().await,
         ProviderSubcommand::Recommend(task) => run_recommend(&task).await,
         ProviderSubcommand::Compare(providers) => run_compare(&providers).await,
+        ProviderSubcommand::Test {
+            provider,
+            capability,
+        } => run_test(&provider, capability).await,
+        ProviderSubcommand::Estimate(tokens) => run_estimate(tokens),
     }
 }
 
@@ -211,7 +248,6 @@ async fn run_matrix() -> Result<()> {
             let mut caps: Vec<String> = Vec::new();
 
             // Show key capabilities
-            use crate::provider::Capability;
             for cap in [
                 Capability::CodeGeneration,
                 Capability::Agentic,
@@ -335,7 +371,6 @@ async fn run_compare(providers: &[String]) -> Result<()> {
 
     let matrix = CapabilityMatrix::build_default();
 
-    use crate::provider::Capability;
     let key_caps = [
         Capability::CodeGeneration,
         Capability::CodeReview,
@@ -410,3 +445,100 @@ async fn run_compare(providers: &[String]) -> Result<()> {
 
     Ok(())
 }
+
+/// Estimated cost of `tokens` tokens per provider, split evenly between
+/// prompt and output, cheapest first
+fn run_estimate(tokens: u32) -> Result<()> {
+    let input = tokens / 2;
+    let output = tokens - input;
+    let matrix = CapabilityMatrix::build_default();
+    let mut profiles = matrix.list();
+    profiles.sort_by(|a, b| {
+        a.estimated_cost(input, output)
+            .total_cmp(&b.estimated_cost(input, output))
+    });
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "Estimated cost for {} tokens ({} prompt, {} output)",
+            tokens, input, output
+        )
+        .white()
+        .bold()
+    );
+    println!();
+    for profile in profiles {
+        let cost = profile.estimated_cost(input, output);
+        let cost = if cost == 0.0 {
+            "Free".to_string()
+        } else {
+            format_cost(cost)
+        };
+        println!(
+            "  {:22} {:>10}  {}",
+            profile.name.cyan(),
+            cost.green(),
+            format!("(tier {})", profile.tier).dimmed()
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Probe a provider for a capability and cross-check the declared score
+async fn run_test(name: &str, cap: Capability) -> Result<()> {
+    use crate::provider::{probe, Context};
+
+    let provider = get_provider(name)?;
+    if !provider.is_available() {
+        return Err(crate::error::provider_unavailable(format!(
+            "Provider '{}' is not available",
+            name
+        )));
+    }
+
+    let context = Context {
+        working_dir: std::env::current_dir()?,
+        session_id: crate::audit::generate_session_id(),
+        contract_hash: None,
+        cancel: Default::default(),
+    };
+    let result = probe::probe(provider.as_ref(), cap, &context).await?;
+
+    println!();
+    println!(
+        "{} {} → {}",
+        "Probe:".white().bold(),
+        result.provider.cyan(),
+        cap.display_name()
+    );
+    match result.declared_score {
+        Some(score) => println!("  Declared score: {}/10", score),
+        None => println!("  Declared score: {}", "not in capability matrix".dimmed()),
+    }
+    println!(
+        "  Probe result:   {}",
+        if result.supported {
+            "supported".green()
+        } else {
+            "not demonstrated".red()
+        }
+    );
+    println!("  Response:       {}", result.excerpt.trim().dimmed());
+    if !result.matches_declared() {
+        crate::cli::style::warn("Probe result disagrees with the capability matrix");
+    }
+    println!();
+
+    if !result.supported {
+        anyhow::bail!(
+            "Provider '{}' did not demonstrate {}",
+            result.provider,
+            cap.display_name()
+        );
+    }
+    Ok(())
+}
@@ -9,6 +9,22 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// Directory names never mapped (dependencies, virtualenvs, build output)
+pub const SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "__pycache__",
+    "venv",
+    "dist",
+    "build",
+];
+
+/// Whether a file or directory name is skipped: hidden entries (`.git`,
+/// `.vibeanvil`, ...) and [`SKIP_DIRS`]
+pub fn is_skipped(file_name: &str) -> bool {
+    file_name.starts_with('.') || SKIP_DIRS.contains(&file_name)
+}
+
 /// A symbol extracted from a file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
@@ -52,6 +68,12 @@ pub struct RepoMap {
     pub total_lines: usize,
     /// Total file count
     pub total_files: usize,
+    /// Depth limit used for the walk (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    /// Directories skipped because they were below the depth limit
+    #[serde(default)]
+    pub truncated_dirs: usize,
 }
 
 /// Statistics for a language
@@ -66,12 +88,20 @@ pub struct LanguageStats {
 impl RepoMap {
     /// Create a new repository map
     pub async fn new(root: &Path) -> Result<Self> {
+        Self::with_max_depth(root, None).await
+    }
+
+    /// Create a repository map, descending at most `max_depth` levels below `root`
+    ///
+    /// Files directly in `root` are at depth 1.
+    pub async fn with_max_depth(root: &Path, max_depth: Option<usize>) -> Result<Self> {
         let mut files = Vec::new();
         let mut language_stats: HashMap<String, LanguageStats> = HashMap::new();
         let mut total_lines = 0;
 
         // Walk the directory tree
-        let entries = collect_source_files(root).await?;
+        let walk = collect_source_files(root, max_depth).await?;
+        let entries = walk.files;
 
         for entry in entries {
             if let Ok(info) = analyze_file(root, &entry).await {
@@ -95,6 +125,8 @@ impl RepoMap {
             language_stats,
             total_lines,
             total_files,
+            max_depth,
+            truncated_dirs: walk.truncated_dirs,
         })
     }
 
@@ -125,7 +157,7 @@ impl RepoMap {
         output.push_str("```\n\n");
 
         // Key symbols (if we have room)
-        let current_size = output.len() / 4; // Rough token estimate
+        let current_size = crate::brain::tokens::estimate(&output);
         if current_size < max_tokens {
             output.push_str("## Key Symbols\n");
             for file in &self.files {
@@ -147,6 +179,46 @@ impl RepoMap {
         output
     }
 
+    /// Files, most central first: those imported by the most other files,
+    /// then those defining the most symbols, then by path
+    ///
+    /// A file counts as imported when another file's import line names its
+    /// module (`pack` for `brain/pack.rs`, `brain` for `brain/mod.rs`).
+    pub fn ranked_files(&self) -> Vec<&FileInfo> {
+        let import_words: Vec<std::collections::HashSet<&str>> = self
+            .files
+            .iter()
+            .map(|f| {
+                f.imports
+                    .iter()
+                    .flat_map(|line| line.split(|c: char| !c.is_alphanumeric() && c != '_'))
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            })
+            .collect();
+
+        let mut ranked: Vec<(usize, &FileInfo)> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let module = module_name(&file.path);
+                let references = import_words
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, words)| *j != i && words.contains(module))
+                    .count();
+                (references, file)
+            })
+            .collect();
+        ranked.sort_by(|(ra, a), (rb, b)| {
+            rb.cmp(ra)
+                .then(b.symbols.len().cmp(&a.symbols.len()))
+                .then(a.path.cmp(&b.path))
+        });
+        ranked.into_iter().map(|(_, file)| file).collect()
+    }
+
     /// Display the map to console
     pub fn display(&self) {
         println!("\n{}", "═".repeat(60).cyan());
@@ -155,7 +227,7 @@ impl RepoMap {
 
         println!("{}", "Languages:".yellow());
         let mut langs: Vec<_> = self.language_stats.iter().collect();
-        langs.sort_by(|a, b| b.1.lines.cmp(&a.1.lines));
+        langs.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.lines));
         for (lang, stats) in langs {
             let bar_width = (stats.lines as f32 / self.total_lines as f32 * 30.0) as usize;
             println!(
@@ -181,6 +253,14 @@ impl RepoMap {
             self.total_files,
             self.total_lines
         );
+        if self.truncated_dirs > 0 {
+            println!(
+                "{} {} directories below --max-depth {} were not scanned",
+                "Note:".yellow(),
+                self.truncated_dirs,
+                self.max_depth.unwrap_or_default()
+            );
+        }
     }
 }
 
@@ -251,15 +331,40 @@ impl Tree {
     }
 }
 
-/// Collect all source files in a directory
-async fn collect_source_files(root: &Path) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
-    collect_files_recursive(root, root, &mut files).await?;
-    Ok(files)
+/// Result of walking a directory tree
+#[derive(Debug, Default)]
+struct SourceWalk {
+    files: Vec<PathBuf>,
+    /// Directories not descended into because of the depth limit
+    truncated_dirs: usize,
+}
+
+/// Name other files import `path` by: its stem, or its directory for
+/// `mod.rs`, `index.*` and `__init__.py`
+fn module_name(path: &str) -> &str {
+    let mut parts = path.rsplit('/');
+    let file = parts.next().unwrap_or(path);
+    let stem = file.split('.').next().unwrap_or(file);
+    match stem {
+        "mod" | "index" | "__init__" => parts.next().unwrap_or(stem),
+        _ => stem,
+    }
+}
+
+/// Collect all source files in a directory, optionally limited in depth
+async fn collect_source_files(root: &Path, max_depth: Option<usize>) -> Result<SourceWalk> {
+    let mut walk = SourceWalk::default();
+    collect_files_recursive(root, 1, max_depth, &mut walk).await?;
+    Ok(walk)
 }
 
 #[async_recursion::async_recursion]
-async fn collect_files_recursive(_root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+async fn collect_files_recursive(
+    dir: &Path,
+    depth: usize,
+    max_depth: Option<usize>,
+    walk: &mut SourceWalk,
+) -> Result<()> {
     let mut entries = fs::read_dir(dir).await?;
 
     while let Some(entry) = entries.next_entry().await? {
@@ -267,22 +372,18 @@ async fn collect_files_recursive(_root: &Path, dir: &Path, files: &mut Vec<PathB
         let file_name = path.file_name().unwrap_or_default().to_string_lossy();
 
         // Skip hidden files and common ignore patterns
-        if file_name.starts_with('.')
-            || file_name == "node_modules"
-            || file_name == "target"
-            || file_name == "__pycache__"
-            || file_name == "venv"
-            || file_name == ".git"
-            || file_name == "dist"
-            || file_name == "build"
-        {
+        if is_skipped(&file_name) {
             continue;
         }
 
         if path.is_dir() {
-            collect_files_recursive(_root, &path, files).await?;
+            if max_depth.is_some_and(|max| depth >= max) {
+                walk.truncated_dirs += 1;
+                continue;
+            }
+            collect_files_recursive(&path, depth + 1, max_depth, walk).await?;
         } else if is_source_file(&path) {
-            files.push(path);
+            walk.files.push(path);
         }
     }
 
@@ -306,7 +407,7 @@ fn is_source_file(path: &Path) -> bool {
 }
 
 /// Get the language from file extension
-fn get_language(path: &Path) -> String {
+pub fn get_language(path: &Path) -> String {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -426,25 +527,19 @@ fn extract_imports(content: &str, language: &str) -> Vec<String> {
         let trimmed = line.trim();
 
         match language {
-            "Rust" => {
-                if trimmed.starts_with("use ") {
-                    imports.push(trimmed.to_string());
-                }
+            "Rust" if trimmed.starts_with("use ") => {
+                imports.push(trimmed.to_string());
             }
-            "Python" => {
-                if trimmed.starts_with("import ") || trimmed.starts_with("from ") {
-                    imports.push(trimmed.to_string());
-                }
+            "Python" if trimmed.starts_with("import ") || trimmed.starts_with("from ") => {
+                imports.push(trimmed.to_string());
             }
-            "JavaScript" | "TypeScript" | "React" => {
-                if trimmed.starts_with("import ") || trimmed.contains("require(") {
-                    imports.push(trimmed.to_string());
-                }
+            "JavaScript" | "TypeScript" | "React"
+                if trimmed.starts_with("import ") || trimmed.contains("require(") =>
+            {
+                imports.push(trimmed.to_string());
             }
-            "Go" => {
-                if trimmed.starts_with("import ") {
-                    imports.push(trimmed.to_string());
-                }
+            "Go" if trimmed.starts_with("import ") => {
+                imports.push(trimmed.to_string());
             }
             _ => {}
         }
@@ -678,14 +773,19 @@ impl Symbol {
 }
 
 /// Generate the repository map
-pub async fn run_map(max_tokens: Option<usize>) -> Result<()> {
+pub async fn run_map(max_tokens: Option<usize>, max_depth: Option<usize>) -> Result<()> {
     use crate::cli::style;
     use crate::workspace;
 
     style::header("Repository Map");
 
     let cwd = std::env::current_dir()?;
-    let map = RepoMap::new(&cwd).await?;
+    let map = {
+        use tracing::Instrument;
+        RepoMap::with_max_depth(&cwd, max_depth)
+            .instrument(tracing::info_span!("scan"))
+            .await?
+    };
 
     map.display();
 
@@ -750,4 +850,26 @@ mod tests {
         assert!(output.contains("src/"));
         assert!(output.contains("main.rs"));
     }
+
+    #[tokio::test]
+    async fn test_max_depth_excludes_deeper_files() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let deep = temp.path().join("a/b/c/d");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(temp.path().join("root.rs"), "fn root() {}").unwrap();
+        std::fs::write(temp.path().join("a/one.rs"), "fn one() {}").unwrap();
+        std::fs::write(temp.path().join("a/b/two.rs"), "fn two() {}").unwrap();
+        std::fs::write(deep.join("four.rs"), "fn four() {}").unwrap();
+
+        let full = RepoMap::new(temp.path()).await.unwrap();
+        assert_eq!(full.total_files, 4);
+        assert_eq!(full.truncated_dirs, 0);
+
+        let map = RepoMap::with_max_depth(temp.path(), Some(2)).await.unwrap();
+        let mut paths: Vec<_> = map.files.iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["a/one.rs", "root.rs"]);
+        assert_eq!(map.truncated_dirs, 1);
+        assert_eq!(map.max_depth, Some(2));
+    }
 }
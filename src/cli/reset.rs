@@ -0,0 +1,253 @@
+//! Reset and abort commands - roll the workflow back or start over
+//!
+//! Only files under `.vibeanvil/` are touched; git history and the working
+//! tree are never modified.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::audit::{generate_session_id, AuditEntry, AuditLogger};
+use crate::contract;
+use crate::error;
+use crate::state::{State, StateData};
+use crate::workspace;
+
+pub async fn run(
+    to: Option<State>,
+    hard: bool,
+    reason: Option<String>,
+    yes: bool,
+    force_unfreeze: bool,
+) -> Result<()> {
+    let state_data = workspace::load_state().await?;
+
+    match (to, hard) {
+        (Some(target), false) => {
+            let reason = reason.unwrap_or_else(|| "reset --to".to_string());
+            rollback(state_data, target, &reason, yes, force_unfreeze).await
+        }
+        (None, true) => hard_reset(state_data, yes, force_unfreeze).await,
+        _ => anyhow::bail!("Specify either --to <STATE> or --hard"),
+    }
+}
+
+/// Refuse to unlock a contract inside its freeze window unless `--force-unfreeze`
+/// is given and confirmed. Returns whether a freeze was overridden.
+pub(crate) async fn check_freeze(force_unfreeze: bool, yes: bool) -> Result<bool> {
+    if !contract::contract_lock_path().exists() {
+        return Ok(false);
+    }
+    let lock = contract::load_lock().await?;
+    check_lock_freeze(&lock, force_unfreeze, yes)
+}
+
+/// `check_freeze` for an already loaded lock, e.g. of a named contract
+pub(crate) fn check_lock_freeze(
+    lock: &contract::ContractLock,
+    force_unfreeze: bool,
+    yes: bool,
+) -> Result<bool> {
+    let Some(until) = lock.frozen_at(chrono::Utc::now()) else {
+        return Ok(false);
+    };
+    if !force_unfreeze {
+        return Err(error::invalid_state(format!(
+            "Contract is frozen until {}. Wait for the freeze to end or re-run with --force-unfreeze.",
+            until
+        )));
+    }
+    if !confirm(
+        &format!("Contract is frozen until {}. Override the freeze?", until),
+        yes,
+    )? {
+        anyhow::bail!("Freeze not overridden; nothing was changed.");
+    }
+    Ok(true)
+}
+
+/// Ask before a destructive reset; non-interactive runs must pass `--yes`
+fn confirm(question: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if std::env::var("CI").is_ok() || !console::user_attended() {
+        anyhow::bail!("Refusing to reset without confirmation. Re-run with --yes.");
+    }
+    Ok(inquire::Confirm::new(question)
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false))
+}
+
+async fn rollback(
+    mut state_data: StateData,
+    target: State,
+    reason: &str,
+    yes: bool,
+    force_unfreeze: bool,
+) -> Result<()> {
+    let from = state_data.current_state;
+    let session_id = generate_session_id();
+    // Validate before asking; nothing is saved until confirmed
+    state_data.rollback_to(target, reason, &session_id)?;
+    let unfrozen =
+        if from.is_at_least(State::ContractLocked) && !target.is_at_least(State::ContractLocked) {
+            check_freeze(force_unfreeze, yes).await?
+        } else {
+            false
+        };
+
+    if !confirm(
+        &format!("Roll back workflow from {} to {}?", from, target),
+        yes,
+    )? {
+        println!("Reset cancelled.");
+        return Ok(());
+    }
+
+    workspace::save_state(&state_data).await?;
+
+    let entry = AuditEntry::new(
+        "reset",
+        vec!["--to".into(), target.to_string()],
+        &session_id,
+    )
+    .with_state_transition(from, target)
+    .with_metadata(serde_json::json!({
+        "mode": "rollback",
+        "reason": reason,
+        "force_unfreeze": unfrozen,
+    }));
+    AuditLogger::new(&session_id).log(&entry).await?;
+
+    println!(
+        "{} Workflow rolled back: {} → {}",
+        "✓".green(),
+        from,
+        target
+    );
+    if !target.is_at_least(State::ContractLocked) && from.is_at_least(State::ContractLocked) {
+        println!("  Contract must be locked again: vibeanvil contract lock");
+    }
+    Ok(())
+}
+
+/// Abandon the workflow and return to INIT without archiving anything
+pub async fn abort(reason: &str, force: bool, yes: bool, force_unfreeze: bool) -> Result<()> {
+    let mut state_data = workspace::load_state().await?;
+    let from = state_data.current_state;
+    let session_id = generate_session_id();
+    // Validate before asking; nothing is saved until confirmed
+    state_data.abort(reason, &session_id, force)?;
+    let unfrozen =
+        from.is_at_least(State::ContractLocked) && check_freeze(force_unfreeze, yes).await?;
+
+    if !confirm(
+        &format!("Abort the workflow at {} and return to INIT?", from),
+        yes,
+    )? {
+        println!("Abort cancelled.");
+        return Ok(());
+    }
+
+    workspace::save_state(&state_data).await?;
+
+    let entry = AuditEntry::new("abort", vec!["--reason".into(), reason.into()], &session_id)
+        .with_state_transition(from, State::Init)
+        .with_metadata(serde_json::json!({
+            "reason": reason,
+            "force": force,
+            "force_unfreeze": unfrozen,
+        }));
+    AuditLogger::new(&session_id).log(&entry).await?;
+
+    println!(
+        "{} Workflow aborted: {} → {}",
+        "✓".green(),
+        from,
+        State::Init
+    );
+    println!("  Files under .vibeanvil/ were kept; use 'reset --hard' to archive them instead.");
+    println!();
+    println!("Next: vibeanvil intake");
+    Ok(())
+}
+
+async fn hard_reset(mut state_data: StateData, yes: bool, force_unfreeze: bool) -> Result<()> {
+    let from = state_data.current_state;
+    let unfrozen =
+        from.is_at_least(State::ContractLocked) && check_freeze(force_unfreeze, yes).await?;
+    if !confirm(
+        &format!(
+            "Reset workflow from {} to INIT? State and contracts will be archived.",
+            from
+        ),
+        yes,
+    )? {
+        println!("Reset cancelled.");
+        return Ok(());
+    }
+
+    let archive = archive_workspace().await?;
+
+    let session_id = generate_session_id();
+    let guardrails = std::mem::take(&mut state_data.guardrails);
+    let fresh = StateData {
+        guardrails,
+        contract_format: state_data.contract_format,
+        ..Default::default()
+    };
+    workspace::save_state(&fresh).await?;
+
+    let entry = AuditEntry::new("reset", vec!["--hard".into()], &session_id)
+        .with_state_transition(from, State::Init)
+        .with_metadata(serde_json::json!({
+            "mode": "hard",
+            "archive": archive.display().to_string(),
+            "force_unfreeze": unfrozen,
+        }));
+    AuditLogger::new(&session_id).log(&entry).await?;
+
+    println!("{} Workflow reset: {} → {}", "✓".green(), from, State::Init);
+    println!("  Previous state archived to {}", archive.display());
+    println!();
+    println!("Next: vibeanvil intake");
+    Ok(())
+}
+
+/// Move state.json, contracts and lock files into a timestamped archive
+async fn archive_workspace() -> Result<PathBuf> {
+    let archive =
+        workspace::archive_path().join(chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string());
+    fs::create_dir_all(&archive)
+        .await
+        .with_context(|| format!("Failed to create archive: {}", archive.display()))?;
+
+    fs::copy(workspace::state_path(), archive.join("state.json"))
+        .await
+        .context("Failed to archive state.json")?;
+
+    let contracts = workspace::contracts_path();
+    if contracts.exists() {
+        move_path(&contracts, &archive.join("contracts")).await?;
+        fs::create_dir_all(&contracts).await?;
+    }
+
+    let mut entries = fs::read_dir(workspace::workspace_path()).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with("contract") && name.ends_with(".lock") {
+            move_path(&entry.path(), &archive.join(&name)).await?;
+        }
+    }
+
+    Ok(archive)
+}
+
+async fn move_path(from: &Path, to: &Path) -> Result<()> {
+    fs::rename(from, to)
+        .await
+        .with_context(|| format!("Failed to archive {}", from.display()))
+}
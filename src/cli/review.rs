@@ -1,35 +1,331 @@
 //! Review command handler
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-use crate::audit::{generate_session_id, AuditLogger};
+use crate::audit::{generate_session_id, AuditEntry, AuditLogger};
 use crate::cli::ReviewAction;
+use crate::error;
+use crate::evidence::{self, archive, EvidenceCollector};
 use crate::state::State;
 use crate::workspace;
 
-pub async fn run(action: ReviewAction) -> Result<()> {
+/// The diff snapshot under review, persisted to `.vibeanvil/review.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewRecord {
+    /// Session that captured the diff evidence
+    pub session_id: String,
+    /// Evidence file holding the reviewed diff
+    pub evidence_file: String,
+    /// SHA-256 of the stored diff evidence
+    pub diff_sha256: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Hash of the snapshot that was approved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Checklist items marked as done with `review check`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checked: Vec<String>,
+    /// Whether the review was passed with `--skip-checklist`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checklist_skipped: bool,
+}
+
+/// A named item of the review checklist
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub id: String,
+    pub description: String,
+    /// Must be checked before `review pass`
+    #[serde(default)]
+    pub mandatory: bool,
+}
+
+/// Team review checklist from `.vibeanvil/review_checklist.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewChecklist {
+    #[serde(default)]
+    pub items: Vec<ChecklistItem>,
+}
+
+impl ReviewChecklist {
+    /// Parse a checklist, rejecting duplicate item IDs
+    pub fn parse(content: &str) -> Result<Self> {
+        let checklist: ReviewChecklist =
+            toml::from_str(content).context("Failed to parse review_checklist.toml")?;
+        for (i, item) in checklist.items.iter().enumerate() {
+            if checklist.items[..i].iter().any(|other| other.id == item.id) {
+                return Err(error::validation(format!(
+                    "Duplicate checklist item '{}' in review_checklist.toml",
+                    item.id
+                )));
+            }
+        }
+        Ok(checklist)
+    }
+
+    /// Load the workspace checklist; None when the team hasn't defined one
+    pub fn load() -> Result<Option<Self>> {
+        let path = workspace::review_checklist_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).context("Failed to read review_checklist.toml")?;
+        Self::parse(&content).map(Some)
+    }
+
+    /// Mandatory items not in `checked`
+    pub fn unchecked_mandatory(&self, checked: &[String]) -> Vec<&ChecklistItem> {
+        self.items
+            .iter()
+            .filter(|item| item.mandatory && !checked.contains(&item.id))
+            .collect()
+    }
+}
+
+/// Items shown at `review start` when no checklist is configured
+const DEFAULT_CHECKLIST: &[&str] = &[
+    "Code quality meets standards",
+    "All tests pass",
+    "Documentation updated",
+    "Contract requirements met",
+];
+
+fn print_checklist(checklist: &ReviewChecklist, checked: &[String]) {
+    for item in &checklist.items {
+        let mark = if checked.contains(&item.id) { "x" } else { " " };
+        let required = if item.mandatory { " (required)" } else { "" };
+        println!("  [{}] {}: {}{}", mark, item.id, item.description, required);
+    }
+}
+
+/// Load the active review record, if a review was started
+pub async fn load_review() -> Result<Option<ReviewRecord>> {
+    let path = workspace::review_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = tokio::fs::read_to_string(&path).await?;
+    let record = serde_json::from_str(&content).context("Failed to parse review.json")?;
+    Ok(Some(record))
+}
+
+async fn save_review(record: &ReviewRecord) -> Result<()> {
+    let content = serde_json::to_string_pretty(record)?;
+    tokio::fs::write(workspace::review_path(), content)
+        .await
+        .context("Failed to write review.json")?;
+    Ok(())
+}
+
+pub async fn run(
+    action: ReviewAction,
+    archive: Option<PathBuf>,
+    output: Option<PathBuf>,
+    items: Vec<String>,
+    skip_checklist: bool,
+    verify_evidence: bool,
+) -> Result<()> {
+    if archive.is_some() && !matches!(action, ReviewAction::Unpack | ReviewAction::VerifyBundle) {
+        anyhow::bail!(
+            "An archive path is only supported with 'review unpack' and 'review verify-bundle'"
+        );
+    }
+    if output.is_some() && !matches!(action, ReviewAction::Archive | ReviewAction::Bundle) {
+        anyhow::bail!("--output is only supported with 'review archive' and 'review bundle'");
+    }
+    if !items.is_empty() && !matches!(action, ReviewAction::Check) {
+        anyhow::bail!("--item is only supported with 'review check'");
+    }
+    if skip_checklist && !matches!(action, ReviewAction::Pass) {
+        anyhow::bail!("--skip-checklist is only supported with 'review pass'");
+    }
+    if verify_evidence && !matches!(action, ReviewAction::Status) {
+        anyhow::bail!("--verify-evidence is only supported with 'review status'");
+    }
+
     match action {
         ReviewAction::Start => start_review().await,
-        ReviewAction::Pass => pass_review().await,
+        ReviewAction::Pass => pass_review(skip_checklist).await,
         ReviewAction::Fail => fail_review().await,
-        ReviewAction::Status => show_status().await,
+        ReviewAction::Status => {
+            show_status().await?;
+            if verify_evidence {
+                verify_all_evidence().await?;
+            }
+            Ok(())
+        }
+        ReviewAction::Check => check_items(&items).await,
+        ReviewAction::Archive => {
+            let output = output
+                .ok_or_else(|| error::validation("'review archive' requires --output <PATH>"))?;
+            archive_evidence(&output, false).await
+        }
+        ReviewAction::Unpack => {
+            let archive = archive
+                .ok_or_else(|| error::validation("'review unpack' requires the archive path"))?;
+            unpack_evidence(&archive).await
+        }
+        ReviewAction::Bundle => {
+            let output = output
+                .ok_or_else(|| error::validation("'review bundle' requires --output <PATH>"))?;
+            archive_evidence(&output, true).await
+        }
+        ReviewAction::VerifyBundle => {
+            let bundle = archive.ok_or_else(|| {
+                error::validation("'review verify-bundle' requires the bundle path")
+            })?;
+            verify_evidence_bundle(&bundle)
+        }
+    }
+}
+
+/// Pack the current session's evidence for reviewers without the workspace
+///
+/// With `checksum`, also write the bundle's `.sha256` sidecar for audits.
+async fn archive_evidence(output: &Path, checksum: bool) -> Result<()> {
+    let state_data = workspace::load_state().await?;
+    let session_id = state_data
+        .current_session_id
+        .ok_or_else(|| error::invalid_state("No session yet; nothing to archive"))?;
+
+    let collector = EvidenceCollector::with_dir(&session_id, workspace::evidence_path(&session_id));
+    let manifest = if checksum {
+        collector.export_bundle(output)?
+    } else {
+        collector.archive(output)?
+    };
+
+    println!("📦 Evidence archived: {}", output.display());
+    println!("   Session: {}", manifest.session_id);
+    println!("   Files:   {}", manifest.file_count);
+    println!("   SHA-256: {}", manifest.sha256);
+    if checksum {
+        println!(
+            "   Checksum file: {} (unsigned; detects accidental corruption only)",
+            archive::bundle_checksum_path(output).display()
+        );
+    }
+    Ok(())
+}
+
+/// Check a bundle against its `.sha256` sidecar (exit 3 on mismatch)
+fn verify_evidence_bundle(bundle: &Path) -> Result<()> {
+    if !archive::verify_bundle(bundle)? {
+        return Err(error::validation(format!(
+            "{} does not match its checksum; it changed after export",
+            bundle.display()
+        )));
     }
+    println!("✓ {} matches its checksum", bundle.display());
+    Ok(())
+}
+
+/// Restore an evidence archive into a fresh session and check it
+async fn unpack_evidence(archive: &Path) -> Result<()> {
+    // Only inside an initialized workspace
+    workspace::load_state().await?;
+    let session_id = generate_session_id();
+    let evidence_dir = workspace::evidence_path(&session_id);
+    tokio::fs::create_dir_all(&evidence_dir).await?;
+
+    let collector = EvidenceCollector::with_dir(&session_id, evidence_dir);
+    let manifest = match collector.unpack(archive) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(workspace::session_path(&session_id)).await;
+            return Err(e);
+        }
+    };
+
+    println!("📥 Evidence restored into session {}", session_id);
+    println!("   From session: {}", manifest.session_id);
+    println!("   Files:        {}", manifest.file_count);
+    println!("   SHA-256:      {}", manifest.sha256);
+
+    let problems = collector.verify_manifest().await?;
+    for problem in &problems {
+        println!("  ✗ {}", problem);
+    }
+    if !problems.is_empty() {
+        return Err(error::validation(format!(
+            "{} evidence file(s) failed integrity checks",
+            problems.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The working-tree diff under review, or an empty diff (with a warning)
+/// when git can't be run
+fn reviewed_diff() -> String {
+    evidence::git_diff_head().unwrap_or_else(|e| {
+        println!(
+            "⚠️  Could not read the git diff ({}); reviewing an empty diff",
+            e
+        );
+        String::new()
+    })
 }
 
 async fn start_review() -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::BuildDone) {
-        anyhow::bail!("Build not done. Run 'vibeanvil build' first.");
+        return Err(error::invalid_state(
+            "Build not done. Run 'vibeanvil build' first.",
+        ));
     }
 
     println!("📋 Starting review...");
     println!();
+
+    // Archive the exact diff being reviewed
+    let session_id = generate_session_id();
+    let collector = EvidenceCollector::new(&session_id).await?;
+    let diff = reviewed_diff();
+    let captured = collector
+        .capture_custom("review", &diff, "Diff under review")
+        .await?;
+    let diff_sha256 = captured.sha256.clone().unwrap_or_default();
+
+    save_review(&ReviewRecord {
+        session_id: session_id.clone(),
+        evidence_file: captured.filename.clone(),
+        diff_sha256: diff_sha256.clone(),
+        started_at: chrono::Utc::now(),
+        approved_sha256: None,
+        approved_at: None,
+        checked: vec![],
+        checklist_skipped: false,
+    })
+    .await?;
+
+    println!(
+        "Captured diff snapshot {} ({} bytes) as evidence in session {}",
+        &diff_sha256[..diff_sha256.len().min(12)],
+        captured.size_bytes,
+        session_id
+    );
+    println!();
     println!("Review checklist:");
-    println!("  [ ] Code quality meets standards");
-    println!("  [ ] All tests pass");
-    println!("  [ ] Documentation updated");
-    println!("  [ ] Contract requirements met");
+    match ReviewChecklist::load()? {
+        Some(checklist) => {
+            print_checklist(&checklist, &[]);
+            println!();
+            println!("Mark items as done:");
+            println!("  vibeanvil review check --item <ID>");
+        }
+        None => {
+            for item in DEFAULT_CHECKLIST {
+                println!("  [ ] {}", item);
+            }
+        }
+    }
     println!();
     println!("When ready:");
     println!("  vibeanvil review pass   - to approve");
@@ -38,11 +334,13 @@ async fn start_review() -> Result<()> {
     Ok(())
 }
 
-async fn pass_review() -> Result<()> {
+async fn pass_review(skip_checklist: bool) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::BuildDone) {
-        anyhow::bail!("Build not done. Complete build first.");
+        return Err(error::invalid_state(
+            "Build not done. Complete build first.",
+        ));
     }
 
     if state_data.current_state == State::ReviewPassed {
@@ -50,17 +348,76 @@ async fn pass_review() -> Result<()> {
         return Ok(());
     }
 
+    let mut review = load_review().await?;
+    let checklist = ReviewChecklist::load()?;
+    if let (Some(checklist), false) = (&checklist, skip_checklist) {
+        let Some(record) = &review else {
+            return Err(error::invalid_state(
+                "A review checklist is configured. Run 'vibeanvil review start' first.",
+            ));
+        };
+        let missing = checklist.unchecked_mandatory(&record.checked);
+        if !missing.is_empty() {
+            println!("✗ Mandatory checklist items not checked:");
+            for item in &missing {
+                println!("  [ ] {}: {}", item.id, item.description);
+            }
+            println!();
+            println!(
+                "Check them with 'vibeanvil review check --item <ID>', or pass --skip-checklist."
+            );
+            return Err(error::validation(format!(
+                "{} mandatory checklist item(s) not checked",
+                missing.len()
+            )));
+        }
+    }
+
     let session_id = generate_session_id();
     let mut state_data = workspace::load_state().await?;
     state_data.transition_to(State::ReviewPassed, "review pass", &session_id)?;
+
+    // Approve the snapshot captured at review start
+    if let Some(record) = review.as_mut() {
+        record.checklist_skipped = skip_checklist && checklist.is_some();
+        let current = evidence::stored_hash(&reviewed_diff());
+        if current != record.diff_sha256 {
+            println!(
+                "⚠️  Working tree changed since 'review start'; approving the snapshot captured then ({})",
+                record.evidence_file
+            );
+        }
+        record.approved_sha256 = Some(record.diff_sha256.clone());
+        record.approved_at = Some(chrono::Utc::now());
+    } else {
+        println!("⚠️  No review snapshot found. Run 'vibeanvil review start' to archive the diff.");
+    }
+
     workspace::save_state(&state_data).await?;
+    if let Some(record) = &review {
+        save_review(record).await?;
+    }
 
-    let logger = AuditLogger::new(&session_id);
-    logger
-        .log_state_transition("review pass", State::BuildDone, State::ReviewPassed)
-        .await?;
+    let mut entry = AuditEntry::new("review pass", vec![], &session_id)
+        .with_state_transition(State::BuildDone, State::ReviewPassed);
+    if checklist.is_some() {
+        entry = entry.with_metadata(serde_json::json!({
+            "checked": review.as_ref().map(|r| r.checked.clone()).unwrap_or_default(),
+            "checklist_skipped": skip_checklist,
+        }));
+    }
+    AuditLogger::new(&session_id).log(&entry).await?;
 
+    if skip_checklist && checklist.is_some() {
+        println!("⚠️  Review checklist skipped (--skip-checklist)");
+    }
     println!("✓ Review PASSED");
+    if let Some(record) = &review {
+        println!(
+            "  Approved diff snapshot {}",
+            &record.diff_sha256[..record.diff_sha256.len().min(12)]
+        );
+    }
     println!();
     println!("Next steps:");
     println!("  vibeanvil snapshot --message \"Description\"");
@@ -73,7 +430,9 @@ async fn fail_review() -> Result<()> {
     let state_data = workspace::load_state().await?;
 
     if !state_data.current_state.is_at_least(State::BuildDone) {
-        anyhow::bail!("Build not done. Complete build first.");
+        return Err(error::invalid_state(
+            "Build not done. Complete build first.",
+        ));
     }
 
     let session_id = generate_session_id();
@@ -94,6 +453,50 @@ async fn fail_review() -> Result<()> {
     Ok(())
 }
 
+/// Mark checklist items as done on the active review
+async fn check_items(items: &[String]) -> Result<()> {
+    if items.is_empty() {
+        anyhow::bail!("'review check' requires --item <ID>");
+    }
+    let Some(checklist) = ReviewChecklist::load()? else {
+        return Err(error::invalid_state(
+            "No review checklist. Define items in .vibeanvil/review_checklist.toml",
+        ));
+    };
+    let Some(mut record) = load_review().await? else {
+        return Err(error::invalid_state(
+            "No review in progress. Run 'vibeanvil review start' first.",
+        ));
+    };
+
+    for id in items {
+        if !checklist.items.iter().any(|item| &item.id == id) {
+            let known: Vec<&str> = checklist.items.iter().map(|i| i.id.as_str()).collect();
+            return Err(error::validation(format!(
+                "Unknown checklist item '{}'. Known items: {}",
+                id,
+                known.join(", ")
+            )));
+        }
+        if !record.checked.contains(id) {
+            record.checked.push(id.clone());
+        }
+    }
+    save_review(&record).await?;
+
+    println!("Review checklist:");
+    print_checklist(&checklist, &record.checked);
+    let missing = checklist.unchecked_mandatory(&record.checked).len();
+    println!();
+    if missing == 0 {
+        println!("✓ All mandatory items checked. Next: vibeanvil review pass");
+    } else {
+        println!("{} mandatory item(s) left", missing);
+    }
+
+    Ok(())
+}
+
 async fn show_status() -> Result<()> {
     let state_data = workspace::load_state().await?;
 
@@ -108,5 +511,104 @@ async fn show_status() -> Result<()> {
         ),
     }
 
+    if let (Some(checklist), Some(record)) = (ReviewChecklist::load()?, load_review().await?) {
+        println!();
+        println!("Checklist:");
+        print_checklist(&checklist, &record.checked);
+    }
+
     Ok(())
 }
+
+/// Re-hash the evidence of every session and report files that changed or disappeared
+async fn verify_all_evidence() -> Result<()> {
+    let mut sessions = vec![];
+    if let Ok(entries) = std::fs::read_dir(workspace::sessions_path()) {
+        for entry in entries.flatten() {
+            let session_id = entry.file_name().to_string_lossy().into_owned();
+            if workspace::evidence_path(&session_id)
+                .join("manifest.json")
+                .exists()
+            {
+                sessions.push(session_id);
+            }
+        }
+    }
+    sessions.sort();
+
+    println!();
+    println!("Evidence integrity:");
+    let mut failed = 0;
+    for session_id in &sessions {
+        let collector =
+            EvidenceCollector::with_dir(session_id, workspace::evidence_path(session_id));
+        for problem in collector.verify_manifest().await? {
+            println!("  ✗ {}/{}", session_id, problem);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(error::validation(format!(
+            "{} evidence file(s) failed integrity checks",
+            failed
+        )));
+    }
+    println!(
+        "  ✓ All evidence matches its manifest ({} session(s))",
+        sessions.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKLIST: &str = r#"
+[[items]]
+id = "tests"
+description = "All tests pass"
+mandatory = true
+
+[[items]]
+id = "docs"
+description = "Docs updated"
+
+[[items]]
+id = "todos"
+description = "No TODOs left"
+mandatory = true
+"#;
+
+    #[test]
+    fn test_checklist_unchecked_mandatory() {
+        let checklist = ReviewChecklist::parse(CHECKLIST).unwrap();
+        assert_eq!(checklist.items.len(), 3);
+        assert!(!checklist.items[1].mandatory);
+
+        let ids = |items: Vec<&ChecklistItem>| -> Vec<String> {
+            items.iter().map(|i| i.id.clone()).collect()
+        };
+        assert_eq!(
+            ids(checklist.unchecked_mandatory(&[])),
+            vec!["tests", "todos"]
+        );
+        assert_eq!(
+            ids(checklist.unchecked_mandatory(&["docs".into(), "tests".into()])),
+            vec!["todos"]
+        );
+        assert!(checklist
+            .unchecked_mandatory(&["tests".into(), "todos".into()])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_checklist_rejects_duplicate_ids() {
+        let duplicated = format!(
+            "{}\n[[items]]\nid = \"docs\"\ndescription = \"Again\"\n",
+            CHECKLIST
+        );
+        assert!(ReviewChecklist::parse(&duplicated).is_err());
+    }
+}
@@ -161,6 +161,7 @@ async fn share_output_with_ai(result: &RunResult) -> Result<()> {
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
     let prompt = format!(
@@ -222,6 +223,7 @@ pub async fn run_tests(test_cmd: Option<&str>, fix: bool) -> Result<RunResult> {
             working_dir: std::env::current_dir()?,
             session_id: state.current_session_id.clone().unwrap_or_default(),
             contract_hash: state.spec_hash.clone(),
+            cancel: Default::default(),
         };
 
         let fix_prompt = format!(
@@ -274,6 +276,7 @@ pub async fn run_lint(lint_cmd: Option<&str>, fix: bool) -> Result<RunResult> {
             working_dir: std::env::current_dir()?,
             session_id: state.current_session_id.clone().unwrap_or_default(),
             contract_hash: state.spec_hash.clone(),
+            cancel: Default::default(),
         };
 
         let fix_prompt = format!(
@@ -302,7 +305,7 @@ Fix all lint errors. Output the specific file changes needed."#,
 }
 
 /// Detect the appropriate test command for the project
-fn detect_test_command() -> Result<String> {
+pub fn detect_test_command() -> Result<String> {
     let cwd = std::env::current_dir()?;
 
     // Check for Cargo.toml (Rust)
@@ -334,7 +337,7 @@ fn detect_test_command() -> Result<String> {
 }
 
 /// Detect the appropriate lint command for the project
-fn detect_lint_command() -> Result<String> {
+pub fn detect_lint_command() -> Result<String> {
     let cwd = std::env::current_dir()?;
 
     // Check for Cargo.toml (Rust)
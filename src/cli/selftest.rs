@@ -0,0 +1,96 @@
+//! Self-test: run the full workflow end to end in a scratch directory
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::Path;
+use std::process::Command;
+
+use crate::state::{State, StateData};
+
+/// Workflow steps, each run as a separate `vibeanvil` invocation
+const STEPS: &[(&str, &[&str])] = &[
+    ("init", &["init"]),
+    ("intake", &["intake", "--message", "Self-test project"]),
+    ("blueprint", &["blueprint", "--auto"]),
+    ("contract create", &["contract", "create"]),
+    ("contract lock", &["contract", "lock"]),
+    ("plan", &["plan", "--provider", "mock", "--no-cache"]),
+    ("build", &["build", "auto", "--provider", "mock"]),
+    ("review start", &["review", "start"]),
+    ("review pass", &["review", "pass"]),
+    ("ship", &["ship", "--tag", "selftest"]),
+];
+
+pub async fn run(keep: bool) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate vibeanvil executable")?;
+    let temp = tempfile::Builder::new()
+        .prefix("vibeanvil-selftest-")
+        .tempdir()
+        .context("Failed to create self-test directory")?;
+
+    println!();
+    println!("{}", "🧪 VibeAnvil self-test".white().bold());
+    println!(
+        "   {} {}",
+        "Workspace:".dimmed(),
+        temp.path().display().to_string().dimmed()
+    );
+    println!();
+
+    let total = STEPS.len();
+    for (i, (name, args)) in STEPS.iter().enumerate() {
+        let output = Command::new(&exe)
+            .args(*args)
+            .current_dir(temp.path())
+            .output()
+            .with_context(|| format!("Failed to run step '{}'", name))?;
+
+        if output.status.success() {
+            println!("  {} [{}/{}] {}", "✓".green(), i + 1, total, name);
+            continue;
+        }
+
+        println!("  {} [{}/{}] {}", "✗".red(), i + 1, total, name.red());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = stderr.lines().collect();
+        for line in &lines[lines.len().saturating_sub(5)..] {
+            println!("      {}", line.dimmed());
+        }
+        if keep {
+            println!();
+            println!("  Workspace kept at {}", temp.keep().display());
+        }
+        anyhow::bail!(
+            "Self-test failed at step '{}' (vibeanvil {})",
+            name,
+            args.join(" ")
+        );
+    }
+
+    let state = final_state(temp.path())?;
+    if state != State::Shipped {
+        anyhow::bail!("Self-test finished in state {} instead of SHIPPED", state);
+    }
+
+    println!();
+    println!(
+        "{} Workflow reached {} — installation looks healthy",
+        "✓".green().bold(),
+        State::Shipped.to_string().green().bold()
+    );
+    if keep {
+        println!("  Workspace kept at {}", temp.keep().display());
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Read the workflow state left in the self-test workspace
+fn final_state(dir: &Path) -> Result<State> {
+    let path = dir.join(".vibeanvil").join("state.json");
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let state: StateData = serde_json::from_str(&content).context("Failed to parse state.json")?;
+    Ok(state.current_state)
+}
@@ -0,0 +1,403 @@
+//! Local HTTP API
+//!
+//! A minimal JSON-over-HTTP/1.1 server so editors and dashboards can read
+//! workspace status without shelling out. One request per connection; every
+//! route reuses the same handler as its CLI counterpart.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::net::IpAddr;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::audit;
+use crate::error;
+
+/// Largest request line plus headers accepted
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Entries returned by `GET /log` without `?n=`
+const DEFAULT_LOG_ENTRIES: usize = 20;
+
+/// A parsed HTTP request
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    /// `Host` header, if sent
+    host: Option<String>,
+    /// `Content-Type` header, if sent
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// Whether the body is declared as JSON (`application/json`, with or
+    /// without parameters such as `charset`)
+    fn is_json(&self) -> bool {
+        self.content_type.as_deref().is_some_and(|value| {
+            value
+                .split(';')
+                .next()
+                .is_some_and(|media| media.trim().eq_ignore_ascii_case("application/json"))
+        })
+    }
+
+    /// Whether `Host` names the loopback interface, so a page on another
+    /// origin (or a DNS-rebound name) can't reach a write route
+    fn has_loopback_host(&self) -> bool {
+        let Some(host) = self.host.as_deref() else {
+            return false;
+        };
+        // Strip the port: `[::1]:8080`, `127.0.0.1:8080`, `localhost:8080`
+        let name = match host.strip_prefix('[') {
+            Some(rest) => rest.split(']').next().unwrap_or_default(),
+            None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+        };
+        name.eq_ignore_ascii_case("localhost")
+            || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
+/// A JSON response
+struct Response {
+    status: u16,
+    body: serde_json::Value,
+}
+
+impl Response {
+    fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+
+    /// Map a handler failure onto the HTTP status matching its exit code
+    fn from_error(err: &anyhow::Error) -> Self {
+        let status = match error::exit_code(err) {
+            error::EXIT_INVALID_STATE => 409,
+            error::EXIT_VALIDATION => 400,
+            _ => 500,
+        };
+        Self::error(status, format!("{:#}", err))
+    }
+}
+
+/// `POST /intake` body
+#[derive(Deserialize)]
+struct IntakeBody {
+    message: String,
+}
+
+/// Serve the API until interrupted
+pub async fn run(port: u16, host: String) -> Result<()> {
+    let ip: IpAddr = host.parse().map_err(|_| {
+        error::validation(format!("Invalid --host '{}': expected an IP address", host))
+    })?;
+    if !ip.is_loopback() {
+        eprintln!(
+            "⚠️  Binding to {} exposes the workspace API to the network (no authentication)",
+            ip
+        );
+    }
+
+    let listener = TcpListener::bind((ip, port))
+        .await
+        .with_context(|| format!("Failed to bind {}:{}", ip, port))?;
+    let addr = listener.local_addr()?;
+
+    println!("Listening on http://{}", addr);
+    println!("  GET  /status  GET /contract  GET /log?n=N  POST /intake");
+    println!("Press Ctrl+C to stop");
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream).await {
+                        tracing::debug!("serve: connection error: {:#}", e);
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => {
+                println!("Shutting down");
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader).await {
+        Ok(request) => route(request).await,
+        Err(e) => Response::error(400, e),
+    };
+    write_response(reader.get_mut(), &response).await
+}
+
+/// Read the request line, headers and a `Content-Length` body
+async fn read_request<R>(reader: &mut R) -> Result<Request>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    let mut head = Vec::new();
+    let mut head_bytes = 0usize;
+    let mut line = String::new();
+    let mut content_length = 0usize;
+    let mut host = None;
+    let mut content_type = None;
+
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line).await?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before the request was complete");
+        }
+        head_bytes += n;
+        if head_bytes > MAX_HEAD_BYTES {
+            anyhow::bail!("Request headers are too large");
+        }
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        if !head.is_empty() {
+            if let Some((name, value)) = trimmed.split_once(':') {
+                let name = name.trim();
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid Content-Length"))?;
+                } else if name.eq_ignore_ascii_case("host") {
+                    host = Some(value.trim().to_string());
+                } else if name.eq_ignore_ascii_case("content-type") {
+                    content_type = Some(value.trim().to_string());
+                }
+            }
+        }
+        head.push(trimmed.to_string());
+    }
+
+    let request_line = head.first().context("Empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target),
+        _ => anyhow::bail!("Malformed request line"),
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+
+    if content_length > MAX_BODY_BYTES {
+        anyhow::bail!("Request body is too large");
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        host,
+        content_type,
+        body,
+    })
+}
+
+async fn route(request: Request) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => super::status::status_report()
+            .await
+            .and_then(|r| Ok(serde_json::to_value(r)?)),
+        ("GET", "/contract") => super::contract::status_report(None)
+            .await
+            .and_then(|r| Ok(serde_json::to_value(r)?)),
+        ("GET", "/log") => {
+            let n = match log_limit(request.query.as_deref()) {
+                Ok(n) => n,
+                Err(e) => return Response::error(400, e),
+            };
+            audit::read_audit_log(Some(n))
+                .await
+                .and_then(|entries| Ok(serde_json::to_value(entries)?))
+        }
+        ("POST", "/intake") => {
+            if !request.has_loopback_host() {
+                return Response::error(403, "POST /intake only accepts a localhost Host header");
+            }
+            if !request.is_json() {
+                return Response::error(415, "POST /intake expects Content-Type: application/json");
+            }
+            let body: IntakeBody = match serde_json::from_slice(&request.body) {
+                Ok(body) => body,
+                Err(e) => return Response::error(400, format!("Invalid JSON body: {}", e)),
+            };
+            match super::intake::save_intake(body.message.trim()).await {
+                Ok(()) => super::status::status_report()
+                    .await
+                    .and_then(|r| Ok(serde_json::to_value(r)?)),
+                Err(e) => Err(e),
+            }
+        }
+        (_, "/status" | "/contract" | "/log" | "/intake") => {
+            return Response::error(405, format!("Method {} not allowed", request.method));
+        }
+        (_, path) => return Response::error(404, format!("No route for {}", path)),
+    };
+
+    match result {
+        Ok(body) => Response::ok(body),
+        Err(e) => Response::from_error(&e),
+    }
+}
+
+/// `n` from a `GET /log` query string
+fn log_limit(query: Option<&str>) -> Result<usize> {
+    let Some(query) = query else {
+        return Ok(DEFAULT_LOG_ENTRIES);
+    };
+    for pair in query.split('&') {
+        if let Some(("n", value)) = pair.split_once('=') {
+            return value
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Invalid n '{}': expected a positive integer", value)
+                });
+        }
+    }
+    Ok(DEFAULT_LOG_ENTRIES)
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        415 => "Unsupported Media Type",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: &Response) -> Result<()> {
+    let body = serde_json::to_vec_pretty(&response.body)?;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn parse(raw: &str) -> Result<Request> {
+        let mut reader = BufReader::new(raw.as_bytes());
+        read_request(&mut reader).await
+    }
+
+    #[tokio::test]
+    async fn test_read_request_parses_query_and_body() {
+        let request = parse(
+            "POST /intake?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 15\r\n\r\n{\"message\":\"a\"}",
+        )
+        .await
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/intake");
+        assert_eq!(request.query.as_deref(), Some("x=1"));
+        assert_eq!(request.body, b"{\"message\":\"a\"}");
+        assert_eq!(request.host.as_deref(), Some("localhost"));
+        assert_eq!(request.content_type, None);
+
+        assert!(parse("GET /status HTTP/1.1\r\n").await.is_err());
+        assert!(parse("\r\n\r\n").await.is_err());
+    }
+
+    #[test]
+    fn test_log_limit() {
+        assert_eq!(log_limit(None).unwrap(), DEFAULT_LOG_ENTRIES);
+        assert_eq!(log_limit(Some("n=5")).unwrap(), 5);
+        assert_eq!(log_limit(Some("x=1&n=3")).unwrap(), 3);
+        assert!(log_limit(Some("n=0")).is_err());
+        assert!(log_limit(Some("n=abc")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_route_and_method() {
+        let request = |method: &str, path: &str| Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: None,
+            host: Some("localhost".to_string()),
+            content_type: Some("application/json".to_string()),
+            body: vec![],
+        };
+        assert_eq!(route(request("GET", "/nope")).await.status, 404);
+        assert_eq!(route(request("DELETE", "/status")).await.status, 405);
+        assert_eq!(route(request("POST", "/intake")).await.status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_intake_requires_json_from_a_loopback_host() {
+        let intake = |host: Option<&str>, content_type: Option<&str>| Request {
+            method: "POST".to_string(),
+            path: "/intake".to_string(),
+            query: None,
+            host: host.map(String::from),
+            content_type: content_type.map(String::from),
+            body: b"{\"message\":\"x\"}".to_vec(),
+        };
+        let json = Some("application/json");
+        for host in ["evil.example", "evil.example:8080", "10.0.0.5:7878"] {
+            assert_eq!(
+                route(intake(Some(host), json)).await.status,
+                403,
+                "{}",
+                host
+            );
+        }
+        assert_eq!(route(intake(None, json)).await.status, 403);
+        for content_type in [
+            None,
+            Some("text/plain"),
+            Some("application/x-www-form-urlencoded"),
+        ] {
+            let request = intake(Some("localhost:7878"), content_type);
+            assert_eq!(route(request).await.status, 415, "{:?}", content_type);
+        }
+
+        for host in [
+            "localhost",
+            "LOCALHOST:7878",
+            "127.0.0.1:7878",
+            "[::1]:7878",
+        ] {
+            let request = intake(Some(host), Some("application/json; charset=utf-8"));
+            assert!(request.has_loopback_host(), "{}", host);
+            assert!(request.is_json());
+        }
+    }
+}
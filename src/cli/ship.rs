@@ -1,17 +1,183 @@
 //! Ship command handler
 
 use anyhow::Result;
+use colored::Colorize;
+use std::process::Command;
 use tokio::fs;
 
 use crate::audit::{generate_session_id, AuditLogger};
-use crate::state::State;
+use crate::cli::review;
+use crate::contract;
+use crate::error;
+use crate::evidence::{EvidenceCollector, HashStatus};
+use crate::state::{State, StateData};
 use crate::workspace;
 
-pub async fn run(tag: Option<String>, message: Option<String>) -> Result<()> {
+/// Result of a single pre-flight check
+struct Check {
+    name: &'static str,
+    passed: bool,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed,
+            detail: detail.into(),
+        }
+    }
+}
+
+fn check_state(state_data: &StateData) -> Check {
+    let state = state_data.current_state;
+    let passed = state == State::ReviewPassed;
+    let detail = if passed {
+        format!("state is {}", state)
+    } else {
+        format!("state is {}, expected {}", state, State::ReviewPassed)
+    };
+    Check::new("Workflow state", passed, detail)
+}
+
+async fn check_contract_hash() -> Check {
+    let result = async {
+        let contract = contract::load_contract().await?;
+        let lock = contract::load_lock().await?;
+        let hash = contract.generate_hash(&lock.tool_version)?;
+        anyhow::Ok((hash == lock.hash, lock.hash))
+    }
+    .await;
+
+    match result {
+        Ok((true, hash)) => Check::new(
+            "Contract hash",
+            true,
+            format!("matches lock {}", &hash[..hash.len().min(12)]),
+        ),
+        Ok((false, _)) => Check::new("Contract hash", false, "contract changed since lock"),
+        Err(e) => Check::new("Contract hash", false, e.to_string()),
+    }
+}
+
+async fn check_evidence() -> Check {
+    let record = match review::load_review().await {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            return Check::new(
+                "Review evidence",
+                false,
+                "no reviewed diff archived (run 'vibeanvil review start')",
+            )
+        }
+        Err(e) => return Check::new("Review evidence", false, e.to_string()),
+    };
+
+    let collector = EvidenceCollector::with_dir(
+        &record.session_id,
+        workspace::evidence_path(&record.session_id),
+    );
+    let manifest = collector.load_manifest().await.unwrap_or_default();
+    let Some(item) = manifest.iter().find(|e| e.filename == record.evidence_file) else {
+        return Check::new(
+            "Review evidence",
+            false,
+            format!("{} missing from manifest", record.evidence_file),
+        );
+    };
+
+    match collector.verify_item(item).await {
+        HashStatus::Verified | HashStatus::Unhashed => {
+            Check::new("Review evidence", true, record.evidence_file.clone())
+        }
+        HashStatus::Mismatch => Check::new(
+            "Review evidence",
+            false,
+            format!("{} failed hash verification", record.evidence_file),
+        ),
+        HashStatus::Missing => Check::new(
+            "Review evidence",
+            false,
+            format!("{} is missing", record.evidence_file),
+        ),
+    }
+}
+
+fn check_clean_tree() -> Check {
+    // Workspace metadata is expected to change during the workflow
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "--", ".", ":(exclude).vibeanvil"])
+        .output();
+
+    match output {
+        Ok(out) if out.status.success() => {
+            let changed = String::from_utf8_lossy(&out.stdout).lines().count();
+            if changed == 0 {
+                Check::new("Working tree", true, "clean")
+            } else {
+                Check::new(
+                    "Working tree",
+                    false,
+                    format!("{} uncommitted change(s)", changed),
+                )
+            }
+        }
+        _ => Check::new("Working tree", false, "not a git repository"),
+    }
+}
+
+/// Run every ship pre-flight check
+async fn preflight(state_data: &StateData) -> Vec<Check> {
+    vec![
+        check_state(state_data),
+        check_contract_hash().await,
+        check_evidence().await,
+        check_clean_tree(),
+    ]
+}
+
+fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let mark = if check.passed {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        println!("  {} {:16} {}", mark, check.name, check.detail.dimmed());
+    }
+}
+
+async fn dry_run(state_data: &StateData) -> Result<()> {
+    println!("🧪 Ship pre-flight (dry run)");
+    println!();
+    let checks = preflight(state_data).await;
+    print_checks(&checks);
+    println!();
+
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    if failed > 0 {
+        return Err(error::validation(format!(
+            "{} ship pre-flight check(s) failed",
+            failed
+        )));
+    }
+
+    println!("✓ All checks passed. Nothing was shipped.");
+    Ok(())
+}
+
+pub async fn run(tag: Option<String>, message: Option<String>, dry: bool) -> Result<()> {
     let state_data = workspace::load_state().await?;
 
+    if dry {
+        return dry_run(&state_data).await;
+    }
+
     if !state_data.current_state.is_at_least(State::ReviewPassed) {
-        anyhow::bail!("Review not passed. Run 'vibeanvil review pass' first.");
+        return Err(error::invalid_state(
+            "Review not passed. Run 'vibeanvil review pass' first.",
+        ));
     }
 
     if state_data.current_state == State::Shipped {
@@ -19,6 +185,18 @@ pub async fn run(tag: Option<String>, message: Option<String>) -> Result<()> {
         return Ok(());
     }
 
+    // Surface pre-flight problems without blocking the ship
+    let warnings: Vec<Check> = preflight(&state_data)
+        .await
+        .into_iter()
+        .filter(|c| !c.passed)
+        .collect();
+    if !warnings.is_empty() {
+        println!("⚠️  Pre-flight warnings:");
+        print_checks(&warnings);
+        println!();
+    }
+
     let session_id = generate_session_id();
 
     // Update state to shipped
@@ -42,9 +42,9 @@ pub async fn run(message: Option<String>) -> Result<()> {
     }
 
     // Copy contract if exists
-    let contract_path = workspace::contracts_path().join("contract.json");
-    if contract_path.exists() {
-        fs::copy(&contract_path, snapshot_dir.join("contract.json")).await?;
+    if let Some((contract_path, format)) = crate::contract::find_contract(None) {
+        let file_name = format!("contract.{}", format.extension());
+        fs::copy(&contract_path, snapshot_dir.join(file_name)).await?;
     }
 
     logger
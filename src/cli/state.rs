@@ -0,0 +1,263 @@
+//! State command handler
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::{StateArgs, StateCommands};
+use crate::error;
+use crate::state::{State, StateHistoryEntry};
+use crate::workspace;
+
+pub async fn run(args: StateArgs) -> Result<()> {
+    match args.command {
+        StateCommands::Diff { from, to, json } => diff(&from, &to, json).await,
+    }
+}
+
+/// One end of a history window
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Bound {
+    /// State ordinal (see `State::ordinal`)
+    Ordinal(u8),
+    /// Session ID recorded on history entries
+    Session(String),
+}
+
+impl Bound {
+    fn parse(s: &str) -> Bound {
+        if let Ok(ordinal) = s.parse::<u8>() {
+            return Bound::Ordinal(ordinal);
+        }
+        match s.parse::<State>() {
+            Ok(state) => Bound::Ordinal(state.ordinal()),
+            Err(_) => Bound::Session(s.to_string()),
+        }
+    }
+}
+
+/// Select the history entries between `from` and `to`, inclusive
+///
+/// With ordinals, the window runs from the first to the last entry whose
+/// states both fall in the range; with session IDs, from the first entry of
+/// `from` to the last entry of `to`.
+fn select<'a>(
+    history: &'a [StateHistoryEntry],
+    from: &Bound,
+    to: &Bound,
+) -> Result<&'a [StateHistoryEntry]> {
+    match (from, to) {
+        (Bound::Ordinal(from), Bound::Ordinal(to)) => {
+            if from > to {
+                return Err(error::validation(format!(
+                    "Reversed range: ordinal {} comes after {}; try 'state diff {} {}'",
+                    from, to, to, from
+                )));
+            }
+            let inside = |e: &StateHistoryEntry| {
+                (*from..=*to).contains(&e.from_state.ordinal())
+                    && (*from..=*to).contains(&e.to_state.ordinal())
+            };
+            let Some(start) = history.iter().position(inside) else {
+                return Ok(&[]);
+            };
+            let end = history.iter().rposition(inside).unwrap_or(start);
+            Ok(&history[start..=end])
+        }
+        (Bound::Session(from), Bound::Session(to)) => {
+            let unknown = |id: &str| error::validation(format!("Unknown session '{}'", id));
+            let start = history
+                .iter()
+                .position(|e| &e.session_id == from)
+                .ok_or_else(|| unknown(from))?;
+            let end = history
+                .iter()
+                .rposition(|e| &e.session_id == to)
+                .ok_or_else(|| unknown(to))?;
+            if start > end {
+                return Err(error::validation(format!(
+                    "Reversed range: session {} comes after {}; try 'state diff {} {}'",
+                    from, to, to, from
+                )));
+            }
+            Ok(&history[start..=end])
+        }
+        _ => Err(error::validation(
+            "Use two states (ordinals or names) or two session IDs, not a mix",
+        )),
+    }
+}
+
+/// Compact duration, e.g. `45s`, `3m 05s`, `2h 10m`
+pub(crate) fn format_elapsed(d: chrono::Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    match secs {
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Sessions in order of first appearance
+fn sessions(entries: &[StateHistoryEntry]) -> Vec<String> {
+    let mut ids: Vec<String> = vec![];
+    for entry in entries {
+        if !ids.contains(&entry.session_id) {
+            ids.push(entry.session_id.clone());
+        }
+    }
+    ids
+}
+
+#[derive(Debug, Serialize)]
+struct DiffEntry<'a> {
+    #[serde(flatten)]
+    entry: &'a StateHistoryEntry,
+    /// Seconds since the previous entry in the window
+    elapsed_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffReport<'a> {
+    from: &'a str,
+    to: &'a str,
+    entries: Vec<DiffEntry<'a>>,
+    sessions: Vec<String>,
+    /// Seconds from the first to the last entry
+    elapsed_secs: i64,
+}
+
+async fn diff(from: &str, to: &str, json: bool) -> Result<()> {
+    let state_data = workspace::load_state().await?;
+    let window = select(&state_data.history, &Bound::parse(from), &Bound::parse(to))?;
+    let total = match (window.first(), window.last()) {
+        (Some(first), Some(last)) => last.elapsed_since(first),
+        _ => chrono::Duration::zero(),
+    };
+
+    if json {
+        let report = DiffReport {
+            from,
+            to,
+            entries: window
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| DiffEntry {
+                    entry,
+                    elapsed_secs: i
+                        .checked_sub(1)
+                        .map(|prev| entry.elapsed_since(&window[prev]).num_seconds()),
+                })
+                .collect(),
+            sessions: sessions(window),
+            elapsed_secs: total.num_seconds(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if window.is_empty() {
+        println!("No transitions between {} and {}.", from, to);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("State history: {} → {}", from, to).cyan().bold()
+    );
+    println!();
+    for (i, entry) in window.iter().enumerate() {
+        let elapsed = match i.checked_sub(1) {
+            Some(prev) => format!("+{}", format_elapsed(entry.elapsed_since(&window[prev]))),
+            None => "start".to_string(),
+        };
+        println!(
+            "  {}  {:>9}  {} → {}  {}",
+            entry
+                .timestamp
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            elapsed.yellow(),
+            entry.from_state,
+            entry.to_state.to_string().green(),
+            entry.action.bold()
+        );
+        println!("      session {}", entry.session_id.dimmed());
+        if let Some(reason) = &entry.reason {
+            println!("      reason: {}", reason);
+        }
+    }
+
+    let sessions = sessions(window);
+    println!();
+    println!(
+        "{} transition(s) over {} across {} session(s)",
+        window.len(),
+        format_elapsed(total),
+        sessions.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(from: State, to: State, session: &str, minutes: i64) -> StateHistoryEntry {
+        StateHistoryEntry {
+            from_state: from,
+            to_state: to,
+            timestamp: chrono::DateTime::UNIX_EPOCH + chrono::Duration::minutes(minutes),
+            action: format!("{} → {}", from, to),
+            session_id: session.to_string(),
+            reason: None,
+        }
+    }
+
+    fn history() -> Vec<StateHistoryEntry> {
+        vec![
+            entry(State::ContractLocked, State::PlanCreated, "s1", 0),
+            entry(State::PlanCreated, State::BuildInProgress, "s2", 5),
+            entry(State::BuildInProgress, State::BuildDone, "s2", 65),
+            entry(State::BuildDone, State::ReviewFailed, "s3", 70),
+            entry(State::ReviewFailed, State::BuildInProgress, "s4", 71),
+        ]
+    }
+
+    #[test]
+    fn test_select_by_ordinal_and_name() {
+        let history = history();
+        let window = select(&history, &Bound::parse("5"), &Bound::parse("build-done")).unwrap();
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[0].to_state, State::BuildInProgress);
+        assert_eq!(
+            format_elapsed(window[1].elapsed_since(&window[0])),
+            "1h 00m"
+        );
+        assert_eq!(sessions(window), vec!["s2"]);
+    }
+
+    #[test]
+    fn test_select_by_session() {
+        let history = history();
+        let window = select(&history, &Bound::parse("s2"), &Bound::parse("s3")).unwrap();
+        assert_eq!(window.len(), 3);
+        assert!(select(&history, &Bound::parse("s9"), &Bound::parse("s3")).is_err());
+    }
+
+    #[test]
+    fn test_select_empty_and_reversed_ranges() {
+        let history = history();
+        let empty = select(&history, &Bound::parse("0"), &Bound::parse("2")).unwrap();
+        assert!(empty.is_empty());
+        assert!(select(&[], &Bound::parse("0"), &Bound::parse("9"))
+            .unwrap()
+            .is_empty());
+
+        assert!(select(&history, &Bound::parse("7"), &Bound::parse("5")).is_err());
+        assert!(select(&history, &Bound::parse("s3"), &Bound::parse("s2")).is_err());
+        assert!(select(&history, &Bound::parse("5"), &Bound::parse("s2")).is_err());
+    }
+}
@@ -2,31 +2,118 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::state::State;
+use crate::cli::OutputFormat;
+use crate::contract::{self, Contract, ContractStatus, Priority};
+use crate::state::{State, StateHistoryEntry};
 use crate::workspace;
 
-#[derive(Serialize)]
-struct StatusJson {
+/// Machine-readable workspace status (`status --json`, `serve`'s `GET /status`)
+#[derive(Serialize, Deserialize)]
+pub struct StatusReport {
     current_state: String,
+    /// Workflow completion, 0–100
+    progress_pct: u8,
     tool_version: String,
     spec_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    contracts: Vec<ContractSummary>,
 }
 
-pub async fn run(verbose: bool, json: bool) -> Result<()> {
+/// Current workflow state with named contracts
+pub async fn status_report() -> Result<StatusReport> {
     let state_data = workspace::load_state().await?;
+    Ok(StatusReport {
+        current_state: state_data.current_state.to_string(),
+        progress_pct: state_data.current_state.progress_percentage(),
+        tool_version: state_data.tool_version.clone(),
+        spec_hash: state_data.spec_hash.clone(),
+        contracts: named_contracts().await,
+    })
+}
 
-    if json {
-        let output = StatusJson {
-            current_state: state_data.current_state.to_string(),
-            tool_version: state_data.tool_version.clone(),
-            spec_hash: state_data.spec_hash.clone(),
+/// State of a named contract
+#[derive(Serialize, Deserialize)]
+struct ContractSummary {
+    name: String,
+    status: ContractStatus,
+    spec_hash: Option<String>,
+}
+
+/// Summarize every named contract in the workspace
+async fn named_contracts() -> Vec<ContractSummary> {
+    let mut summaries = vec![];
+    for name in contract::list_named_contracts() {
+        let Ok(spec) = contract::load_named_contract(Some(&name)).await else {
+            continue;
+        };
+        let spec_hash = match spec.status {
+            ContractStatus::Locked => contract::load_named_lock(Some(&name))
+                .await
+                .ok()
+                .map(|lock| lock.hash),
+            ContractStatus::Draft => None,
         };
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        summaries.push(ContractSummary {
+            name,
+            status: spec.status,
+            spec_hash,
+        });
+    }
+    summaries
+}
+
+/// One page of the transition history
+#[derive(Serialize)]
+struct HistoryPage<'a> {
+    /// Transitions recorded in total
+    total: usize,
+    offset: usize,
+    limit: usize,
+    /// Transitions on this page, newest first
+    entries: Vec<&'a StateHistoryEntry>,
+}
+
+/// Shields.io endpoint badge object
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BadgeJson {
+    schema_version: u8,
+    label: String,
+    message: String,
+    color: String,
+}
+
+/// Highest state ordinal (SHIPPED)
+const MAX_ORDINAL: u8 = 9;
+
+/// Characters inside the brackets of the status progress bar
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+pub async fn run(verbose: bool, format: Option<OutputFormat>, badge: bool) -> Result<()> {
+    let state_data = workspace::load_state().await?;
+
+    if badge {
+        if let Some(format) = format {
+            let output = badge_json(&state_data.current_state, state_data.spec_hash.as_deref());
+            println!("{}", format.render(&output)?);
+        } else {
+            println!(
+                "{}",
+                badge_text(&state_data.current_state, state_data.spec_hash.as_deref())
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(format) = format {
+        println!("{}", format.render(&status_report().await?)?);
         return Ok(());
     }
 
+    let contracts = named_contracts().await;
+
     // Print beautiful header
     println!();
     println!(
@@ -53,6 +140,13 @@ pub async fn run(verbose: bool, json: bool) -> Result<()> {
         state_icon,
         state_str.green().bold()
     );
+    let pct = state_data.current_state.progress_percentage();
+    println!(
+        "  {} {} {}%",
+        "Progress:     ".white().bold(),
+        progress_bar(pct, PROGRESS_BAR_WIDTH).green(),
+        pct
+    );
     println!(
         "  {} v{}",
         "Tool Version: ".white().bold(),
@@ -67,6 +161,33 @@ pub async fn run(verbose: bool, json: bool) -> Result<()> {
         );
     }
 
+    if let Ok(contract) = contract::load_contract().await {
+        if !contract.requirements.is_empty() {
+            println!(
+                "  {} {}",
+                "Requirements: ".white().bold(),
+                priority_breakdown(&contract)
+            );
+        }
+    }
+
+    if !contracts.is_empty() {
+        println!();
+        println!("  {}", "Named Contracts:".white().bold());
+        for summary in &contracts {
+            let status = match summary.status {
+                ContractStatus::Locked => "🔒 LOCKED".green(),
+                ContractStatus::Draft => "📝 DRAFT".yellow(),
+            };
+            let hash = summary
+                .spec_hash
+                .as_deref()
+                .map(|h| format!(" {}", short_hash(h)))
+                .unwrap_or_default();
+            println!("    • {:16} {}{}", summary.name, status, hash.dimmed());
+        }
+    }
+
     println!();
     print_workflow_progress(&state_data.current_state);
 
@@ -99,6 +220,25 @@ pub async fn run(verbose: bool, json: bool) -> Result<()> {
             "{}",
             "└─────────────────────────────────────────────────────────┘".dimmed()
         );
+
+        println!();
+        println!("{}", "⏱  Time in state".dimmed());
+        let times = state_data.time_in_state();
+        for state in State::all() {
+            if let Some(time) = times.get(state) {
+                let marker = if *state == state_data.current_state {
+                    " (current)"
+                } else {
+                    ""
+                };
+                println!(
+                    "   {:<18} {:>9}{}",
+                    state.to_string(),
+                    crate::cli::state::format_elapsed(*time).yellow(),
+                    marker.dimmed()
+                );
+            }
+        }
     }
 
     // Footer with tips
@@ -110,6 +250,121 @@ pub async fn run(verbose: bool, json: bool) -> Result<()> {
     Ok(())
 }
 
+/// `status --history`: a page of state transitions, newest first
+pub async fn history(limit: usize, offset: usize, format: Option<OutputFormat>) -> Result<()> {
+    let state_data = workspace::load_state().await?;
+    let page = HistoryPage {
+        total: state_data.history.len(),
+        offset,
+        limit,
+        entries: state_data.history_page(offset, limit),
+    };
+
+    if let Some(format) = format {
+        println!("{}", format.render(&page)?);
+        return Ok(());
+    }
+
+    if page.entries.is_empty() {
+        println!(
+            "No transitions at offset {} ({} recorded)",
+            offset, page.total
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!(
+            "📜 Transitions {}–{} of {} (newest first)",
+            offset + 1,
+            offset + page.entries.len(),
+            page.total
+        )
+        .dimmed()
+    );
+    for entry in &page.entries {
+        println!(
+            "  {}  {} {} {} {} {}",
+            entry
+                .timestamp
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .dimmed(),
+            entry.from_state.to_string().yellow(),
+            "→".dimmed(),
+            entry.to_state.to_string().green(),
+            format!("({})", entry.action).dimmed(),
+            short_hash(&entry.session_id).dimmed()
+        );
+    }
+    if offset + page.entries.len() < page.total {
+        println!(
+            "{}",
+            format!(
+                "  More: vibeanvil status --history --limit {} --offset {}",
+                limit,
+                offset + limit
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Requirement counts per priority, e.g. `2 must · 1 should · 0 could · 1 won't`
+pub fn priority_breakdown(contract: &Contract) -> String {
+    let groups = contract.requirements_by_priority();
+    Priority::all()
+        .iter()
+        .map(|p| format!("{} {}", groups.get(p).map_or(0, Vec::len), p.label()))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Short form of the spec hash shown in badges
+fn short_hash(hash: &str) -> &str {
+    &hash[..6.min(hash.len())]
+}
+
+/// Badge message without the label, e.g. `CONTRACT_LOCKED (4/9) • spec a1b2c3`
+fn badge_message(state: &State, spec_hash: Option<&str>) -> String {
+    let mut message = format!("{} ({}/{})", state, state.ordinal(), MAX_ORDINAL);
+    if let Some(hash) = spec_hash {
+        message.push_str(&format!(" • spec {}", short_hash(hash)));
+    }
+    message
+}
+
+/// Compact one-line badge text
+fn badge_text(state: &State, spec_hash: Option<&str>) -> String {
+    format!("VibeAnvil: {}", badge_message(state, spec_hash))
+}
+
+/// Shields.io-compatible endpoint object
+fn badge_json(state: &State, spec_hash: Option<&str>) -> BadgeJson {
+    let color = match state {
+        State::Shipped => "brightgreen",
+        State::ReviewFailed => "red",
+        s if s.is_at_least(State::ContractLocked) => "blue",
+        _ => "lightgrey",
+    };
+
+    BadgeJson {
+        schema_version: 1,
+        label: "VibeAnvil".to_string(),
+        message: badge_message(state, spec_hash),
+        color: color.to_string(),
+    }
+}
+
+/// ASCII progress bar, e.g. `[#########-----------]` for 44%
+fn progress_bar(pct: u8, width: usize) -> String {
+    let filled = (usize::from(pct.min(100)) * width + 50) / 100;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
 fn get_state_icon(state: &State) -> &'static str {
     match state {
         State::Init => "📁",
@@ -172,3 +427,82 @@ fn print_workflow_progress(current: &State) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_breakdown_lists_every_priority() {
+        let mut contract = Contract::new("Test");
+        contract.add_requirement("REQ-1", "Core", Priority::Must);
+        contract.add_requirement("REQ-2", "Core too", Priority::Must);
+        contract.add_requirement("REQ-3", "Later", Priority::Wont);
+        assert_eq!(
+            priority_breakdown(&contract),
+            "2 must · 0 should · 0 could · 1 won't"
+        );
+    }
+
+    #[test]
+    fn test_badge_text_includes_fraction_and_short_hash() {
+        let text = badge_text(&State::ContractLocked, Some("a1b2c3d4e5f6"));
+        assert_eq!(text, "VibeAnvil: CONTRACT_LOCKED (4/9) • spec a1b2c3");
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(0, 10), "[----------]");
+        assert_eq!(progress_bar(44, 20), "[#########-----------]");
+        assert_eq!(progress_bar(100, 10), "[##########]");
+    }
+
+    #[test]
+    fn test_badge_text_without_spec_hash() {
+        let text = badge_text(&State::Init, None);
+        assert_eq!(text, "VibeAnvil: INIT (0/9)");
+    }
+
+    #[test]
+    fn test_status_report_round_trips_through_yaml() {
+        let report = StatusReport {
+            current_state: State::ContractLocked.to_string(),
+            progress_pct: 44,
+            tool_version: "0.1.0".to_string(),
+            spec_hash: Some("a1b2c3d4e5f6".to_string()),
+            contracts: vec![ContractSummary {
+                name: "api".to_string(),
+                status: ContractStatus::Draft,
+                spec_hash: None,
+            }],
+        };
+        let yaml = OutputFormat::Yaml.render(&report).unwrap();
+        for field in [
+            "current_state: CONTRACT_LOCKED",
+            "progress_pct: 44",
+            "tool_version: 0.1.0",
+            "spec_hash: a1b2c3d4e5f6",
+            "contracts:",
+            "- name: api",
+            "status: DRAFT",
+        ] {
+            assert!(yaml.contains(field), "missing {:?} in:\n{}", field, yaml);
+        }
+
+        let parsed: StatusReport = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(
+            OutputFormat::Json.render(&parsed).unwrap(),
+            OutputFormat::Json.render(&report).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_badge_json_is_shields_endpoint() {
+        let badge = badge_json(&State::Shipped, Some("ffffffffff"));
+        let value = serde_json::to_value(&badge).unwrap();
+        assert_eq!(value["schemaVersion"], 1);
+        assert_eq!(value["label"], "VibeAnvil");
+        assert_eq!(value["message"], "SHIPPED (9/9) • spec ffffff");
+        assert_eq!(value["color"], "brightgreen");
+    }
+}
@@ -6,6 +6,7 @@ use anyhow::Result;
 use colored::*;
 use serde::{Deserialize, Serialize};
 
+use crate::error;
 use crate::provider::{get_provider, Context};
 use crate::workspace;
 
@@ -93,7 +94,9 @@ pub async fn run_tasks(provider: &str, regenerate: bool) -> Result<()> {
         .current_state
         .is_at_least(crate::state::State::PlanCreated)
     {
-        anyhow::bail!("No implementation plan found. Run 'vibeanvil plan' first.");
+        return Err(error::invalid_state(
+            "No implementation plan found. Run 'vibeanvil plan' first.",
+        ));
     }
 
     let tasks_path = workspace::get_anvil_dir()?.join("tasks.json");
@@ -132,6 +135,7 @@ pub async fn run_tasks(provider: &str, regenerate: bool) -> Result<()> {
         working_dir: std::env::current_dir()?,
         session_id: state.current_session_id.clone().unwrap_or_default(),
         contract_hash: state.spec_hash.clone(),
+        cancel: Default::default(),
     };
 
     let response = provider_instance.execute(&prompt, &context).await?;
@@ -2,128 +2,203 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use notify_debouncer_mini::new_debouncer;
-use notify_debouncer_mini::notify::RecursiveMode;
-use std::path::Path;
-use std::sync::mpsc::channel;
-use std::time::Duration;
-
-/// Default directories to watch for changes
-const WATCH_DIRS: &[&str] = &["src", "lib", "app", "tests", "examples"];
-
-/// Files/patterns to ignore
-const IGNORE_PATTERNS: &[&str] = &[
-    "target",
-    ".git",
-    ".vibeanvil",
-    "node_modules",
-    ".next",
-    "dist",
-    "build",
-    "coverage",
-];
+use notify_debouncer_mini::notify::{self, EventKind, RecursiveMode, Watcher};
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use super::repomap;
+
+/// Top-level directories ignored on top of repomap's skip list
+const EXTRA_TOP_LEVEL_IGNORES: &[&str] = &["coverage"];
+
+/// Dependency directories ignored at any depth; the rest of the skip list
+/// (`build`, `dist`, ...) is only build output at the top level
+const NESTED_IGNORE_DIRS: &[&str] = &["node_modules", "__pycache__"];
+
+/// Whether a top-level entry of the watched root is ignored
+fn is_ignored_top_level(name: &str) -> bool {
+    repomap::is_skipped(name) || EXTRA_TOP_LEVEL_IGNORES.contains(&name)
+}
+
+/// Default quiet period before a burst of changes triggers a rebuild
+pub const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// Coalesces a burst of change events into a single trigger
+///
+/// Every change pushes the trigger back by the interval, so a rebuild only
+/// starts once the files have been quiet for that long.
+#[derive(Debug)]
+pub struct Debounce {
+    interval: Duration,
+    deadline: Option<Instant>,
+    paths: Vec<PathBuf>,
+}
+
+impl Debounce {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            deadline: None,
+            paths: vec![],
+        }
+    }
+
+    /// Record a change seen at `now`
+    pub fn push(&mut self, path: PathBuf, now: Instant) {
+        if !self.paths.contains(&path) {
+            self.paths.push(path);
+        }
+        self.deadline = Some(now + self.interval);
+    }
+
+    /// When the pending trigger fires, if there is one
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Take the changed paths once the quiet period has passed
+    pub fn take_ready(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        match self.deadline {
+            Some(deadline) if now >= deadline => {
+                self.deadline = None;
+                Some(std::mem::take(&mut self.paths))
+            }
+            _ => None,
+        }
+    }
+}
 
 /// Watch for file changes and trigger rebuild
 pub struct FileWatcher {
-    debounce_ms: u64,
+    root: PathBuf,
+    debounce: Duration,
 }
 
 impl FileWatcher {
-    pub fn new() -> Self {
-        Self { debounce_ms: 500 }
+    pub fn new(root: PathBuf, debounce: Duration) -> Self {
+        Self { root, debounce }
     }
 
-    /// Start watching and call callback on changes
-    pub fn watch<F>(&self, callback: F) -> Result<()>
+    /// Watch until Ctrl-C, calling `on_change` with the changed paths after each quiet period
+    ///
+    /// Changes made while `on_change` runs (e.g. by the build itself) are
+    /// dropped so they don't immediately trigger another rebuild. Ctrl-C
+    /// while it runs cancels the token it was given, waits for it to return
+    /// and stops watching.
+    pub async fn watch<F, Fut>(&self, mut on_change: F) -> Result<()>
     where
-        F: Fn() -> Result<()> + Send + 'static,
+        F: FnMut(Vec<PathBuf>, CancellationToken) -> Fut,
+        Fut: Future<Output = Result<()>>,
     {
-        let cwd = std::env::current_dir()?;
-
-        // Find directories to watch
-        let watch_dirs: Vec<_> = WATCH_DIRS
-            .iter()
-            .map(|d| cwd.join(d))
-            .filter(|p| p.exists())
-            .collect();
-
-        if watch_dirs.is_empty() {
-            println!(
-                "{}",
-                "⚠️  No source directories found to watch. Watching current directory.".yellow()
-            );
+        // Top-level directories are watched recursively unless repomap skips
+        // them, so build output is never scanned
+        let mut watch_dirs = vec![];
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            let skipped = path
+                .file_name()
+                .is_some_and(|name| is_ignored_top_level(&name.to_string_lossy()));
+            if path.is_dir() && !skipped {
+                watch_dirs.push(path);
+            }
         }
+        watch_dirs.sort();
 
         println!();
         println!("{}", "👁️  Watch Mode Active".cyan().bold());
         println!("{}", "─".repeat(40).dimmed());
-        println!(
-            "{}",
-            "Watching for file changes... Press Ctrl+C to stop.".dimmed()
-        );
+        print_waiting();
         println!();
 
-        // Setup debounced watcher
-        let (tx, rx) = channel();
-        let mut debouncer = new_debouncer(Duration::from_millis(self.debounce_ms), tx)?;
-
-        // Watch directories
-        if watch_dirs.is_empty() {
-            debouncer.watcher().watch(&cwd, RecursiveMode::Recursive)?;
-        } else {
-            for dir in &watch_dirs {
-                if let Err(e) = debouncer.watcher().watch(dir, RecursiveMode::Recursive) {
-                    eprintln!("Warning: Could not watch {:?}: {}", dir, e);
-                } else {
-                    println!("  {} {}", "•".cyan(), dir.display());
-                }
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let _ = tx.send(res);
+            })?;
+
+        // Files directly in the root (manifests, configs)
+        watcher.watch(&self.root, RecursiveMode::NonRecursive)?;
+        println!("  {} {}", "•".cyan(), self.root.display());
+        for dir in &watch_dirs {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                eprintln!("Warning: Could not watch {:?}: {}", dir, e);
+            } else {
+                println!("  {} {}", "•".cyan(), dir.display());
             }
         }
         println!();
 
-        // Event loop
+        let mut debounce = Debounce::new(self.debounce);
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
         loop {
-            match rx.recv() {
-                Ok(Ok(events)) => {
-                    // Filter out ignored paths
-                    let relevant_events: Vec<_> = events
-                        .iter()
-                        .filter(|e| !self.should_ignore(&e.path))
-                        .collect();
-
-                    if !relevant_events.is_empty() {
-                        println!();
-                        println!("🔄 {} file(s) changed", relevant_events.len());
-
-                        for event in &relevant_events {
-                            let path_str = event
-                                .path
-                                .strip_prefix(&cwd)
-                                .unwrap_or(&event.path)
-                                .display();
-                            println!("   {} {}", "→".dimmed(), path_str);
+            let quiet = async {
+                match debounce.deadline() {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = &mut ctrl_c => {
+                    println!();
+                    println!("{}", "Stopping watch mode.".dimmed());
+                    break;
+                }
+                event = rx.recv() => match event {
+                    Some(Ok(event)) if !matches!(event.kind, EventKind::Access(_)) => {
+                        for path in event.paths {
+                            if !self.should_ignore(&path) {
+                                debounce.push(path, Instant::now());
+                            }
                         }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => eprintln!("Watch error: {}", e),
+                    None => break,
+                },
+                _ = quiet => {
+                    let Some(paths) = debounce.take_ready(Instant::now()) else {
+                        continue;
+                    };
 
-                        println!();
-                        println!("{}", "Rebuilding...".yellow());
+                    println!();
+                    println!("🔄 {} file(s) changed", paths.len());
+                    for path in &paths {
+                        let shown = path.strip_prefix(&self.root).unwrap_or(path).display();
+                        println!("   {} {}", "→".dimmed(), shown);
+                    }
+                    println!();
+                    println!("{}", "Rebuilding...".yellow());
 
-                        if let Err(e) = callback() {
-                            eprintln!("{} {}", "❌ Build failed:".red(), e);
+                    let cancel = CancellationToken::new();
+                    let run = on_change(paths, cancel.clone());
+                    tokio::pin!(run);
+                    let mut stopping = false;
+                    let result = tokio::select! {
+                        result = &mut run => result,
+                        _ = &mut ctrl_c => {
+                            println!();
+                            println!("{}", "Cancelling the running build...".yellow());
+                            cancel.cancel();
+                            stopping = true;
+                            run.await
                         }
-
-                        println!();
-                        println!(
-                            "{}",
-                            "Watching for file changes... Press Ctrl+C to stop.".dimmed()
-                        );
+                    };
+                    if let Err(e) = result {
+                        eprintln!("{} {}", "❌ Build failed:".red(), e);
                     }
-                }
-                Ok(Err(e)) => {
-                    eprintln!("Watch error: {:?}", e);
-                }
-                Err(e) => {
-                    eprintln!("Channel error: {:?}", e);
-                    break;
+                    if stopping {
+                        println!("{}", "Stopping watch mode.".dimmed());
+                        break;
+                    }
+                    while rx.try_recv().is_ok() {}
+
+                    println!();
+                    print_waiting();
                 }
             }
         }
@@ -131,28 +206,84 @@ impl FileWatcher {
         Ok(())
     }
 
-    fn should_ignore(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
+    /// Whether a change to `path` should be ignored: anything under a skipped
+    /// top-level directory (VCS, workspace, build output), under a nested
+    /// dependency directory, or hidden
+    pub fn should_ignore(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .enumerate()
+            .any(|(depth, name)| {
+                if depth == 0 {
+                    is_ignored_top_level(&name)
+                } else {
+                    name.starts_with('.') || NESTED_IGNORE_DIRS.contains(&name.as_ref())
+                }
+            })
+    }
+}
 
-        for pattern in IGNORE_PATTERNS {
-            if path_str.contains(pattern) {
-                return true;
-            }
-        }
+fn print_waiting() {
+    println!(
+        "{}",
+        "Watching for file changes... Press Ctrl+C to stop.".dimmed()
+    );
+}
 
-        // Ignore hidden files
-        if let Some(file_name) = path.file_name() {
-            if file_name.to_string_lossy().starts_with('.') {
-                return true;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_coalesces_burst_into_one_trigger() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut debounce = Debounce::new(ms(500));
+        assert_eq!(debounce.take_ready(start), None);
+
+        // Rapid saves 100ms apart keep pushing the trigger back
+        for (i, file) in ["a.rs", "b.rs", "a.rs", "c.rs"].iter().enumerate() {
+            debounce.push(PathBuf::from(file), start + ms(100 * i as u64));
         }
+        assert_eq!(debounce.take_ready(start + ms(500)), None);
+        assert_eq!(debounce.deadline(), Some(start + ms(800)));
 
-        false
+        let paths = debounce.take_ready(start + ms(800)).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("a.rs"),
+                PathBuf::from("b.rs"),
+                PathBuf::from("c.rs")
+            ]
+        );
+        assert_eq!(debounce.take_ready(start + ms(2000)), None);
+        assert_eq!(debounce.deadline(), None);
     }
-}
 
-impl Default for FileWatcher {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_should_ignore_workspace_and_vcs_dirs() {
+        let root = PathBuf::from("/project");
+        let watcher = FileWatcher::new(root.clone(), Duration::from_millis(10));
+
+        assert!(watcher.should_ignore(&root.join(".vibeanvil/state.json")));
+        assert!(watcher.should_ignore(&root.join(".git/index")));
+        assert!(watcher.should_ignore(&root.join("src/.main.rs.swp")));
+        assert!(watcher.should_ignore(&root.join("target/debug/app")));
+        assert!(watcher.should_ignore(&root.join("coverage/lcov.info")));
+        assert!(watcher.should_ignore(&root.join("web/node_modules/x/index.js")));
+        assert!(!watcher.should_ignore(&root.join("src/main.rs")));
+        // Only whole directory names match
+        assert!(!watcher.should_ignore(&root.join("src/build/mod.rs")));
+        assert!(!watcher.should_ignore(&root.join("src/builder/mod.rs")));
+        // Build output names are only skipped at the top level
+        assert!(!watcher.should_ignore(&root.join("src/dist/mod.rs")));
+        assert!(!watcher.should_ignore(&root.join("src/target/mod.rs")));
+        assert!(!watcher.should_ignore(&root.join("src/targeting.rs")));
     }
 }
@@ -91,7 +91,7 @@ async fn handle_action(action: WizardAction) -> Result<()> {
 
     match action {
         WizardAction::Init => {
-            crate::cli::init::run(false).await?;
+            crate::cli::init::run(false, crate::contract::ContractFormat::default()).await?;
         }
         WizardAction::BrainEnsure => {
             crate::cli::brain::run(crate::cli::BrainArgs {
@@ -111,12 +111,16 @@ async fn handle_action(action: WizardAction) -> Result<()> {
                 Ok(q) if !q.is_empty() => {
                     crate::cli::brain::run(crate::cli::BrainArgs {
                         command: crate::cli::BrainCommands::Search {
-                            query: q,
+                            query: Some(q),
+                            regex: None,
                             limit: 10,
                             record_type: None,
+                            content_types: vec![],
                             language: None,
                             tags: vec![],
                             source: None,
+                            signals: vec![],
+                            min_score: None,
                         },
                     })
                     .await?;
@@ -141,7 +145,7 @@ async fn handle_action(action: WizardAction) -> Result<()> {
             }
         }
         WizardAction::Status => {
-            crate::cli::status::run(false, false).await?;
+            crate::cli::status::run(false, None, false).await?;
         }
         WizardAction::Doctor => {
             crate::cli::doctor::run().await?;
@@ -0,0 +1,312 @@
+//! Field-by-field comparison between a locked contract and its current draft
+
+use serde::Serialize;
+use std::fmt;
+
+use super::{Contract, Requirement};
+
+/// One difference between the locked and current contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum FieldChange {
+    /// A scalar field or keyed item changed value
+    Changed {
+        field: &'static str,
+        from: String,
+        to: String,
+    },
+    /// An item was added to a list field
+    Added { field: &'static str, item: String },
+    /// An item was removed from a list field
+    Removed { field: &'static str, item: String },
+    /// A list field holds the same items in a different order
+    Reordered { field: &'static str },
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldChange::Changed { field, from, to } => {
+                write!(f, "{}: ~ \"{}\" → \"{}\"", field, from, to)
+            }
+            FieldChange::Added { field, item } => write!(f, "{}: + \"{}\"", field, item),
+            FieldChange::Removed { field, item } => write!(f, "{}: - \"{}\"", field, item),
+            FieldChange::Reordered { field } => write!(f, "{}: reordered", field),
+        }
+    }
+}
+
+impl FieldChange {
+    /// Contract field this change belongs to
+    pub fn field(&self) -> &'static str {
+        match self {
+            FieldChange::Changed { field, .. }
+            | FieldChange::Added { field, .. }
+            | FieldChange::Removed { field, .. }
+            | FieldChange::Reordered { field } => field,
+        }
+    }
+
+    /// Diff-style lines for this change, each with its `+`, `-` or `~` marker
+    ///
+    /// A changed value is shown as its old line removed and its new line added.
+    pub fn lines(&self) -> Vec<(char, String)> {
+        match self {
+            FieldChange::Changed { field, from, to } => vec![
+                ('-', format!("{}: {}", field, from)),
+                ('+', format!("{}: {}", field, to)),
+            ],
+            FieldChange::Added { field, item } => vec![('+', format!("{}: {}", field, item))],
+            FieldChange::Removed { field, item } => vec![('-', format!("{}: {}", field, item))],
+            FieldChange::Reordered { field } => vec![('~', format!("{}: reordered", field))],
+        }
+    }
+}
+
+/// Structured difference between two versions of a contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ContractDiff {
+    /// Changes in field order: scalars, goals, requirements (by ID), then the other lists
+    pub changes: Vec<FieldChange>,
+}
+
+impl ContractDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// All diff lines, in order
+    pub fn lines(&self) -> Vec<(char, String)> {
+        self.changes.iter().flat_map(FieldChange::lines).collect()
+    }
+
+    /// Changes to `field` only
+    pub fn for_field(&self, field: &str) -> Vec<&FieldChange> {
+        self.changes.iter().filter(|c| c.field() == field).collect()
+    }
+}
+
+/// Compare two contracts field by field; timestamps are included because they are hashed
+pub fn compute_diff(old: &Contract, new: &Contract) -> ContractDiff {
+    let mut changes = vec![];
+
+    scalar(
+        &mut changes,
+        "project_name",
+        &old.project_name,
+        &new.project_name,
+    );
+    scalar(
+        &mut changes,
+        "description",
+        &old.description,
+        &new.description,
+    );
+    scalar(
+        &mut changes,
+        "status",
+        &format!("{:?}", old.status),
+        &format!("{:?}", new.status),
+    );
+    list(&mut changes, "goals", &old.goals, &new.goals);
+    requirements(&mut changes, &old.requirements, &new.requirements);
+    list(
+        &mut changes,
+        "acceptance_criteria",
+        &old.acceptance_criteria,
+        &new.acceptance_criteria,
+    );
+    list(
+        &mut changes,
+        "constraints",
+        &old.constraints,
+        &new.constraints,
+    );
+    list(
+        &mut changes,
+        "out_of_scope",
+        &old.out_of_scope,
+        &new.out_of_scope,
+    );
+    list(&mut changes, "tags", &old.tags, &new.tags);
+    scalar(
+        &mut changes,
+        "contract_version",
+        &old.contract_version.to_string(),
+        &new.contract_version.to_string(),
+    );
+    scalar(
+        &mut changes,
+        "supersedes",
+        old.supersedes.as_deref().unwrap_or("none"),
+        new.supersedes.as_deref().unwrap_or("none"),
+    );
+    scalar(
+        &mut changes,
+        "created_at",
+        &old.created_at.to_rfc3339(),
+        &new.created_at.to_rfc3339(),
+    );
+    scalar(
+        &mut changes,
+        "updated_at",
+        &old.updated_at.to_rfc3339(),
+        &new.updated_at.to_rfc3339(),
+    );
+
+    ContractDiff { changes }
+}
+
+fn scalar(changes: &mut Vec<FieldChange>, field: &'static str, from: &str, to: &str) {
+    if from != to {
+        changes.push(FieldChange::Changed {
+            field,
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+}
+
+fn list(changes: &mut Vec<FieldChange>, field: &'static str, from: &[String], to: &[String]) {
+    let before = changes.len();
+    for item in from.iter().filter(|item| !to.contains(item)) {
+        changes.push(FieldChange::Removed {
+            field,
+            item: item.clone(),
+        });
+    }
+    for item in to.iter().filter(|item| !from.contains(item)) {
+        changes.push(FieldChange::Added {
+            field,
+            item: item.clone(),
+        });
+    }
+    if changes.len() == before && from != to {
+        changes.push(FieldChange::Reordered { field });
+    }
+}
+
+fn requirements(changes: &mut Vec<FieldChange>, from: &[Requirement], to: &[Requirement]) {
+    const FIELD: &str = "requirements";
+    let describe = |r: &Requirement| {
+        let deps = if r.depends_on.is_empty() {
+            String::new()
+        } else {
+            format!(" [depends on {}]", r.depends_on.join(", "))
+        };
+        format!(
+            "{} ({}): {}{}",
+            r.id,
+            format!("{:?}", r.priority).to_lowercase(),
+            r.description,
+            deps
+        )
+    };
+
+    let before = changes.len();
+    for old in from {
+        match to.iter().find(|r| r.id == old.id) {
+            None => changes.push(FieldChange::Removed {
+                field: FIELD,
+                item: describe(old),
+            }),
+            Some(new) if describe(old) != describe(new) => changes.push(FieldChange::Changed {
+                field: FIELD,
+                from: describe(old),
+                to: describe(new),
+            }),
+            Some(_) => {}
+        }
+    }
+    for new in to.iter().filter(|r| !from.iter().any(|old| old.id == r.id)) {
+        changes.push(FieldChange::Added {
+            field: FIELD,
+            item: describe(new),
+        });
+    }
+
+    let ids = |reqs: &[Requirement]| reqs.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+    if changes.len() == before && ids(from) != ids(to) {
+        changes.push(FieldChange::Reordered { field: FIELD });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::Priority;
+
+    #[test]
+    fn test_diff_reports_added_removed_changed_and_reordered() {
+        let mut locked = Contract::new("Todo");
+        locked.goals = vec!["Sync".into(), "Share".into()];
+        locked.acceptance_criteria = vec!["Loads fast".into(), "Works offline".into()];
+        locked.add_requirement("REQ-1", "Store todos", Priority::Must);
+        locked.add_requirement("REQ-2", "Tag todos", Priority::Could);
+
+        let mut current = locked.clone();
+        current.goals = vec!["Sync".into(), "Export".into()];
+        current.acceptance_criteria.reverse();
+        current.requirements[0].priority = Priority::Should;
+        current.requirements.remove(1);
+
+        let diff = compute_diff(&locked, &current);
+        assert_eq!(
+            diff.changes,
+            vec![
+                FieldChange::Removed {
+                    field: "goals",
+                    item: "Share".into()
+                },
+                FieldChange::Added {
+                    field: "goals",
+                    item: "Export".into()
+                },
+                FieldChange::Changed {
+                    field: "requirements",
+                    from: "REQ-1 (must): Store todos".into(),
+                    to: "REQ-1 (should): Store todos".into()
+                },
+                FieldChange::Removed {
+                    field: "requirements",
+                    item: "REQ-2 (could): Tag todos".into()
+                },
+                FieldChange::Reordered {
+                    field: "acceptance_criteria"
+                },
+            ]
+        );
+        assert!(compute_diff(&locked, &locked).is_empty());
+    }
+
+    #[test]
+    fn test_diff_lines_and_json() {
+        let mut locked = Contract::new("Todo");
+        locked.add_requirement("REQ-1", "Store todos", Priority::Must);
+        let mut current = locked.clone();
+        current.description = "Offline todos".into();
+        current.add_goal("Sync");
+        current.add_requirement("REQ-2", "Share lists", Priority::Should);
+        current.requirements.remove(0);
+        current.updated_at = locked.updated_at;
+
+        let diff = compute_diff(&locked, &current);
+        assert_eq!(
+            diff.lines(),
+            vec![
+                ('-', "description: ".to_string()),
+                ('+', "description: Offline todos".to_string()),
+                ('+', "goals: Sync".to_string()),
+                ('-', "requirements: REQ-1 (must): Store todos".to_string()),
+                ('+', "requirements: REQ-2 (should): Share lists".to_string()),
+            ]
+        );
+        assert_eq!(diff.for_field("requirements").len(), 2);
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["changes"][1]["change"], "added");
+        assert_eq!(json["changes"][1]["field"], "goals");
+        assert_eq!(json["changes"][1]["item"], "Sync");
+        assert_eq!(json["changes"][0]["to"], "Offline todos");
+    }
+}
@@ -0,0 +1,336 @@
+//! Three-way merge of contracts edited on diverging branches
+
+use std::fmt;
+
+use super::{Contract, ContractStatus, Requirement};
+
+/// A field that could not be merged automatically
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Field name, e.g. `description` or `requirements[REQ-1]`
+    pub field: String,
+    /// Value in the common ancestor (None if absent)
+    pub base: Option<String>,
+    /// Value in our contract (None if removed)
+    pub ours: Option<String>,
+    /// Value in their contract (None if removed)
+    pub theirs: Option<String>,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<String>| {
+            v.as_ref()
+                .map_or_else(|| "(removed)".to_string(), |v| format!("\"{}\"", v))
+        };
+        write!(
+            f,
+            "{}: ours {} vs theirs {}",
+            self.field,
+            show(&self.ours),
+            show(&self.theirs)
+        )
+    }
+}
+
+/// Outcome of a three-way contract merge
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// Merged contract; conflicting fields keep our value
+    pub merged: Contract,
+    /// Fields that need a manual decision
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    /// Whether every field merged cleanly
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl Contract {
+    /// Three-way merge of `ours` and `theirs` against their common `base`
+    ///
+    /// Scalars conflict when both sides changed them differently. Goals and
+    /// acceptance criteria (and tags) merge as sets (keyed by text) and requirements by
+    /// ID, so removals on one side are kept. Constraints and out-of-scope
+    /// items are appended, skipping duplicates. The result is always a draft.
+    pub fn merge(base: &Contract, ours: &Contract, theirs: &Contract) -> MergeResult {
+        let mut conflicts = vec![];
+        let mut merged = ours.clone();
+
+        merged.schema_version = scalar(
+            &mut conflicts,
+            "schema_version",
+            &base.schema_version,
+            &ours.schema_version,
+            &theirs.schema_version,
+        );
+        merged.project_name = scalar(
+            &mut conflicts,
+            "project_name",
+            &base.project_name,
+            &ours.project_name,
+            &theirs.project_name,
+        );
+        merged.description = scalar(
+            &mut conflicts,
+            "description",
+            &base.description,
+            &ours.description,
+            &theirs.description,
+        );
+
+        merged.goals = set_merge(&base.goals, &ours.goals, &theirs.goals);
+        merged.acceptance_criteria = set_merge(
+            &base.acceptance_criteria,
+            &ours.acceptance_criteria,
+            &theirs.acceptance_criteria,
+        );
+        merged.tags = set_merge(&base.tags, &ours.tags, &theirs.tags);
+        merged.requirements = requirements(
+            &mut conflicts,
+            &base.requirements,
+            &ours.requirements,
+            &theirs.requirements,
+        );
+        merged.constraints = append_merge(&ours.constraints, &theirs.constraints);
+        merged.out_of_scope = append_merge(&ours.out_of_scope, &theirs.out_of_scope);
+
+        merged.status = ContractStatus::Draft;
+        merged.contract_version = ours.contract_version.max(theirs.contract_version);
+        merged.created_at = ours.created_at.min(theirs.created_at);
+        merged.updated_at = chrono::Utc::now();
+
+        MergeResult { merged, conflicts }
+    }
+}
+
+fn scalar(
+    conflicts: &mut Vec<MergeConflict>,
+    field: &str,
+    base: &str,
+    ours: &str,
+    theirs: &str,
+) -> String {
+    if ours == theirs || theirs == base {
+        return ours.to_string();
+    }
+    if ours == base {
+        return theirs.to_string();
+    }
+    conflicts.push(MergeConflict {
+        field: field.to_string(),
+        base: Some(base.to_string()),
+        ours: Some(ours.to_string()),
+        theirs: Some(theirs.to_string()),
+    });
+    ours.to_string()
+}
+
+/// Our items minus those they removed, plus those they added
+fn set_merge(base: &[String], ours: &[String], theirs: &[String]) -> Vec<String> {
+    let mut merged: Vec<String> = ours
+        .iter()
+        .filter(|item| !base.contains(item) || theirs.contains(item))
+        .cloned()
+        .collect();
+    for item in theirs {
+        if !base.contains(item) && !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+/// Our items followed by theirs, skipping case/whitespace-insensitive duplicates
+fn append_merge(ours: &[String], theirs: &[String]) -> Vec<String> {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    let mut merged: Vec<String> = vec![];
+    for item in ours.iter().chain(theirs) {
+        if !merged.iter().any(|m| normalize(m) == normalize(item)) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+fn requirements(
+    conflicts: &mut Vec<MergeConflict>,
+    base: &[Requirement],
+    ours: &[Requirement],
+    theirs: &[Requirement],
+) -> Vec<Requirement> {
+    let find = |reqs: &[Requirement], id: &str| reqs.iter().find(|r| r.id == id).cloned();
+    let describe = |r: &Option<Requirement>| {
+        r.as_ref().map(|r| {
+            format!(
+                "{}: {}",
+                format!("{:?}", r.priority).to_lowercase(),
+                r.description
+            )
+        })
+    };
+
+    // Our order first, then IDs only they have
+    let mut ids: Vec<&str> = ours.iter().map(|r| r.id.as_str()).collect();
+    for r in theirs.iter().chain(base) {
+        if !ids.contains(&r.id.as_str()) {
+            ids.push(&r.id);
+        }
+    }
+
+    let mut merged = vec![];
+    for id in ids {
+        let (b, o, t) = (find(base, id), find(ours, id), find(theirs, id));
+        let chosen = if o == t || t == b {
+            o
+        } else if o == b {
+            t
+        } else {
+            conflicts.push(MergeConflict {
+                field: format!("requirements[{}]", id),
+                base: describe(&b),
+                ours: describe(&o),
+                theirs: describe(&t),
+            });
+            o.or(t)
+        };
+        merged.extend(chosen);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::Priority;
+
+    fn base() -> Contract {
+        let mut contract = Contract::new("Todo");
+        contract.description = "A todo app".into();
+        contract.goals = vec!["Sync".into(), "Share".into()];
+        contract.acceptance_criteria = vec!["Loads fast".into()];
+        contract.constraints = vec!["Rust only".into()];
+        contract.add_requirement("REQ-1", "Store todos", Priority::Must);
+        contract.add_requirement("REQ-2", "Tag todos", Priority::Could);
+        contract
+    }
+
+    #[test]
+    fn test_merge_clean_combines_both_sides() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.description = "A collaborative todo app".into();
+        ours.goals.push("Export".into());
+        ours.requirements[0].priority = Priority::Should;
+        ours.constraints.push("No unsafe".into());
+
+        let mut theirs = base.clone();
+        theirs.goals.retain(|g| g != "Share");
+        theirs.acceptance_criteria.push("Works offline".into());
+        theirs.requirements.remove(1);
+        theirs.add_requirement("REQ-3", "Due dates", Priority::Should);
+        theirs.constraints.push("  no   UNSAFE ".into());
+        theirs.out_of_scope.push("Mobile".into());
+
+        let result = Contract::merge(&base, &ours, &theirs);
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        let merged = result.merged;
+        assert_eq!(merged.description, "A collaborative todo app");
+        assert_eq!(merged.goals, vec!["Sync", "Export"]);
+        assert_eq!(
+            merged.acceptance_criteria,
+            vec!["Loads fast", "Works offline"]
+        );
+        let ids: Vec<&str> = merged.requirements.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["REQ-1", "REQ-3"]);
+        assert_eq!(merged.requirements[0].priority, Priority::Should);
+        assert_eq!(merged.constraints, vec!["Rust only", "No unsafe"]);
+        assert_eq!(merged.out_of_scope, vec!["Mobile"]);
+        assert_eq!(merged.status, ContractStatus::Draft);
+    }
+
+    #[test]
+    fn test_merge_scalar_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.project_name = "Todo Pro".into();
+        theirs.project_name = "Todo Plus".into();
+        ours.description = "Ours".into();
+        theirs.description = "Theirs".into();
+        theirs.schema_version = "2.0.0".into();
+
+        let result = Contract::merge(&base, &ours, &theirs);
+        let fields: Vec<&str> = result.conflicts.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["project_name", "description"]);
+        assert_eq!(result.merged.project_name, "Todo Pro");
+        assert_eq!(result.merged.schema_version, "2.0.0");
+        assert_eq!(
+            result.conflicts[0].to_string(),
+            "project_name: ours \"Todo Pro\" vs theirs \"Todo Plus\""
+        );
+
+        ours.schema_version = "1.2.0".into();
+        let result = Contract::merge(&base, &ours, &theirs);
+        assert_eq!(result.conflicts[0].field, "schema_version");
+    }
+
+    #[test]
+    fn test_merge_requirement_conflicts() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        // Both changed REQ-1 differently
+        ours.requirements[0].description = "Store todos locally".into();
+        theirs.requirements[0].description = "Store todos in the cloud".into();
+        // We changed REQ-2, they removed it
+        ours.requirements[1].priority = Priority::Must;
+        theirs.requirements.remove(1);
+        // Both added REQ-9 with different content
+        ours.add_requirement("REQ-9", "Reminders", Priority::Could);
+        theirs.add_requirement("REQ-9", "Recurring todos", Priority::Could);
+
+        let result = Contract::merge(&base, &ours, &theirs);
+        let fields: Vec<&str> = result.conflicts.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "requirements[REQ-1]",
+                "requirements[REQ-2]",
+                "requirements[REQ-9]"
+            ]
+        );
+        assert_eq!(result.conflicts[1].theirs, None);
+        assert_eq!(result.conflicts[2].base, None);
+        assert!(result.conflicts[1].to_string().contains("(removed)"));
+        // Conflicting requirements keep our version
+        assert_eq!(result.merged.requirements.len(), 3);
+        assert_eq!(
+            result.merged.requirements[0].description,
+            "Store todos locally"
+        );
+    }
+
+    #[test]
+    fn test_merge_identical_changes_do_not_conflict() {
+        let base = base();
+        let mut ours = base.clone();
+        ours.description = "Same edit".into();
+        ours.requirements.remove(0);
+        let theirs = ours.clone();
+
+        let result = Contract::merge(&base, &ours, &theirs);
+        assert!(result.is_clean());
+        assert_eq!(result.merged.description, "Same edit");
+        assert_eq!(result.merged.requirements.len(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+//! Contract schema migrations
+//!
+//! Each step upgrades the raw contract JSON from one `schema_version` to the
+//! next, so contracts written by older releases keep loading after the schema
+//! changes. Steps run on the untyped value, before deserialization.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::error;
+
+/// Schema version written by this release
+pub const CURRENT_VERSION: &str = "1.1.0";
+
+/// Version assumed when a contract has no `schema_version`
+const INITIAL_VERSION: &str = "1.0.0";
+
+type Step = fn(Value) -> Result<Value>;
+
+/// Migration steps keyed by `(from_version, to_version)`, oldest first
+const STEPS: &[(&str, &str, Step)] = &[("1.0.0", "1.1.0", add_tags)];
+
+/// 1.1.0 adds free-form `tags`
+fn add_tags(mut raw: Value) -> Result<Value> {
+    if let Some(obj) = raw.as_object_mut() {
+        obj.entry("tags").or_insert_with(|| Value::Array(vec![]));
+    }
+    Ok(raw)
+}
+
+/// Schema version recorded in a raw contract
+pub fn version_of(raw: &Value) -> &str {
+    raw.get("schema_version")
+        .and_then(Value::as_str)
+        .unwrap_or(INITIAL_VERSION)
+}
+
+/// Migrate a raw contract from `from` to `to` by chaining registered steps
+pub fn migrate(mut raw: Value, from: &str, to: &str) -> Result<Value> {
+    let mut version = from.to_string();
+    while version != to {
+        let Some((_, next, step)) = STEPS.iter().find(|(f, _, _)| *f == version) else {
+            return Err(error::validation(format!(
+                "No contract migration from schema {} to {}",
+                from, to
+            )));
+        };
+        raw = step(raw)?;
+        if let Some(obj) = raw.as_object_mut() {
+            obj.insert("schema_version".into(), Value::String(next.to_string()));
+        }
+        version = next.to_string();
+    }
+    Ok(raw)
+}
+
+/// Bring a raw contract up to `CURRENT_VERSION`
+///
+/// Locked contracts are left as they are: migrating them would change their
+/// hash and break the lock. Amend the contract to move it to the new schema.
+pub fn upgrade(raw: Value) -> Result<Value> {
+    let locked = raw.get("status").and_then(Value::as_str) == Some("LOCKED");
+    let version = version_of(&raw).to_string();
+    if version == CURRENT_VERSION || locked {
+        return Ok(raw);
+    }
+    migrate(raw, &version, CURRENT_VERSION).map_err(|_| {
+        error::validation(format!(
+            "Contract schema {} is not supported by this vibeanvil (expected {} or older); upgrade vibeanvil",
+            version, CURRENT_VERSION
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::Contract;
+
+    fn v1_0() -> Value {
+        serde_json::json!({
+            "schema_version": "1.0.0",
+            "status": "DRAFT",
+            "project_name": "Todo",
+            "description": "A todo app",
+            "goals": ["Sync"],
+            "requirements": [{"id": "REQ-1", "description": "Store todos", "priority": "must"}],
+            "acceptance_criteria": [],
+            "constraints": [],
+            "out_of_scope": [],
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        })
+    }
+
+    #[test]
+    fn test_migrate_1_0_to_1_1_round_trip() {
+        let migrated = migrate(v1_0(), "1.0.0", "1.1.0").unwrap();
+        assert_eq!(migrated["schema_version"], "1.1.0");
+        assert_eq!(migrated["tags"], serde_json::json!([]));
+
+        let contract: Contract = serde_json::from_value(migrated.clone()).unwrap();
+        assert_eq!(contract.schema_version, CURRENT_VERSION);
+        assert_eq!(contract.requirements[0].id, "REQ-1");
+
+        // Writing the migrated contract back out and loading it again is a no-op
+        let written = serde_json::to_value(&contract).unwrap();
+        assert_eq!(version_of(&written), "1.1.0");
+        let again = upgrade(written.clone()).unwrap();
+        assert_eq!(again, written);
+        assert_eq!(migrate(written.clone(), "1.1.0", "1.1.0").unwrap(), written);
+    }
+
+    #[test]
+    fn test_upgrade_skips_locked_and_rejects_unknown() {
+        let mut locked = v1_0();
+        locked["status"] = "LOCKED".into();
+        assert_eq!(upgrade(locked.clone()).unwrap(), locked);
+
+        let mut missing = v1_0();
+        missing.as_object_mut().unwrap().remove("schema_version");
+        assert_eq!(upgrade(missing).unwrap()["schema_version"], "1.1.0");
+
+        let mut future = v1_0();
+        future["schema_version"] = "9.0.0".into();
+        assert!(upgrade(future).is_err());
+        assert!(migrate(v1_0(), "1.1.0", "1.0.0").is_err());
+    }
+}
@@ -1,13 +1,18 @@
 //! Contract validation, locking, and management
 
+pub mod diff;
+pub mod merge;
+pub mod migration;
 pub mod refinement;
 pub mod schema;
+pub mod signing;
 pub mod templates;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::workspace;
@@ -45,26 +50,71 @@ pub struct Contract {
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// Timestamp of last update
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Contract version, bumped by each `contract amend` (omitted while 1 so
+    /// hashes of unamended contracts are unchanged)
+    #[serde(default = "first_version", skip_serializing_if = "is_first_version")]
+    pub contract_version: u32,
+    /// Hash of the lock this contract amends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// Free-form labels (schema 1.1.0)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+fn is_first_version(version: &u32) -> bool {
+    *version == 1
 }
 
 /// A requirement entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Requirement {
     /// Requirement ID
     pub id: String,
     /// Requirement description
     pub description: String,
-    /// Priority (must, should, could)
+    /// Priority (must, should, could, wont)
     pub priority: Priority,
+    /// IDs of requirements this one depends on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
 }
 
-/// Requirement priority
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Requirement priority (MoSCoW)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Priority {
     Must,
     Should,
     Could,
+    /// Won't be done this cycle; kept so the requirement ID isn't lost
+    Wont,
+}
+
+impl Priority {
+    /// All priorities, highest first
+    pub fn all() -> &'static [Priority] {
+        &[
+            Priority::Must,
+            Priority::Should,
+            Priority::Could,
+            Priority::Wont,
+        ]
+    }
+
+    /// Short label, e.g. `won't`
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::Must => "must",
+            Priority::Should => "should",
+            Priority::Could => "could",
+            Priority::Wont => "won't",
+        }
+    }
 }
 
 /// Contract lock information
@@ -78,13 +128,47 @@ pub struct ContractLock {
     pub tool_version: String,
     /// Schema version of the contract
     pub schema_version: String,
+    /// Hex-encoded ed25519 signature over the hash (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Hex-encoded ed25519 public key for the signature (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Contract as it was locked, used by `contract diff` (absent in older locks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<Contract>,
+    /// Hash of the lock this one replaces (set when an amended contract is locked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// Release freeze: the contract can't be rolled back or reset before this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ContractLock {
+    /// Written by a release that hashed the draft form of the contract:
+    /// such locks carry none of the fields added since
+    pub fn is_legacy(&self) -> bool {
+        self.contract.is_none()
+            && self.signature.is_none()
+            && self.supersedes.is_none()
+            && self.frozen_until.is_none()
+    }
+
+    /// End of the freeze window, if it is still in effect at `now`
+    pub fn frozen_at(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.frozen_until.filter(|until| now < *until)
+    }
 }
 
 impl Default for Contract {
     fn default() -> Self {
         let now = chrono::Utc::now();
         Self {
-            schema_version: "1.0.0".to_string(),
+            schema_version: migration::CURRENT_VERSION.to_string(),
             status: ContractStatus::Draft,
             project_name: String::new(),
             description: String::new(),
@@ -95,6 +179,9 @@ impl Default for Contract {
             out_of_scope: vec![],
             created_at: now,
             updated_at: now,
+            contract_version: first_version(),
+            supersedes: None,
+            tags: vec![],
         }
     }
 }
@@ -114,10 +201,78 @@ impl Contract {
             id: id.to_string(),
             description: description.to_string(),
             priority,
+            depends_on: vec![],
         });
         self.updated_at = chrono::Utc::now();
     }
 
+    /// Add a requirement, rejecting an ID that is already used
+    pub fn try_add_requirement(
+        &mut self,
+        id: &str,
+        description: &str,
+        priority: Priority,
+    ) -> Result<()> {
+        let id = id.trim();
+        if id.is_empty() {
+            return Err(crate::error::validation("Requirement ID cannot be empty"));
+        }
+        if self.requirements.iter().any(|r| r.id == id) {
+            return Err(crate::error::validation(format!(
+                "Requirement {} already exists",
+                id
+            )));
+        }
+        self.add_requirement(id, description, priority);
+        Ok(())
+    }
+
+    /// Change a requirement's description and/or priority
+    pub fn update_requirement(
+        &mut self,
+        id: &str,
+        description: Option<&str>,
+        priority: Option<Priority>,
+    ) -> Result<()> {
+        let requirement = self
+            .requirements
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| unknown_requirement(id))?;
+        if let Some(description) = description {
+            requirement.description = description.to_string();
+        }
+        if let Some(priority) = priority {
+            requirement.priority = priority;
+        }
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Remove a requirement no other requirement depends on
+    pub fn remove_requirement(&mut self, id: &str) -> Result<Requirement> {
+        let index = self
+            .requirements
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| unknown_requirement(id))?;
+        let dependents: Vec<&str> = self
+            .requirements
+            .iter()
+            .filter(|r| r.depends_on.iter().any(|d| d == id))
+            .map(|r| r.id.as_str())
+            .collect();
+        if !dependents.is_empty() {
+            return Err(crate::error::validation(format!(
+                "Requirement {} is a dependency of {}; remove or edit those first",
+                id,
+                dependents.join(", ")
+            )));
+        }
+        self.updated_at = chrono::Utc::now();
+        Ok(self.requirements.remove(index))
+    }
+
     /// Add a goal
     pub fn add_goal(&mut self, goal: &str) {
         self.goals.push(goal.to_string());
@@ -149,6 +304,19 @@ impl Contract {
 
         if self.requirements.is_empty() {
             warnings.push("No requirements defined".to_string());
+        } else if !self
+            .requirements
+            .iter()
+            .any(|r| r.priority == Priority::Must)
+        {
+            warnings.push(
+                "No 'must' requirements; a contract with only should/could items is usually underspecified"
+                    .to_string(),
+            );
+        }
+
+        if let Err(e) = self.validate_requirement_deps() {
+            errors.push(e.to_string());
         }
 
         if self.acceptance_criteria.is_empty() {
@@ -162,6 +330,43 @@ impl Contract {
         }
     }
 
+    /// Check that every `depends_on` names an existing requirement and that there are no cycles
+    ///
+    /// A cycle is reported with its full path, e.g. `REQ-1 → REQ-2 → REQ-1`.
+    pub fn validate_requirement_deps(&self) -> Result<()> {
+        let by_id: HashMap<&str, &Requirement> = self
+            .requirements
+            .iter()
+            .map(|r| (r.id.as_str(), r))
+            .collect();
+
+        for req in &self.requirements {
+            if let Some(dep) = req
+                .depends_on
+                .iter()
+                .find(|d| !by_id.contains_key(d.as_str()))
+            {
+                return Err(crate::error::validation(format!(
+                    "Requirement {} depends on unknown requirement {}",
+                    req.id, dep
+                )));
+            }
+        }
+
+        let items: Vec<(&str, &[String])> = self
+            .requirements
+            .iter()
+            .map(|r| (r.id.as_str(), r.depends_on.as_slice()))
+            .collect();
+        if let Some(cycle) = crate::build::order::find_cycle(&items) {
+            return Err(crate::error::validation(format!(
+                "Requirement dependency cycle: {}",
+                cycle.join(" → ")
+            )));
+        }
+        Ok(())
+    }
+
     /// Generate canonical JSON for hashing
     pub fn canonical_json(&self) -> Result<String> {
         // Sort keys and format consistently
@@ -185,24 +390,122 @@ impl Contract {
             anyhow::bail!("Cannot lock invalid contract: {:?}", validation.errors);
         }
 
-        let hash = self.generate_hash(tool_version)?;
+        // Hash the contract as saved (locked), so it can be re-verified later
         self.status = ContractStatus::Locked;
         self.updated_at = chrono::Utc::now();
+        let hash = self.generate_hash(tool_version)?;
 
         Ok(ContractLock {
             hash,
             locked_at: chrono::Utc::now(),
             tool_version: tool_version.to_string(),
             schema_version: self.schema_version.clone(),
+            signature: None,
+            public_key: None,
+            contract: Some(self.clone()),
+            supersedes: self.supersedes.clone(),
+            frozen_until: None,
         })
     }
 
+    /// Start the next version of a locked contract as a draft superseding `lock`
+    pub fn amend(&self, lock: &ContractLock) -> Result<Contract> {
+        if !self.is_locked() {
+            anyhow::bail!("Only a locked contract can be amended");
+        }
+
+        let mut next = self.clone();
+        next.status = ContractStatus::Draft;
+        next.contract_version = self.contract_version + 1;
+        next.supersedes = Some(lock.hash.clone());
+        next.updated_at = chrono::Utc::now();
+        Ok(next)
+    }
+
+    /// Requirements grouped by priority (priorities without requirements are absent)
+    pub fn requirements_by_priority(&self) -> HashMap<Priority, Vec<&Requirement>> {
+        let mut groups: HashMap<Priority, Vec<&Requirement>> = HashMap::new();
+        for requirement in &self.requirements {
+            groups
+                .entry(requirement.priority)
+                .or_default()
+                .push(requirement);
+        }
+        groups
+    }
+
     /// Check if contract is locked
     pub fn is_locked(&self) -> bool {
         self.status == ContractStatus::Locked
     }
 }
 
+/// On-disk format of a contract file
+///
+/// Only affects how the file is read and written; hashes are always taken
+/// over the canonical JSON form, so converting a contract keeps its lock valid.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl ContractFormat {
+    /// All formats, in lookup order
+    pub fn all() -> [ContractFormat; 2] {
+        [ContractFormat::Json, ContractFormat::Toml]
+    }
+
+    /// File extension without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ContractFormat::Json => "json",
+            ContractFormat::Toml => "toml",
+        }
+    }
+
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Option<ContractFormat> {
+        let ext = path.extension()?.to_str()?;
+        Self::all()
+            .into_iter()
+            .find(|f| f.extension().eq_ignore_ascii_case(ext))
+    }
+
+    /// Serialize a contract in this format
+    pub fn serialize(self, contract: &Contract) -> Result<String> {
+        match self {
+            ContractFormat::Json => Ok(serde_json::to_string_pretty(contract)?),
+            ContractFormat::Toml => {
+                toml::to_string_pretty(contract).context("Failed to serialize contract as TOML")
+            }
+        }
+    }
+
+    /// Parse a contract written in this format, migrating older drafts to the current schema
+    pub fn parse(self, content: &str) -> Result<Contract> {
+        let raw: serde_json::Value = match self {
+            ContractFormat::Json => {
+                serde_json::from_str(content).context("Failed to parse contract.json")?
+            }
+            ContractFormat::Toml => {
+                toml::from_str(content).context("Failed to parse contract.toml")?
+            }
+        };
+        let raw = migration::upgrade(raw)?;
+        serde_json::from_value(raw)
+            .with_context(|| format!("Failed to parse contract.{}", self.extension()))
+    }
+}
+
+impl std::fmt::Display for ContractFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
 /// Contract validation result
 #[derive(Debug, Clone)]
 pub struct ContractValidation {
@@ -214,9 +517,13 @@ pub struct ContractValidation {
     pub warnings: Vec<String>,
 }
 
-/// Get path to contract.json
+fn unknown_requirement(id: &str) -> anyhow::Error {
+    crate::error::validation(format!("No requirement with ID {}", id))
+}
+
+/// Get path to the default contract (contract.json or contract.toml)
 pub fn contract_path() -> PathBuf {
-    workspace::contracts_path().join("contract.json")
+    named_contract_path(None)
 }
 
 /// Get path to contract.lock
@@ -224,34 +531,199 @@ pub fn contract_lock_path() -> PathBuf {
     workspace::workspace_path().join("contract.lock")
 }
 
+/// Directory holding a contract; named specs live under `contracts/<name>/`
+fn contract_dir(name: Option<&str>) -> PathBuf {
+    match name {
+        Some(name) => workspace::contracts_path().join(name),
+        None => workspace::contracts_path(),
+    }
+}
+
+/// Path of a contract file in the given format
+pub fn contract_path_as(name: Option<&str>, format: ContractFormat) -> PathBuf {
+    contract_dir(name).join(format!("contract.{}", format.extension()))
+}
+
+/// Path and format of the contract file on disk, if there is one
+pub fn find_contract(name: Option<&str>) -> Option<(PathBuf, ContractFormat)> {
+    ContractFormat::all()
+        .into_iter()
+        .map(|format| (contract_path_as(name, format), format))
+        .find(|(path, _)| path.exists())
+}
+
+/// Get path to a contract, falling back to contract.json when none exists yet
+pub fn named_contract_path(name: Option<&str>) -> PathBuf {
+    find_contract(name)
+        .map(|(path, _)| path)
+        .unwrap_or_else(|| contract_path_as(name, ContractFormat::Json))
+}
+
+/// Get path to a contract lock; named specs use `contract.<name>.lock`
+pub fn named_lock_path(name: Option<&str>) -> PathBuf {
+    match name {
+        Some(name) => workspace::workspace_path().join(format!("contract.{}.lock", name)),
+        None => contract_lock_path(),
+    }
+}
+
+/// Validate a contract name so it is safe to use as a directory name
+pub fn validate_name(name: &str) -> Result<()> {
+    crate::security::validate_filename(name)?;
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid contract name '{}': use letters, numbers, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// List named contracts in the workspace, sorted by name
+pub fn list_named_contracts() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(workspace::contracts_path()) else {
+        return vec![];
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| find_contract(Some(name)).is_some())
+        .collect();
+    names.sort();
+    names
+}
+
+/// First 12 characters of a hash for display, or all of a shorter one
+/// (e.g. a hand-edited lock)
+pub fn short_hash(hash: &str) -> &str {
+    hash.get(..12).unwrap_or(hash)
+}
+
+/// A locked contract whose content no longer matches its lock
+#[derive(Debug, Clone)]
+pub struct LockMismatch {
+    /// Hash recorded in contract.lock
+    pub lock_hash: String,
+    /// Hash of the contract file as it is now (None if it can't be parsed)
+    pub contract_hash: Option<String>,
+}
+
+/// Check that the workflow contract still matches its lock
+///
+/// Only applies once the workflow is at or past `ContractLocked`; before that
+/// (e.g. after `reset --to ContractDrafted`) the draft is expected to differ.
+pub async fn check_lock_integrity() -> Result<Option<LockMismatch>> {
+    let Ok(state) = workspace::load_state().await else {
+        return Ok(None);
+    };
+    if !state
+        .current_state
+        .is_at_least(crate::state::State::ContractLocked)
+        || !contract_lock_path().exists()
+    {
+        return Ok(None);
+    }
+
+    let lock = load_lock().await?;
+    let contract_hash = match load_contract().await {
+        Ok(contract) => Some(contract.generate_hash(&lock.tool_version)?),
+        Err(_) => None,
+    };
+    if contract_hash.as_deref() == Some(lock.hash.as_str()) {
+        return Ok(None);
+    }
+
+    Ok(Some(LockMismatch {
+        lock_hash: lock.hash,
+        contract_hash,
+    }))
+}
+
 /// Load contract from file
 pub async fn load_contract() -> Result<Contract> {
-    let path = contract_path();
-    if !path.exists() {
-        anyhow::bail!("No contract found. Create one with 'vibeanvil contract create'");
-    }
+    load_named_contract(None).await
+}
 
-    let content = fs::read_to_string(&path)
+/// Load the default or a named contract, detecting its format from the file extension
+pub async fn load_named_contract(name: Option<&str>) -> Result<Contract> {
+    let Some((_, format)) = find_contract(name) else {
+        match name {
+            Some(name) => anyhow::bail!(
+                "No contract named '{}'. Create one with 'vibeanvil contract create --name {}'",
+                name,
+                name
+            ),
+            None => {
+                anyhow::bail!("No contract found. Create one with 'vibeanvil contract create'")
+            }
+        }
+    };
+    load_contract_as(name, format).await
+}
+
+/// Load a contract from the file of the given format
+pub async fn load_contract_as(name: Option<&str>, format: ContractFormat) -> Result<Contract> {
+    let content = fs::read_to_string(contract_path_as(name, format))
         .await
         .context("Failed to read contract file")?;
-
-    serde_json::from_str(&content).context("Failed to parse contract.json")
+    format.parse(&content)
 }
 
 /// Save contract to file
 pub async fn save_contract(contract: &Contract) -> Result<()> {
-    let path = contract_path();
+    save_named_contract(contract, None).await
+}
+
+/// Save the default or a named contract
+///
+/// Keeps the format of the existing file; new contracts use the format the
+/// workspace was initialized with.
+pub async fn save_named_contract(contract: &Contract, name: Option<&str>) -> Result<()> {
+    let format = match find_contract(name) {
+        Some((_, format)) => format,
+        None => workspace::load_state()
+            .await
+            .map(|s| s.contract_format)
+            .unwrap_or_default(),
+    };
+    save_contract_as(contract, name, format).await
+}
+
+/// Save a contract in the given format, removing any copy in the other format
+pub async fn save_contract_as(
+    contract: &Contract,
+    name: Option<&str>,
+    format: ContractFormat,
+) -> Result<()> {
+    let path = contract_path_as(name, format);
     fs::create_dir_all(path.parent().unwrap()).await?;
 
-    let content = serde_json::to_string_pretty(contract)?;
+    let content = format.serialize(contract)?;
     fs::write(&path, content)
         .await
-        .context("Failed to write contract.json")
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    for other in ContractFormat::all().into_iter().filter(|f| *f != format) {
+        let stale = contract_path_as(name, other);
+        if stale.exists() {
+            fs::remove_file(&stale).await?;
+        }
+    }
+    Ok(())
 }
 
 /// Save contract lock file
 pub async fn save_lock(lock: &ContractLock) -> Result<()> {
-    let path = contract_lock_path();
+    save_named_lock(lock, None).await
+}
+
+/// Save the lock of the default or a named contract
+pub async fn save_named_lock(lock: &ContractLock, name: Option<&str>) -> Result<()> {
+    let path = named_lock_path(name);
     let content = serde_json::to_string_pretty(lock)?;
     fs::write(&path, content)
         .await
@@ -260,7 +732,12 @@ pub async fn save_lock(lock: &ContractLock) -> Result<()> {
 
 /// Load contract lock file
 pub async fn load_lock() -> Result<ContractLock> {
-    let path = contract_lock_path();
+    load_named_lock(None).await
+}
+
+/// Load the lock of the default or a named contract
+pub async fn load_named_lock(name: Option<&str>) -> Result<ContractLock> {
+    let path = named_lock_path(name);
     if !path.exists() {
         anyhow::bail!("Contract is not locked");
     }
@@ -269,13 +746,189 @@ pub async fn load_lock() -> Result<ContractLock> {
         .await
         .context("Failed to read contract.lock")?;
 
-    serde_json::from_str(&content).context("Failed to parse contract.lock")
+    let lock: ContractLock =
+        serde_json::from_str(&content).context("Failed to parse contract.lock")?;
+    if lock.is_legacy() {
+        return migrate_legacy_lock(lock, name).await;
+    }
+    Ok(lock)
+}
+
+/// Re-hash a lock written before locks were hashed in their locked form
+///
+/// Older releases hashed the contract while it was still a draft, before
+/// stamping it locked, so the contract as saved can never match that hash.
+/// The lock is re-hashed over the contract as it is now and saved with a
+/// snapshot, which also marks it migrated. Edits made before the migration
+/// can't be detected.
+async fn migrate_legacy_lock(mut lock: ContractLock, name: Option<&str>) -> Result<ContractLock> {
+    let Ok(contract) = load_named_contract(name).await else {
+        return Ok(lock);
+    };
+    if !contract.is_locked() {
+        return Ok(lock);
+    }
+
+    lock.hash = contract.generate_hash(&lock.tool_version)?;
+    lock.contract = Some(contract);
+    save_named_lock(&lock, name).await?;
+    eprintln!(
+        "Note: {} was written by an older version; re-hashed it over the current contract.",
+        named_lock_path(name)
+            .file_name()
+            .map_or_else(|| "contract.lock".into(), |f| f.to_string_lossy())
+    );
+    Ok(lock)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_short_hash_tolerates_short_input() {
+        assert_eq!(short_hash("3f786850e387550fdab8"), "3f786850e387");
+        assert_eq!(short_hash("abc"), "abc");
+        assert_eq!(short_hash(""), "");
+    }
+
+    #[test]
+    fn test_lock_frozen_at() {
+        let mut contract = Contract::new("Frozen");
+        contract.description = "Release candidate".to_string();
+        contract.add_goal("Ship");
+        let mut lock = contract.lock("1.0.0").unwrap();
+        let now = chrono::Utc::now();
+        assert_eq!(lock.frozen_at(now), None);
+
+        let until = now + chrono::Duration::days(7);
+        lock.frozen_until = Some(until);
+        assert_eq!(lock.frozen_at(now), Some(until));
+        assert_eq!(lock.frozen_at(until), None);
+        assert_eq!(lock.frozen_at(until + chrono::Duration::seconds(1)), None);
+    }
+
+    #[test]
+    fn test_requirement_add_update_remove() {
+        let mut contract = Contract::new("Reqs");
+        contract
+            .try_add_requirement("REQ-1", "Core", Priority::Must)
+            .unwrap();
+        contract
+            .try_add_requirement("REQ-2", "Extra", Priority::Could)
+            .unwrap();
+        assert!(contract
+            .try_add_requirement("REQ-1", "Dup", Priority::Must)
+            .is_err());
+        assert!(contract
+            .try_add_requirement("  ", "Blank", Priority::Must)
+            .is_err());
+
+        contract
+            .update_requirement("REQ-2", None, Some(Priority::Should))
+            .unwrap();
+        assert_eq!(contract.requirements[1].description, "Extra");
+        assert_eq!(contract.requirements[1].priority, Priority::Should);
+        assert!(contract
+            .update_requirement("REQ-9", Some("x"), None)
+            .is_err());
+
+        contract.requirements[1].depends_on = vec!["REQ-1".into()];
+        let err = contract.remove_requirement("REQ-1").unwrap_err();
+        assert!(err.to_string().contains("dependency of REQ-2"));
+        assert_eq!(contract.remove_requirement("REQ-2").unwrap().id, "REQ-2");
+        assert_eq!(contract.remove_requirement("REQ-1").unwrap().id, "REQ-1");
+        assert!(contract.requirements.is_empty());
+    }
+
+    #[test]
+    fn test_validate_contract_name() {
+        assert!(validate_name("api").is_ok());
+        assert!(validate_name("web_v2-beta").is_ok());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn test_contract_format_round_trip_keeps_hash() {
+        let mut contract = Contract::new("Todo");
+        contract.description = "A todo app".into();
+        contract.add_goal("Sync");
+        contract.add_requirement("REQ-1", "Store todos", Priority::Must);
+        contract.supersedes = Some("abc123".into());
+        let hash = contract.generate_hash("0.1.0").unwrap();
+
+        for format in ContractFormat::all() {
+            let text = format.serialize(&contract).unwrap();
+            let parsed = format.parse(&text).unwrap();
+            assert_eq!(parsed.requirements, contract.requirements);
+            assert_eq!(parsed.generate_hash("0.1.0").unwrap(), hash);
+        }
+
+        assert_eq!(
+            ContractFormat::from_path(Path::new("contracts/contract.TOML")),
+            Some(ContractFormat::Toml)
+        );
+        assert_eq!(ContractFormat::from_path(Path::new("contract.yaml")), None);
+        assert!(ContractFormat::Toml.parse("{\"project_name\": 1}").is_err());
+    }
+
+    fn with_deps(deps: &[(&str, &[&str])]) -> Contract {
+        let mut contract = Contract::new("Deps");
+        for (id, depends_on) in deps {
+            contract.add_requirement(id, "Requirement", Priority::Must);
+            contract.requirements.last_mut().unwrap().depends_on =
+                depends_on.iter().map(|d| d.to_string()).collect();
+        }
+        contract
+    }
+
+    #[test]
+    fn test_requirement_deps_three_node_cycle() {
+        let contract = with_deps(&[
+            ("REQ-1", &["REQ-2"]),
+            ("REQ-2", &["REQ-3"]),
+            ("REQ-3", &["REQ-1"]),
+            ("REQ-4", &[]),
+        ]);
+        let err = contract.validate_requirement_deps().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Requirement dependency cycle: REQ-1 → REQ-2 → REQ-3 → REQ-1"
+        );
+        assert!(contract
+            .validate()
+            .errors
+            .iter()
+            .any(|e| e.contains("REQ-3 → REQ-1")));
+
+        let self_loop = with_deps(&[("REQ-1", &["REQ-1"])]);
+        assert!(self_loop
+            .validate_requirement_deps()
+            .unwrap_err()
+            .to_string()
+            .ends_with("REQ-1 → REQ-1"));
+    }
+
+    #[test]
+    fn test_requirement_deps_diamond_and_unknown() {
+        // REQ-4 needs REQ-2 and REQ-3, which both need REQ-1
+        let diamond = with_deps(&[
+            ("REQ-4", &["REQ-2", "REQ-3"]),
+            ("REQ-2", &["REQ-1"]),
+            ("REQ-3", &["REQ-1"]),
+            ("REQ-1", &[]),
+        ]);
+        assert!(diamond.validate_requirement_deps().is_ok());
+
+        let unknown = with_deps(&[("REQ-1", &["REQ-9"])]);
+        assert_eq!(
+            unknown.validate_requirement_deps().unwrap_err().to_string(),
+            "Requirement REQ-1 depends on unknown requirement REQ-9"
+        );
+    }
+
     #[test]
     fn test_contract_validation() {
         let mut contract = Contract::new("Test Project");
@@ -288,6 +941,36 @@ mod tests {
         assert!(validation.valid);
     }
 
+    #[test]
+    fn test_priority_wont_and_must_warning() {
+        let mut contract = Contract::new("Test");
+        contract.description = "Test description".to_string();
+        contract.add_goal("Goal 1");
+        contract.add_requirement("REQ-1", "Nice to have", Priority::Could);
+        contract.add_requirement("REQ-2", "Dark mode", Priority::Wont);
+
+        let validation = contract.validate();
+        assert!(validation.valid);
+        assert!(validation.warnings.iter().any(|w| w.contains("'must'")));
+
+        contract.add_requirement("REQ-3", "Core", Priority::Must);
+        assert!(!contract
+            .validate()
+            .warnings
+            .iter()
+            .any(|w| w.contains("'must'")));
+
+        let groups = contract.requirements_by_priority();
+        assert_eq!(groups[&Priority::Wont][0].id, "REQ-2");
+        assert_eq!(groups[&Priority::Must].len(), 1);
+        assert!(!groups.contains_key(&Priority::Should));
+
+        let json = serde_json::to_string(&contract.requirements[1]).unwrap();
+        assert!(json.contains("\"priority\":\"wont\""));
+        let back: Requirement = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.priority, Priority::Wont);
+    }
+
     #[test]
     fn test_contract_hash() {
         let mut contract = Contract::new("Test");
@@ -301,4 +984,33 @@ mod tests {
         let hash3 = contract.generate_hash("1.0.1").unwrap();
         assert_ne!(hash1, hash3);
     }
+
+    #[test]
+    fn test_amend_chains_locks() {
+        let mut contract = Contract::new("Test");
+        contract.description = "Test description".to_string();
+        contract.add_goal("Goal 1");
+        assert!(!contract
+            .canonical_json()
+            .unwrap()
+            .contains("contract_version"));
+
+        let first = contract.lock("1.0.0").unwrap();
+        assert_eq!(first.supersedes, None);
+
+        let mut amended = contract.amend(&first).unwrap();
+        assert_eq!(amended.status, ContractStatus::Draft);
+        assert_eq!(amended.contract_version, 2);
+        assert_eq!(amended.supersedes.as_deref(), Some(first.hash.as_str()));
+        assert!(amended.amend(&first).is_err());
+
+        amended.add_goal("Goal 2");
+        let second = amended.lock("1.0.0").unwrap();
+        assert_eq!(second.supersedes.as_deref(), Some(first.hash.as_str()));
+        assert_ne!(second.hash, first.hash);
+
+        let reloaded: Contract =
+            serde_json::from_str(&serde_json::to_string(&amended).unwrap()).unwrap();
+        assert_eq!(reloaded.contract_version, 2);
+    }
 }
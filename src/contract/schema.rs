@@ -28,6 +28,7 @@ pub struct SchemaProperties {
     pub acceptance_criteria: SchemaArrayProperty,
     pub constraints: SchemaArrayProperty,
     pub out_of_scope: SchemaArrayProperty,
+    pub tags: SchemaArrayProperty,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +135,14 @@ impl Default for ContractSchema {
                         reference: None,
                     }),
                 },
+                tags: SchemaArrayProperty {
+                    property_type: "array".to_string(),
+                    description: Some("Free-form labels (schema 1.1.0)".to_string()),
+                    items: Some(SchemaItems {
+                        item_type: Some("string".to_string()),
+                        reference: None,
+                    }),
+                },
             },
         }
     }
@@ -0,0 +1,130 @@
+//! Optional ed25519 signing of contract locks
+//!
+//! The signature covers the contract lock hash. Verification recomputes the
+//! hash from the current contract, so any edit after locking invalidates it.
+
+use anyhow::{Context, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::path::Path;
+
+use super::{Contract, ContractLock};
+
+/// Load a signing key from a file holding a 32-byte seed, either raw or hex-encoded
+pub fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read signing key: {}", path.display()))?;
+
+    let seed: Vec<u8> = match std::str::from_utf8(&bytes) {
+        Ok(text) if text.trim().len() == 64 => {
+            hex::decode(text.trim()).context("Signing key is not valid hex")?
+        }
+        _ => bytes,
+    };
+
+    let seed: [u8; 32] = seed.as_slice().try_into().map_err(|_| {
+        anyhow::anyhow!(
+            "Signing key must be a 32-byte ed25519 seed (raw or 64 hex characters), got {} bytes",
+            seed.len()
+        )
+    })?;
+
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Sign the lock hash, storing the signature and public key in the lock
+pub fn sign_lock(lock: &mut ContractLock, key: &SigningKey) {
+    let signature = key.sign(lock.hash.as_bytes());
+    lock.signature = Some(hex::encode(signature.to_bytes()));
+    lock.public_key = Some(hex::encode(key.verifying_key().to_bytes()));
+}
+
+/// Verify a signed lock against the current contract
+pub fn verify_lock(lock: &ContractLock, contract: &Contract) -> Result<()> {
+    let (Some(signature), Some(public_key)) = (&lock.signature, &lock.public_key) else {
+        anyhow::bail!("Contract lock is not signed");
+    };
+
+    let key_bytes: [u8; 32] = hex::decode(public_key)
+        .context("Invalid public key encoding in contract.lock")?
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key in contract.lock must be 32 bytes"))?;
+    let verifying_key =
+        VerifyingKey::from_bytes(&key_bytes).context("Invalid ed25519 public key")?;
+
+    let sig_bytes: [u8; 64] = hex::decode(signature)
+        .context("Invalid signature encoding in contract.lock")?
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Signature in contract.lock must be 64 bytes"))?;
+    let signature = Signature::from_bytes(&sig_bytes);
+
+    let hash = contract.generate_hash(&lock.tool_version)?;
+    verifying_key
+        .verify(hash.as_bytes(), &signature)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Signature verification failed: contract does not match the signed lock"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked_contract() -> (Contract, ContractLock) {
+        let mut contract = Contract::new("Signed");
+        contract.description = "A signed contract".to_string();
+        contract.add_goal("Ship it");
+        let lock = contract.lock("1.0.0").unwrap();
+        (contract, lock)
+    }
+
+    fn test_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    #[test]
+    fn test_valid_signature_verifies() {
+        let (contract, mut lock) = locked_contract();
+        sign_lock(&mut lock, &test_key());
+
+        assert!(lock.signature.is_some());
+        assert!(lock.public_key.is_some());
+        verify_lock(&lock, &contract).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_contract_fails_verification() {
+        let (mut contract, mut lock) = locked_contract();
+        sign_lock(&mut lock, &test_key());
+
+        contract.goals.push("Sneaky extra scope".to_string());
+        let err = verify_lock(&lock, &contract).unwrap_err();
+        assert!(err.to_string().contains("verification failed"));
+    }
+
+    #[test]
+    fn test_unsigned_lock_is_rejected() {
+        let (contract, lock) = locked_contract();
+        assert!(verify_lock(&lock, &contract).is_err());
+    }
+
+    #[test]
+    fn test_load_signing_key_accepts_hex_and_raw() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let hex_path = temp.path().join("key.hex");
+        std::fs::write(&hex_path, format!("{}\n", hex::encode([7u8; 32]))).unwrap();
+        let raw_path = temp.path().join("key.bin");
+        std::fs::write(&raw_path, [7u8; 32]).unwrap();
+
+        let from_hex = load_signing_key(&hex_path).unwrap();
+        let from_raw = load_signing_key(&raw_path).unwrap();
+        assert_eq!(from_hex.to_bytes(), from_raw.to_bytes());
+
+        let short_path = temp.path().join("short");
+        std::fs::write(&short_path, [1u8; 5]).unwrap();
+        assert!(load_signing_key(&short_path).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Typed errors and the process exit-code contract
+//!
+//! Commands keep returning `anyhow::Result`; failures that scripts need to
+//! branch on are raised as a `VibeError` somewhere in the error chain and
+//! mapped to a stable exit code in `main`.
+
+use thiserror::Error;
+
+/// Command succeeded
+pub const EXIT_SUCCESS: u8 = 0;
+/// Unclassified failure
+pub const EXIT_FAILURE: u8 = 1;
+/// Workflow is in the wrong state or the transition is not allowed
+pub const EXIT_INVALID_STATE: u8 = 2;
+/// Input or contract failed validation
+pub const EXIT_VALIDATION: u8 = 3;
+/// The selected AI provider is not installed or configured
+pub const EXIT_PROVIDER_UNAVAILABLE: u8 = 4;
+/// Network request failed or the machine is offline
+pub const EXIT_NETWORK: u8 = 5;
+
+/// Exit code table shown in `--help`
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Unclassified failure
+  2  Invalid workflow state or transition
+  3  Validation failure
+  4  Provider unavailable
+  5  Network error or offline";
+
+/// Errors with a stable exit code
+#[derive(Debug, Error)]
+pub enum VibeError {
+    #[error("{0}")]
+    InvalidState(String),
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    ProviderUnavailable(String),
+    #[error("{0}")]
+    Network(String),
+}
+
+impl VibeError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            VibeError::InvalidState(_) => EXIT_INVALID_STATE,
+            VibeError::Validation(_) => EXIT_VALIDATION,
+            VibeError::ProviderUnavailable(_) => EXIT_PROVIDER_UNAVAILABLE,
+            VibeError::Network(_) => EXIT_NETWORK,
+        }
+    }
+}
+
+/// Workflow precondition or transition failure
+pub fn invalid_state(message: impl Into<String>) -> anyhow::Error {
+    VibeError::InvalidState(message.into()).into()
+}
+
+/// Validation failure
+pub fn validation(message: impl Into<String>) -> anyhow::Error {
+    VibeError::Validation(message.into()).into()
+}
+
+/// Provider not installed or not configured
+pub fn provider_unavailable(message: impl Into<String>) -> anyhow::Error {
+    VibeError::ProviderUnavailable(message.into()).into()
+}
+
+/// Network request failure
+pub fn network(message: impl Into<String>) -> anyhow::Error {
+    VibeError::Network(message.into()).into()
+}
+
+/// Exit code for an error, based on the first classified error in its chain
+pub fn exit_code(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(vibe) = cause.downcast_ref::<VibeError>() {
+            return vibe.exit_code();
+        }
+        if cause.downcast_ref::<reqwest::Error>().is_some() {
+            return EXIT_NETWORK;
+        }
+    }
+    EXIT_FAILURE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_finds_vibe_error_in_chain() {
+        let err = Err::<(), _>(invalid_state("Build not done"))
+            .context("review pass")
+            .unwrap_err();
+        assert_eq!(exit_code(&err), EXIT_INVALID_STATE);
+        assert_eq!(err.root_cause().to_string(), "Build not done");
+    }
+
+    #[test]
+    fn test_exit_code_defaults_to_failure() {
+        assert_eq!(exit_code(&anyhow::anyhow!("boom")), EXIT_FAILURE);
+        assert_eq!(exit_code(&validation("bad")), EXIT_VALIDATION);
+        assert_eq!(
+            exit_code(&provider_unavailable("missing")),
+            EXIT_PROVIDER_UNAVAILABLE
+        );
+    }
+}
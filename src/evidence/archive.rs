@@ -0,0 +1,304 @@
+//! Evidence archives: a session's evidence packed into one `.tar.gz` for sharing
+//!
+//! Entries live under a top-level directory named after the session
+//! (`<session_id>/manifest.json`, `<session_id>/build_log_….txt`), so the
+//! archive never carries the workspace's absolute paths.
+//!
+//! A bundle is an archive handed off for audit: it gets a `<bundle>.sha256`
+//! sidecar in `sha256sum` format. The sidecar isn't signed, so it only
+//! detects accidental corruption; whoever can alter the bundle can rewrite
+//! the sidecar too.
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use super::{content_hash, Evidence, EvidenceCollector};
+use crate::{error, workspace};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Summary of an evidence archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArchiveManifest {
+    /// Session the evidence was captured in
+    pub session_id: String,
+    /// SHA-256 of the `.tar.gz` file
+    pub sha256: String,
+    /// Files in the archive, including `manifest.json`
+    pub file_count: usize,
+}
+
+impl EvidenceCollector {
+    /// Pack every file in the evidence directory into a gzip-compressed tar
+    pub fn archive(&self, output_path: &Path) -> Result<ArchiveManifest> {
+        if !self.evidence_dir.join(MANIFEST_FILE).exists() {
+            return Err(error::invalid_state(format!(
+                "Session {} has no evidence to archive",
+                self.session_id()
+            )));
+        }
+
+        let mut files = vec![];
+        collect_files(&self.evidence_dir, &self.evidence_dir, &mut files)?;
+        files.sort();
+
+        let output = File::create(output_path)
+            .with_context(|| format!("Failed to create {}", output_path.display()))?;
+        let mut builder = tar::Builder::new(GzEncoder::new(output, Compression::default()));
+        for relative in &files {
+            builder
+                .append_path_with_name(
+                    self.evidence_dir.join(relative),
+                    Path::new(self.session_id()).join(relative),
+                )
+                .with_context(|| format!("Failed to add {} to archive", relative.display()))?;
+        }
+        builder.into_inner()?.finish()?;
+
+        Ok(ArchiveManifest {
+            session_id: self.session_id().to_string(),
+            sha256: content_hash(&std::fs::read(output_path)?),
+            file_count: files.len(),
+        })
+    }
+
+    /// `archive`, plus a `.sha256` sidecar next to it (`bundle_checksum_path`)
+    pub fn export_bundle(&self, out: &Path) -> Result<ArchiveManifest> {
+        let manifest = self.archive(out)?;
+        let file_name = out
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let checksum_path = bundle_checksum_path(out);
+        std::fs::write(
+            &checksum_path,
+            format!("{}  {}\n", manifest.sha256, file_name),
+        )
+        .with_context(|| format!("Failed to write {}", checksum_path.display()))?;
+        Ok(manifest)
+    }
+
+    /// Restore an archive made by `archive` into this collector's (empty) evidence directory
+    ///
+    /// The returned manifest names the session the archive was made from;
+    /// the restored manifest's paths point at this collector's session.
+    pub fn unpack(&self, archive_path: &Path) -> Result<ArchiveManifest> {
+        if self.evidence_dir.join(MANIFEST_FILE).exists() {
+            return Err(error::invalid_state(format!(
+                "Session {} already has evidence",
+                self.session_id()
+            )));
+        }
+
+        let bytes = std::fs::read(archive_path)
+            .with_context(|| format!("Failed to read {}", archive_path.display()))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes.as_slice()));
+
+        let mut source_session = None;
+        let mut file_count = 0;
+        let mut has_manifest = false;
+        for entry in archive
+            .entries()
+            .map_err(|e| error::validation(format!("Not an evidence archive: {}", e)))?
+        {
+            let mut entry =
+                entry.map_err(|e| error::validation(format!("Corrupt evidence archive: {}", e)))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.into_owned();
+            let (session, relative) = split_entry_path(&path).ok_or_else(|| {
+                error::validation(format!(
+                    "Unsafe path in evidence archive: {}",
+                    path.display()
+                ))
+            })?;
+            if source_session.get_or_insert_with(|| session.clone()) != &session {
+                return Err(error::validation(
+                    "Evidence archive holds more than one session",
+                ));
+            }
+
+            let target = self.evidence_dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry
+                .unpack(&target)
+                .with_context(|| format!("Failed to extract {}", relative.display()))?;
+            has_manifest |= relative == Path::new(MANIFEST_FILE);
+            file_count += 1;
+        }
+
+        if !has_manifest {
+            return Err(error::validation("Evidence archive has no manifest.json"));
+        }
+        self.rebase_manifest()?;
+
+        Ok(ArchiveManifest {
+            session_id: source_session.unwrap_or_default(),
+            sha256: content_hash(&bytes),
+            file_count,
+        })
+    }
+
+    /// Point every manifest entry's workspace-relative path at this session
+    fn rebase_manifest(&self) -> Result<()> {
+        let manifest_path = self.evidence_dir.join(MANIFEST_FILE);
+        let content = std::fs::read_to_string(&manifest_path)?;
+        let mut evidence: Vec<Evidence> = serde_json::from_str(&content)
+            .map_err(|e| error::validation(format!("Malformed evidence manifest: {}", e)))?;
+        for item in &mut evidence {
+            item.path = Some(workspace::relative_evidence_path(
+                self.session_id(),
+                &item.filename,
+            ));
+        }
+        std::fs::write(manifest_path, serde_json::to_string_pretty(&evidence)?)?;
+        Ok(())
+    }
+}
+
+/// Sidecar holding a bundle's SHA-256: `<bundle>.sha256`
+pub fn bundle_checksum_path(bundle: &Path) -> PathBuf {
+    let mut name = bundle.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Recompute a bundle's SHA-256 and compare it with its `.sha256` sidecar
+///
+/// Fails when the bundle or sidecar can't be read; `Ok(false)` means the
+/// bundle changed since it was exported. A bundle whose sidecar was
+/// rewritten along with it still matches.
+pub fn verify_bundle(path: &Path) -> Result<bool> {
+    let checksum_path = bundle_checksum_path(path);
+    let sidecar = std::fs::read_to_string(&checksum_path).map_err(|_| {
+        error::validation(format!(
+            "No checksum file for {} (expected {})",
+            path.display(),
+            checksum_path.display()
+        ))
+    })?;
+    let expected = sidecar
+        .split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            error::validation(format!(
+                "Malformed checksum file {}",
+                checksum_path.display()
+            ))
+        })?;
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content_hash(&bytes).eq_ignore_ascii_case(expected))
+}
+
+/// Files under `dir`, relative to `root`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if path.is_file() {
+            files.push(path.strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Split `<session>/<relative path>`, rejecting absolute paths and `..`
+fn split_entry_path(path: &Path) -> Option<(String, PathBuf)> {
+    let mut components = path.components();
+    let Some(Component::Normal(session)) = components.next() else {
+        return None;
+    };
+    let relative: PathBuf = components
+        .map(|c| match c {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some((session.to_string_lossy().into_owned(), relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let source = EvidenceCollector::with_dir("s1", temp.path().join("s1"));
+        tokio::fs::create_dir_all(&source.evidence_dir)
+            .await
+            .unwrap();
+        source.capture_test_log("ok: 3 passed").await.unwrap();
+        source.capture_lint_log("no warnings").await.unwrap();
+
+        let archive_path = temp.path().join("evidence.tar.gz");
+        let packed = source.archive(&archive_path).unwrap();
+        assert_eq!(packed.session_id, "s1");
+        assert_eq!(packed.file_count, 3);
+
+        let target = EvidenceCollector::with_dir("s2", temp.path().join("s2"));
+        let unpacked = target.unpack(&archive_path).unwrap();
+        assert_eq!(unpacked, packed);
+        let manifest = target.load_manifest().await.unwrap();
+        assert_eq!(manifest.len(), 2);
+        for item in &manifest {
+            let path = item.path.as_deref().unwrap();
+            assert!(path.starts_with("sessions/s2/evidence/"), "{}", path);
+        }
+        assert!(target.verify_manifest().await.unwrap().is_empty());
+
+        // Restoring twice into the same session is refused
+        assert!(target.unpack(&archive_path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bundle_detects_corruption() {
+        let temp = TempDir::new().unwrap();
+        let collector = EvidenceCollector::with_dir("s1", temp.path().join("s1"));
+        tokio::fs::create_dir_all(&collector.evidence_dir)
+            .await
+            .unwrap();
+        collector.capture_test_log("ok: 3 passed").await.unwrap();
+
+        let bundle = temp.path().join("evidence.tar.gz");
+        let manifest = collector.export_bundle(&bundle).unwrap();
+        assert_eq!(manifest.file_count, 2);
+        let sidecar = std::fs::read_to_string(bundle_checksum_path(&bundle)).unwrap();
+        assert!(sidecar.ends_with("  evidence.tar.gz\n"));
+        assert!(verify_bundle(&bundle).unwrap());
+
+        let mut bytes = std::fs::read(&bundle).unwrap();
+        bytes.push(0);
+        std::fs::write(&bundle, bytes).unwrap();
+        assert!(!verify_bundle(&bundle).unwrap());
+
+        std::fs::remove_file(bundle_checksum_path(&bundle)).unwrap();
+        assert!(verify_bundle(&bundle).is_err());
+    }
+
+    #[test]
+    fn test_split_entry_path_rejects_escapes() {
+        assert_eq!(
+            split_entry_path(Path::new("s1/manifest.json")),
+            Some(("s1".to_string(), PathBuf::from("manifest.json")))
+        );
+        assert_eq!(split_entry_path(Path::new("s1/../../etc/passwd")), None);
+        assert_eq!(split_entry_path(Path::new("/etc/passwd")), None);
+        assert_eq!(split_entry_path(Path::new("s1")), None);
+    }
+}
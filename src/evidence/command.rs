@@ -0,0 +1,140 @@
+//! Running a command for evidence: output capture with a timeout
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+
+/// Default limit for `capture_command`
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How a captured command ran, recorded in the evidence manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRun {
+    /// Program and arguments as run
+    pub command: String,
+    /// Exit code; absent when the process was killed or ended by a signal
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    /// The process was killed after running past the timeout
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+impl CommandRun {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// `exit 0, 1.2s` or `killed after 600s timeout`
+    pub fn outcome(&self) -> String {
+        let secs = self.duration_ms as f64 / 1000.0;
+        match (self.timed_out, self.exit_code) {
+            (true, _) => format!("killed after {:.0}s timeout", secs),
+            (false, Some(code)) => format!("exit {}, {:.1}s", code, secs),
+            (false, None) => format!("terminated by signal, {:.1}s", secs),
+        }
+    }
+}
+
+/// Output of a finished (or killed) command
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub run: CommandRun,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run `program` with `args`, killing it once `timeout` elapses
+///
+/// Output written before a kill is kept. Fails only if the process can't be
+/// started.
+pub async fn run_command(program: &str, args: &[&str], timeout: Duration) -> Result<CommandOutput> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    let stdout = Arc::new(Mutex::new(Vec::new()));
+    let stderr = Arc::new(Mutex::new(Vec::new()));
+    let readers = [
+        read_into(child.stdout.take(), stdout.clone()),
+        read_into(child.stderr.take(), stderr.clone()),
+    ];
+
+    let start = Instant::now();
+    let (exit_code, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => {
+            let status = status.with_context(|| format!("Failed to wait for {}", program))?;
+            // A grandchild can keep the pipes open past the exit; read until
+            // the same deadline at most
+            let deadline = tokio::time::Instant::from_std(start) + timeout;
+            for mut reader in readers {
+                if tokio::time::timeout_at(deadline, &mut reader)
+                    .await
+                    .is_err()
+                {
+                    reader.abort();
+                }
+            }
+            (status.code(), false)
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            // Pipes may be held open by grandchildren; keep what was read so far
+            for reader in readers {
+                reader.abort();
+            }
+            (None, true)
+        }
+    };
+
+    let text = |buf: &Arc<Mutex<Vec<u8>>>| {
+        let buf = buf.lock().unwrap_or_else(|e| e.into_inner());
+        String::from_utf8_lossy(&buf).into_owned()
+    };
+    let command = std::iter::once(program)
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok(CommandOutput {
+        run: CommandRun {
+            command,
+            exit_code,
+            duration_ms: start.elapsed().as_millis() as u64,
+            timed_out,
+        },
+        stdout: text(&stdout),
+        stderr: text(&stderr),
+    })
+}
+
+/// Copy a pipe into `buf` as data arrives
+fn read_into<R>(pipe: Option<R>, buf: Arc<Mutex<Vec<u8>>>) -> JoinHandle<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut chunk = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut chunk).await {
+            if n == 0 {
+                break;
+            }
+            buf.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .extend_from_slice(&chunk[..n]);
+        }
+    })
+}
@@ -0,0 +1,292 @@
+//! Test coverage reports: format detection and summary extraction
+//!
+//! Only the totals are read, enough to describe a report in the evidence
+//! manifest. The report itself is stored as-is (after redaction).
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::error;
+
+/// Coverage report formats understood by `capture_coverage_report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFormat {
+    /// `lcov.info` tracefile
+    Lcov,
+    /// Cobertura XML (`cobertura.xml`, `coverage.xml`)
+    Cobertura,
+    /// cargo-tarpaulin JSON report
+    Tarpaulin,
+    /// SimpleCov `.last_run.json`
+    SimpleCov,
+}
+
+impl fmt::Display for CoverageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CoverageFormat::Lcov => "lcov",
+            CoverageFormat::Cobertura => "cobertura",
+            CoverageFormat::Tarpaulin => "tarpaulin",
+            CoverageFormat::SimpleCov => "simplecov",
+        };
+        f.write_str(name)
+    }
+}
+
+impl CoverageFormat {
+    /// Guess the format from a report's file name
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".info") || name.ends_with(".lcov") {
+            Some(CoverageFormat::Lcov)
+        } else if name.ends_with(".xml") {
+            Some(CoverageFormat::Cobertura)
+        } else if name.contains("tarpaulin") && name.ends_with(".json") {
+            Some(CoverageFormat::Tarpaulin)
+        } else if name == ".last_run.json" || name == "last_run.json" {
+            Some(CoverageFormat::SimpleCov)
+        } else {
+            None
+        }
+    }
+
+    /// Extract the totals from a report in this format
+    pub fn summarize(self, content: &str) -> Result<CoverageSummary> {
+        let summary = match self {
+            CoverageFormat::Lcov => lcov(content),
+            CoverageFormat::Cobertura => cobertura(content),
+            CoverageFormat::Tarpaulin => tarpaulin(content),
+            CoverageFormat::SimpleCov => simplecov(content),
+        };
+        summary
+            .ok_or_else(|| error::validation(format!("Could not read a {} coverage summary", self)))
+    }
+}
+
+/// Totals from a coverage report
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageSummary {
+    /// Line coverage, 0–100
+    pub line_percent: f64,
+    /// Branch coverage, 0–100, for formats that record branches
+    pub branch_percent: Option<f64>,
+    /// Covered and coverable lines, when the report has counts
+    pub lines: Option<(u64, u64)>,
+    /// Covered and total branches, when the report has counts
+    pub branches: Option<(u64, u64)>,
+}
+
+impl CoverageSummary {
+    fn from_counts(lines: (u64, u64), branches: Option<(u64, u64)>) -> Self {
+        Self {
+            line_percent: percent(lines),
+            branch_percent: branches.map(percent),
+            lines: Some(lines),
+            branches,
+        }
+    }
+}
+
+impl fmt::Display for CoverageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lines {:.1}%", self.line_percent)?;
+        if let Some((covered, total)) = self.lines {
+            write!(f, " ({}/{})", covered, total)?;
+        }
+        if let Some(branch) = self.branch_percent {
+            write!(f, ", branches {:.1}%", branch)?;
+            if let Some((covered, total)) = self.branches {
+                write!(f, " ({}/{})", covered, total)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn percent((covered, total): (u64, u64)) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    }
+}
+
+/// Sum the per-file `LF`/`LH` (and `BRF`/`BRH`) records
+fn lcov(content: &str) -> Option<CoverageSummary> {
+    let (mut lf, mut lh, mut brf, mut brh) = (None::<u64>, 0, None::<u64>, 0);
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match key {
+            "LF" => lf = Some(lf.unwrap_or(0) + value),
+            "LH" => lh += value,
+            "BRF" => brf = Some(brf.unwrap_or(0) + value),
+            "BRH" => brh += value,
+            _ => {}
+        }
+    }
+    let branches = brf.filter(|total| *total > 0).map(|total| (brh, total));
+    Some(CoverageSummary::from_counts((lh, lf?), branches))
+}
+
+/// Read the totals from the root `<coverage>` element's attributes
+fn cobertura(content: &str) -> Option<CoverageSummary> {
+    let root = Regex::new(r"<coverage\b([^>]*)>").ok()?;
+    let attrs = root.captures(content)?.get(1)?.as_str();
+    let attr_re = Regex::new(r#"([\w-]+)\s*=\s*"([^"]*)""#).ok()?;
+    let attr = |name: &str| {
+        attr_re
+            .captures_iter(attrs)
+            .find(|c| &c[1] == name)
+            .map(|c| c[2].to_string())
+    };
+    let count = |name: &str| attr(name).and_then(|v| v.parse::<u64>().ok());
+    let rate = |name: &str| attr(name).and_then(|v| v.parse::<f64>().ok());
+
+    let branches = match (count("branches-covered"), count("branches-valid")) {
+        (Some(covered), Some(total)) if total > 0 => Some((covered, total)),
+        _ => None,
+    };
+    if let (Some(covered), Some(total)) = (count("lines-covered"), count("lines-valid")) {
+        return Some(CoverageSummary::from_counts((covered, total), branches));
+    }
+
+    // Older writers only record rates (0–1)
+    Some(CoverageSummary {
+        line_percent: rate("line-rate")? * 100.0,
+        branch_percent: branches
+            .map(percent)
+            .or_else(|| rate("branch-rate").filter(|r| *r > 0.0).map(|r| r * 100.0)),
+        lines: None,
+        branches,
+    })
+}
+
+/// Top-level `covered`/`coverable`, or the per-file counts summed
+fn tarpaulin(content: &str) -> Option<CoverageSummary> {
+    let report: serde_json::Value = serde_json::from_str(content).ok()?;
+    let counts =
+        |v: &serde_json::Value| Some((v.get("covered")?.as_u64()?, v.get("coverable")?.as_u64()?));
+
+    let lines = counts(&report).or_else(|| {
+        report
+            .get("files")?
+            .as_array()?
+            .iter()
+            .map(counts)
+            .try_fold((0, 0), |(c, t), file| file.map(|(fc, ft)| (c + fc, t + ft)))
+    })?;
+    Some(CoverageSummary::from_counts(lines, None))
+}
+
+/// `result.line` (or `result.covered_percent` from older SimpleCov) and `result.branch`
+fn simplecov(content: &str) -> Option<CoverageSummary> {
+    let report: serde_json::Value = serde_json::from_str(content).ok()?;
+    let result = report.get("result")?;
+    let line = result
+        .get("line")
+        .or_else(|| result.get("covered_percent"))?
+        .as_f64()?;
+    Some(CoverageSummary {
+        line_percent: line,
+        branch_percent: result.get("branch").and_then(|b| b.as_f64()),
+        lines: None,
+        branches: None,
+    })
+}
+
+/// Home directory prefixes in report paths (`/home/<user>`, `/Users/<user>`,
+/// `C:\Users\<user>`, `/root`) and what they are replaced with
+const HOME_PATH_PATTERNS: &[(&str, &str)] = &[
+    (r#"/home/[^/\s"'<>]+"#, "~"),
+    (r#"/Users/[^/\s"'<>]+"#, "~"),
+    (r#"(?i)\b[a-z]:[\\/]+Users[\\/]+[^\\/\s"'<>]+"#, "~"),
+    (r"(^|[^\w.~])/root\b", "${1}~"),
+    // tarpaulin stores paths as component arrays: ["/", "home", "<user>", ...]
+    (r#""/",\s*"(?:home|Users)",\s*"[^"]+""#, "\"~\""),
+];
+
+/// Replace home directory prefixes in file paths with `~`
+pub fn redact_home_paths(content: &str) -> (String, bool) {
+    let mut result = content.to_string();
+    let mut was_redacted = false;
+
+    for (pattern, replacement) in HOME_PATH_PATTERNS {
+        if let Ok(re) = Regex::new(pattern) {
+            if re.is_match(&result) {
+                was_redacted = true;
+                result = re.replace_all(&result, *replacement).to_string();
+            }
+        }
+    }
+
+    (result, was_redacted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format_from_file_name() {
+        let detect = |name: &str| CoverageFormat::detect(Path::new(name));
+        assert_eq!(detect("coverage/lcov.info"), Some(CoverageFormat::Lcov));
+        assert_eq!(detect("cobertura.xml"), Some(CoverageFormat::Cobertura));
+        assert_eq!(
+            detect("tarpaulin-report.json"),
+            Some(CoverageFormat::Tarpaulin)
+        );
+        assert_eq!(
+            detect("coverage/.last_run.json"),
+            Some(CoverageFormat::SimpleCov)
+        );
+        assert_eq!(detect("report.json"), None);
+    }
+
+    #[test]
+    fn test_lcov_sums_records() {
+        let content =
+            "SF:/home/alice/app/src/a.rs\nDA:1,1\nLF:4\nLH:3\nBRF:2\nBRH:1\nend_of_record\n\
+                       SF:/home/alice/app/src/b.rs\nLF:6\nLH:3\nend_of_record\n";
+        let summary = CoverageFormat::Lcov.summarize(content).unwrap();
+        assert_eq!(summary.lines, Some((6, 10)));
+        assert_eq!(summary.branches, Some((1, 2)));
+        assert_eq!(
+            summary.to_string(),
+            "lines 60.0% (6/10), branches 50.0% (1/2)"
+        );
+
+        assert!(CoverageFormat::Lcov.summarize("not a tracefile").is_err());
+    }
+
+    #[test]
+    fn test_cobertura_rates_without_counts() {
+        let content = r#"<?xml version="1.0"?><coverage line-rate="0.5" branch-rate="0" version="1"></coverage>"#;
+        let summary = CoverageFormat::Cobertura.summarize(content).unwrap();
+        assert_eq!(summary.to_string(), "lines 50.0%");
+    }
+
+    #[test]
+    fn test_redact_home_paths() {
+        let (out, redacted) = redact_home_paths(
+            "SF:/home/alice/app/a.rs\n<source>C:\\Users\\Bob\\app</source>\n/Users/carol/x\nSF:/root/app/b.rs\n\"path\":[\"/\",\"home\",\"alice\",\"app\"]",
+        );
+        assert!(redacted);
+        assert_eq!(
+            out,
+            "SF:~/app/a.rs\n<source>~\\app</source>\n~/x\nSF:~/app/b.rs\n\"path\":[\"~\",\"app\"]"
+        );
+
+        let (out, redacted) = redact_home_paths("SF:src/lib.rs\n/srv/app/root/x");
+        assert!(!redacted);
+        assert_eq!(out, "SF:src/lib.rs\n/srv/app/root/x");
+    }
+}
@@ -0,0 +1,191 @@
+//! Lifecycle hooks: user scripts run around workflow commands
+//!
+//! A script named `pre-<command>` or `post-<command>` (optionally with an
+//! extension, e.g. `pre-ship.sh`) in `.vibeanvil/hooks/` runs before or after
+//! that command. A failing `pre-*` hook aborts the command; a failing
+//! `post-*` hook only warns. Hook output is stored as redacted evidence.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::evidence::{redact_secrets, EvidenceCollector};
+use crate::workspace;
+
+/// When a hook runs relative to its command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPhase {
+    Pre,
+    Post,
+}
+
+impl HookPhase {
+    fn prefix(&self) -> &'static str {
+        match self {
+            HookPhase::Pre => "pre",
+            HookPhase::Post => "post",
+        }
+    }
+}
+
+/// Result of running one hook script
+#[derive(Debug)]
+pub struct HookRun {
+    pub name: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// Combined stdout and stderr, redacted
+    pub output: String,
+}
+
+/// Find the script for `<phase>-<command>`, if one exists
+pub fn find_hook(dir: &Path, phase: HookPhase, command: &str) -> Option<PathBuf> {
+    let name = format!("{}-{}", phase.prefix(), command);
+    let exact = dir.join(&name);
+    if exact.is_file() {
+        return Some(exact);
+    }
+
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.file_stem().is_some_and(|s| s == name.as_str()))
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// Run the hook for `command` in `phase`, if any
+///
+/// Returns an error when a `pre` hook fails so the command is not run.
+pub async fn run(phase: HookPhase, command: &str) -> Result<Option<HookRun>> {
+    let Some(script) = find_hook(&workspace::hooks_path(), phase, command) else {
+        return Ok(None);
+    };
+
+    let state = workspace::load_state().await.ok();
+    let name = format!("{}-{}", phase.prefix(), command);
+
+    let mut cmd = hook_command(&script);
+    cmd.env("VIBEANVIL_HOOK", &name)
+        .env("VIBEANVIL_COMMAND", command)
+        .kill_on_drop(true);
+    if let Some(state) = &state {
+        cmd.env("VIBEANVIL_STATE", state.current_state.to_string())
+            .env(
+                "VIBEANVIL_SESSION_ID",
+                state.current_session_id.as_deref().unwrap_or_default(),
+            )
+            .env(
+                "VIBEANVIL_SPEC_HASH",
+                state.spec_hash.as_deref().unwrap_or_default(),
+            );
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .with_context(|| format!("Failed to run hook {}", script.display()))?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    let (redacted, _) = redact_secrets(&combined);
+
+    let hook = HookRun {
+        name,
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        output: redacted,
+    };
+
+    for line in hook.output.lines() {
+        println!("  [{}] {}", hook.name, line);
+    }
+
+    // Evidence goes to the session active after the command (post) or before it (pre)
+    if let Some(session_id) = state.as_ref().and_then(|s| s.current_session_id.as_deref()) {
+        let collector = EvidenceCollector::new(session_id).await?;
+        collector
+            .capture_custom(
+                &format!("hook_{}", hook.name.replace('-', "_")),
+                &combined,
+                &format!(
+                    "Output of {} hook (exit {})",
+                    hook.name,
+                    exit_label(hook.exit_code)
+                ),
+            )
+            .await?;
+    }
+
+    if !hook.success {
+        let message = format!(
+            "{} hook failed (exit {})",
+            hook.name,
+            exit_label(hook.exit_code)
+        );
+        match phase {
+            HookPhase::Pre => anyhow::bail!("{}; aborting '{}'", message, command),
+            HookPhase::Post => crate::cli::style::warn(&message),
+        }
+    }
+
+    Ok(Some(hook))
+}
+
+fn exit_label(code: Option<i32>) -> String {
+    code.map_or_else(|| "signal".to_string(), |c| c.to_string())
+}
+
+/// Executable scripts run directly; anything else goes through the shell
+fn hook_command(script: &Path) -> Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let executable = std::fs::metadata(script)
+            .map(|m| m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false);
+        if executable {
+            Command::new(script)
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg(script);
+            cmd
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(script);
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_hook_matches_exact_name_or_extension() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("pre-ship.sh"), "exit 0").unwrap();
+        std::fs::write(dir.join("post-build"), "exit 0").unwrap();
+        std::fs::write(dir.join("pre-shipping"), "exit 0").unwrap();
+
+        assert_eq!(
+            find_hook(dir, HookPhase::Pre, "ship"),
+            Some(dir.join("pre-ship.sh"))
+        );
+        assert_eq!(
+            find_hook(dir, HookPhase::Post, "build"),
+            Some(dir.join("post-build"))
+        );
+        assert_eq!(find_hook(dir, HookPhase::Post, "ship"), None);
+        assert_eq!(
+            find_hook(&dir.join("missing"), HookPhase::Pre, "ship"),
+            None
+        );
+    }
+}
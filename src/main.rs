@@ -14,14 +14,20 @@
 
 use anyhow::Result;
 use clap::Parser;
+use std::process::ExitCode;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 mod audit;
 mod brain;
 mod build;
 mod cli;
 mod contract;
+mod error;
 mod evidence;
 mod guardrails;
+mod hooks;
 mod mcp;
 mod prompt;
 mod provider;
@@ -32,35 +38,238 @@ mod workspace;
 use cli::{ChatModeArg, Cli, Commands};
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // Initialize tracing (plus span timing when profiling)
+    let timings = cli.profile.then(cli::profile::SpanTimings::new);
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_filter(
+                    tracing_subscriber::EnvFilter::try_from_default_env()
+                        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+                ),
         )
-        .with_target(false)
+        .with(timings.clone().map(|t| {
+            // Only time our own phases, not spans from dependencies
+            cli::profile::ProfileLayer::new(t).with_filter(tracing_subscriber::filter::filter_fn(
+                |meta| meta.target().starts_with(env!("CARGO_CRATE_NAME")),
+            ))
+        }))
         .init();
 
-    let cli = Cli::parse();
+    if let Err(err) = recover_workspace_state().await {
+        eprintln!("Warning: state recovery failed: {:#}", err);
+    }
+
+    let result = run(cli.command).await;
+
+    if let Some(timings) = timings {
+        timings.print_report();
+    }
+
+    match result {
+        Ok(()) => ExitCode::from(error::EXIT_SUCCESS),
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::from(error::exit_code(&err))
+        }
+    }
+}
+
+async fn run(command: Commands) -> Result<()> {
+    guard_locked_contract(&command).await?;
+
+    let hook = hook_command(&command);
+    if let Some(name) = hook {
+        hooks::run(hooks::HookPhase::Pre, name).await?;
+    }
+
+    dispatch(command).await?;
+
+    if let Some(name) = hook {
+        hooks::run(hooks::HookPhase::Post, name).await?;
+    }
+    Ok(())
+}
+
+/// Offer to finish or clean up a state save interrupted by a crash
+async fn recover_workspace_state() -> Result<()> {
+    match workspace::recover_state().await? {
+        Some(workspace::StateRecovery::Restored) => {
+            eprintln!("Restored state.json from an unfinished save.")
+        }
+        Some(workspace::StateRecovery::Discarded) => {
+            eprintln!("Removed stale state.json.tmp.")
+        }
+        Some(workspace::StateRecovery::Kept) => eprintln!(
+            "Warning: found {} from an interrupted save; run interactively to recover it.",
+            workspace::state_tmp_path().display()
+        ),
+        None => {}
+    }
+    Ok(())
+}
+
+/// How a command is affected by a contract edited after locking
+#[derive(Debug, PartialEq, Eq)]
+enum ContractGuard {
+    /// Refuse to run: the command builds on the locked contract
+    Refuse,
+    /// Run, but warn that the contract no longer matches its lock
+    Warn,
+    /// Not affected (includes the commands used to resolve the mismatch)
+    Skip,
+}
 
-    match cli.command {
-        Commands::Init { force } => cli::init::run(force).await,
+fn contract_guard(command: &Commands) -> ContractGuard {
+    match command {
+        Commands::Plan { .. }
+        | Commands::Build(_)
+        | Commands::Snapshot { .. }
+        | Commands::Ship { .. }
+        | Commands::Tasks { .. }
+        | Commands::Implement { .. } => ContractGuard::Refuse,
+        Commands::Review { action, .. } => match action {
+            cli::ReviewAction::Status
+            | cli::ReviewAction::Archive
+            | cli::ReviewAction::Unpack
+            | cli::ReviewAction::Bundle
+            | cli::ReviewAction::VerifyBundle => ContractGuard::Warn,
+            _ => ContractGuard::Refuse,
+        },
+        Commands::Status { .. }
+        | Commands::Log { .. }
+        | Commands::Analyze { .. }
+        | Commands::Serve { .. } => ContractGuard::Warn,
+        _ => ContractGuard::Skip,
+    }
+}
+
+/// Stop state-changing commands when contract.json was edited after locking
+async fn guard_locked_contract(command: &Commands) -> Result<()> {
+    let guard = contract_guard(command);
+    if guard == ContractGuard::Skip {
+        return Ok(());
+    }
+    let Some(mismatch) = contract::check_lock_integrity().await? else {
+        return Ok(());
+    };
+
+    let found = mismatch.contract_hash.as_deref().map_or_else(
+        || "an unreadable contract".to_string(),
+        |h| contract::short_hash(h).to_string(),
+    );
+    let file = contract::contract_path()
+        .file_name()
+        .map_or_else(|| "contract".into(), |f| f.to_string_lossy().to_string());
+    let summary = format!(
+        "{} was edited after locking (lock {}, contract now {})",
+        file,
+        contract::short_hash(&mismatch.lock_hash),
+        found
+    );
+    match guard {
+        ContractGuard::Refuse => Err(error::invalid_state(format!(
+            "{}.\n  See the changes with 'vibeanvil contract diff' or 'vibeanvil contract verify', then either\n  restore {}, start a new version with 'vibeanvil contract amend', or roll back with\n  'vibeanvil reset --to ContractDrafted' and lock again.",
+            summary, file
+        ))),
+        _ => {
+            eprintln!(
+                "Warning: {}; run 'vibeanvil contract diff' for details.",
+                summary
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Workflow commands that run `.vibeanvil/hooks/pre-<name>` and `post-<name>`
+fn hook_command(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Intake { .. } => Some("intake"),
+        Commands::Blueprint { .. } => Some("blueprint"),
+        Commands::Contract(_) => Some("contract"),
+        Commands::Plan { .. } => Some("plan"),
+        Commands::Build(_) => Some("build"),
+        Commands::Review { .. } => Some("review"),
+        Commands::Snapshot { .. } => Some("snapshot"),
+        Commands::Ship { .. } => Some("ship"),
+        _ => None,
+    }
+}
+
+async fn dispatch(command: Commands) -> Result<()> {
+    match command {
+        Commands::Init {
+            force,
+            contract_format,
+        } => cli::init::run(force, contract_format.into()).await,
         Commands::Intake { message } => cli::intake::run(message).await,
         Commands::Blueprint { auto } => cli::blueprint::run(auto).await,
-        Commands::Contract { action } => cli::contract::run(action).await,
-        Commands::Plan { provider } => cli::plan::run(provider).await,
+        Commands::Contract(args) => cli::contract::run(args).await,
+        Commands::Plan {
+            provider,
+            no_cache,
+            from_brain,
+            persona,
+            prompt_template,
+        } => cli::plan::run(provider, no_cache, from_brain, persona, prompt_template).await,
         Commands::Build(args) => cli::build::run(args).await,
-        Commands::Review { action } => cli::review::run(action).await,
+        Commands::Review {
+            action,
+            archive,
+            output,
+            items,
+            skip_checklist,
+            verify_evidence,
+        } => {
+            cli::review::run(
+                action,
+                archive,
+                output,
+                items,
+                skip_checklist,
+                verify_evidence,
+            )
+            .await
+        }
         Commands::Snapshot { message } => cli::snapshot::run(message).await,
-        Commands::Ship { tag, message } => cli::ship::run(tag, message).await,
+        Commands::Ship {
+            tag,
+            message,
+            dry_run,
+        } => cli::ship::run(tag, message, dry_run).await,
         Commands::Harvest(args) => cli::harvest::run(args).await,
         Commands::Brain(args) => cli::brain::run(args).await,
-        Commands::Status { verbose, json } => cli::status::run(verbose, json).await,
-        Commands::Log { lines, json } => cli::log::run(lines, json).await,
+        Commands::Evidence(args) => cli::evidence::run(args).await,
+        Commands::Status {
+            history: true,
+            json,
+            format,
+            limit,
+            offset,
+            ..
+        } => cli::status::history(limit, offset, cli::OutputFormat::select(json, format)).await,
+        Commands::Status {
+            verbose,
+            json,
+            format,
+            badge,
+            ..
+        } => cli::status::run(verbose, cli::OutputFormat::select(json, format), badge).await,
+        Commands::State(args) => cli::state::run(args).await,
+        Commands::Log {
+            lines,
+            json,
+            format,
+        } => cli::log::run(lines, cli::OutputFormat::select(json, format)).await,
         Commands::Update => cli::update::check_update().await,
         Commands::Upgrade => cli::update::upgrade().await,
         Commands::Doctor => cli::doctor::run().await,
+        Commands::Selftest { keep } => cli::selftest::run(keep).await,
         Commands::Wizard => cli::wizard::run().await,
         Commands::Prompt {
             kind,
@@ -69,35 +278,70 @@ async fn main() -> Result<()> {
             strict_vars,
             vars,
         } => cli::prompt::run(kind, list, render, strict_vars, vars).await,
-        Commands::Providers { subcommand, args } => {
+        Commands::Providers {
+            subcommand,
+            args,
+            capability,
+            estimate_tokens,
+        } => {
+            if capability.is_some() && subcommand.as_deref() != Some("test") {
+                anyhow::bail!("--capability is only supported with 'providers test'");
+            }
             let cmd = match subcommand.as_deref() {
+                Some("list") | None if estimate_tokens.is_some() => {
+                    cli::providers::ProviderSubcommand::Estimate(estimate_tokens.unwrap_or(0))
+                }
+                _ if estimate_tokens.is_some() => {
+                    anyhow::bail!("--estimate-tokens is only supported with 'providers list'")
+                }
                 Some("matrix") => cli::providers::ProviderSubcommand::Matrix,
                 Some("recommend") => {
                     let task = args.join(" ");
                     if task.is_empty() {
-                        eprintln!("Usage: vibeanvil providers recommend \"<task description>\"");
-                        std::process::exit(1);
+                        anyhow::bail!(
+                            "Usage: vibeanvil providers recommend \"<task description>\""
+                        );
                     }
                     cli::providers::ProviderSubcommand::Recommend(task)
                 }
                 Some("compare") => {
                     if args.is_empty() {
-                        eprintln!("Usage: vibeanvil providers compare <provider1> <provider2> ...");
-                        std::process::exit(1);
+                        anyhow::bail!(
+                            "Usage: vibeanvil providers compare <provider1> <provider2> ..."
+                        );
                     }
                     cli::providers::ProviderSubcommand::Compare(args)
                 }
+                Some("test") => match (args.as_slice(), capability) {
+                    ([provider], Some(capability)) => cli::providers::ProviderSubcommand::Test {
+                        provider: provider.clone(),
+                        capability,
+                    },
+                    _ => anyhow::bail!(
+                        "Usage: vibeanvil providers test <provider> --capability <capability>"
+                    ),
+                },
                 Some("list") | None => cli::providers::ProviderSubcommand::List,
-                Some(other) => {
-                    eprintln!(
-                        "Unknown subcommand: {}. Use list, matrix, recommend, or compare.",
-                        other
-                    );
-                    std::process::exit(1);
-                }
+                Some(other) => anyhow::bail!(
+                    "Unknown subcommand: {}. Use list, matrix, recommend, compare, or test.",
+                    other
+                ),
             };
             cli::providers::run_subcommand(cmd).await
         }
+        Commands::Reset {
+            to,
+            hard,
+            reason,
+            yes,
+            force_unfreeze,
+        } => cli::reset::run(to, hard, reason, yes, force_unfreeze).await,
+        Commands::Abort {
+            reason,
+            force,
+            yes,
+            force_unfreeze,
+        } => cli::reset::abort(&reason, force, yes, force_unfreeze).await,
         Commands::Undo { dry_run } => cli::undo::run(dry_run).await,
 
         // New workflow commands
@@ -118,7 +362,9 @@ async fn main() -> Result<()> {
                 cli::tasks::run_tasks(&provider, regenerate).await
             }
         }
-        Commands::Analyze { provider } => cli::analyze::run_analyze(&provider).await,
+        Commands::Analyze { provider, no_cache } => {
+            cli::analyze::run_analyze(&provider, no_cache).await
+        }
         Commands::Implement {
             provider,
             task,
@@ -134,7 +380,10 @@ async fn main() -> Result<()> {
             .map(|_| ()),
         Commands::Test { cmd, fix } => cli::run::run_tests(cmd.as_deref(), fix).await.map(|_| ()),
         Commands::Lint { cmd, fix } => cli::run::run_lint(cmd.as_deref(), fix).await.map(|_| ()),
-        Commands::Map { max_tokens } => cli::repomap::run_map(max_tokens).await,
+        Commands::Map {
+            max_tokens,
+            max_depth,
+        } => cli::repomap::run_map(max_tokens, max_depth).await,
         Commands::Chat {
             mode,
             message,
@@ -151,5 +400,6 @@ async fn main() -> Result<()> {
 
         // MCP Server
         Commands::Mcp { action } => cli::mcp::run(action).await,
+        Commands::Serve { port, host } => cli::serve::run(port, host).await,
     }
 }
@@ -45,6 +45,16 @@ pub fn load_template(name: &str) -> Result<String> {
     anyhow::bail!("Template '{}' not found", name)
 }
 
+/// Check that a template picked by name (e.g. `--prompt-template`) exists
+pub fn require_template(name: &str) -> Result<()> {
+    load_template(name).map(|_| ()).map_err(|_| {
+        crate::error::validation(format!(
+            "Prompt template '{}' not found. Add .vibeanvil/prompts/{}.md or pick one from 'vibeanvil prompt --list'",
+            name, name
+        ))
+    })
+}
+
 /// Render a template with variables
 pub fn render(template: &str, vars: &HashMap<&str, &str>) -> String {
     render_template_with_lookup(template, |key, filter| {
@@ -53,6 +63,35 @@ pub fn render(template: &str, vars: &HashMap<&str, &str>) -> String {
     })
 }
 
+/// Render template `name` for a workflow step whose own template is `base`
+///
+/// Persona templates such as `review` or `architect` have no `{{contract}}`
+/// placeholder. Rendered alone they would drop the contract, so their
+/// guidance is layered on top of `base` instead.
+pub fn render_over(name: &str, base: &str, vars: &HashMap<&str, &str>) -> Result<String> {
+    let template = load_template(name)?;
+    let rendered = render(&template, vars);
+    if name == base
+        || extract_placeholders(&template)
+            .iter()
+            .any(|p| p == "contract")
+    {
+        return Ok(rendered);
+    }
+
+    let base_rendered = render(&load_template(base)?, vars);
+    Ok(format!("{}\n\n---\n\n{}", rendered, base_rendered))
+}
+
+/// Whether template `name` works on the current changes (`{{diff}}` or `{{code}}`)
+pub fn uses_diff(name: &str) -> bool {
+    load_template(name).is_ok_and(|template| {
+        extract_placeholders(&template)
+            .iter()
+            .any(|p| p == "diff" || p == "code")
+    })
+}
+
 pub fn extract_placeholders(template: &str) -> Vec<String> {
     let mut keys = Vec::new();
     let mut i = 0;
@@ -389,6 +428,27 @@ mod tests {
         assert_eq!(apply_filter(input, "title"), "Hello World Case Value");
     }
 
+    #[test]
+    fn render_over_keeps_the_contract_for_persona_templates() {
+        let mut vars = HashMap::new();
+        vars.insert("contract", "CONTRACT-MARKER");
+        vars.insert("task", "Implement the plan");
+        vars.insert("context", "src/");
+        vars.insert("diff", "DIFF-MARKER");
+
+        for persona in ["architect", "review"] {
+            let prompt = render_over(persona, "developer", &vars).unwrap();
+            assert!(prompt.contains("CONTRACT-MARKER"), "{}", persona);
+            assert!(!prompt.contains("{{diff}}"), "{}", persona);
+        }
+        let review = render_over("review", "developer", &vars).unwrap();
+        assert!(review.contains("DIFF-MARKER"));
+
+        // Templates that already carry the contract are rendered alone
+        let qa = render_over("qa", "developer", &vars).unwrap();
+        assert_eq!(qa, render(&load_template("qa").unwrap(), &vars));
+    }
+
     #[test]
     fn template_description_prefers_mission() {
         let template =
@@ -16,9 +16,8 @@
 
 use anyhow::{Context as AnyhowContext, Result};
 use async_trait::async_trait;
-use std::io::Write;
-use std::process::{Command, Stdio};
 use std::time::Duration;
+use tokio::process::Command;
 
 use super::safety::{get_timeout_secs, redact_secrets, truncate_output, MAX_OUTPUT_BYTES};
 use super::{Context, Provider, ProviderResponse};
@@ -143,56 +142,40 @@ impl AiderProvider {
     }
 
     /// Execute aider and capture output
-    fn execute_aider(&self, prompt: &str, context: &Context) -> Result<(bool, String, String)> {
-        let mut cmd = self.build_command(prompt, context);
-
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-
-        if self.mode == AiderMode::Chat {
-            cmd.stdin(Stdio::piped());
-        }
-
-        let mut child = cmd.spawn().with_context(|| {
+    ///
+    /// Returns `None` when `context.cancel` fires; the process is killed.
+    async fn execute_aider(
+        &self,
+        prompt: &str,
+        context: &Context,
+    ) -> Result<Option<(bool, String, String)>> {
+        let cmd = self.build_command(prompt, context);
+
+        // For chat mode, write prompt to stdin and exit after processing
+        let input = format!("{}\n/exit\n", prompt);
+        let stdin = (self.mode == AiderMode::Chat).then_some(input.as_bytes());
+
+        // Dropping the run on timeout kills the process
+        let run = super::run_process(cmd, stdin, context);
+        let Ok(output) = tokio::time::timeout(self.timeout, run).await else {
+            return Err(anyhow::anyhow!(
+                "Aider timed out after {} seconds.\n\n\
+                 The operation did not complete in time.\n\
+                 To increase timeout: export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
+                self.timeout.as_secs()
+            ));
+        };
+        let output = output.with_context(|| {
             "Failed to spawn aider. Is it installed? Run: pip install aider-chat"
         })?;
 
-        // For chat mode, write prompt to stdin
-        if self.mode == AiderMode::Chat {
-            if let Some(mut stdin) = child.stdin.take() {
-                writeln!(stdin, "{}", prompt)?;
-                writeln!(stdin, "/exit")?; // Exit after processing
-            }
-        }
-
-        // Wait with timeout
-        let start = std::time::Instant::now();
-        loop {
-            match child.try_wait() {
-                Ok(Some(status)) => {
-                    let output = child.wait_with_output()?;
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                    return Ok((status.success(), stdout, stderr));
-                }
-                Ok(None) => {
-                    if start.elapsed() > self.timeout {
-                        let _ = child.kill();
-                        let _ = child.wait();
-                        return Err(anyhow::anyhow!(
-                            "Aider timed out after {} seconds.\n\n\
-                             The operation did not complete in time.\n\
-                             To increase timeout: export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
-                            self.timeout.as_secs()
-                        ));
-                    }
-                    std::thread::sleep(Duration::from_millis(100));
-                }
-                Err(e) => {
-                    return Err(anyhow::anyhow!("Failed to wait for aider: {}", e));
-                }
-            }
-        }
+        Ok(output.map(|output| {
+            (
+                output.status.success(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )
+        }))
     }
 
     /// Parse aider output to extract modified files
@@ -224,7 +207,7 @@ impl Default for AiderProvider {
 impl Provider for AiderProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
         if !self.is_available() {
-            return Err(anyhow::anyhow!(
+            return Err(crate::error::provider_unavailable(
                 "Aider not found.\n\n\
                  Install with: pip install aider-chat\n\
                  Or: pipx install aider-chat\n\n\
@@ -232,11 +215,13 @@ impl Provider for AiderProvider {
                  export ANTHROPIC_API_KEY=<your-key>  # For Claude\n  \
                  export OPENAI_API_KEY=<your-key>     # For OpenAI\n\n\
                  For local models, install Ollama and run:\n  \
-                 aider --model ollama/llama3.2"
+                 aider --model ollama/llama3.2",
             ));
         }
 
-        let (success, stdout, stderr) = self.execute_aider(prompt, context)?;
+        let Some((success, stdout, stderr)) = self.execute_aider(prompt, context).await? else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         // Redact secrets and truncate output
         let stdout = redact_secrets(&stdout);
@@ -258,6 +243,7 @@ impl Provider for AiderProvider {
             errors,
             warnings: vec![],
             files_modified,
+            cancelled: false,
         })
     }
 
@@ -313,11 +299,12 @@ mod tests {
             working_dir: PathBuf::from("/tmp/test"),
             session_id: "test-session".to_string(),
             contract_hash: Some("abc123".to_string()),
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("Fix the bug", &context);
         // Command should be configured correctly
-        assert_eq!(cmd.get_program(), "aider");
+        assert_eq!(cmd.as_std().get_program(), "aider");
     }
 
     #[test]
@@ -0,0 +1,217 @@
+//! Provider response cache for idempotent commands
+//!
+//! Read-only commands such as `plan` and `analyze` produce the same output for
+//! the same contract and prompt, so their responses are cached under
+//! `.vibeanvil/provider_cache/` to avoid re-invoking (and paying for) the provider.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use super::{Context, Provider, ProviderResponse};
+use crate::workspace;
+
+/// On-disk cache of provider responses
+pub struct ProviderCache {
+    dir: PathBuf,
+}
+
+impl ProviderCache {
+    /// Open the cache in the workspace
+    pub fn new() -> Self {
+        Self::at(workspace::provider_cache_path())
+    }
+
+    /// Open a cache rooted at a specific directory
+    pub fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Compute the cache key for a (command, contract hash, prompt, provider) tuple
+    pub fn key(command: &str, contract_hash: Option<&str>, prompt: &str, provider: &str) -> String {
+        let prompt_hash = hex::encode(Sha256::digest(prompt.as_bytes()));
+
+        let mut hasher = Sha256::new();
+        hasher.update(command.as_bytes());
+        hasher.update([0]);
+        hasher.update(contract_hash.unwrap_or("").as_bytes());
+        hasher.update([0]);
+        hasher.update(prompt_hash.as_bytes());
+        hasher.update([0]);
+        hasher.update(provider.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Look up a cached response
+    pub async fn get(&self, key: &str) -> Option<ProviderResponse> {
+        let content = tokio::fs::read_to_string(self.entry_path(key)).await.ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Store a response
+    pub async fn put(&self, key: &str, response: &ProviderResponse) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let content = serde_json::to_string_pretty(response)?;
+        tokio::fs::write(self.entry_path(key), content).await?;
+        Ok(())
+    }
+
+    /// Execute a prompt, serving from the cache when possible.
+    ///
+    /// Pass `use_cache = false` to bypass lookup (the fresh response still
+    /// refreshes the cache). Only successful responses are stored.
+    pub async fn execute(
+        &self,
+        command: &str,
+        provider: &dyn Provider,
+        prompt: &str,
+        context: &Context,
+        use_cache: bool,
+    ) -> Result<(ProviderResponse, bool)> {
+        let key = Self::key(
+            command,
+            context.contract_hash.as_deref(),
+            prompt,
+            provider.name(),
+        );
+
+        if use_cache {
+            if let Some(cached) = self.get(&key).await {
+                return Ok((cached, true));
+            }
+        }
+
+        let response = provider.execute(prompt, context).await?;
+        if response.success {
+            if let Err(e) = self.put(&key, &response).await {
+                tracing::warn!("Failed to write provider cache: {}", e);
+            }
+        }
+
+        Ok((response, false))
+    }
+}
+
+impl Default for ProviderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn execute(&self, prompt: &str, _context: &Context) -> Result<ProviderResponse> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ProviderResponse {
+                success: true,
+                output: format!("call {}: {}", n, prompt),
+                errors: vec![],
+                warnings: vec![],
+                files_modified: vec![],
+                cancelled: false,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn context() -> Context {
+        Context {
+            working_dir: PathBuf::from("."),
+            session_id: "test".to_string(),
+            contract_hash: Some("abc123".to_string()),
+            cancel: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_skips_provider() {
+        let temp = TempDir::new().unwrap();
+        let cache = ProviderCache::at(temp.path().join("provider_cache"));
+        let provider = CountingProvider {
+            calls: AtomicUsize::new(0),
+        };
+
+        let (first, hit) = cache
+            .execute("plan", &provider, "make a plan", &context(), true)
+            .await
+            .unwrap();
+        assert!(!hit);
+
+        let (second, hit) = cache
+            .execute("plan", &provider, "make a plan", &context(), true)
+            .await
+            .unwrap();
+        assert!(hit);
+        assert_eq!(second.output, first.output);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_cache_bypasses_lookup() {
+        let temp = TempDir::new().unwrap();
+        let cache = ProviderCache::at(temp.path().join("provider_cache"));
+        let provider = CountingProvider {
+            calls: AtomicUsize::new(0),
+        };
+
+        cache
+            .execute("plan", &provider, "make a plan", &context(), true)
+            .await
+            .unwrap();
+        let (response, hit) = cache
+            .execute("plan", &provider, "make a plan", &context(), false)
+            .await
+            .unwrap();
+
+        assert!(!hit);
+        assert_eq!(response.output, "call 2: make a plan");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_key_depends_on_all_parts() {
+        let base = ProviderCache::key("plan", Some("h1"), "prompt", "mock");
+        assert_eq!(
+            base,
+            ProviderCache::key("plan", Some("h1"), "prompt", "mock")
+        );
+        assert_ne!(
+            base,
+            ProviderCache::key("analyze", Some("h1"), "prompt", "mock")
+        );
+        assert_ne!(
+            base,
+            ProviderCache::key("plan", Some("h2"), "prompt", "mock")
+        );
+        assert_ne!(
+            base,
+            ProviderCache::key("plan", Some("h1"), "other", "mock")
+        );
+        assert_ne!(
+            base,
+            ProviderCache::key("plan", Some("h1"), "prompt", "human")
+        );
+    }
+}
@@ -53,30 +53,33 @@ pub enum Capability {
 }
 
 impl Capability {
+    /// Every capability, in matrix order
+    const ALL: [Capability; 20] = [
+        Capability::CodeGeneration,
+        Capability::CodeReview,
+        Capability::CodeExplanation,
+        Capability::Refactoring,
+        Capability::BugFixing,
+        Capability::TestGeneration,
+        Capability::Documentation,
+        Capability::Architecture,
+        Capability::Planning,
+        Capability::Debugging,
+        Capability::ShellCommands,
+        Capability::MultiFile,
+        Capability::CodeSearch,
+        Capability::WebAccess,
+        Capability::Vision,
+        Capability::Streaming,
+        Capability::Interactive,
+        Capability::Agentic,
+        Capability::Background,
+        Capability::McpSupport,
+    ];
+
     /// Get all capabilities
     pub fn all() -> Vec<Capability> {
-        vec![
-            Capability::CodeGeneration,
-            Capability::CodeReview,
-            Capability::CodeExplanation,
-            Capability::Refactoring,
-            Capability::BugFixing,
-            Capability::TestGeneration,
-            Capability::Documentation,
-            Capability::Architecture,
-            Capability::Planning,
-            Capability::Debugging,
-            Capability::ShellCommands,
-            Capability::MultiFile,
-            Capability::CodeSearch,
-            Capability::WebAccess,
-            Capability::Vision,
-            Capability::Streaming,
-            Capability::Interactive,
-            Capability::Agentic,
-            Capability::Background,
-            Capability::McpSupport,
-        ]
+        Self::ALL.to_vec()
     }
 
     /// Get capability display name
@@ -105,6 +108,23 @@ impl Capability {
         }
     }
 
+    /// Parse a capability from its variant, display or short name
+    /// (`CodeGeneration`, `code-generation`, `Code Generation`, `GEN`)
+    pub fn from_name(name: &str) -> Option<Capability> {
+        let normalize = |s: &str| {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        let wanted = normalize(name);
+        Self::all().into_iter().find(|cap| {
+            normalize(&format!("{:?}", cap)) == wanted
+                || normalize(cap.display_name()) == wanted
+                || cap.short_code().eq_ignore_ascii_case(name.trim())
+        })
+    }
+
     /// Get capability short code for matrix display
     pub fn short_code(&self) -> &'static str {
         match self {
@@ -146,6 +166,13 @@ pub struct ProviderProfile {
     pub capabilities: HashMap<Capability, CapabilityScore>,
     /// Estimated cost per 1K tokens (input + output)
     pub cost_per_1k: f32,
+    /// Share of the input and output price in `cost_per_1k`
+    ///
+    /// `cost_per_1k` is the average of the input and output price, so
+    /// `(0.5, 0.5)` prices both alike and `(0.2, 0.8)` makes output tokens
+    /// four times as expensive as input tokens.
+    #[serde(default = "default_cost_split")]
+    pub cost_split: (f32, f32),
     /// Typical response latency in ms
     pub latency_ms: u32,
     /// Maximum context window size
@@ -162,6 +189,7 @@ impl ProviderProfile {
             tier,
             capabilities: HashMap::new(),
             cost_per_1k: 0.0,
+            cost_split: default_cost_split(),
             latency_ms: 1000,
             context_window: 8000,
             tags: Vec::new(),
@@ -180,6 +208,12 @@ impl ProviderProfile {
         self
     }
 
+    /// Set the input/output share of `cost_per_1k`
+    pub fn with_cost_split(mut self, input: f32, output: f32) -> Self {
+        self.cost_split = (input, output);
+        self
+    }
+
     /// Set latency
     pub fn with_latency(mut self, ms: u32) -> Self {
         self.latency_ms = ms;
@@ -208,6 +242,13 @@ impl ProviderProfile {
         self.capabilities.get(&cap).copied().unwrap_or(0)
     }
 
+    /// Estimated cost in dollars of a run with the given token counts
+    pub fn estimated_cost(&self, input_tokens: u32, output_tokens: u32) -> f32 {
+        let (input_share, output_share) = self.cost_split;
+        let weighted = input_tokens as f32 * input_share + output_tokens as f32 * output_share;
+        self.cost_per_1k * 2.0 * weighted / 1000.0
+    }
+
     /// Calculate overall score for a set of required capabilities
     pub fn match_score(&self, required: &[Capability]) -> u32 {
         let mut score: u32 = 0;
@@ -218,6 +259,19 @@ impl ProviderProfile {
     }
 }
 
+fn default_cost_split() -> (f32, f32) {
+    (0.5, 0.5)
+}
+
+/// Format a dollar amount, keeping four decimals below one cent
+pub fn format_cost(cost: f32) -> String {
+    if cost > 0.0 && cost < 0.01 {
+        format!("${:.4}", cost)
+    } else {
+        format!("${:.2}", cost)
+    }
+}
+
 /// Provider capability matrix containing all provider profiles
 #[derive(Debug, Clone)]
 pub struct CapabilityMatrix {
@@ -473,6 +527,7 @@ impl CapabilityMatrix {
                 .with_capability(Capability::Vision, 10)
                 .with_capability(Capability::Streaming, 10)
                 .with_cost(0.015)
+                .with_cost_split(1.0 / 6.0, 5.0 / 6.0)
                 .with_latency(800)
                 .with_context(200000)
                 .with_tag("api")
@@ -490,6 +545,7 @@ impl CapabilityMatrix {
                 .with_capability(Capability::Vision, 10)
                 .with_capability(Capability::Streaming, 10)
                 .with_cost(0.01)
+                .with_cost_split(0.2, 0.8)
                 .with_latency(700)
                 .with_context(128000)
                 .with_tag("api")
@@ -507,6 +563,7 @@ impl CapabilityMatrix {
                 .with_capability(Capability::Streaming, 10)
                 .with_capability(Capability::WebAccess, 9)
                 .with_cost(0.005)
+                .with_cost_split(0.2, 0.8)
                 .with_latency(600)
                 .with_context(1000000)
                 .with_tag("api")
@@ -686,7 +743,7 @@ impl CapabilityMatrix {
             .filter(|(_, score)| *score > 0)
             .collect();
 
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
         scored.into_iter().take(limit).map(|(p, _)| p).collect()
     }
 
@@ -766,10 +823,70 @@ impl Default for CapabilityMatrix {
     }
 }
 
+/// `--capability` values: the kebab-case name (`code-generation`), with the
+/// variant name (`CodeGeneration`) and matrix short code (`GEN`) as aliases
+impl clap::ValueEnum for Capability {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Self::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let variant = format!("{:?}", self);
+        let mut name = String::new();
+        for (i, c) in variant.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                name.push('-');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        Some(
+            clap::builder::PossibleValue::new(name)
+                .aliases([variant, self.short_code().to_string()])
+                .help(self.display_name()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_capability_value_enum_accepts_documented_names() {
+        use clap::ValueEnum;
+        for name in ["code-generation", "CodeGeneration", "GEN", "gen"] {
+            assert_eq!(
+                Capability::from_str(name, true),
+                Ok(Capability::CodeGeneration)
+            );
+        }
+        assert_eq!(
+            Capability::from_str("mcp-support", true),
+            Ok(Capability::McpSupport)
+        );
+        assert!(Capability::from_str("telepathy", true).is_err());
+    }
+
+    #[test]
+    fn test_capability_from_name() {
+        for name in [
+            "CodeGeneration",
+            "code-generation",
+            "Code Generation",
+            "gen",
+        ] {
+            assert_eq!(
+                Capability::from_name(name),
+                Some(Capability::CodeGeneration)
+            );
+        }
+        assert_eq!(
+            Capability::from_name("mcp_support"),
+            Some(Capability::McpSupport)
+        );
+        assert_eq!(Capability::from_name("telepathy"), None);
+    }
+
     #[test]
     fn test_capability_all() {
         let caps = Capability::all();
@@ -791,6 +908,38 @@ mod tests {
         assert_eq!(profile.cost_per_1k, 0.01);
     }
 
+    #[test]
+    fn test_estimated_cost_per_provider() {
+        let matrix = CapabilityMatrix::build_default();
+        let cost = |name: &str| matrix.get(name).unwrap().estimated_cost(10_000, 2_000);
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+
+        // Default split: 12K tokens at the blended $0.015/1K
+        assert!(close(cost("claude_code"), 0.18));
+        // $0.005/1K input, $0.025/1K output
+        assert!(close(cost("claude_api"), 0.10));
+        // $0.004/1K input, $0.016/1K output
+        assert!(close(cost("openai_api"), 0.072));
+        assert!(close(cost("gemini_api"), 0.036));
+        assert_eq!(cost("ollama"), 0.0);
+
+        // A split only moves cost between input and output
+        let even = ProviderProfile::new("even", 2).with_cost(0.01);
+        let skewed = even.clone().with_cost_split(0.2, 0.8);
+        assert!(close(
+            even.estimated_cost(5_000, 5_000),
+            skewed.estimated_cost(5_000, 5_000)
+        ));
+    }
+
+    #[test]
+    fn test_format_cost() {
+        assert_eq!(format_cost(0.0042), "$0.0042");
+        assert_eq!(format_cost(0.0), "$0.00");
+        assert_eq!(format_cost(0.18), "$0.18");
+        assert_eq!(format_cost(12.5), "$12.50");
+    }
+
     #[test]
     fn test_capability_matrix_default() {
         let matrix = CapabilityMatrix::build_default();
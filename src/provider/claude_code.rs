@@ -2,7 +2,7 @@
 
 use anyhow::{Context as AnyhowContext, Result};
 use async_trait::async_trait;
-use std::process::Command;
+use tokio::process::Command;
 
 use super::{Context, Provider, ProviderResponse};
 
@@ -55,16 +55,19 @@ impl Default for ClaudeCodeProvider {
 impl Provider for ClaudeCodeProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
         if !self.is_available() {
-            return Err(anyhow::anyhow!(
-                "Claude Code CLI not found. Install with: npm install -g @anthropic-ai/claude-code"
+            return Err(crate::error::provider_unavailable(
+                "Claude Code CLI not found. Install with: npm install -g @anthropic-ai/claude-code",
             ));
         }
 
-        let mut cmd = self.build_command(prompt, context);
+        let cmd = self.build_command(prompt, context);
 
-        let output = cmd
-            .output()
-            .with_context(|| "Failed to execute claude command")?;
+        let Some(output) = super::run_process(cmd, None, context)
+            .await
+            .with_context(|| "Failed to execute claude command")?
+        else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -82,6 +85,7 @@ impl Provider for ClaudeCodeProvider {
             errors,
             warnings: vec![],
             files_modified: vec![], // Would need to parse claude output for this
+            cancelled: false,
         })
     }
 
@@ -112,10 +116,15 @@ mod tests {
             working_dir: PathBuf::from("/tmp"),
             session_id: "test-session".to_string(),
             contract_hash: Some("abc123".to_string()),
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("test prompt", &context);
         // Command is built successfully
-        assert!(cmd.get_program().to_string_lossy().contains("claude"));
+        assert!(cmd
+            .as_std()
+            .get_program()
+            .to_string_lossy()
+            .contains("claude"));
     }
 }
@@ -17,8 +17,8 @@
 use super::{Context, Provider, ProviderResponse};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::process::Command;
 use std::time::Duration;
+use tokio::process::Command;
 
 /// Cline AI coding assistant provider
 pub struct ClineProvider {
@@ -77,17 +77,16 @@ impl ClineProvider {
 #[async_trait]
 impl Provider for ClineProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
-        let mut cmd = self.build_command(prompt, context);
-
-        // Execute with timeout
-        let output = tokio::time::timeout(self.timeout, async {
-            tokio::task::spawn_blocking(move || cmd.output())
-                .await
-                .map_err(|e| anyhow!("Task join error: {}", e))?
-                .map_err(|e| anyhow!("Failed to execute cline: {}", e))
-        })
-        .await
-        .map_err(|_| anyhow!("Cline timed out after {:?}", self.timeout))??;
+        let cmd = self.build_command(prompt, context);
+
+        // Execute with timeout; the process is killed on timeout
+        let output = tokio::time::timeout(self.timeout, super::run_process(cmd, None, context))
+            .await
+            .map_err(|_| anyhow!("Cline timed out after {:?}", self.timeout))?
+            .map_err(|e| anyhow!("Failed to execute cline: {}", e))?;
+        let Some(output) = output else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -108,6 +107,7 @@ impl Provider for ClineProvider {
             errors,
             warnings: vec![],
             files_modified,
+            cancelled: false,
         })
     }
 
@@ -183,10 +183,11 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("test prompt", &context);
-        let args: Vec<_> = cmd.get_args().collect();
+        let args: Vec<_> = cmd.as_std().get_args().collect();
 
         assert!(args.contains(&std::ffi::OsStr::new("--prompt")));
         assert!(args.contains(&std::ffi::OsStr::new("test prompt")));
@@ -18,8 +18,8 @@
 use super::{Context, Provider, ProviderResponse};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::process::Command;
 use std::time::Duration;
+use tokio::process::Command;
 
 /// Cody AI coding assistant provider (Sourcegraph)
 pub struct CodyProvider {
@@ -81,17 +81,16 @@ impl CodyProvider {
 #[async_trait]
 impl Provider for CodyProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
-        let mut cmd = self.build_command(prompt, context);
-
-        // Execute with timeout
-        let output = tokio::time::timeout(self.timeout, async {
-            tokio::task::spawn_blocking(move || cmd.output())
-                .await
-                .map_err(|e| anyhow!("Task join error: {}", e))?
-                .map_err(|e| anyhow!("Failed to execute cody: {}", e))
-        })
-        .await
-        .map_err(|_| anyhow!("Cody timed out after {:?}", self.timeout))??;
+        let cmd = self.build_command(prompt, context);
+
+        // Execute with timeout; the process is killed on timeout
+        let output = tokio::time::timeout(self.timeout, super::run_process(cmd, None, context))
+            .await
+            .map_err(|_| anyhow!("Cody timed out after {:?}", self.timeout))?
+            .map_err(|e| anyhow!("Failed to execute cody: {}", e))?;
+        let Some(output) = output else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -109,6 +108,7 @@ impl Provider for CodyProvider {
             errors,
             warnings: vec![],
             files_modified: vec![], // Cody chat doesn't modify files directly
+            cancelled: false,
         })
     }
 
@@ -167,10 +167,11 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("test prompt", &context);
-        let args: Vec<_> = cmd.get_args().collect();
+        let args: Vec<_> = cmd.as_std().get_args().collect();
 
         assert!(args.contains(&std::ffi::OsStr::new("chat")));
         assert!(args.contains(&std::ffi::OsStr::new("-m")));
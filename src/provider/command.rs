@@ -9,9 +9,9 @@
 use anyhow::{Context as AnyhowContext, Result};
 use async_trait::async_trait;
 use std::io::Write;
-use std::process::{Command, Stdio};
 use std::time::Duration;
 use tempfile::NamedTempFile;
+use tokio::process::Command;
 
 use super::safety::{
     get_timeout_secs, redact_secrets, tail_lines, truncate_output, ERROR_TAIL_BYTES,
@@ -92,7 +92,13 @@ impl CommandProvider {
     }
 
     /// Build and execute the command with timeout
-    fn execute_command(&self, prompt: &str, context: &Context) -> Result<(bool, String, String)> {
+    ///
+    /// Returns `None` when `context.cancel` fires; the process is killed.
+    async fn execute_command(
+        &self,
+        prompt: &str,
+        context: &Context,
+    ) -> Result<Option<(bool, String, String)>> {
         let cmd_name = self.command.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "VIBEANVIL_PROVIDER_COMMAND not set.\n\n\
@@ -119,144 +125,45 @@ impl CommandProvider {
         }
         cmd.env("VIBEANVIL_SESSION_ID", &context.session_id);
 
-        // Handle prompt based on mode
-        match self.mode {
-            PromptMode::Stdin => {
-                cmd.stdin(Stdio::piped());
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
-
-                let mut child = cmd
-                    .spawn()
-                    .with_context(|| format!("Failed to spawn command: {}", cmd_name))?;
-
-                if let Some(mut stdin) = child.stdin.take() {
-                    stdin
-                        .write_all(prompt.as_bytes())
-                        .with_context(|| "Failed to write prompt to stdin")?;
-                }
-
-                // Wait with timeout
-                let start = std::time::Instant::now();
-                loop {
-                    match child.try_wait() {
-                        Ok(Some(status)) => {
-                            // Process finished
-                            let output = child.wait_with_output()?;
-                            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                            return Ok((status.success(), stdout, stderr));
-                        }
-                        Ok(None) => {
-                            // Still running - check timeout
-                            if start.elapsed() > self.timeout {
-                                // Kill the process
-                                let _ = child.kill();
-                                let _ = child.wait(); // Reap zombie
-                                return Err(anyhow::anyhow!(
-                                    "Provider command timed out after {} seconds.\n\n\
-                                     The command '{}' did not complete in time and was terminated.\n\n\
-                                     To increase the timeout, set:\n  \
-                                     export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>\n\n\
-                                     Current timeout: {} seconds",
-                                    self.timeout.as_secs(),
-                                    cmd_name,
-                                    self.timeout.as_secs()
-                                ));
-                            }
-                            std::thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Failed to wait for process: {}", e));
-                        }
-                    }
-                }
-            }
+        // Handle prompt based on mode; the temp file must outlive the process
+        let mut prompt_file = None;
+        let stdin = match self.mode {
+            PromptMode::Stdin => Some(prompt.as_bytes()),
             PromptMode::Arg => {
                 cmd.arg(prompt);
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
-
-                let mut child = cmd
-                    .spawn()
-                    .with_context(|| format!("Failed to spawn command: {}", cmd_name))?;
-
-                // Wait with timeout (same loop as above)
-                let start = std::time::Instant::now();
-                loop {
-                    match child.try_wait() {
-                        Ok(Some(status)) => {
-                            let output = child.wait_with_output()?;
-                            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                            return Ok((status.success(), stdout, stderr));
-                        }
-                        Ok(None) => {
-                            if start.elapsed() > self.timeout {
-                                let _ = child.kill();
-                                let _ = child.wait();
-                                return Err(anyhow::anyhow!(
-                                    "Provider command timed out after {} seconds.\n\n\
-                                     The command '{}' did not complete in time and was terminated.\n\n\
-                                     To increase the timeout, set:\n  \
-                                     export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
-                                    self.timeout.as_secs(),
-                                    cmd_name
-                                ));
-                            }
-                            std::thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Failed to wait for process: {}", e));
-                        }
-                    }
-                }
+                None
             }
             PromptMode::File => {
                 let mut temp_file = NamedTempFile::new()?;
                 temp_file.write_all(prompt.as_bytes())?;
-                let temp_path = temp_file.path().to_string_lossy().to_string();
-
-                cmd.arg(&temp_path);
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
-
-                let mut child = cmd
-                    .spawn()
-                    .with_context(|| format!("Failed to spawn command: {}", cmd_name))?;
-
-                // Wait with timeout
-                let start = std::time::Instant::now();
-                loop {
-                    match child.try_wait() {
-                        Ok(Some(status)) => {
-                            let output = child.wait_with_output()?;
-                            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                            return Ok((status.success(), stdout, stderr));
-                        }
-                        Ok(None) => {
-                            if start.elapsed() > self.timeout {
-                                let _ = child.kill();
-                                let _ = child.wait();
-                                return Err(anyhow::anyhow!(
-                                    "Provider command timed out after {} seconds.\n\n\
-                                     The command '{}' did not complete in time and was terminated.\n\n\
-                                     To increase the timeout, set:\n  \
-                                     export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
-                                    self.timeout.as_secs(),
-                                    cmd_name
-                                ));
-                            }
-                            std::thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Failed to wait for process: {}", e));
-                        }
-                    }
-                }
+                cmd.arg(temp_file.path());
+                prompt_file = Some(temp_file);
+                None
             }
-        }
+        };
+
+        // Dropping the run on timeout kills the process
+        let run = super::run_process(cmd, stdin, context);
+        let Ok(output) = tokio::time::timeout(self.timeout, run).await else {
+            return Err(anyhow::anyhow!(
+                "Provider command timed out after {} seconds.\n\n\
+                 The command '{}' did not complete in time and was terminated.\n\n\
+                 To increase the timeout, set:\n  \
+                 export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
+                self.timeout.as_secs(),
+                cmd_name
+            ));
+        };
+        let output = output.with_context(|| format!("Failed to run command: {}", cmd_name))?;
+        drop(prompt_file);
+
+        Ok(output.map(|output| {
+            (
+                output.status.success(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )
+        }))
     }
 }
 
@@ -271,7 +178,7 @@ impl Provider for CommandProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
         if !self.is_available() {
             let cmd = self.command.as_deref().unwrap_or("<not set>");
-            return Err(anyhow::anyhow!(
+            return Err(crate::error::provider_unavailable(format!(
                 "Command provider not available.\n\n\
                  Command '{}' not found in PATH.\n\n\
                  Configuration:\n  \
@@ -283,10 +190,12 @@ impl Provider for CommandProvider {
                  export VIBEANVIL_PROVIDER_ARGS=\"--yes --message\"\n  \
                  export VIBEANVIL_PROVIDER_MODE=arg",
                 cmd
-            ));
+            )));
         }
 
-        let (success, stdout, stderr) = self.execute_command(prompt, context)?;
+        let Some((success, stdout, stderr)) = self.execute_command(prompt, context).await? else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         // Apply safety transformations
         let stdout_safe = redact_secrets(&truncate_output(&stdout, MAX_OUTPUT_BYTES));
@@ -309,6 +218,7 @@ impl Provider for CommandProvider {
             errors,
             warnings: vec![],
             files_modified: vec![], // Would need to detect from command output
+            cancelled: false,
         })
     }
 
@@ -360,6 +270,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[allow(clippy::await_holding_lock)]
     async fn test_execute_without_command_set() {
         let _guard = ENV_LOCK.lock().unwrap();
         std::env::remove_var("VIBEANVIL_PROVIDER_COMMAND");
@@ -369,6 +280,7 @@ mod tests {
             working_dir: PathBuf::from("."),
             session_id: "test".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let result = provider.execute("test", &context).await;
@@ -390,6 +302,7 @@ mod tests {
             working_dir: PathBuf::from("."),
             session_id: "test".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         // Sleep for 3 seconds should timeout after 1 second
@@ -400,6 +313,40 @@ mod tests {
         assert!(err_msg.contains("timed out"));
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_cancel_unix() {
+        let provider = CommandProvider {
+            command: Some("sh".to_string()),
+            args: vec!["-c".to_string()],
+            mode: PromptMode::Arg,
+            timeout: Duration::from_secs(30),
+        };
+        let context = Context {
+            working_dir: PathBuf::from("."),
+            session_id: "test".to_string(),
+            contract_hash: None,
+            cancel: Default::default(),
+        };
+
+        // Cancel mid-execution, long before the command or timeout finish
+        let cancel = context.cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            cancel.cancel();
+        });
+        let start = std::time::Instant::now();
+        let response = provider
+            .execute("sleep 10; echo done", &context)
+            .await
+            .unwrap();
+
+        assert!(response.cancelled);
+        assert!(!response.success);
+        assert!(!response.output.contains("done"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
     #[tokio::test]
     #[cfg(windows)]
     async fn test_timeout_windows() {
@@ -413,6 +360,7 @@ mod tests {
             working_dir: PathBuf::from("."),
             session_id: "test".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         // Sleep for 3 seconds should timeout after 1 second
@@ -438,6 +386,7 @@ mod tests {
             working_dir: PathBuf::from("."),
             session_id: "test".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let result = provider.execute("hello world", &context).await;
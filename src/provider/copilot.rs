@@ -123,6 +123,7 @@ impl Provider for CopilotProvider {
             errors: vec![],
             warnings: vec![],
             files_modified: vec![prompt_path.to_string_lossy().to_string()],
+            cancelled: false,
         })
     }
 
@@ -157,6 +158,7 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: Some("abc123".to_string()),
+            cancel: Default::default(),
         };
 
         let content = CopilotProvider::generate_prompt_content("Fix the bug", &context);
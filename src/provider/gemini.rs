@@ -118,6 +118,7 @@ impl Provider for GeminiProvider {
             errors: vec![],
             warnings: vec![],
             files_modified: vec![prompt_path.to_string_lossy().to_string()],
+            cancelled: false,
         })
     }
 
@@ -15,8 +15,8 @@
 use super::{Context, Provider, ProviderResponse};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::process::Command;
 use std::time::Duration;
+use tokio::process::Command;
 
 /// Goose AI coding agent provider
 pub struct GooseProvider {
@@ -77,17 +77,16 @@ impl GooseProvider {
 #[async_trait]
 impl Provider for GooseProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
-        let mut cmd = self.build_command(prompt, context);
-
-        // Execute with timeout
-        let output = tokio::time::timeout(self.timeout, async {
-            tokio::task::spawn_blocking(move || cmd.output())
-                .await
-                .map_err(|e| anyhow!("Task join error: {}", e))?
-                .map_err(|e| anyhow!("Failed to execute goose: {}", e))
-        })
-        .await
-        .map_err(|_| anyhow!("Goose timed out after {:?}", self.timeout))??;
+        let cmd = self.build_command(prompt, context);
+
+        // Execute with timeout; the process is killed on timeout
+        let output = tokio::time::timeout(self.timeout, super::run_process(cmd, None, context))
+            .await
+            .map_err(|_| anyhow!("Goose timed out after {:?}", self.timeout))?
+            .map_err(|e| anyhow!("Failed to execute goose: {}", e))?;
+        let Some(output) = output else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -108,6 +107,7 @@ impl Provider for GooseProvider {
             errors,
             warnings: vec![],
             files_modified,
+            cancelled: false,
         })
     }
 
@@ -185,10 +185,11 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("test prompt", &context);
-        let args: Vec<_> = cmd.get_args().collect();
+        let args: Vec<_> = cmd.as_std().get_args().collect();
 
         assert!(args.contains(&std::ffi::OsStr::new("--message")));
         assert!(args.contains(&std::ffi::OsStr::new("test prompt")));
@@ -203,10 +204,11 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("test", &context);
-        let args: Vec<_> = cmd.get_args().collect();
+        let args: Vec<_> = cmd.as_std().get_args().collect();
 
         assert!(args.contains(&std::ffi::OsStr::new("--model")));
         assert!(args.contains(&std::ffi::OsStr::new("claude-sonnet-4")));
@@ -1,22 +1,57 @@
 //! Human provider - generates prompt files for IDE assistants (Copilot/Cursor/VS Code Chat)
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use async_trait::async_trait;
 use colored::Colorize;
+use std::io::BufRead;
 use std::path::PathBuf;
+use std::sync::Mutex;
 use tokio::fs;
 
 use super::{Context, Provider, ProviderResponse};
+use crate::error;
+
+/// Line that ends a pasted response
+const END_OF_RESPONSE: &str = ".";
 
 /// Human provider for IDE assistant workflows
 ///
 /// This provider generates a markdown prompt file that users can copy/paste
-/// into their IDE assistant (GitHub Copilot, Cursor, VS Code Chat, etc.)
-pub struct HumanProvider;
+/// into their IDE assistant (GitHub Copilot, Cursor, VS Code Chat, etc.),
+/// then waits for the assistant's answer to be pasted back.
+pub struct HumanProvider {
+    /// Where the response is read from; `None` reads the terminal
+    input: Option<Mutex<Box<dyn BufRead + Send>>>,
+}
 
 impl HumanProvider {
     pub fn new() -> Self {
-        Self
+        Self { input: None }
+    }
+
+    /// Read responses from `input` instead of the terminal
+    pub fn with_input(input: impl BufRead + Send + 'static) -> Self {
+        Self {
+            input: Some(Mutex::new(Box::new(input))),
+        }
+    }
+
+    /// Whether someone is at the terminal to answer
+    fn is_interactive() -> bool {
+        console::user_attended() && std::env::var("CI").is_err()
+    }
+
+    /// Read lines until `END_OF_RESPONSE` or end of input
+    fn read_response(input: &mut dyn BufRead) -> Result<String> {
+        let mut lines = vec![];
+        for line in input.lines() {
+            let line = line?;
+            if line.trim_end() == END_OF_RESPONSE {
+                break;
+            }
+            lines.push(line);
+        }
+        Ok(lines.join("\n").trim().to_string())
     }
 
     /// Generate the prompt file path for a session
@@ -110,6 +145,14 @@ impl Provider for HumanProvider {
         let content = Self::generate_prompt_content(prompt, context);
         fs::write(&prompt_path, &content).await?;
 
+        if self.input.is_none() && !Self::is_interactive() {
+            return Err(error::provider_unavailable(format!(
+                "The human provider needs an interactive terminal. The prompt was saved to {}; \
+                 use an automated provider (e.g. --provider claude-code) in non-interactive runs.",
+                prompt_path.display()
+            )));
+        }
+
         // Print instructions to console
         println!();
         println!("{}", "═".repeat(60).cyan());
@@ -137,13 +180,47 @@ impl Provider for HumanProvider {
         println!("     • VS Code Chat");
         println!("     • Or any other AI assistant");
         println!();
-        println!("  {} Apply the suggested changes in your IDE", "3.".cyan());
+        println!(
+            "  {} Paste the assistant's response below and finish with a line containing only '{}'",
+            "3.".cyan(),
+            END_OF_RESPONSE
+        );
+        println!("     (or leave it empty and apply the changes in your IDE)");
+        println!();
+        println!("{}", "═".repeat(60).cyan());
+        println!();
+        println!("{}", prompt);
         println!();
-        println!("  {} Run evidence capture and complete:", "4.".cyan());
+        println!("{}", "─".repeat(60).dimmed());
+
+        let response = match &self.input {
+            Some(input) => {
+                let mut input = input.lock().unwrap_or_else(|e| e.into_inner());
+                Self::read_response(&mut *input)?
+            }
+            // Reading the terminal blocks until the user finishes the response
+            None => {
+                tokio::task::spawn_blocking(|| Self::read_response(&mut std::io::stdin().lock()))
+                    .await
+                    .context("Reading the response panicked")??
+            }
+        };
+
+        if !response.is_empty() {
+            return Ok(ProviderResponse {
+                success: true,
+                output: response,
+                errors: vec![],
+                warnings: vec![],
+                files_modified: vec![],
+                cancelled: false,
+            });
+        }
+
+        println!();
+        println!("  Apply the suggested changes in your IDE, then run:");
         println!("     {}", "vibeanvil build manual evidence".green());
         println!("     {}", "vibeanvil build manual complete".green());
-        println!();
-        println!("{}", "═".repeat(60).cyan());
 
         Ok(ProviderResponse {
             success: true,
@@ -156,6 +233,7 @@ impl Provider for HumanProvider {
                 "Human provider: No automatic code changes. User must apply changes manually.".to_string()
             ],
             files_modified: vec![prompt_path.display().to_string()],
+            cancelled: false,
         })
     }
 
@@ -196,9 +274,11 @@ mod tests {
             working_dir: temp_dir.path().to_path_buf(),
             session_id: "test-session".to_string(),
             contract_hash: Some("abc123".to_string()),
+            cancel: Default::default(),
         };
 
-        let provider = HumanProvider::new();
+        // An empty response keeps the IDE workflow
+        let provider = HumanProvider::with_input(std::io::Cursor::new(""));
         let response = provider.execute("Test prompt", &context).await.unwrap();
 
         assert!(response.success);
@@ -212,4 +292,30 @@ mod tests {
         assert!(content.contains("test-session"));
         assert!(content.contains("abc123"));
     }
+
+    #[tokio::test]
+    async fn test_returns_pasted_response() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = Context {
+            working_dir: temp_dir.path().to_path_buf(),
+            session_id: "test-session".to_string(),
+            contract_hash: None,
+            cancel: Default::default(),
+        };
+
+        let provider = HumanProvider::with_input(std::io::Cursor::new(
+            "Use a layered design.\n\nKeep storage behind a trait.\n.\nignored after the end marker\n",
+        ));
+        let response = provider
+            .execute("Review the architecture", &context)
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert_eq!(
+            response.output,
+            "Use a layered design.\n\nKeep storage behind a trait."
+        );
+        assert!(response.files_modified.is_empty());
+    }
 }
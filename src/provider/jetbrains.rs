@@ -122,6 +122,7 @@ impl Provider for JetbrainsProvider {
             errors: vec![],
             warnings: vec![],
             files_modified: vec![prompt_path.to_string_lossy().to_string()],
+            cancelled: false,
         })
     }
 
@@ -150,6 +151,7 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let content = JetbrainsProvider::generate_prompt_content("Refactor code", &context);
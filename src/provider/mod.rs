@@ -1,6 +1,7 @@
 //! Provider plugins for AI coding assistants
 
 pub mod aider;
+pub mod cache;
 pub mod capability;
 pub mod claude_code;
 pub mod cline;
@@ -17,6 +18,7 @@ pub mod kiro;
 pub mod ollama;
 pub mod opencode;
 pub mod patch;
+pub mod probe;
 pub mod safety;
 pub mod selector;
 pub mod supermaven;
@@ -32,6 +34,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 
 /// Provider context
 #[derive(Debug, Clone)]
@@ -42,6 +45,10 @@ pub struct Context {
     pub session_id: String,
     /// Contract hash (if locked)
     pub contract_hash: Option<String>,
+    /// Cancelled by the caller to abort an in-flight call, e.g. on Ctrl-C
+    /// during a watch-mode rebuild; only providers that run a process (via
+    /// `run_process`) and the mock check it
+    pub cancel: CancellationToken,
 }
 
 /// Provider response
@@ -57,6 +64,63 @@ pub struct ProviderResponse {
     pub warnings: Vec<String>,
     /// Files modified
     pub files_modified: Vec<String>,
+    /// The call was aborted through `Context::cancel` before it finished
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl ProviderResponse {
+    /// Outcome of a call aborted through `Context::cancel`
+    pub fn cancelled() -> Self {
+        Self {
+            success: false,
+            output: String::new(),
+            errors: vec!["Provider call cancelled".to_string()],
+            warnings: vec![],
+            files_modified: vec![],
+            cancelled: true,
+        }
+    }
+}
+
+/// Run a provider's process to completion without blocking the runtime
+///
+/// `stdin` is written to the process, which otherwise gets no input. The
+/// process is killed when `context.cancel` fires, returning `None`, and when
+/// the returned future is dropped, e.g. by `tokio::time::timeout`.
+pub async fn run_process(
+    mut cmd: tokio::process::Command,
+    stdin: Option<&[u8]>,
+    context: &Context,
+) -> std::io::Result<Option<std::process::Output>> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true);
+    let mut child = cmd.spawn()?;
+
+    let input = child.stdin.take();
+    let write = async {
+        if let (Some(mut pipe), Some(bytes)) = (input, stdin) {
+            // A process that exits without reading its input is not an error
+            let _ = pipe.write_all(bytes).await;
+        }
+    };
+    let run = async {
+        let (_, output) = tokio::join!(write, child.wait_with_output());
+        output
+    };
+    tokio::select! {
+        output = run => output.map(Some),
+        _ = context.cancel.cancelled() => Ok(None),
+    }
 }
 
 /// Provider trait - implement for each AI provider
@@ -81,6 +145,38 @@ pub trait Provider: Send + Sync {
     fn is_available(&self) -> bool;
 }
 
+/// The first provider in `names` that is available, for a fallback chain
+/// such as `claude-code,mock`
+///
+/// Returns the chosen name with the provider. A single name is returned as
+/// is, so its own error explains how to install it when it runs. Unknown
+/// names fail up front.
+pub fn first_available(names: &[String]) -> Result<(String, Box<dyn Provider>)> {
+    if let [name] = names {
+        return Ok((name.clone(), get_provider(name)?));
+    }
+    let providers = names
+        .iter()
+        .map(|name| get_provider(name))
+        .collect::<Result<Vec<_>>>()?;
+    for (i, provider) in providers.into_iter().enumerate() {
+        if provider.is_available() {
+            if i > 0 {
+                println!(
+                    "→ Provider {} selected ({} not available)",
+                    names[i],
+                    names[..i].join(", ")
+                );
+            }
+            return Ok((names[i].clone(), provider));
+        }
+    }
+    Err(crate::error::provider_unavailable(format!(
+        "None of the providers is available: {}",
+        names.join(", ")
+    )))
+}
+
 /// Get a provider by name
 pub fn get_provider(name: &str) -> Result<Box<dyn Provider>> {
     match name {
@@ -254,15 +350,48 @@ pub fn list_providers() -> Vec<&'static str> {
 /// Mock provider for testing
 pub struct MockProvider;
 
+impl MockProvider {
+    /// Declared score for `cap`: text-only capabilities are supported, tooling ones are not
+    pub fn capability_score(cap: Capability) -> u8 {
+        match cap {
+            Capability::CodeGeneration
+            | Capability::CodeReview
+            | Capability::CodeExplanation
+            | Capability::Refactoring
+            | Capability::BugFixing
+            | Capability::TestGeneration
+            | Capability::Documentation
+            | Capability::Architecture
+            | Capability::Planning
+            | Capability::Debugging => 5,
+            _ => 0,
+        }
+    }
+}
+
 #[async_trait]
 impl Provider for MockProvider {
-    async fn execute(&self, prompt: &str, _context: &Context) -> Result<ProviderResponse> {
+    async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
+        if context.cancel.is_cancelled() {
+            return Ok(ProviderResponse::cancelled());
+        }
+        // Simulates a slow provider, e.g. to exercise build --timeout
+        if let Some(ms) = std::env::var("VIBEANVIL_MOCK_DELAY_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_millis(ms)) => {}
+                _ = context.cancel.cancelled() => return Ok(ProviderResponse::cancelled()),
+            }
+        }
         Ok(ProviderResponse {
             success: true,
             output: format!("[MOCK] Would execute: {}", &prompt[..prompt.len().min(100)]),
             errors: vec![],
             warnings: vec!["Using mock provider - no actual changes made".to_string()],
             files_modified: vec![],
+            cancelled: false,
         })
     }
 
@@ -370,6 +499,7 @@ mod tests {
             working_dir: PathBuf::from("."),
             session_id: "test".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let response = provider.execute("test prompt", &context).await.unwrap();
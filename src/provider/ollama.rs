@@ -310,6 +310,7 @@ impl Provider for OllamaProvider {
                     errors: vec![],
                     warnings: vec![format!("Using local model: {}", self.model)],
                     files_modified: vec![],
+                    cancelled: false,
                 })
             }
             Err(e) => {
@@ -18,9 +18,8 @@
 
 use anyhow::{Context as AnyhowContext, Result};
 use async_trait::async_trait;
-use std::io::Write;
-use std::process::{Command, Stdio};
 use std::time::Duration;
+use tokio::process::Command;
 
 use super::safety::{get_timeout_secs, redact_secrets, truncate_output, MAX_OUTPUT_BYTES};
 use super::{Context, Provider, ProviderResponse};
@@ -138,58 +137,42 @@ impl OpenCodeProvider {
     }
 
     /// Execute and capture output
-    fn execute_command(&self, prompt: &str, context: &Context) -> Result<(bool, String, String)> {
-        let mut cmd = self.build_command(prompt, context);
+    ///
+    /// Returns `None` when `context.cancel` fires; the process is killed.
+    async fn execute_command(
+        &self,
+        prompt: &str,
+        context: &Context,
+    ) -> Result<Option<(bool, String, String)>> {
+        let cmd = self.build_command(prompt, context);
 
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-
-        if self.mode == OpenCodeMode::Interactive {
-            cmd.stdin(Stdio::piped());
-        }
+        // For interactive mode, send prompt and exit
+        let input = format!("{}\n/quit\n", prompt);
+        let stdin = (self.mode == OpenCodeMode::Interactive).then_some(input.as_bytes());
 
-        let mut child = cmd.spawn().with_context(|| {
+        // Dropping the run on timeout kills the process
+        let run = super::run_process(cmd, stdin, context);
+        let Ok(output) = tokio::time::timeout(self.timeout, run).await else {
+            return Err(anyhow::anyhow!(
+                "OpenCode timed out after {} seconds.\n\
+                 To increase: export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
+                self.timeout.as_secs()
+            ));
+        };
+        let output = output.with_context(|| {
             format!(
                 "Failed to spawn {}. Install from: https://github.com/opencode-ai/opencode",
                 self.command
             )
         })?;
 
-        // For interactive mode, send prompt and exit
-        if self.mode == OpenCodeMode::Interactive {
-            if let Some(mut stdin) = child.stdin.take() {
-                writeln!(stdin, "{}", prompt)?;
-                writeln!(stdin, "/quit")?;
-            }
-        }
-
-        // Wait with timeout
-        let start = std::time::Instant::now();
-        loop {
-            match child.try_wait() {
-                Ok(Some(status)) => {
-                    let output = child.wait_with_output()?;
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                    return Ok((status.success(), stdout, stderr));
-                }
-                Ok(None) => {
-                    if start.elapsed() > self.timeout {
-                        let _ = child.kill();
-                        let _ = child.wait();
-                        return Err(anyhow::anyhow!(
-                            "OpenCode timed out after {} seconds.\n\
-                             To increase: export VIBEANVIL_PROVIDER_TIMEOUT_SECS=<seconds>",
-                            self.timeout.as_secs()
-                        ));
-                    }
-                    std::thread::sleep(Duration::from_millis(100));
-                }
-                Err(e) => {
-                    return Err(anyhow::anyhow!("Failed to wait for process: {}", e));
-                }
-            }
-        }
+        Ok(output.map(|output| {
+            (
+                output.status.success(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )
+        }))
     }
 }
 
@@ -203,7 +186,7 @@ impl Default for OpenCodeProvider {
 impl Provider for OpenCodeProvider {
     async fn execute(&self, prompt: &str, context: &Context) -> Result<ProviderResponse> {
         if !self.is_available() {
-            return Err(anyhow::anyhow!(
+            return Err(crate::error::provider_unavailable(
                 "OpenCode/Crush not found.\n\n\
                  OpenCode has been archived. Install its successor Crush:\n  \
                  go install github.com/ryboe/crush@latest\n\n\
@@ -211,11 +194,13 @@ impl Provider for OpenCodeProvider {
                  export VIBEANVIL_PROVIDER_COMMAND=<your-agent>\n\n\
                  Alternative terminal agents:\n  \
                  • aider: pip install aider-chat\n  \
-                 • claude: npm install -g @anthropic-ai/claude-code"
+                 • claude: npm install -g @anthropic-ai/claude-code",
             ));
         }
 
-        let (success, stdout, stderr) = self.execute_command(prompt, context)?;
+        let Some((success, stdout, stderr)) = self.execute_command(prompt, context).await? else {
+            return Ok(ProviderResponse::cancelled());
+        };
 
         // Redact secrets and truncate
         let stdout = redact_secrets(&stdout);
@@ -235,6 +220,7 @@ impl Provider for OpenCodeProvider {
             errors,
             warnings: vec![],
             files_modified: vec![],
+            cancelled: false,
         })
     }
 
@@ -271,9 +257,10 @@ mod tests {
             working_dir: PathBuf::from("/tmp/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let cmd = provider.build_command("Fix bug", &context);
-        assert!(cmd.get_program() == "crush" || cmd.get_program() == "opencode");
+        assert!(cmd.as_std().get_program() == "crush" || cmd.as_std().get_program() == "opencode");
     }
 }
@@ -335,6 +335,7 @@ impl Provider for PatchProvider {
                     "No VIBEANVIL_PATCH_FILE set. Generated prompt file instead.".to_string(),
                 ],
                 files_modified: vec![prompt_path.display().to_string()],
+                cancelled: false,
             });
         }
 
@@ -361,6 +362,7 @@ impl Provider for PatchProvider {
                 errors: vec!["No valid hunks found in patch file".to_string()],
                 warnings: vec![],
                 files_modified: vec![],
+                cancelled: false,
             });
         }
 
@@ -396,6 +398,7 @@ impl Provider for PatchProvider {
                         errors: vec![],
                         warnings: vec![],
                         files_modified,
+                        cancelled: false,
                     })
                 } else {
                     let stderr = String::from_utf8_lossy(&apply_output.stderr).to_string();
@@ -405,6 +408,7 @@ impl Provider for PatchProvider {
                         errors: vec![redact_secrets(&stderr)],
                         warnings: vec![],
                         files_modified: vec![],
+                        cancelled: false,
                     })
                 }
             }
@@ -419,6 +423,7 @@ impl Provider for PatchProvider {
                     )],
                     warnings: vec![],
                     files_modified: vec![],
+                    cancelled: false,
                 })
             }
             Err(e) => Err(anyhow::anyhow!("Failed to run git apply: {}", e)),
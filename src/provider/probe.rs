@@ -0,0 +1,163 @@
+//! Capability probes
+//!
+//! Sends a small capability-specific prompt to a provider and checks whether
+//! the response demonstrates support, then compares that with the score the
+//! capability matrix declares.
+
+use anyhow::Result;
+
+use super::{Capability, CapabilityMatrix, Context, Provider};
+
+/// Marker a provider is asked to answer with when the capability can only be self-reported
+const SUPPORTED: &str = "SUPPORTED";
+const UNSUPPORTED: &str = "UNSUPPORTED";
+
+/// Outcome of probing one provider for one capability
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub provider: String,
+    pub capability: Capability,
+    /// Score from the capability matrix (None if the provider isn't listed)
+    pub declared_score: Option<u8>,
+    /// Whether the response indicated support
+    pub supported: bool,
+    /// First part of the provider response
+    pub excerpt: String,
+}
+
+impl ProbeResult {
+    /// Whether the probe agrees with the matrix (unlisted providers always agree)
+    pub fn matches_declared(&self) -> bool {
+        self.declared_score
+            .is_none_or(|score| (score > 0) == self.supported)
+    }
+}
+
+/// Prompt that exercises `cap`, and the text expected in a supporting response
+pub fn probe_prompt(cap: Capability) -> (String, &'static str) {
+    let task = match cap {
+        Capability::CodeGeneration => Some((
+            "Write a Rust function `fn add(a: i32, b: i32) -> i32` that returns the sum. Reply with only the code.",
+            "fn add",
+        )),
+        Capability::TestGeneration => Some((
+            "Write a Rust unit test for `fn add(a: i32, b: i32) -> i32`. Reply with only the code.",
+            "#[test]",
+        )),
+        Capability::CodeReview | Capability::BugFixing | Capability::Debugging => Some((
+            "What input makes `fn div(a: i32, b: i32) -> i32 { a / b }` panic? Answer in one short sentence.",
+            "zero",
+        )),
+        Capability::CodeExplanation => Some((
+            "In one sentence, what does `xs.iter().sum::<i32>()` compute in Rust?",
+            "sum",
+        )),
+        Capability::Documentation => Some((
+            "Write a one-line Rust doc comment for `fn add(a: i32, b: i32) -> i32`. Reply with only the comment.",
+            "///",
+        )),
+        _ => None,
+    };
+
+    match task {
+        Some((prompt, expected)) => (prompt.to_string(), expected),
+        None => (
+            format!(
+                "Do you support the '{}' capability? Answer with exactly one word: {} or {}.",
+                cap.display_name(),
+                SUPPORTED,
+                UNSUPPORTED
+            ),
+            SUPPORTED,
+        ),
+    }
+}
+
+/// Whether `output` demonstrates `cap`
+pub fn indicates_support(cap: Capability, output: &str) -> bool {
+    let (_, expected) = probe_prompt(cap);
+    if expected == SUPPORTED {
+        let upper = output.to_uppercase();
+        return upper.contains(SUPPORTED) && !upper.contains(UNSUPPORTED);
+    }
+    output.to_lowercase().contains(&expected.to_lowercase())
+}
+
+/// Probe `provider` for `cap`
+///
+/// The mock provider cannot answer prompts, so it reports its declared
+/// capabilities instead.
+pub async fn probe(
+    provider: &dyn Provider,
+    cap: Capability,
+    context: &Context,
+) -> Result<ProbeResult> {
+    let matrix_name = provider.name().replace('-', "_");
+    let declared_score = match provider.name() {
+        "mock" => Some(super::MockProvider::capability_score(cap)),
+        _ => CapabilityMatrix::build_default()
+            .get(&matrix_name)
+            .map(|profile| profile.capability_score(cap)),
+    };
+
+    let (supported, excerpt) = if provider.name() == "mock" {
+        let supported = declared_score.unwrap_or(0) > 0;
+        let answer = if supported { SUPPORTED } else { UNSUPPORTED };
+        (supported, format!("[MOCK] {}", answer))
+    } else {
+        let (prompt, _) = probe_prompt(cap);
+        let response = provider.execute(&prompt, context).await?;
+        let supported = response.success && indicates_support(cap, &response.output);
+        (supported, response.output.chars().take(200).collect())
+    };
+
+    Ok(ProbeResult {
+        provider: provider.name().to_string(),
+        capability: cap,
+        declared_score,
+        supported,
+        excerpt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::MockProvider;
+
+    fn context() -> Context {
+        Context {
+            working_dir: std::env::temp_dir(),
+            session_id: "probe-test".to_string(),
+            contract_hash: None,
+            cancel: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_probe_mock_code_generation_succeeds() {
+        let result = probe(&MockProvider, Capability::CodeGeneration, &context())
+            .await
+            .unwrap();
+        assert!(result.supported);
+        assert!(result.matches_declared());
+        assert!(result.declared_score.unwrap() > 0);
+
+        let vision = probe(&MockProvider, Capability::Vision, &context())
+            .await
+            .unwrap();
+        assert!(!vision.supported);
+        assert!(vision.matches_declared());
+    }
+
+    #[test]
+    fn test_indicates_support() {
+        assert!(indicates_support(
+            Capability::CodeGeneration,
+            "fn add(a: i32, b: i32) -> i32 { a + b }"
+        ));
+        assert!(indicates_support(Capability::Vision, "Supported."));
+        assert!(!indicates_support(Capability::Vision, "UNSUPPORTED"));
+        assert!(!indicates_support(Capability::TestGeneration, "I can't"));
+    }
+}
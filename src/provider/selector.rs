@@ -128,6 +128,27 @@ impl TaskType {
     }
 }
 
+/// Relative weights of the ranking factors
+#[derive(Debug, Clone, Copy)]
+pub struct RankingWeights {
+    /// Weight of the capability match (0..1 normalized)
+    pub capability: f32,
+    /// Weight of low cost, applied when `prefer_low_cost` is set
+    pub cost: f32,
+    /// Weight of low latency, applied when `prefer_low_latency` is set
+    pub latency: f32,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            capability: 1.0,
+            cost: 0.5,
+            latency: 0.3,
+        }
+    }
+}
+
 /// Selection criteria
 #[derive(Debug, Clone)]
 pub struct SelectionCriteria {
@@ -145,6 +166,8 @@ pub struct SelectionCriteria {
     pub exclude: Vec<String>,
     /// Minimum capability threshold (1-10)
     pub min_capability: u8,
+    /// Ranking weights
+    pub weights: RankingWeights,
 }
 
 impl Default for SelectionCriteria {
@@ -157,6 +180,7 @@ impl Default for SelectionCriteria {
             require_local: false,
             exclude: Vec::new(),
             min_capability: 6,
+            weights: RankingWeights::default(),
         }
     }
 }
@@ -193,6 +217,99 @@ impl SelectionCriteria {
         self.exclude.push(name.to_string());
         self
     }
+
+    pub fn with_weights(mut self, weights: RankingWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+}
+
+/// Combine capability match, cost and latency into a single score (higher is better)
+fn weighted_score(
+    p: &ProviderProfile,
+    criteria: &SelectionCriteria,
+    required: &[Capability],
+    preferred: &[Capability],
+    max_cost: f32,
+    max_latency: u32,
+) -> f32 {
+    const PREFERRED_FACTOR: f32 = 0.3;
+
+    let achieved: f32 = required
+        .iter()
+        .map(|c| p.capability_score(*c) as f32)
+        .sum::<f32>()
+        + preferred
+            .iter()
+            .map(|c| p.capability_score(*c) as f32 * PREFERRED_FACTOR)
+            .sum::<f32>();
+    let possible = 10.0 * (required.len() as f32 + preferred.len() as f32 * PREFERRED_FACTOR);
+    let mut capability = if possible > 0.0 {
+        achieved / possible
+    } else {
+        0.0
+    };
+    // Lower tier breaks near-ties
+    capability += (5 - p.tier.min(5) as i32) as f32 * 0.01;
+
+    let mut score = criteria.weights.capability * capability;
+
+    if criteria.prefer_low_cost {
+        let cheapness = if max_cost > 0.0 {
+            1.0 - p.cost_per_1k / max_cost
+        } else {
+            1.0
+        };
+        score += criteria.weights.cost * cheapness;
+    }
+
+    if criteria.prefer_low_latency {
+        let speed = if max_latency > 0 {
+            1.0 - p.latency_ms as f32 / max_latency as f32
+        } else {
+            1.0
+        };
+        score += criteria.weights.latency * speed;
+    }
+
+    score
+}
+
+/// Capability score below which a provider is considered weak for a task
+pub const WEAK_CAPABILITY_THRESHOLD: u8 = 6;
+
+/// Warning that a chosen provider is a poor fit for a task
+#[derive(Debug, Clone)]
+pub struct CapabilityWarning {
+    /// Provider the user chose
+    pub provider: String,
+    /// Task it was chosen for
+    pub task_type: TaskType,
+    /// Capabilities scoring below the threshold, with their scores
+    pub weak: Vec<(Capability, u8)>,
+    /// Better-suited provider, if any
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for CapabilityWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let weak: Vec<String> = self
+            .weak
+            .iter()
+            .map(|(cap, score)| format!("{} {}/10", cap.display_name(), score))
+            .collect();
+        write!(
+            f,
+            "Provider '{}' is weak for {:?} tasks ({})",
+            self.provider,
+            self.task_type,
+            weak.join(", ")
+        )?;
+        if let Some(better) = &self.suggestion {
+            write!(f, ". Consider '{}' instead", better)?;
+        }
+        Ok(())
+    }
 }
 
 /// Provider selector
@@ -228,90 +345,62 @@ impl ProviderSelector {
         self.available.is_empty() || self.available.contains(&name.to_string())
     }
 
-    /// Select best provider for criteria
-    pub fn select(&self, criteria: &SelectionCriteria) -> Option<&ProviderProfile> {
+    /// Hard filters: availability, exclusions, agentic/local requirements and minimum capability
+    fn passes_filters(
+        &self,
+        p: &ProviderProfile,
+        criteria: &SelectionCriteria,
+        required: &[Capability],
+    ) -> bool {
+        if !self.is_available(&p.name) || criteria.exclude.contains(&p.name) {
+            return false;
+        }
+        if criteria.require_agentic && !p.has_capability(Capability::Agentic, 7) {
+            return false;
+        }
+        if criteria.require_local && !p.tags.contains(&"local".to_string()) {
+            return false;
+        }
+        required
+            .iter()
+            .all(|cap| p.capability_score(*cap) >= criteria.min_capability)
+    }
+
+    /// Rank candidates by weighted score, best first
+    fn rank(&self, criteria: &SelectionCriteria) -> Vec<(&ProviderProfile, f32)> {
         let required = criteria.task_type.required_capabilities();
         let preferred = criteria.task_type.preferred_capabilities();
 
-        // Get candidates
-        let candidates: Vec<_> = self
+        let candidates: Vec<&ProviderProfile> = self
             .matrix
             .list()
             .into_iter()
-            .filter(|p| {
-                // Check availability
-                if !self.is_available(&p.name) {
-                    return false;
-                }
-
-                // Check exclusions
-                if criteria.exclude.contains(&p.name) {
-                    return false;
-                }
-
-                // Check agentic requirement
-                if criteria.require_agentic && !p.has_capability(Capability::Agentic, 7) {
-                    return false;
-                }
-
-                // Check local requirement
-                if criteria.require_local && !p.tags.contains(&"local".to_string()) {
-                    return false;
-                }
-
-                // Check minimum capability for required caps
-                for cap in &required {
-                    if p.capability_score(*cap) < criteria.min_capability {
-                        return false;
-                    }
-                }
-
-                true
-            })
+            .filter(|p| self.passes_filters(p, criteria, &required))
             .collect();
 
-        if candidates.is_empty() {
-            return None;
-        }
-
-        // Score candidates
-        let scored: Vec<_> = candidates
+        // Cost and latency are normalized against the worst candidate
+        let max_cost = candidates
             .iter()
-            .map(|p| {
-                let mut score: i32 = 0;
-
-                // Base score from required capabilities
-                for cap in &required {
-                    score += p.capability_score(*cap) as i32 * 10;
-                }
+            .map(|p| p.cost_per_1k)
+            .fold(0.0_f32, f32::max);
+        let max_latency = candidates.iter().map(|p| p.latency_ms).max().unwrap_or(0);
 
-                // Bonus from preferred capabilities
-                for cap in &preferred {
-                    score += p.capability_score(*cap) as i32 * 3;
-                }
-
-                // Tier bonus (lower tier = better)
-                score += (5 - p.tier as i32) * 20;
-
-                // Cost penalty
-                if criteria.prefer_low_cost && p.cost_per_1k > 0.0 {
-                    score -= (p.cost_per_1k * 100.0) as i32;
-                }
-
-                // Latency penalty
-                if criteria.prefer_low_latency {
-                    score -= (p.latency_ms / 100) as i32;
-                }
-
-                (*p, score)
+        let mut scored: Vec<_> = candidates
+            .into_iter()
+            .map(|p| {
+                let score =
+                    weighted_score(p, criteria, &required, &preferred, max_cost, max_latency);
+                (p, score)
             })
             .collect();
 
-        // Find best
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
         scored
-            .into_iter()
-            .max_by_key(|(_, score)| *score)
-            .map(|(p, _)| p)
+    }
+
+    /// Select best provider for criteria
+    pub fn select(&self, criteria: &SelectionCriteria) -> Option<&ProviderProfile> {
+        self.rank(criteria).into_iter().next().map(|(p, _)| p)
     }
 
     /// Select multiple providers (for fallback chain)
@@ -320,43 +409,11 @@ impl ProviderSelector {
         criteria: &SelectionCriteria,
         count: usize,
     ) -> Vec<&ProviderProfile> {
-        let required = criteria.task_type.required_capabilities();
-
-        // Get all matching providers
-        let candidates: Vec<_> = self
-            .matrix
-            .list()
+        self.rank(criteria)
             .into_iter()
-            .filter(|p| {
-                if !self.is_available(&p.name) {
-                    return false;
-                }
-                if criteria.exclude.contains(&p.name) {
-                    return false;
-                }
-                if criteria.require_agentic && !p.has_capability(Capability::Agentic, 7) {
-                    return false;
-                }
-                if criteria.require_local && !p.tags.contains(&"local".to_string()) {
-                    return false;
-                }
-                true
-            })
-            .collect();
-
-        // Score and sort
-        let mut scored: Vec<_> = candidates
-            .iter()
-            .map(|p| {
-                let mut score: i32 = p.match_score(&required) as i32;
-                score += (5 - p.tier as i32) * 10;
-                (*p, score)
-            })
-            .collect();
-
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
-
-        scored.into_iter().take(count).map(|(p, _)| p).collect()
+            .take(count)
+            .map(|(p, _)| p)
+            .collect()
     }
 
     /// Quick select for task description
@@ -366,6 +423,42 @@ impl ProviderSelector {
         self.select(&criteria)
     }
 
+    /// Check whether a chosen provider is weak for a task.
+    ///
+    /// Providers missing from the matrix (custom commands, mock) are not judged.
+    pub fn capability_warning(
+        &self,
+        provider: &str,
+        task_type: TaskType,
+        require_agentic: bool,
+    ) -> Option<CapabilityWarning> {
+        let profile = self.matrix.get(&provider.replace('-', "_"))?;
+
+        let mut required = task_type.required_capabilities();
+        if require_agentic && !required.contains(&Capability::Agentic) {
+            required.push(Capability::Agentic);
+        }
+        let weak: Vec<(Capability, u8)> = required
+            .into_iter()
+            .map(|cap| (cap, profile.capability_score(cap)))
+            .filter(|(_, score)| *score < WEAK_CAPABILITY_THRESHOLD)
+            .collect();
+        if weak.is_empty() {
+            return None;
+        }
+
+        let mut criteria = SelectionCriteria::for_task(task_type).exclude_provider(&profile.name);
+        if require_agentic {
+            criteria = criteria.agentic();
+        }
+        Some(CapabilityWarning {
+            provider: provider.to_string(),
+            task_type,
+            weak,
+            suggestion: self.select(&criteria).map(|p| p.name.replace('_', "-")),
+        })
+    }
+
     /// Get recommendations with explanations
     pub fn recommend(&self, description: &str, count: usize) -> Vec<ProviderRecommendation> {
         let task_type = TaskType::infer(description);
@@ -544,6 +637,67 @@ mod tests {
         assert!(!recs[0].reason.is_empty());
     }
 
+    fn cost_matrix() -> CapabilityMatrix {
+        let mut matrix = CapabilityMatrix::new();
+        matrix.add(
+            ProviderProfile::new("premium", 1)
+                .with_capability(Capability::CodeGeneration, 10)
+                .with_capability(Capability::Streaming, 10)
+                .with_cost(0.03)
+                .with_latency(1500),
+        );
+        matrix.add(
+            ProviderProfile::new("budget", 2)
+                .with_capability(Capability::CodeGeneration, 8)
+                .with_capability(Capability::Streaming, 8)
+                .with_cost(0.002)
+                .with_latency(400),
+        );
+        matrix.add(
+            ProviderProfile::new("weak", 3)
+                .with_capability(Capability::CodeGeneration, 4)
+                .with_capability(Capability::Streaming, 4),
+        );
+        matrix
+    }
+
+    #[test]
+    fn test_prefer_low_cost_ranks_cheaper_adequate_provider_first() {
+        let selector = ProviderSelector::with_matrix(cost_matrix());
+
+        let criteria = SelectionCriteria::for_task(TaskType::CodeGeneration);
+        assert_eq!(selector.select(&criteria).unwrap().name, "premium");
+
+        let criteria = SelectionCriteria::for_task(TaskType::CodeGeneration).low_cost();
+        let chain = selector.select_chain(&criteria, 3);
+        let names: Vec<&str> = chain.iter().map(|p| p.name.as_str()).collect();
+        // "weak" is free but falls below min_capability, so it is filtered out
+        assert_eq!(names, vec!["budget", "premium"]);
+    }
+
+    #[test]
+    fn test_weights_control_tradeoff() {
+        let selector = ProviderSelector::with_matrix(cost_matrix());
+
+        let capability_only = SelectionCriteria::for_task(TaskType::CodeGeneration)
+            .fast()
+            .with_weights(RankingWeights {
+                capability: 1.0,
+                cost: 0.0,
+                latency: 0.0,
+            });
+        assert_eq!(selector.select(&capability_only).unwrap().name, "premium");
+
+        let latency_heavy = SelectionCriteria::for_task(TaskType::CodeGeneration)
+            .fast()
+            .with_weights(RankingWeights {
+                capability: 1.0,
+                cost: 0.0,
+                latency: 1.0,
+            });
+        assert_eq!(selector.select(&latency_heavy).unwrap().name, "budget");
+    }
+
     #[test]
     fn test_recommendation_display() {
         let rec = ProviderRecommendation {
@@ -561,4 +715,43 @@ mod tests {
         assert!(display.contains("Tier 1"));
         assert!(display.contains("9/10"));
     }
+
+    #[test]
+    fn test_capability_warning_for_weak_reviewer() {
+        let selector = ProviderSelector::new();
+
+        let warning = selector
+            .capability_warning("tabnine", TaskType::CodeReview, false)
+            .expect("tabnine should be flagged for review");
+        assert!(warning
+            .weak
+            .iter()
+            .any(|(cap, _)| *cap == Capability::CodeReview));
+        let better = warning.suggestion.clone().unwrap();
+        assert_ne!(better, "tabnine");
+        assert!(warning.to_string().contains("is weak for CodeReview"));
+        assert!(warning
+            .to_string()
+            .contains(&format!("Consider '{}'", better)));
+
+        // Strong providers and unknown providers are not flagged
+        assert!(selector
+            .capability_warning("claude-code", TaskType::CodeReview, false)
+            .is_none());
+        assert!(selector
+            .capability_warning("mock", TaskType::CodeReview, false)
+            .is_none());
+    }
+
+    #[test]
+    fn test_capability_warning_for_non_agentic_iterate() {
+        let selector = ProviderSelector::new();
+        let warning = selector
+            .capability_warning("tabnine", TaskType::CodeGeneration, true)
+            .unwrap();
+        assert!(warning
+            .weak
+            .iter()
+            .any(|(cap, _)| *cap == Capability::Agentic));
+    }
 }
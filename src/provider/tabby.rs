@@ -123,6 +123,7 @@ impl Provider for TabbyProvider {
             errors: vec![],
             warnings: vec![],
             files_modified: vec![],
+            cancelled: false,
         })
     }
 
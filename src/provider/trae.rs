@@ -121,6 +121,7 @@ impl Provider for TraeProvider {
             errors: vec![],
             warnings: vec![],
             files_modified: vec![prompt_path.to_string_lossy().to_string()],
+            cancelled: false,
         })
     }
 
@@ -149,6 +150,7 @@ mod tests {
             working_dir: PathBuf::from("/test"),
             session_id: "test-session".to_string(),
             contract_hash: None,
+            cancel: Default::default(),
         };
 
         let content = TraeProvider::generate_prompt_content("Create API", &context);
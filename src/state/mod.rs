@@ -1,11 +1,13 @@
 //! State machine for the vibeanvil workflow
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
 
 /// All possible workflow states
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum State {
     /// Initial state - workspace just created
@@ -51,7 +53,42 @@ impl fmt::Display for State {
     }
 }
 
+impl FromStr for State {
+    type Err = anyhow::Error;
+
+    /// Parse `INTAKE_CAPTURED`, `IntakeCaptured` or `intake-captured`
+    fn from_str(s: &str) -> Result<Self> {
+        let wanted: String = s
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        State::all()
+            .iter()
+            .copied()
+            .find(|state| state.to_string().replace('_', "").to_lowercase() == wanted)
+            .ok_or_else(|| anyhow::anyhow!("Unknown state '{}'", s))
+    }
+}
+
 impl State {
+    /// All states in workflow order
+    pub fn all() -> &'static [State] {
+        &[
+            State::Init,
+            State::IntakeCaptured,
+            State::BlueprintDrafted,
+            State::ContractDrafted,
+            State::ContractLocked,
+            State::PlanCreated,
+            State::BuildInProgress,
+            State::BuildDone,
+            State::ReviewPassed,
+            State::ReviewFailed,
+            State::Shipped,
+        ]
+    }
+
     /// Get the ordinal value of this state (for ordering)
     pub fn ordinal(&self) -> u8 {
         match self {
@@ -69,6 +106,18 @@ impl State {
         }
     }
 
+    /// Workflow completion from 0 to 100, from `ordinal()` relative to SHIPPED
+    ///
+    /// REVIEW_FAILED counts as BUILD_DONE: a failed review sends the work back
+    /// to the build, so it isn't progress past it.
+    pub fn progress_percentage(&self) -> u8 {
+        let ordinal = match self {
+            State::ReviewFailed => State::BuildDone.ordinal(),
+            state => state.ordinal(),
+        };
+        (f64::from(ordinal) * 100.0 / f64::from(State::Shipped.ordinal())).round() as u8
+    }
+
     /// Check if this state is at or past the target state
     pub fn is_at_least(&self, target: State) -> bool {
         self.ordinal() >= target.ordinal()
@@ -99,6 +148,48 @@ impl State {
     pub fn can_transition_to(&self, target: State) -> bool {
         self.valid_transitions().contains(&target)
     }
+
+    /// Earlier states this state may be rolled back to.
+    ///
+    /// Only stable checkpoints qualify: not `Init` (use a full reset), not the
+    /// transient `BuildInProgress`, and not review outcomes. `Shipped` is final.
+    pub fn rollback_targets(&self) -> Vec<State> {
+        if *self == State::Shipped {
+            return vec![];
+        }
+        [
+            State::IntakeCaptured,
+            State::BlueprintDrafted,
+            State::ContractDrafted,
+            State::ContractLocked,
+            State::PlanCreated,
+            State::BuildDone,
+        ]
+        .into_iter()
+        .filter(|target| target.ordinal() < self.ordinal())
+        .collect()
+    }
+
+    /// Check if rolling back to target state is allowed
+    pub fn can_rollback_to(&self, target: State) -> bool {
+        self.rollback_targets().contains(&target)
+    }
+
+    /// Target of an abort: any state goes back to `Init`.
+    ///
+    /// `Shipped` is terminal, so aborting it requires `force`. There is nothing
+    /// to abort from `Init` itself.
+    pub fn abort(&self, force: bool) -> Result<State> {
+        match self {
+            State::Init => Err(crate::error::invalid_state(
+                "Nothing to abort: the workflow is already at INIT",
+            )),
+            State::Shipped if !force => Err(crate::error::invalid_state(
+                "Refusing to abort a shipped workflow; pass --force to start over anyway",
+            )),
+            _ => Ok(State::Init),
+        }
+    }
 }
 
 /// State history entry
@@ -114,6 +205,16 @@ pub struct StateHistoryEntry {
     pub action: String,
     /// Session ID active during transition
     pub session_id: String,
+    /// Why the transition happened (set for rollbacks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl StateHistoryEntry {
+    /// Time between `previous` and this entry
+    pub fn elapsed_since(&self, previous: &StateHistoryEntry) -> chrono::Duration {
+        self.timestamp - previous.timestamp
+    }
 }
 
 /// Complete state data persisted to state.json
@@ -138,6 +239,9 @@ pub struct StateData {
     /// Guardrails configuration
     #[serde(default)]
     pub guardrails: crate::guardrails::GuardrailsConfig,
+    /// Format new contracts are written in
+    #[serde(default)]
+    pub contract_format: crate::contract::ContractFormat,
 }
 
 impl Default for StateData {
@@ -153,6 +257,7 @@ impl Default for StateData {
             updated_at: now,
             created_at: now,
             guardrails: crate::guardrails::GuardrailsConfig::default(),
+            contract_format: crate::contract::ContractFormat::default(),
         }
     }
 }
@@ -166,12 +271,12 @@ impl StateData {
         session_id: &str,
     ) -> Result<()> {
         if !self.current_state.can_transition_to(new_state) {
-            return Err(anyhow!(
+            return Err(crate::error::invalid_state(format!(
                 "Invalid transition: {} → {}. Allowed: {:?}",
                 self.current_state,
                 new_state,
                 self.current_state.valid_transitions()
-            ));
+            )));
         }
 
         let entry = StateHistoryEntry {
@@ -180,6 +285,7 @@ impl StateData {
             timestamp: chrono::Utc::now(),
             action: action.to_string(),
             session_id: session_id.to_string(),
+            reason: None,
         };
 
         self.history.push(entry);
@@ -190,17 +296,183 @@ impl StateData {
         Ok(())
     }
 
+    /// Deliberately move back to an earlier checkpoint state.
+    ///
+    /// Rolling back before `ContractLocked` clears the recorded spec hash so
+    /// the contract must be locked again.
+    pub fn rollback_to(&mut self, target: State, reason: &str, session_id: &str) -> Result<()> {
+        if !self.current_state.can_rollback_to(target) {
+            return Err(crate::error::invalid_state(format!(
+                "Cannot roll back: {} → {}. Allowed: {:?}",
+                self.current_state,
+                target,
+                self.current_state.rollback_targets()
+            )));
+        }
+
+        self.history.push(StateHistoryEntry {
+            from_state: self.current_state,
+            to_state: target,
+            timestamp: chrono::Utc::now(),
+            action: "rollback".to_string(),
+            session_id: session_id.to_string(),
+            reason: Some(reason.to_string()),
+        });
+        self.current_state = target;
+        self.current_session_id = Some(session_id.to_string());
+        self.updated_at = chrono::Utc::now();
+        if !target.is_at_least(State::ContractLocked) {
+            self.spec_hash = None;
+        }
+
+        Ok(())
+    }
+
+    /// Reopen a locked contract for amendment: `ContractLocked → ContractDrafted`.
+    ///
+    /// This is the only forward path back to drafting; `transition_to` refuses it.
+    /// The spec hash is cleared until the amended contract is locked.
+    pub fn amend_contract(&mut self, session_id: &str) -> Result<()> {
+        if self.current_state != State::ContractLocked {
+            return Err(crate::error::invalid_state(format!(
+                "Cannot amend contract in state {}; amend is only allowed from {}",
+                self.current_state,
+                State::ContractLocked
+            )));
+        }
+
+        self.history.push(StateHistoryEntry {
+            from_state: State::ContractLocked,
+            to_state: State::ContractDrafted,
+            timestamp: chrono::Utc::now(),
+            action: "contract amend".to_string(),
+            session_id: session_id.to_string(),
+            reason: None,
+        });
+        self.current_state = State::ContractDrafted;
+        self.current_session_id = Some(session_id.to_string());
+        self.updated_at = chrono::Utc::now();
+        self.spec_hash = None;
+
+        Ok(())
+    }
+
+    /// Abandon the current workflow and return to `Init`.
+    ///
+    /// The abort is recorded in history with its reason. The spec hash and the
+    /// current session are cleared; contracts and evidence on disk are kept.
+    pub fn abort(&mut self, reason: &str, session_id: &str, force: bool) -> Result<()> {
+        let target = self.current_state.abort(force)?;
+
+        self.history.push(StateHistoryEntry {
+            from_state: self.current_state,
+            to_state: target,
+            timestamp: chrono::Utc::now(),
+            action: "abort".to_string(),
+            session_id: session_id.to_string(),
+            reason: Some(reason.to_string()),
+        });
+        self.current_state = target;
+        self.current_session_id = None;
+        self.spec_hash = None;
+        self.updated_at = chrono::Utc::now();
+
+        Ok(())
+    }
+
+    /// Wall-clock time spent in each state, up to now
+    pub fn time_in_state(&self) -> HashMap<State, chrono::Duration> {
+        self.time_in_state_at(chrono::Utc::now())
+    }
+
+    /// Wall-clock time spent in each state, up to `now`
+    ///
+    /// Repeated visits (e.g. the `ReviewFailed → BuildInProgress` retry loop)
+    /// are summed. Time before the first transition counts towards the state
+    /// that entry left; time since the last one counts towards `current_state`.
+    pub fn time_in_state_at(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> HashMap<State, chrono::Duration> {
+        let mut totals: HashMap<State, chrono::Duration> = HashMap::new();
+        let mut add = |state: State, span: chrono::Duration| {
+            *totals.entry(state).or_insert_with(chrono::Duration::zero) +=
+                span.max(chrono::Duration::zero());
+        };
+
+        let mut since = self.created_at;
+        for entry in &self.history {
+            add(entry.from_state, entry.timestamp - since);
+            since = entry.timestamp;
+        }
+        add(self.current_state, now - since);
+
+        totals
+    }
+
     /// Get the last N history entries
     pub fn recent_history(&self, n: usize) -> &[StateHistoryEntry] {
         let start = self.history.len().saturating_sub(n);
         &self.history[start..]
     }
+
+    /// One page of history, newest first, skipping the `offset` most recent entries
+    pub fn history_page(&self, offset: usize, limit: usize) -> Vec<&StateHistoryEntry> {
+        self.history.iter().rev().skip(offset).take(limit).collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_progress_percentage() {
+        assert_eq!(State::Init.progress_percentage(), 0);
+        assert_eq!(State::ContractLocked.progress_percentage(), 44);
+        assert_eq!(State::BuildDone.progress_percentage(), 78);
+        assert_eq!(State::ReviewPassed.progress_percentage(), 89);
+        assert_eq!(State::Shipped.progress_percentage(), 100);
+        // A failed review is a step back, not progress past the build
+        assert_eq!(
+            State::ReviewFailed.progress_percentage(),
+            State::BuildDone.progress_percentage()
+        );
+    }
+
+    #[test]
+    fn test_history_page_newest_first() {
+        let path = [
+            State::IntakeCaptured,
+            State::BlueprintDrafted,
+            State::ContractDrafted,
+            State::ContractLocked,
+            State::PlanCreated,
+            State::BuildInProgress,
+            State::BuildDone,
+            State::ReviewFailed,
+            State::BuildInProgress,
+            State::BuildDone,
+        ];
+        let mut state = StateData::default();
+        for (i, next) in path.into_iter().enumerate() {
+            state
+                .transition_to(next, &format!("step-{}", i + 1), "s1")
+                .unwrap();
+        }
+        assert_eq!(state.history.len(), 10);
+
+        // Page 2 with a limit of 3
+        let page = state.history_page(3, 3);
+        let actions: Vec<&str> = page.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, vec!["step-7", "step-6", "step-5"]);
+        assert_eq!(page[0].from_state, State::BuildInProgress);
+        assert_eq!(page[0].to_state, State::BuildDone);
+
+        assert_eq!(state.history_page(9, 3).len(), 1);
+        assert!(state.history_page(10, 3).is_empty());
+    }
+
     #[test]
     fn test_valid_transitions() {
         assert!(State::Init.can_transition_to(State::IntakeCaptured));
@@ -223,6 +495,167 @@ mod tests {
         assert_eq!(state.history.len(), 1);
     }
 
+    #[test]
+    fn test_rollback_to_checkpoint() {
+        let mut state = StateData {
+            current_state: State::PlanCreated,
+            spec_hash: Some("abc".to_string()),
+            ..Default::default()
+        };
+
+        state
+            .rollback_to(State::ContractLocked, "fix plan", "session-1")
+            .unwrap();
+        assert_eq!(state.current_state, State::ContractLocked);
+        assert_eq!(state.spec_hash.as_deref(), Some("abc"));
+
+        state
+            .rollback_to(State::ContractDrafted, "contract defect", "session-2")
+            .unwrap();
+        assert_eq!(state.spec_hash, None);
+
+        let last = state.history.last().unwrap();
+        assert_eq!(last.action, "rollback");
+        assert_eq!(last.reason.as_deref(), Some("contract defect"));
+        assert_eq!(last.from_state, State::ContractLocked);
+    }
+
+    #[test]
+    fn test_rollback_guards() {
+        let mut shipped = StateData {
+            current_state: State::Shipped,
+            ..Default::default()
+        };
+        assert!(shipped.rollback_to(State::BuildDone, "oops", "s").is_err());
+
+        let mut state = StateData {
+            current_state: State::ContractDrafted,
+            ..Default::default()
+        };
+        assert!(state.rollback_to(State::Init, "start over", "s").is_err());
+        assert!(state
+            .rollback_to(State::PlanCreated, "forward", "s")
+            .is_err());
+        assert!(state.history.is_empty());
+    }
+
+    #[test]
+    fn test_time_in_state_sums_retry_loop() {
+        let start = chrono::DateTime::UNIX_EPOCH;
+        let at = |minutes: i64| start + chrono::Duration::minutes(minutes);
+        let entry = |from, to, minutes| StateHistoryEntry {
+            from_state: from,
+            to_state: to,
+            timestamp: at(minutes),
+            action: "test".to_string(),
+            session_id: "s".to_string(),
+            reason: None,
+        };
+        let state = StateData {
+            current_state: State::BuildDone,
+            created_at: start,
+            history: vec![
+                entry(State::PlanCreated, State::BuildInProgress, 10),
+                entry(State::BuildInProgress, State::BuildDone, 40),
+                entry(State::BuildDone, State::ReviewFailed, 45),
+                entry(State::ReviewFailed, State::BuildInProgress, 50),
+                entry(State::BuildInProgress, State::BuildDone, 70),
+            ],
+            ..Default::default()
+        };
+
+        let times = state.time_in_state_at(at(100));
+        let minutes = |s: State| times.get(&s).map(|d| d.num_minutes());
+        assert_eq!(minutes(State::PlanCreated), Some(10));
+        assert_eq!(minutes(State::BuildInProgress), Some(30 + 20));
+        assert_eq!(minutes(State::ReviewFailed), Some(5));
+        // 40..45 plus the open span 70..100
+        assert_eq!(minutes(State::BuildDone), Some(5 + 30));
+        assert_eq!(minutes(State::Shipped), None);
+
+        let fresh = StateData {
+            created_at: start,
+            ..Default::default()
+        };
+        assert_eq!(
+            fresh.time_in_state_at(at(3)).get(&State::Init),
+            Some(&chrono::Duration::minutes(3))
+        );
+    }
+
+    #[test]
+    fn test_abort_returns_to_init() {
+        let mut state = StateData {
+            current_state: State::BuildInProgress,
+            current_session_id: Some("s1".to_string()),
+            spec_hash: Some("abc".to_string()),
+            ..Default::default()
+        };
+        // Normal transitions still can't skip back
+        assert!(state.transition_to(State::Init, "manual", "s2").is_err());
+
+        state.abort("build went sideways", "s2", false).unwrap();
+        assert_eq!(state.current_state, State::Init);
+        assert_eq!(state.spec_hash, None);
+        assert_eq!(state.current_session_id, None);
+        let last = state.history.last().unwrap();
+        assert_eq!(last.action, "abort");
+        assert_eq!(last.from_state, State::BuildInProgress);
+        assert_eq!(last.reason.as_deref(), Some("build went sideways"));
+
+        assert!(state.abort("again", "s3", true).is_err());
+    }
+
+    #[test]
+    fn test_abort_shipped_requires_force() {
+        let mut state = StateData {
+            current_state: State::Shipped,
+            ..Default::default()
+        };
+        assert!(state.abort("redo", "s", false).is_err());
+        assert!(state.history.is_empty());
+
+        state.abort("redo", "s", true).unwrap();
+        assert_eq!(state.current_state, State::Init);
+    }
+
+    #[test]
+    fn test_amend_is_the_only_way_back_to_drafting() {
+        let mut state = StateData {
+            current_state: State::ContractLocked,
+            spec_hash: Some("abc".to_string()),
+            ..Default::default()
+        };
+        assert!(state
+            .transition_to(State::ContractDrafted, "contract create", "s")
+            .is_err());
+
+        state.amend_contract("s").unwrap();
+        assert_eq!(state.current_state, State::ContractDrafted);
+        assert_eq!(state.spec_hash, None);
+        assert_eq!(state.history.last().unwrap().action, "contract amend");
+
+        let mut planned = StateData {
+            current_state: State::PlanCreated,
+            ..Default::default()
+        };
+        assert!(planned.amend_contract("s").is_err());
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(
+            "IntakeCaptured".parse::<State>().unwrap(),
+            State::IntakeCaptured
+        );
+        assert_eq!(
+            "CONTRACT_LOCKED".parse::<State>().unwrap(),
+            State::ContractLocked
+        );
+        assert_eq!("build-done".parse::<State>().unwrap(), State::BuildDone);
+        assert!("nowhere".parse::<State>().is_err());
+    }
+
     #[test]
     fn test_invalid_transition() {
         let mut state = StateData::default();
@@ -1,7 +1,7 @@
 //! Workspace management for .vibeanvil directory
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use crate::state::StateData;
@@ -20,7 +20,10 @@ pub async fn workspace_exists() -> bool {
 }
 
 /// Initialize the workspace directory structure
-pub async fn init_workspace(force: bool) -> Result<()> {
+pub async fn init_workspace(
+    force: bool,
+    contract_format: crate::contract::ContractFormat,
+) -> Result<()> {
     let ws = workspace_path();
 
     if ws.exists() && !force {
@@ -36,6 +39,7 @@ pub async fn init_workspace(force: bool) -> Result<()> {
         ws.join("blueprints"),
         ws.join("prompts"),
         ws.join("cache"),
+        ws.join("hooks"),
     ];
 
     for dir in &dirs {
@@ -45,7 +49,10 @@ pub async fn init_workspace(force: bool) -> Result<()> {
     }
 
     // Initialize state.json
-    let state = StateData::default();
+    let state = StateData {
+        contract_format,
+        ..StateData::default()
+    };
     save_state(&state).await?;
 
     // Create .gitignore for sensitive files
@@ -65,6 +72,11 @@ pub fn state_path() -> PathBuf {
     workspace_path().join("state.json")
 }
 
+/// Get path to the in-progress state write
+pub fn state_tmp_path() -> PathBuf {
+    workspace_path().join("state.json.tmp")
+}
+
 /// Get path to logs directory
 pub fn logs_path() -> PathBuf {
     workspace_path().join("logs")
@@ -85,6 +97,36 @@ pub fn contracts_path() -> PathBuf {
     workspace_path().join("contracts")
 }
 
+/// Get path to the active review record
+pub fn review_path() -> PathBuf {
+    workspace_path().join("review.json")
+}
+
+/// Get path to the review checklist
+pub fn review_checklist_path() -> PathBuf {
+    workspace_path().join("review_checklist.toml")
+}
+
+/// Get path to the workspace's custom redaction patterns
+pub fn redaction_config_path() -> PathBuf {
+    workspace_path().join("redaction.toml")
+}
+
+/// Get path to archived workflow state from `reset --hard`
+pub fn archive_path() -> PathBuf {
+    workspace_path().join("archive")
+}
+
+/// Get path to lifecycle hook scripts
+pub fn hooks_path() -> PathBuf {
+    workspace_path().join("hooks")
+}
+
+/// Get path to the provider response cache
+pub fn provider_cache_path() -> PathBuf {
+    workspace_path().join("provider_cache")
+}
+
 /// Get path to a specific session
 pub fn session_path(session_id: &str) -> PathBuf {
     sessions_path().join(session_id)
@@ -95,6 +137,19 @@ pub fn evidence_path(session_id: &str) -> PathBuf {
     session_path(session_id).join("evidence")
 }
 
+/// Path of a session's evidence file relative to the workspace directory,
+/// `/`-separated so manifests read the same on every platform
+pub fn relative_evidence_path(session_id: &str, filename: &str) -> String {
+    let dir = evidence_path(session_id);
+    dir.strip_prefix(workspace_path())
+        .unwrap_or(&dir)
+        .join(filename)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Load state from state.json
 pub async fn load_state() -> Result<StateData> {
     let path = state_path();
@@ -114,17 +169,110 @@ pub async fn load_state() -> Result<StateData> {
 }
 
 /// Save state to state.json
+///
+/// Writes `state.json.tmp` first and renames it over `state.json`, so a crash
+/// mid-write leaves the previous state intact.
 pub async fn save_state(state: &StateData) -> Result<()> {
-    let path = state_path();
     let content = serde_json::to_string_pretty(state)?;
-
-    fs::write(&path, content)
+    write_atomic(&state_path(), &state_tmp_path(), content.as_bytes())
         .await
-        .with_context(|| format!("Failed to write state file: {}", path.display()))?;
+        .with_context(|| format!("Failed to write state file: {}", state_path().display()))
+}
+
+/// Write `content` to `tmp`, flush it to disk, then move it over `path`
+async fn write_atomic(path: &Path, tmp: &Path, content: &[u8]) -> Result<()> {
+    let mut file = fs::File::create(tmp).await?;
+    tokio::io::AsyncWriteExt::write_all(&mut file, content).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    replace_file(tmp, path).await
+}
 
+/// Move `from` over `to`
+async fn replace_file(from: &Path, to: &Path) -> Result<()> {
+    if let Err(err) = fs::rename(from, to).await {
+        // Windows can refuse to rename over a file; copy then delete instead
+        if cfg!(windows) {
+            fs::copy(from, to).await?;
+            fs::remove_file(from).await?;
+        } else {
+            return Err(err.into());
+        }
+    }
     Ok(())
 }
 
+/// What `recover_state` did with a leftover `state.json.tmp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateRecovery {
+    /// The temp file replaced state.json
+    Restored,
+    /// The temp file was deleted
+    Discarded,
+    /// The user declined; both files were left alone
+    Kept,
+}
+
+/// Detect a state write interrupted before its rename and ask what to do
+pub async fn recover_state() -> Result<Option<StateRecovery>> {
+    recover_state_in(&workspace_path(), |question| {
+        console::user_attended()
+            && std::env::var("CI").is_err()
+            && inquire::Confirm::new(question)
+                .with_default(true)
+                .prompt()
+                .unwrap_or(false)
+    })
+    .await
+}
+
+/// Recovery for the workspace in `dir`; `confirm` answers yes/no questions
+pub async fn recover_state_in(
+    dir: &Path,
+    confirm: impl Fn(&str) -> bool,
+) -> Result<Option<StateRecovery>> {
+    let path = dir.join("state.json");
+    let tmp = dir.join("state.json.tmp");
+    if !tmp.exists() {
+        return Ok(None);
+    }
+
+    let parse = |p: &Path| {
+        std::fs::read_to_string(p)
+            .ok()
+            .and_then(|c| serde_json::from_str::<StateData>(&c).ok())
+    };
+    let pending = parse(&tmp);
+    let current = parse(&path);
+
+    let (question, restore) = match (&pending, &current) {
+        (Some(_), None) => (
+            "state.json is missing or damaged, but an unfinished save was found in state.json.tmp. Restore it?",
+            true,
+        ),
+        (Some(pending), Some(current)) if pending.updated_at > current.updated_at => (
+            "An unfinished state save (state.json.tmp) is newer than state.json. Apply it?",
+            true,
+        ),
+        _ => (
+            "Found a stale or incomplete state save (state.json.tmp). Delete it?",
+            false,
+        ),
+    };
+
+    if !confirm(question) {
+        return Ok(Some(StateRecovery::Kept));
+    }
+    if restore {
+        replace_file(&tmp, &path).await?;
+        Ok(Some(StateRecovery::Restored))
+    } else {
+        fs::remove_file(&tmp).await?;
+        Ok(Some(StateRecovery::Discarded))
+    }
+}
+
 /// Ensure session directory exists
 pub async fn ensure_session(session_id: &str) -> Result<PathBuf> {
     let path = session_path(session_id);
@@ -161,3 +309,74 @@ pub fn get_anvil_dir() -> Result<PathBuf> {
     }
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_state(path: &Path, state: &StateData) {
+        std::fs::write(path, serde_json::to_string_pretty(state).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_atomic_replaces_file_and_removes_tmp() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("state.json");
+        let tmp = temp.path().join("state.json.tmp");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, &tmp, b"new").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert!(!tmp.exists());
+    }
+
+    #[tokio::test]
+    async fn test_recover_restores_pending_save_when_state_damaged() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        assert_eq!(recover_state_in(dir, |_| true).await.unwrap(), None);
+
+        std::fs::write(dir.join("state.json"), "{\"current_st").unwrap();
+        let pending = StateData {
+            current_state: crate::state::State::IntakeCaptured,
+            ..Default::default()
+        };
+        write_state(&dir.join("state.json.tmp"), &pending);
+
+        // Declining leaves both files alone
+        assert_eq!(
+            recover_state_in(dir, |_| false).await.unwrap(),
+            Some(StateRecovery::Kept)
+        );
+        assert!(dir.join("state.json.tmp").exists());
+
+        assert_eq!(
+            recover_state_in(dir, |q| q.contains("Restore"))
+                .await
+                .unwrap(),
+            Some(StateRecovery::Restored)
+        );
+        let restored: StateData =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("state.json")).unwrap())
+                .unwrap();
+        assert_eq!(restored.current_state, crate::state::State::IntakeCaptured);
+        assert!(!dir.join("state.json.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn test_recover_discards_truncated_tmp() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        write_state(&dir.join("state.json"), &StateData::default());
+        std::fs::write(dir.join("state.json.tmp"), "{\"current_st").unwrap();
+
+        assert_eq!(
+            recover_state_in(dir, |q| q.contains("Delete"))
+                .await
+                .unwrap(),
+            Some(StateRecovery::Discarded)
+        );
+        assert!(!dir.join("state.json.tmp").exists());
+        assert!(dir.join("state.json").exists());
+    }
+}
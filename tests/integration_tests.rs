@@ -160,6 +160,402 @@ fn test_full_workflow_to_contract_lock() {
     assert!(temp.path().join(".vibeanvil/contract.lock").exists());
 }
 
+#[test]
+fn test_signed_contract_lock_verifies() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build a signed project"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    let key_path = temp.path().join("anvil.key");
+    std::fs::write(&key_path, "07".repeat(32)).unwrap();
+
+    vibeanvil()
+        .args(["contract", "lock", "--sign", "--key"])
+        .arg(&key_path)
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Signed by"));
+
+    vibeanvil()
+        .args(["contract", "verify"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Signature valid"));
+
+    // Tamper with the locked contract
+    let contract_path = temp.path().join(".vibeanvil/contracts/contract.json");
+    let tampered = std::fs::read_to_string(&contract_path)
+        .unwrap()
+        .replace("Primary project goal", "A different goal");
+    std::fs::write(&contract_path, tampered).unwrap();
+
+    vibeanvil()
+        .args(["contract", "verify"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("mismatch"));
+}
+
+#[test]
+fn test_named_contracts_lock_independently() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build two services"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create", "--name", "api"],
+        vec!["contract", "create", "--name", "web"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    let contracts = temp.path().join(".vibeanvil/contracts");
+    assert!(contracts.join("api/contract.json").exists());
+    assert!(contracts.join("web/contract.json").exists());
+
+    vibeanvil()
+        .args(["contract", "lock", "--name", "api"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("'api' LOCKED"));
+
+    assert!(temp.path().join(".vibeanvil/contract.api.lock").exists());
+    assert!(!temp.path().join(".vibeanvil/contract.web.lock").exists());
+    // The default contract and workflow are untouched
+    assert!(!temp.path().join(".vibeanvil/contract.lock").exists());
+
+    let status = vibeanvil()
+        .args(["status", "--json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    assert_eq!(status["current_state"], "BLUEPRINT_DRAFTED");
+    assert_eq!(status["progress_pct"], 22);
+    assert_eq!(status["contracts"][0]["name"], "api");
+    assert_eq!(status["contracts"][0]["status"], "LOCKED");
+    assert_eq!(status["contracts"][1]["name"], "web");
+    assert_eq!(status["contracts"][1]["status"], "DRAFT");
+
+    // YAML carries the same report
+    let yaml = vibeanvil()
+        .args(["status", "--format", "yaml"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(yaml.status.success());
+    let yaml: serde_json::Value = serde_yaml::from_slice(&yaml.stdout).unwrap();
+    assert_eq!(yaml, status);
+    vibeanvil()
+        .args(["status", "--json", "--format", "yaml"])
+        .current_dir(temp.path())
+        .assert()
+        .failure();
+
+    vibeanvil()
+        .args(["contract", "lock", "--name", "web"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    vibeanvil()
+        .args(["contract", "verify", "--name", "web"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    vibeanvil()
+        .args(["contract", "verify", "--name", "api"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_review_archives_diff_snapshot() {
+    let temp = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+
+    git(&["init", "-q"]);
+    std::fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+    std::fs::write(
+        temp.path().join("main.rs"),
+        "fn main() { println!(\"hi\"); }\n",
+    )
+    .unwrap();
+
+    vibeanvil()
+        .arg("init")
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    // Jump straight to BUILD_DONE
+    let state_path = temp.path().join(".vibeanvil/state.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["current_state"] = "BUILD_DONE".into();
+    std::fs::write(&state_path, state.to_string()).unwrap();
+
+    vibeanvil()
+        .args(["review", "start"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Captured diff snapshot"));
+
+    let review_path = temp.path().join(".vibeanvil/review.json");
+    let review: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&review_path).unwrap()).unwrap();
+    let session = review["session_id"].as_str().unwrap();
+    let evidence_file = review["evidence_file"].as_str().unwrap();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(session)
+        .join("evidence");
+
+    let diff = std::fs::read_to_string(evidence_dir.join(evidence_file)).unwrap();
+    assert!(diff.contains("println!"));
+    let manifest = std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap();
+    assert!(manifest.contains("review"));
+
+    vibeanvil()
+        .args(["review", "pass"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Approved diff snapshot"))
+        .stdout(predicate::str::contains("Working tree changed").not());
+
+    let review: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&review_path).unwrap()).unwrap();
+    assert_eq!(review["approved_sha256"], review["diff_sha256"]);
+}
+
+#[test]
+fn test_review_pass_requires_mandatory_checklist_items() {
+    let temp = TempDir::new().unwrap();
+    let status = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(temp.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    run_steps(temp.path(), &[&["init"]]);
+    force_state(temp.path(), "BUILD_DONE");
+    std::fs::write(
+        temp.path().join(".vibeanvil/review_checklist.toml"),
+        r#"
+[[items]]
+id = "tests"
+description = "All tests pass"
+mandatory = true
+
+[[items]]
+id = "docs"
+description = "Docs updated"
+"#,
+    )
+    .unwrap();
+
+    vibeanvil()
+        .args(["review", "start"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[ ] tests: All tests pass (required)",
+        ));
+
+    vibeanvil()
+        .args(["review", "check", "--item", "docs"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 mandatory item(s) left"));
+
+    // Unchecked mandatory item blocks the pass
+    vibeanvil()
+        .args(["review", "pass"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("[ ] tests"));
+    assert_eq!(read_state(temp.path())["current_state"], "BUILD_DONE");
+
+    vibeanvil()
+        .args(["review", "check", "--item", "nope"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3);
+    vibeanvil()
+        .args(["review", "check", "--item", "tests"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    vibeanvil()
+        .args(["review", "pass"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    assert_eq!(read_state(temp.path())["current_state"], "REVIEW_PASSED");
+    let review: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/review.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(review["checked"], serde_json::json!(["docs", "tests"]));
+}
+
+#[test]
+fn test_review_pass_skip_checklist() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &[&["init"]]);
+    force_state(temp.path(), "BUILD_DONE");
+    std::fs::write(
+        temp.path().join(".vibeanvil/review_checklist.toml"),
+        "[[items]]\nid = \"tests\"\ndescription = \"All tests pass\"\nmandatory = true\n",
+    )
+    .unwrap();
+
+    // Without a started review the checklist can't have been checked
+    vibeanvil()
+        .args(["review", "pass"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2);
+    vibeanvil()
+        .args(["review", "pass", "--skip-checklist"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("checklist skipped"));
+}
+
+#[test]
+fn test_ship_dry_run_checks_without_shipping() {
+    let temp = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+
+    git(&["init", "-q"]);
+    std::fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Ship it"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create"],
+        vec!["contract", "lock"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    let state_path = temp.path().join(".vibeanvil/state.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["current_state"] = "BUILD_DONE".into();
+    std::fs::write(&state_path, state.to_string()).unwrap();
+
+    for args in [vec!["review", "start"], vec!["review", "pass"]] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    vibeanvil()
+        .args(["ship", "--dry-run"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All checks passed"))
+        .stdout(predicate::str::contains("✗").not());
+
+    let state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(state["current_state"], "REVIEW_PASSED");
+    assert!(!temp.path().join(".vibeanvil/shipped.json").exists());
+}
+
+#[test]
+fn test_invalid_transition_exits_with_code_2() {
+    let temp = TempDir::new().unwrap();
+
+    vibeanvil()
+        .arg("init")
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    vibeanvil()
+        .args(["review", "pass"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Build not done"));
+}
+
+#[test]
+fn test_selftest_reaches_shipped() {
+    let temp = TempDir::new().unwrap();
+
+    vibeanvil()
+        .arg("selftest")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[10/10] ship"))
+        .stdout(predicate::str::contains("reached SHIPPED"));
+
+    // The self-test must not touch the caller's directory
+    assert!(!temp.path().join(".vibeanvil").exists());
+}
+
 #[test]
 fn test_log_empty() {
     let temp = TempDir::new().unwrap();
@@ -186,6 +582,106 @@ fn test_brain_stats_empty() {
         .stdout(predicate::str::contains("Records:"));
 }
 
+#[test]
+fn test_harvest_local_indexes_own_sources() {
+    let temp = TempDir::new().unwrap();
+    let cache = temp.path().join("cache");
+    let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    vibeanvil()
+        .args(["harvest", "--local"])
+        .arg(&src)
+        .args(["--label", "vibeanvil-src"])
+        .env("XDG_CACHE_HOME", &cache)
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("records"));
+
+    let output = vibeanvil()
+        .args(["brain", "stats", "--json"])
+        .env("XDG_CACHE_HOME", &cache)
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["total_sources"], 1);
+    assert!(stats["total_records"].as_u64().unwrap() > 10);
+    assert_eq!(stats["by_language"]["Rust"], 1);
+
+    vibeanvil()
+        .args(["brain", "search", "harvest_local"])
+        .env("XDG_CACHE_HOME", &cache)
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("harvest"));
+
+    vibeanvil()
+        .args(["harvest", "--local", "missing-dir", "--label", "x"])
+        .env("XDG_CACHE_HOME", &cache)
+        .current_dir(temp.path())
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_harvest_local_again_replaces_the_source() {
+    let temp = TempDir::new().unwrap();
+    let cache = temp.path().join("cache");
+    let project = temp.path().join("project");
+    std::fs::create_dir(&project).unwrap();
+    std::fs::write(project.join("a.rs"), "fn alpha_first() {}\n").unwrap();
+    // Chunk IDs are line ranges, so the files differ in length
+    std::fs::write(project.join("b.rs"), "fn beta_removed() {\n}\n").unwrap();
+
+    let harvest = || {
+        vibeanvil()
+            .args(["harvest", "--local"])
+            .arg(&project)
+            .args(["--label", "demo"])
+            .env("XDG_CACHE_HOME", &cache)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    };
+    let stats = || -> serde_json::Value {
+        let output = vibeanvil()
+            .args(["brain", "stats", "--json"])
+            .env("XDG_CACHE_HOME", &cache)
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    harvest();
+    assert_eq!(stats()["total_records"], 2);
+    assert_eq!(stats()["total_chunks"], 2);
+
+    // The edited file replaces its old chunks and the deleted one is dropped
+    std::fs::write(project.join("a.rs"), "fn alpha_second() {}\n").unwrap();
+    std::fs::remove_file(project.join("b.rs")).unwrap();
+    harvest();
+    let stats = stats();
+    assert_eq!(stats["total_sources"], 1);
+    assert_eq!(stats["total_records"], 1);
+    assert_eq!(stats["total_chunks"], 1);
+
+    let search = |query: &str| {
+        let output = vibeanvil()
+            .args(["brain", "search", query])
+            .env("XDG_CACHE_HOME", &cache)
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    assert!(search("alpha_second").contains("Path: a.rs"));
+    assert!(!search("beta_removed").contains("Path: b.rs"));
+    assert!(!search("alpha_first").contains("Path: a.rs"));
+}
+
 #[test]
 fn test_brain_ensure_imports_core() {
     let temp = TempDir::new().unwrap();
@@ -213,3 +709,2309 @@ fn test_brain_ensure_imports_core() {
         .success()
         .stdout(predicate::str::contains("contract").or(predicate::str::contains("Contract")));
 }
+
+#[test]
+fn test_reset_to_intake_captured() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build a todo app"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    // Destructive resets require confirmation
+    vibeanvil()
+        .args(["reset", "--to", "IntakeCaptured"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--yes"));
+
+    vibeanvil()
+        .args(["reset", "--to", "IntakeCaptured", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "CONTRACT_DRAFTED → INTAKE_CAPTURED",
+        ));
+
+    let state: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/state.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(state["current_state"], "INTAKE_CAPTURED");
+    let last = state["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["action"], "rollback");
+    assert_eq!(last["from_state"], "CONTRACT_DRAFTED");
+
+    let audit = std::fs::read_to_string(temp.path().join(".vibeanvil/logs/audit.jsonl")).unwrap();
+    assert!(audit.lines().last().unwrap().contains("\"reset\""));
+
+    // Moving forward through a rollback is rejected
+    vibeanvil()
+        .args(["reset", "--to", "PlanCreated", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_reset_hard_archives_prior_state() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build a todo app"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create"],
+        vec!["contract", "lock"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    vibeanvil()
+        .args(["reset", "--hard", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("archived to"));
+
+    let ws = temp.path().join(".vibeanvil");
+    let state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(ws.join("state.json")).unwrap()).unwrap();
+    assert_eq!(state["current_state"], "INIT");
+    assert!(!ws.join("contracts/contract.json").exists());
+    assert!(!ws.join("contract.lock").exists());
+
+    let archives: Vec<_> = std::fs::read_dir(ws.join("archive"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(archives.len(), 1);
+    let archived: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(archives[0].join("state.json")).unwrap())
+            .unwrap();
+    assert_eq!(archived["current_state"], "CONTRACT_LOCKED");
+    assert!(archives[0].join("contracts/contract.json").exists());
+    assert!(archives[0].join("contract.lock").exists());
+
+    // The workflow can start over
+    vibeanvil()
+        .args(["intake", "--message", "Second attempt"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_contract_status_json_reports_lock() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build a todo app"],
+        vec!["blueprint", "--auto"],
+        vec!["contract", "create"],
+        vec!["contract", "lock"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    let output = vibeanvil()
+        .args(["contract", "status", "--json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let lock: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/contract.lock")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(status["status"], "LOCKED");
+    assert_eq!(status["locked"], true);
+    assert_eq!(status["valid"], true);
+    assert_eq!(status["lock_hash"], lock["hash"]);
+    assert_eq!(status["tool_version"], lock["tool_version"]);
+    assert_eq!(status["goals_count"], 1);
+}
+
+#[test]
+fn test_contract_validate_json() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    let output = vibeanvil()
+        .args(["contract", "validate", "--json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["errors"].as_array().unwrap().len(), 0);
+    assert_eq!(report["schema_version"], "1.1.0");
+    assert_eq!(report["hash"], read_state(temp.path())["spec_hash"]);
+
+    let path = temp.path().join(".vibeanvil/contracts/contract.json");
+    let mut contract: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    contract["goals"] = serde_json::json!([]);
+    std::fs::write(&path, contract.to_string()).unwrap();
+
+    let output = vibeanvil()
+        .args(["contract", "validate", "--json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["errors"][0], "At least one goal is required");
+    assert_ne!(report["hash"], read_state(temp.path())["spec_hash"]);
+}
+
+#[test]
+fn test_abort_returns_to_init_and_keeps_contract() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    vibeanvil()
+        .args(["abort", "--reason", "wrong direction", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CONTRACT_LOCKED → INIT"));
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "INIT");
+    assert!(state["spec_hash"].is_null());
+    assert!(state["current_session_id"].is_null());
+    let last = state["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["action"], "abort");
+    assert_eq!(last["reason"], "wrong direction");
+    assert!(temp
+        .path()
+        .join(".vibeanvil/contracts/contract.json")
+        .exists());
+
+    // Nothing left to abort; the workflow restarts normally
+    vibeanvil()
+        .args(["abort", "--reason", "again", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2);
+    run_steps(temp.path(), &[&["intake", "--message", "Build a todo app"]]);
+    assert_eq!(read_state(temp.path())["current_state"], "INTAKE_CAPTURED");
+}
+
+#[test]
+fn test_toml_contract_workflow_and_convert() {
+    let temp = TempDir::new().unwrap();
+    let mut steps = TO_CONTRACT_LOCKED.to_vec();
+    steps[0] = &["init", "--contract-format", "toml"];
+    run_steps(temp.path(), &steps);
+
+    let contracts = temp.path().join(".vibeanvil/contracts");
+    assert!(contracts.join("contract.toml").exists());
+    assert!(!contracts.join("contract.json").exists());
+    let spec_hash = read_state(temp.path())["spec_hash"].clone();
+
+    vibeanvil()
+        .args(["contract", "convert", "--to", "json"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hash unchanged"));
+    assert!(contracts.join("contract.json").exists());
+    assert!(!contracts.join("contract.toml").exists());
+    assert_eq!(read_state(temp.path())["contract_format"], "json");
+
+    // The converted contract still matches its lock
+    vibeanvil()
+        .args(["contract", "verify"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    let output = vibeanvil()
+        .args(["contract", "validate", "--json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["hash"], spec_hash);
+
+    vibeanvil()
+        .args(["contract", "status", "--to", "toml"])
+        .current_dir(temp.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_evidence_capture_from_file_redacts_and_records() {
+    let temp = TempDir::new().unwrap();
+
+    for args in [
+        vec!["init"],
+        vec!["intake", "--message", "Build a todo app"],
+    ] {
+        vibeanvil()
+            .args(&args)
+            .current_dir(temp.path())
+            .assert()
+            .success();
+    }
+
+    std::fs::write(
+        temp.path().join("ci-test.log"),
+        "running 3 tests\nAPI_KEY=sk-1234567890abcdefghijklmnopqrstuvwxyz1234567890ab\ntest result: ok\n",
+    )
+    .unwrap();
+
+    vibeanvil()
+        .args([
+            "evidence",
+            "capture",
+            "--type",
+            "test",
+            "--file",
+            "ci-test.log",
+            "--description",
+            "CI test run",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Captured test_log"));
+
+    let state: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/state.json")).unwrap(),
+    )
+    .unwrap();
+    let session = state["current_session_id"].as_str().unwrap();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(session)
+        .join("evidence");
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap())
+            .unwrap();
+    let item = &manifest[0];
+    assert_eq!(item["evidence_type"], "test_log");
+    assert_eq!(item["redacted"], true);
+    assert_eq!(item["description"], "CI test run");
+
+    let stored =
+        std::fs::read_to_string(evidence_dir.join(item["filename"].as_str().unwrap())).unwrap();
+    assert!(stored.contains("test result: ok"));
+    assert!(!stored.contains("sk-1234567890"));
+
+    // Paths outside the project are rejected
+    vibeanvil()
+        .args([
+            "evidence", "capture", "--type", "build", "--file", "../x.log",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("traversal"));
+}
+
+#[test]
+fn test_human_provider_refuses_non_interactive_runs() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+
+    vibeanvil()
+        .args(["analyze", "--provider", "human", "--no-cache"])
+        .current_dir(temp.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("use an automated provider"));
+}
+
+#[test]
+fn test_review_archive_and_unpack_evidence() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+    std::fs::write(temp.path().join("ci.log"), "all 12 tests passed\n").unwrap();
+    vibeanvil()
+        .args(["evidence", "capture", "--type", "test", "--file", "ci.log"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    vibeanvil()
+        .args(["review", "archive", "--output", "evidence.tar.gz"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files:   2"));
+    assert!(temp.path().join("evidence.tar.gz").exists());
+
+    // A reviewer restores it into their own workspace
+    let reviewer = TempDir::new().unwrap();
+    run_steps(reviewer.path(), &[&["init"]]);
+    vibeanvil()
+        .args(["review", "unpack"])
+        .arg(temp.path().join("evidence.tar.gz"))
+        .current_dir(reviewer.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "From session: {}",
+            session
+        )));
+
+    let sessions: Vec<_> = std::fs::read_dir(reviewer.path().join(".vibeanvil/sessions"))
+        .unwrap()
+        .flatten()
+        .filter(|e| e.path().join("evidence/manifest.json").exists())
+        .collect();
+    assert_eq!(sessions.len(), 1);
+    assert_ne!(sessions[0].file_name().to_string_lossy(), session);
+
+    vibeanvil()
+        .args(["review", "archive"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_review_bundle_and_verify_bundle() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+    std::fs::write(temp.path().join("ci.log"), "all 12 tests passed\n").unwrap();
+    vibeanvil()
+        .args(["evidence", "capture", "--type", "test", "--file", "ci.log"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    vibeanvil()
+        .args(["review", "bundle", "--output", "audit.tar.gz"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("audit.tar.gz.sha256"));
+    assert!(temp.path().join("audit.tar.gz.sha256").exists());
+
+    vibeanvil()
+        .args(["review", "verify-bundle", "audit.tar.gz"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("matches its checksum"));
+
+    // Any change after export is caught
+    let mut bytes = std::fs::read(temp.path().join("audit.tar.gz")).unwrap();
+    bytes.extend_from_slice(b"tampered");
+    std::fs::write(temp.path().join("audit.tar.gz"), bytes).unwrap();
+    vibeanvil()
+        .args(["review", "verify-bundle", "audit.tar.gz"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_review_status_verify_evidence_detects_tampering() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+    std::fs::write(temp.path().join("ci.log"), "all 12 tests passed\n").unwrap();
+    vibeanvil()
+        .args(["evidence", "capture", "--type", "test", "--file", "ci.log"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    vibeanvil()
+        .args(["review", "status", "--verify-evidence"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "All evidence matches its manifest",
+        ));
+
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(&session)
+        .join("evidence");
+    let manifest: Vec<serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap())
+            .unwrap();
+    let filename = manifest[0]["filename"].as_str().unwrap().to_string();
+    std::fs::write(
+        evidence_dir.join(&filename),
+        "all 12 tests passed (edited)\n",
+    )
+    .unwrap();
+
+    vibeanvil()
+        .args(["review", "status", "--verify-evidence"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains(filename.as_str()))
+        .stderr(predicate::str::contains("1 evidence file(s) failed"));
+
+    // The flag only applies to `review status`
+    vibeanvil()
+        .args(["review", "start", "--verify-evidence"])
+        .current_dir(temp.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_workspace_redaction_patterns() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+    std::fs::write(
+        temp.path().join(".vibeanvil/redaction.toml"),
+        "custom_patterns = [\"ACME-GW-[0-9a-f]{8}\", \"broken[\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("ci.log"),
+        "calling gateway with ACME-GW-deadbeef\n",
+    )
+    .unwrap();
+
+    // The invalid pattern is skipped with a warning; the valid one still applies
+    vibeanvil()
+        .args(["evidence", "capture", "--type", "build", "--file", "ci.log"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Skipping invalid redaction pattern broken[",
+        ));
+
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(&session)
+        .join("evidence");
+    let manifest: Vec<serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap())
+            .unwrap();
+    assert_eq!(manifest[0]["redaction_summary"]["custom"], 1);
+    let stored =
+        std::fs::read_to_string(evidence_dir.join(manifest[0]["filename"].as_str().unwrap()))
+            .unwrap();
+    assert!(!stored.contains("ACME-GW-deadbeef"));
+
+    vibeanvil()
+        .arg("doctor")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Invalid pattern(s) in redaction.toml: broken[",
+        ));
+}
+
+#[test]
+fn test_evidence_capture_coverage_reports() {
+    let temp = TempDir::new().unwrap();
+    run_steps(
+        temp.path(),
+        &[&["init"], &["intake", "--message", "Build a todo app"]],
+    );
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/coverage");
+    for (file, summary) in [
+        (
+            "lcov.info",
+            "Coverage (lcov): lines 62.5% (5/8), branches 75.0% (3/4)",
+        ),
+        (
+            "cobertura.xml",
+            "Coverage (cobertura): lines 80.0% (8/10), branches 25.0% (1/4)",
+        ),
+        (
+            "tarpaulin-report.json",
+            "Coverage (tarpaulin): lines 60.0% (3/5)",
+        ),
+        (
+            ".last_run.json",
+            "Coverage (simplecov): lines 91.7%, branches 75.0%",
+        ),
+    ] {
+        std::fs::copy(fixtures.join(file), temp.path().join(file)).unwrap();
+        vibeanvil()
+            .args(["evidence", "capture", "--type", "coverage", "--file", file])
+            .current_dir(temp.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(summary));
+    }
+
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(&session)
+        .join("evidence");
+    let manifest: Vec<serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap())
+            .unwrap();
+    assert_eq!(manifest.len(), 4);
+    for item in &manifest {
+        assert_eq!(item["evidence_type"], "coverage");
+        let stored =
+            std::fs::read_to_string(evidence_dir.join(item["filename"].as_str().unwrap())).unwrap();
+        assert!(!stored.contains("alice") && !stored.contains("bob"));
+    }
+    // Reports with home directory paths are marked redacted; SimpleCov has none
+    let redacted: Vec<bool> = manifest
+        .iter()
+        .map(|item| item["redacted"].as_bool().unwrap())
+        .collect();
+    assert_eq!(redacted, vec![true, true, true, false]);
+
+    // Unknown file names need --format, and unreadable reports are rejected
+    std::fs::write(temp.path().join("coverage.txt"), "LF:2\nLH:1\n").unwrap();
+    vibeanvil()
+        .args([
+            "evidence",
+            "capture",
+            "--type",
+            "coverage",
+            "--file",
+            "coverage.txt",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("--format"));
+    vibeanvil()
+        .args([
+            "evidence",
+            "capture",
+            "--type",
+            "coverage",
+            "--file",
+            "coverage.txt",
+            "--format",
+            "lcov",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lines 50.0% (1/2)"));
+    vibeanvil()
+        .args([
+            "evidence",
+            "capture",
+            "--type",
+            "coverage",
+            "--file",
+            "coverage.txt",
+            "--format",
+            "cobertura",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("cobertura coverage summary"));
+}
+
+#[test]
+fn test_providers_test_probes_mock_capability() {
+    let temp = TempDir::new().unwrap();
+
+    vibeanvil()
+        .args([
+            "providers",
+            "test",
+            "mock",
+            "--capability",
+            "code-generation",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("supported"));
+
+    vibeanvil()
+        .args(["providers", "test", "mock", "--capability", "GEN"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    vibeanvil()
+        .args(["providers", "test", "mock", "--capability", "telepathy"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'telepathy'"));
+
+    vibeanvil()
+        .args(["providers", "test", "mock"])
+        .current_dir(temp.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Usage: vibeanvil providers test <provider> --capability <capability>",
+        ));
+
+    vibeanvil()
+        .args(["providers", "matrix", "--capability", "gen"])
+        .current_dir(temp.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "--capability is only supported with 'providers test'",
+        ));
+}
+
+#[test]
+fn test_providers_estimate_tokens() {
+    vibeanvil()
+        .args(["providers", "--estimate-tokens", "10000"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5000 prompt, 5000 output"))
+        .stdout(predicate::str::contains("$0.15"));
+
+    vibeanvil()
+        .args(["providers", "matrix", "--estimate-tokens", "10000"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--estimate-tokens is only supported with 'providers list'",
+        ));
+}
+
+fn run_steps(dir: &std::path::Path, steps: &[&[&str]]) {
+    for args in steps {
+        vibeanvil().args(*args).current_dir(dir).assert().success();
+    }
+}
+
+#[test]
+fn test_review_without_git_warns_and_reviews_an_empty_diff() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &[&["init"]]);
+    force_state(temp.path(), "BUILD_DONE");
+
+    // An empty PATH leaves git unreachable
+    for args in [["review", "start"], ["review", "pass"]] {
+        vibeanvil()
+            .args(args)
+            .env("PATH", "")
+            .current_dir(temp.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Could not read the git diff"));
+    }
+    assert_eq!(read_state(temp.path())["current_state"], "REVIEW_PASSED");
+}
+
+fn read_state(dir: &std::path::Path) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(dir.join(".vibeanvil/state.json")).unwrap())
+        .unwrap()
+}
+
+fn force_state(dir: &std::path::Path, state: &str) {
+    let mut value = read_state(dir);
+    value["current_state"] = state.into();
+    std::fs::write(dir.join(".vibeanvil/state.json"), value.to_string()).unwrap();
+}
+
+const TO_CONTRACT_LOCKED: &[&[&str]] = &[
+    &["init"],
+    &["intake", "--message", "Build a todo app"],
+    &["blueprint", "--auto"],
+    &["contract", "create"],
+    &["contract", "lock"],
+];
+
+#[test]
+fn test_reset_respects_contract_freeze_window() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+    vibeanvil()
+        .args(["contract", "lock", "--freeze-until", "2999-01-01"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Frozen until: 2999-01-01"));
+
+    // Blocked inside the window, for both rollback and hard reset
+    for args in [
+        &["reset", "--to", "ContractDrafted", "--yes"][..],
+        &["reset", "--hard", "--yes"][..],
+    ] {
+        vibeanvil()
+            .args(args)
+            .current_dir(temp.path())
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("frozen until 2999-01-01"));
+    }
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    // Once the window has passed the rollback goes through
+    let lock_path = temp.path().join(".vibeanvil/contract.lock");
+    let mut lock: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&lock_path).unwrap()).unwrap();
+    lock["frozen_until"] = serde_json::json!("2000-01-01T00:00:00Z");
+    std::fs::write(&lock_path, serde_json::to_string_pretty(&lock).unwrap()).unwrap();
+    vibeanvil()
+        .args(["reset", "--to", "ContractDrafted", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_DRAFTED");
+}
+
+#[test]
+fn test_abort_respects_contract_freeze_window() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+    run_steps(
+        temp.path(),
+        &[&["contract", "lock", "--freeze-until", "2999-01-01"]],
+    );
+
+    vibeanvil()
+        .args(["abort", "--reason", "scrap it", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("frozen until 2999-01-01"));
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    vibeanvil()
+        .args(["abort", "--reason", "scrap it", "--force-unfreeze", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    assert_eq!(read_state(temp.path())["current_state"], "INIT");
+}
+
+#[test]
+fn test_contract_amend_respects_contract_freeze_window() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+    run_steps(
+        temp.path(),
+        &[&["contract", "lock", "--freeze-until", "2999-01-01"]],
+    );
+
+    vibeanvil()
+        .args(["contract", "amend"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("frozen until 2999-01-01"));
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    vibeanvil()
+        .args(["contract", "amend", "--force-unfreeze", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_DRAFTED");
+
+    let audit = std::fs::read_to_string(temp.path().join(".vibeanvil/logs/audit.jsonl")).unwrap();
+    assert!(audit.contains("\"force_unfreeze\":true"));
+}
+
+#[test]
+fn test_reset_force_unfreeze_overrides_freeze() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+    run_steps(
+        temp.path(),
+        &[&["contract", "lock", "--freeze-until", "2999-01-01T12:00:00Z"]],
+    );
+
+    vibeanvil()
+        .args([
+            "reset",
+            "--to",
+            "ContractDrafted",
+            "--force-unfreeze",
+            "--yes",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_DRAFTED");
+
+    // A freeze must end in the future
+    vibeanvil()
+        .args(["contract", "lock", "--freeze-until", "2000-01-01"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_rollback_contract_locked_to_drafted_records_reason() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    assert!(read_state(temp.path())["spec_hash"].is_string());
+
+    vibeanvil()
+        .args([
+            "reset",
+            "--to",
+            "ContractDrafted",
+            "--reason",
+            "missing offline requirement",
+            "--yes",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("contract lock"));
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "CONTRACT_DRAFTED");
+    assert!(state["spec_hash"].is_null());
+    let last = state["history"].as_array().unwrap().last().unwrap().clone();
+    assert_eq!(last["action"], "rollback");
+    assert_eq!(last["from_state"], "CONTRACT_LOCKED");
+    assert_eq!(last["to_state"], "CONTRACT_DRAFTED");
+    assert_eq!(last["reason"], "missing offline requirement");
+    assert_eq!(last["session_id"], state["current_session_id"]);
+}
+
+#[test]
+fn test_rollback_then_relock_moves_forward_again() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[
+            &["reset", "--to", "ContractDrafted", "--yes"],
+            &["contract", "lock"],
+        ],
+    );
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "CONTRACT_LOCKED");
+    assert!(state["spec_hash"].is_string());
+    let actions: Vec<&str> = state["history"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions.iter().filter(|a| **a == "rollback").count(), 1);
+    assert_ne!(*actions.last().unwrap(), "rollback");
+}
+
+#[test]
+fn test_rollback_plan_created_to_contract_locked_keeps_spec_hash() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    let spec_hash = read_state(temp.path())["spec_hash"].clone();
+    force_state(temp.path(), "PLAN_CREATED");
+
+    vibeanvil()
+        .args(["reset", "--to", "ContractLocked", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PLAN_CREATED → CONTRACT_LOCKED"));
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "CONTRACT_LOCKED");
+    assert_eq!(state["spec_hash"], spec_hash);
+}
+
+#[test]
+fn test_rollback_refused_from_shipped() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &[&["init"]]);
+    force_state(temp.path(), "SHIPPED");
+    let history_len = read_state(temp.path())["history"].as_array().unwrap().len();
+
+    vibeanvil()
+        .args(["reset", "--to", "BuildDone", "--yes"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Cannot roll back"));
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "SHIPPED");
+    assert_eq!(state["history"].as_array().unwrap().len(), history_len);
+}
+
+#[test]
+fn test_rollback_to_init_or_non_checkpoint_refused() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    for target in ["Init", "BuildInProgress", "ContractLocked"] {
+        vibeanvil()
+            .args(["reset", "--to", target, "--yes"])
+            .current_dir(temp.path())
+            .assert()
+            .code(2);
+    }
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+}
+
+#[test]
+fn test_status_history_json_paginates_newest_first() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    let output = vibeanvil()
+        .args([
+            "status",
+            "--history",
+            "--json",
+            "--limit",
+            "2",
+            "--offset",
+            "1",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let page: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(page["total"], 4);
+    assert_eq!(page["offset"], 1);
+    assert_eq!(page["limit"], 2);
+    let entries = page["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["from_state"], "BLUEPRINT_DRAFTED");
+    assert_eq!(entries[0]["to_state"], "CONTRACT_DRAFTED");
+    assert_eq!(entries[1]["to_state"], "BLUEPRINT_DRAFTED");
+    assert!(entries[0]["session_id"].is_string());
+    assert!(entries[0]["timestamp"].is_string());
+
+    vibeanvil()
+        .args(["status", "--offset", "2"])
+        .current_dir(temp.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_contract_diff_reports_drift_after_lock() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    vibeanvil()
+        .args(["contract", "diff"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No drift"));
+
+    let path = temp.path().join(".vibeanvil/contracts/contract.json");
+    let mut contract: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    contract["goals"]
+        .as_array_mut()
+        .unwrap()
+        .push("Offline mode".into());
+    std::fs::write(&path, contract.to_string()).unwrap();
+
+    vibeanvil()
+        .args(["contract", "diff"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("+ goals: Offline mode"));
+
+    let output = vibeanvil()
+        .args(["contract", "diff", "--json"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["drift"], true);
+    assert!(report["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|c| c["change"] == "added" && c["field"] == "goals" && c["item"] == "Offline mode"));
+
+    // validate --diff shows the changes but only fails on validation errors
+    vibeanvil()
+        .args(["contract", "validate", "--diff"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Changes since lock"))
+        .stdout(predicate::str::contains("+ goals: Offline mode"));
+
+    contract["schema_version"] = "2.0.0".into();
+    std::fs::write(&path, contract.to_string()).unwrap();
+
+    vibeanvil()
+        .args(["contract", "diff"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("Schema changed"));
+}
+
+#[test]
+fn test_contract_amend_chains_new_lock_to_previous() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    let first_hash = read_state(temp.path())["spec_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    vibeanvil()
+        .args(["contract", "amend"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("draft version 2"));
+
+    let state = read_state(temp.path());
+    assert_eq!(state["current_state"], "CONTRACT_DRAFTED");
+    assert!(state["spec_hash"].is_null());
+    assert_eq!(
+        state["history"].as_array().unwrap().last().unwrap()["action"],
+        "contract amend"
+    );
+
+    // A second amend needs a locked contract again
+    vibeanvil()
+        .args(["contract", "amend"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2);
+
+    vibeanvil()
+        .args(["contract", "diff"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("Draft version 2 amends lock"));
+
+    run_steps(temp.path(), &[&["contract", "lock"]]);
+    let lock: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/contract.lock")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(lock["supersedes"], first_hash.as_str());
+    assert_ne!(lock["hash"], first_hash.as_str());
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    let audit = std::fs::read_to_string(temp.path().join(".vibeanvil/logs/audit.jsonl")).unwrap();
+    assert!(audit.contains("contract amend"));
+    assert!(audit.contains(&format!("\"supersedes\":\"{}\"", first_hash)));
+}
+
+#[test]
+fn test_legacy_contract_lock_is_migrated() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    // A lock as written by releases that hashed the draft contract
+    let lock_path = temp.path().join(".vibeanvil/contract.lock");
+    let legacy_hash = "9f2c4e1ab2d3c4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d";
+    std::fs::write(
+        &lock_path,
+        serde_json::json!({
+            "hash": legacy_hash,
+            "locked_at": "2025-01-01T00:00:00Z",
+            "tool_version": "0.1.0",
+            "schema_version": "1.0.0",
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    vibeanvil()
+        .args(["plan", "--provider", "mock", "--no-cache"])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("written by an older version"));
+    assert_eq!(read_state(temp.path())["current_state"], "PLAN_CREATED");
+
+    let lock: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&lock_path).unwrap()).unwrap();
+    assert_ne!(lock["hash"], legacy_hash);
+    assert_eq!(lock["tool_version"], "0.1.0");
+    assert!(lock["contract"].is_object());
+
+    // Migrated once: later commands run without the notice
+    vibeanvil()
+        .arg("status")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("older version").not());
+}
+
+#[test]
+fn test_short_lock_hash_is_reported_not_panicked_on() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    let lock_path = temp.path().join(".vibeanvil/contract.lock");
+    let mut lock: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&lock_path).unwrap()).unwrap();
+    lock["hash"] = "abc".into();
+    std::fs::write(&lock_path, lock.to_string()).unwrap();
+
+    vibeanvil()
+        .args(["plan", "--provider", "mock", "--no-cache"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("(lock abc, contract now"));
+
+    vibeanvil()
+        .args(["contract", "diff"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("Contract drifted from lock abc"));
+}
+
+#[test]
+fn test_contract_edit_after_lock_blocks_plan() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    let path = temp.path().join(".vibeanvil/contracts/contract.json");
+    let original = std::fs::read_to_string(&path).unwrap();
+    let mut contract: serde_json::Value = serde_json::from_str(&original).unwrap();
+    contract["goals"]
+        .as_array_mut()
+        .unwrap()
+        .push("Sneaky extra goal".into());
+    std::fs::write(&path, contract.to_string()).unwrap();
+
+    vibeanvil()
+        .args(["plan", "--provider", "mock", "--no-cache"])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("edited after locking"))
+        .stderr(predicate::str::contains("contract verify"));
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    // Read-only commands still run, with a warning
+    vibeanvil()
+        .arg("status")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: contract.json was edited",
+        ));
+
+    std::fs::write(&path, original).unwrap();
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+    assert_eq!(read_state(temp.path())["current_state"], "PLAN_CREATED");
+}
+
+#[test]
+fn test_contract_merge_three_way() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+
+    let path = temp.path().join(".vibeanvil/contracts/contract.json");
+    let base: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::write(temp.path().join("base.json"), base.to_string()).unwrap();
+
+    let mut ours = base.clone();
+    ours["description"] = "Our description".into();
+    std::fs::write(&path, ours.to_string()).unwrap();
+
+    let mut theirs = base.clone();
+    theirs["goals"]
+        .as_array_mut()
+        .unwrap()
+        .push("Their goal".into());
+    std::fs::write(temp.path().join("theirs.json"), theirs.to_string()).unwrap();
+
+    vibeanvil()
+        .args([
+            "contract",
+            "merge",
+            "--base",
+            "base.json",
+            "--theirs",
+            "theirs.json",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("goals: + \"Their goal\""));
+
+    let merged: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(merged["description"], "Our description");
+    assert_eq!(merged["goals"].as_array().unwrap().len(), 2);
+
+    theirs["description"] = "Their description".into();
+    std::fs::write(temp.path().join("theirs.json"), theirs.to_string()).unwrap();
+
+    vibeanvil()
+        .args([
+            "contract",
+            "merge",
+            "--base",
+            "base.json",
+            "--theirs",
+            "theirs.json",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("description: ours"));
+}
+
+#[test]
+fn test_build_only_test_skips_provider_and_commit() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // An unknown provider would fail the build if it were looked up
+    vibeanvil()
+        .args([
+            "build",
+            "iterate",
+            "--only",
+            "test",
+            "--provider",
+            "no-such-provider",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Phases: test"))
+        .stdout(predicate::str::contains("Provider phase skipped"))
+        .stdout(predicate::str::contains("Applying initial changes").not());
+    assert_eq!(read_state(temp.path())["current_state"], "BUILD_DONE");
+
+    // Auto mode would run nothing and still finish the build
+    force_state(temp.path(), "PLAN_CREATED");
+    for selection in [["--only", "test"], ["--skip", "provider"]] {
+        vibeanvil()
+            .args(["build", "auto", "--provider", "mock"])
+            .args(selection)
+            .current_dir(temp.path())
+            .assert()
+            .code(3)
+            .stderr(predicate::str::contains(
+                "'build auto' needs the provider phase",
+            ));
+    }
+    assert_eq!(read_state(temp.path())["current_state"], "PLAN_CREATED");
+
+    vibeanvil()
+        .args(["build", "iterate", "--only", "deploy"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'deploy'"));
+
+    vibeanvil()
+        .args(["build", "iterate", "--only", "test", "--no-test"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("No build phases left"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_iterate_captures_evidence_per_iteration() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // A Rust project whose `cargo` always fails
+    std::fs::write(temp.path().join("Cargo.toml"), "[package]\n").unwrap();
+    let bin = temp.path().join("bin");
+    std::fs::create_dir(&bin).unwrap();
+    let cargo = bin.join("cargo");
+    std::fs::write(
+        &cargo,
+        "#!/bin/sh\necho 'test result: FAILED. 1 failed'\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let git = std::process::Command::new("sh")
+        .args(["-c", "command -v git"])
+        .output()
+        .unwrap();
+    std::os::unix::fs::symlink(String::from_utf8_lossy(&git.stdout).trim(), bin.join("git"))
+        .unwrap();
+
+    vibeanvil()
+        .args(["build", "iterate", "--provider", "mock", "--max", "2"])
+        .env("PATH", &bin)
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Iteration 1: tests ✗, lint ✗"))
+        .stdout(predicate::str::contains("Iteration 2: tests ✗, lint ✗"));
+
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(session)
+        .join("evidence");
+    let files: Vec<String> = std::fs::read_dir(&evidence_dir)
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    for iteration in 1..=2 {
+        for prefix in ["build_log", "git_diff", "test_log", "lint_log"] {
+            let expected = format!("{}_iter_{}_", prefix, iteration);
+            assert!(
+                files.iter().any(|f| f.starts_with(&expected)),
+                "missing {} in {:?}",
+                expected,
+                files
+            );
+        }
+    }
+}
+
+#[test]
+fn test_manual_build_complete_without_tty_saves_message_or_auto_commits() {
+    let temp = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    git(&["init", "-q"]);
+    std::fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+    git(&["add", "main.rs"]);
+    git(&["commit", "-q", "-m", "initial"]);
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[
+            &["plan", "--provider", "mock", "--no-cache"],
+            &["build", "manual", "start"],
+        ],
+    );
+    std::fs::write(temp.path().join("main.rs"), "fn main() { run(); }\n").unwrap();
+
+    // No TTY and no --auto-commit: the message is saved, nothing is committed
+    vibeanvil()
+        .args(["build", "manual", "complete"])
+        .current_dir(temp.path())
+        .env_remove("CI")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No TTY, skipping the commit"));
+    assert_eq!(git(&["rev-list", "--count", "HEAD"]), "1");
+    let sessions = temp.path().join(".vibeanvil/sessions");
+    let saved: Vec<_> = std::fs::read_dir(&sessions)
+        .unwrap()
+        .flatten()
+        .map(|e| e.path().join("commit_message.txt"))
+        .filter(|p| p.exists())
+        .collect();
+    assert_eq!(saved.len(), 1);
+    assert!(!std::fs::read_to_string(&saved[0])
+        .unwrap()
+        .trim()
+        .is_empty());
+
+    // --auto-commit commits with the generated message
+    run_steps(temp.path(), &[&["build", "manual", "start"]]);
+    vibeanvil()
+        .args(["build", "manual", "complete", "--auto-commit"])
+        .current_dir(temp.path())
+        .env("GIT_AUTHOR_NAME", "test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Auto-commit successful"));
+    assert_eq!(git(&["rev-list", "--count", "HEAD"]), "2");
+
+    vibeanvil()
+        .args(["build", "iterate", "--auto-commit"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--auto-commit is only supported with 'build manual complete'",
+        ));
+}
+
+#[test]
+fn test_manual_build_outside_a_build_explains_the_state() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    let manual = |action: &str, expected: &str| {
+        vibeanvil()
+            .args(["build", "manual", action])
+            .current_dir(temp.path())
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains(expected));
+    };
+
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+    manual("evidence", "Build not started");
+    force_state(temp.path(), "BUILD_DONE");
+    manual("evidence", "the last one is finished (state: BUILD_DONE)");
+    force_state(temp.path(), "REVIEW_PASSED");
+    manual("complete", "already passed review (state: REVIEW_PASSED)");
+
+    // A failed review can go back to building
+    force_state(temp.path(), "REVIEW_FAILED");
+    manual(
+        "evidence",
+        "the last one is finished (state: REVIEW_FAILED)",
+    );
+    run_steps(temp.path(), &[&["build", "manual", "start"]]);
+    assert_eq!(
+        read_state(temp.path())["current_state"],
+        "BUILD_IN_PROGRESS"
+    );
+}
+
+#[test]
+fn test_manual_build_evidence_needs_a_git_repo() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[
+            &["plan", "--provider", "mock", "--no-cache"],
+            &["build", "manual", "start"],
+        ],
+    );
+
+    // Detected the same way when git's messages are translated
+    vibeanvil()
+        .args(["build", "manual", "evidence"])
+        .env("LANGUAGE", "de")
+        .env("LC_ALL", "C.UTF-8")
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("not inside a git repository"));
+}
+
+#[test]
+fn test_manual_build_evidence_applies_workspace_redaction_patterns() {
+    let temp = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    };
+
+    git(&["init", "-q"]);
+    std::fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+    git(&["add", "main.rs"]);
+    git(&["commit", "-q", "-m", "initial"]);
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[
+            &["plan", "--provider", "mock", "--no-cache"],
+            &["build", "manual", "start"],
+        ],
+    );
+    std::fs::write(
+        temp.path().join(".vibeanvil/redaction.toml"),
+        "custom_patterns = [\"ACME-GW-[0-9a-f]{8}\"]\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp.path().join("main.rs"),
+        "fn main() { call(\"ACME-GW-deadbeef\"); }\n",
+    )
+    .unwrap();
+
+    run_steps(temp.path(), &[&["build", "manual", "evidence"]]);
+
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(&session)
+        .join("evidence");
+    let manifest: Vec<serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string(evidence_dir.join("manifest.json")).unwrap())
+            .unwrap();
+    // `manual start` records the (empty) starting diff first
+    let diff = manifest
+        .iter()
+        .rfind(|e| e["evidence_type"] == "git_diff")
+        .unwrap();
+    assert_eq!(diff["redaction_summary"]["custom"], 1);
+    let stored =
+        std::fs::read_to_string(evidence_dir.join(diff["filename"].as_str().unwrap())).unwrap();
+    assert!(!stored.contains("ACME-GW-deadbeef"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_failing_pre_ship_hook_blocks_ship() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &[&["init"]]);
+    force_state(temp.path(), "REVIEW_PASSED");
+    std::fs::write(
+        temp.path().join(".vibeanvil/hooks/pre-ship.sh"),
+        "echo \"checking $VIBEANVIL_STATE\"\nexit 1\n",
+    )
+    .unwrap();
+
+    vibeanvil()
+        .args(["ship", "--tag", "v1"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("checking REVIEW_PASSED"))
+        .stderr(predicate::str::contains("pre-ship hook failed"));
+
+    assert_eq!(read_state(temp.path())["current_state"], "REVIEW_PASSED");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_post_build_hook_runs_after_build() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+    std::fs::write(
+        temp.path().join(".vibeanvil/hooks/post-build"),
+        "echo \"$VIBEANVIL_HOOK $VIBEANVIL_STATE $VIBEANVIL_SPEC_HASH\" > hook.out\n\
+         echo \"api_key=abcdefghijklmnopqrstuvwxyz\"\n",
+    )
+    .unwrap();
+
+    run_steps(temp.path(), &[&["build", "auto", "--provider", "mock"]]);
+
+    let state = read_state(temp.path());
+    let marker = std::fs::read_to_string(temp.path().join("hook.out")).unwrap();
+    assert_eq!(
+        marker.trim(),
+        format!(
+            "post-build {} {}",
+            state["current_state"].as_str().unwrap(),
+            state["spec_hash"].as_str().unwrap()
+        )
+    );
+
+    let evidence_dir = temp
+        .path()
+        .join(".vibeanvil/sessions")
+        .join(state["current_session_id"].as_str().unwrap())
+        .join("evidence");
+    let hook_log = std::fs::read_dir(&evidence_dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| {
+            p.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("hook_post_build")
+        })
+        .expect("hook output captured as evidence");
+    let stored = std::fs::read_to_string(hook_log).unwrap();
+    assert!(stored.contains("[REDACTED]"));
+    assert!(!stored.contains("abcdefghijklmnopqrstuvwxyz"));
+}
+
+#[test]
+fn test_serve_status_endpoint() {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let temp = TempDir::new().unwrap();
+    vibeanvil()
+        .arg("init")
+        .current_dir(temp.path())
+        .assert()
+        .success();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("vibeanvil"))
+        .args(["serve", "--port", "0"])
+        .current_dir(temp.path())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    let addr = loop {
+        line.clear();
+        assert!(stdout.read_line(&mut line).unwrap() > 0, "server exited");
+        if let Some(addr) = line.trim().strip_prefix("Listening on http://") {
+            break addr.to_string();
+        }
+    };
+
+    let mut stream = std::net::TcpStream::connect(&addr).unwrap();
+    stream
+        .write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200"), "{}", head);
+    assert!(head.contains("Content-Type: application/json"));
+    let json: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(json["current_state"], "INIT");
+    assert_eq!(json["progress_pct"], 0);
+    assert!(json["tool_version"].is_string());
+}
+
+#[test]
+fn test_build_iterate_runs_custom_check_commands() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    vibeanvil()
+        .args([
+            "build",
+            "iterate",
+            "--provider",
+            "mock",
+            "--max",
+            "3",
+            "--test-cmd",
+            "echo custom-test-ran; exit 1",
+            "--lint-cmd",
+            "true",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Test command: echo custom-test-ran",
+        ))
+        .stdout(predicate::str::contains("Iteration 3: tests ✗, lint ✓"))
+        .stdout(predicate::str::contains("Iteration 4").not())
+        .stdout(predicate::str::contains("Max iterations (3) reached"));
+
+    vibeanvil()
+        .args(["build", "auto", "--provider", "mock", "--test-cmd", "true"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only supported with 'build iterate'",
+        ));
+}
+
+#[test]
+fn test_contract_req_add_edit_remove() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), &TO_CONTRACT_LOCKED[..4]);
+    let requirements = || -> Vec<serde_json::Value> {
+        let contract: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(temp.path().join(".vibeanvil/contracts/contract.json"))
+                .unwrap(),
+        )
+        .unwrap();
+        contract["requirements"].as_array().unwrap().clone()
+    };
+    let before = requirements().len();
+
+    vibeanvil()
+        .args([
+            "contract",
+            "req",
+            "add",
+            "--id",
+            "REQ-100",
+            "--desc",
+            "Export to CSV",
+            "--priority",
+            "should",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added requirement REQ-100"));
+    let added = requirements();
+    assert_eq!(added.len(), before + 1);
+    let req = added.iter().find(|r| r["id"] == "REQ-100").unwrap();
+    assert_eq!(req["description"], "Export to CSV");
+    assert_eq!(req["priority"], "should");
+
+    // IDs are unique
+    vibeanvil()
+        .args([
+            "contract", "req", "add", "--id", "REQ-100", "--desc", "Again",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("already exists"));
+
+    vibeanvil()
+        .args([
+            "contract",
+            "req",
+            "edit",
+            "REQ-100",
+            "--desc",
+            "Export to CSV and JSON",
+            "--priority",
+            "must",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    let edited = requirements();
+    let req = edited.iter().find(|r| r["id"] == "REQ-100").unwrap();
+    assert_eq!(req["description"], "Export to CSV and JSON");
+    assert_eq!(req["priority"], "must");
+
+    vibeanvil()
+        .args(["contract", "req", "rm", "REQ-100"])
+        .current_dir(temp.path())
+        .assert()
+        .success();
+    assert_eq!(requirements().len(), before);
+    vibeanvil()
+        .args(["contract", "req", "rm", "REQ-100"])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("No requirement with ID REQ-100"));
+
+    // A locked contract can't be edited
+    run_steps(temp.path(), &[&["contract", "lock"]]);
+    vibeanvil()
+        .args([
+            "contract", "req", "add", "--id", "REQ-200", "--desc", "Late",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("contract amend"));
+}
+
+#[test]
+fn test_build_provider_timeout_keeps_build_in_progress() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    for mode in ["auto", "iterate"] {
+        vibeanvil()
+            .args(["build", mode, "--provider", "mock", "--timeout", "1"])
+            .env("VIBEANVIL_MOCK_DELAY_MS", "10000")
+            .current_dir(temp.path())
+            .timeout(std::time::Duration::from_secs(60))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Provider timed out after 1s"));
+        assert_eq!(
+            read_state(temp.path())["current_state"],
+            "BUILD_IN_PROGRESS"
+        );
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_build_timeout_kills_command_provider() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // An agent that outlives --timeout is killed, not waited for
+    let started = std::time::Instant::now();
+    vibeanvil()
+        .args(["build", "auto", "--provider", "command", "--timeout", "1"])
+        .env("VIBEANVIL_PROVIDER_COMMAND", "sleep")
+        .env("VIBEANVIL_PROVIDER_ARGS", "30")
+        .env("VIBEANVIL_PROVIDER_MODE", "stdin")
+        .current_dir(temp.path())
+        .timeout(std::time::Duration::from_secs(60))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Provider timed out after 1s"));
+    assert!(started.elapsed() < std::time::Duration::from_secs(15));
+    assert_eq!(
+        read_state(temp.path())["current_state"],
+        "BUILD_IN_PROGRESS"
+    );
+}
+
+#[test]
+fn test_plan_uses_prompt_template_override() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+
+    vibeanvil()
+        .args([
+            "plan",
+            "--provider",
+            "mock",
+            "--no-cache",
+            "--prompt-template",
+            "missing-variant",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "Prompt template 'missing-variant' not found",
+        ));
+    assert_eq!(read_state(temp.path())["current_state"], "CONTRACT_LOCKED");
+
+    let prompts = temp.path().join(".vibeanvil/prompts");
+    std::fs::create_dir_all(&prompts).unwrap();
+    std::fs::write(
+        prompts.join("variant-b.md"),
+        "VARIANT-B: plan this contract\n\n{{contract}}\n",
+    )
+    .unwrap();
+
+    run_steps(
+        temp.path(),
+        &[&[
+            "plan",
+            "--provider",
+            "mock",
+            "--no-cache",
+            "--prompt-template",
+            "variant-b",
+        ]],
+    );
+    let plan = std::fs::read_to_string(temp.path().join(".vibeanvil/plan.md")).unwrap();
+    assert!(plan.contains("VARIANT-B: plan this contract"), "{}", plan);
+}
+
+#[test]
+fn test_build_auto_falls_back_to_first_available_provider() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // The command provider is unavailable without VIBEANVIL_PROVIDER_COMMAND
+    vibeanvil()
+        .args(["build", "auto", "--provider", "command,mock"])
+        .env_remove("VIBEANVIL_PROVIDER_COMMAND")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Provider mock selected (command not available)",
+        ));
+    assert_eq!(read_state(temp.path())["current_state"], "BUILD_DONE");
+
+    // Saved progress names the provider that ran, not the first choice
+    force_state(temp.path(), "PLAN_CREATED");
+    vibeanvil()
+        .args([
+            "build",
+            "iterate",
+            "--provider",
+            "command,mock",
+            "--timeout",
+            "1",
+        ])
+        .env_remove("VIBEANVIL_PROVIDER_COMMAND")
+        .env("VIBEANVIL_MOCK_DELAY_MS", "10000")
+        .current_dir(temp.path())
+        .timeout(std::time::Duration::from_secs(60))
+        .assert()
+        .failure();
+    let progress: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp.path().join(".vibeanvil/build_progress.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(progress["provider"], "mock");
+
+    force_state(temp.path(), "PLAN_CREATED");
+    vibeanvil()
+        .args(["build", "auto", "--provider", "command,command"])
+        .env_remove("VIBEANVIL_PROVIDER_COMMAND")
+        .current_dir(temp.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "None of the providers is available: command, command",
+        ));
+}
+
+#[test]
+fn test_build_iterate_resume_continues_from_saved_iteration() {
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // An interrupted build leaves its progress behind
+    vibeanvil()
+        .args(["build", "iterate", "--provider", "mock", "--timeout", "1"])
+        .env("VIBEANVIL_MOCK_DELAY_MS", "10000")
+        .current_dir(temp.path())
+        .timeout(std::time::Duration::from_secs(60))
+        .assert()
+        .failure();
+    let progress_path = temp.path().join(".vibeanvil/build_progress.json");
+    let mut progress: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&progress_path).unwrap()).unwrap();
+    assert_eq!(progress["current_iteration"], 0);
+    assert!(!progress["last_prompt"].as_str().unwrap().is_empty());
+
+    // As if it had been interrupted while fixing after two iterations
+    progress["current_iteration"] = 2.into();
+    progress["last_prompt"] = "RESUMED-FIX-PROMPT".into();
+    std::fs::write(&progress_path, progress.to_string()).unwrap();
+
+    vibeanvil()
+        .args([
+            "build",
+            "iterate",
+            "--resume",
+            "--provider",
+            "mock",
+            "--test-cmd",
+            "true",
+            "--lint-cmd",
+            "true",
+        ])
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resuming after iteration 2"))
+        .stdout(predicate::str::contains("Iteration 3: Resuming"))
+        .stdout(predicate::str::contains("completed in 3 iteration(s)"));
+    assert_eq!(read_state(temp.path())["current_state"], "BUILD_DONE");
+    assert!(!progress_path.exists());
+
+    vibeanvil()
+        .args(["build", "auto", "--resume", "--provider", "mock"])
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only supported with 'build iterate'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_iterate_resume_skips_finished_tasks() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+    force_state(temp.path(), "BUILD_IN_PROGRESS");
+
+    let task = |id: &str, title: &str, deps: &[&str]| {
+        serde_json::json!({
+            "id": id, "title": title, "description": "", "effort": "small",
+            "dependencies": deps, "files": [], "completed": false,
+        })
+    };
+    let tasks = serde_json::json!({
+        "generated_at": "2026-01-01T00:00:00Z",
+        "tasks": [task("1", "First", &[]), task("2", "Second", &["1"]), task("3", "Third", &["2"])],
+        "total_count": 3,
+        "completed_count": 0,
+    });
+    std::fs::write(temp.path().join(".vibeanvil/tasks.json"), tasks.to_string()).unwrap();
+
+    // Task 2 was interrupted after one iteration
+    let session = read_state(temp.path())["current_session_id"]
+        .as_str()
+        .unwrap_or("s1")
+        .to_string();
+    let progress = serde_json::json!({
+        "session_id": session, "mode": "iterate", "provider": "command",
+        "current_iteration": 1, "max_iterations": 5, "current_step": "Fixing",
+        "started_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z",
+        "tests_passed": false, "lint_passed": false, "last_error": null,
+        "files_modified": [], "last_prompt": "RESUMED-FIX-PROMPT",
+        "errors": [], "evidence_files": [], "task": "Task 2: Second",
+    });
+    std::fs::write(
+        temp.path().join(".vibeanvil/build_progress.json"),
+        progress.to_string(),
+    )
+    .unwrap();
+
+    // The provider appends every prompt it gets to a log
+    let script = temp.path().join("provider.sh");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\n{{ cat; echo; echo ===; }} >> '{}'\necho done\n",
+            temp.path().join("prompts.log").display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    vibeanvil()
+        .args([
+            "build",
+            "iterate",
+            "--resume",
+            "--provider",
+            "command",
+            "--test-cmd",
+            "true",
+            "--lint-cmd",
+            "true",
+        ])
+        .env("VIBEANVIL_PROVIDER_COMMAND", &script)
+        .env("VIBEANVIL_PROVIDER_MODE", "stdin")
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Resuming Task 2: Second after iteration 1",
+        ))
+        .stdout(predicate::str::contains("Skipping 1 task(s)"))
+        .stdout(predicate::str::contains("▶ Task 1: First").not());
+
+    let log = std::fs::read_to_string(temp.path().join("prompts.log")).unwrap();
+    let prompts: Vec<&str> = log
+        .split("\n===\n")
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+    assert_eq!(prompts.len(), 2, "{}", log);
+    assert!(prompts[0].contains("RESUMED-FIX-PROMPT"));
+    assert!(prompts[1].contains("Task 3: Third"));
+    assert!(!prompts[1].contains("RESUMED-FIX-PROMPT"));
+    assert_eq!(read_state(temp.path())["current_state"], "BUILD_DONE");
+}
+
+#[test]
+fn test_watch_ctrl_c_cancels_the_running_rebuild() {
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, Instant};
+
+    let temp = TempDir::new().unwrap();
+    run_steps(temp.path(), TO_CONTRACT_LOCKED);
+    run_steps(
+        temp.path(),
+        &[&["plan", "--provider", "mock", "--no-cache"]],
+    );
+
+    // The provider stalls once `slow` exists, i.e. on the rebuild it triggers
+    let started = temp.path().join(".vibeanvil/rebuild_started");
+    let script = temp.path().join("provider.sh");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\ncat > /dev/null\nif [ -f '{}' ]; then touch '{}'; sleep 30; fi\necho done\n",
+            temp.path().join("slow").display(),
+            started.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("vibeanvil"))
+        .args([
+            "build",
+            "iterate",
+            "--watch",
+            "--debounce-ms",
+            "100",
+            "--provider",
+            "command",
+            "--test-cmd",
+            "true",
+            "--lint-cmd",
+            "true",
+        ])
+        .env("VIBEANVIL_PROVIDER_COMMAND", &script)
+        .env("VIBEANVIL_PROVIDER_MODE", "stdin")
+        .current_dir(temp.path())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert!(
+            stdout.read_line(&mut line).unwrap() > 0,
+            "watch mode exited"
+        );
+        if line.contains("Watch Mode Active") {
+            break;
+        }
+    }
+    // Let the watcher register before changing anything
+    std::thread::sleep(Duration::from_millis(500));
+    std::fs::write(temp.path().join("slow"), "").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while !started.exists() {
+        assert!(
+            Instant::now() < deadline,
+            "rebuild never reached the provider"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let interrupted = Instant::now();
+    let status = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = child.wait_with_output().unwrap();
+    assert!(interrupted.elapsed() < Duration::from_secs(20));
+    let mut rest = String::new();
+    std::io::Read::read_to_string(&mut stdout, &mut rest).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(rest.contains("Build cancelled in iteration 1"), "{}", rest);
+    assert!(stderr.contains("build iterate --resume"), "{}", stderr);
+    assert_eq!(
+        read_state(temp.path())["current_state"],
+        "BUILD_IN_PROGRESS"
+    );
+}